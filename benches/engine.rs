@@ -0,0 +1,87 @@
+//! Benchmarks of the stepping kernel, run with `cargo bench`.
+//!
+//! Each benchmark is timed over a number of samples after a warm-up, and the median time
+//! per iteration is printed, so runs before and after a change can be compared. Pass a
+//! name filter to run only some of them, as in `cargo bench -- update_grid`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use gameoflife::{boundary::Boundary, live_neighbors, random_grid, rule::Rule, update_grid, update_grid_naive, world::World};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// The sizes of the square boards the benchmarks run on.
+const SIZES: [usize; 3] = [64, 256, 1024];
+
+/// The number of timed samples per benchmark.
+const SAMPLES: usize = 15;
+
+/// The time each sample should take at least, so fast benchmarks are repeated often
+/// enough to be measured.
+const SAMPLE_TIME: Duration = Duration::from_millis(40);
+
+fn main() {
+    let filter = std::env::args().skip(1).find(|arg| !arg.starts_with('-'));
+    let rule = Rule::parse("B3/S23").unwrap();
+
+    for size in SIZES {
+        let grid = random_grid(size, size, 0.35, &mut StdRng::seed_from_u64(size as u64));
+
+        bench(&format!("update_grid/{}", size), filter.as_deref(), || update_grid(black_box(&grid), &rule));
+        let mut world = World::new(grid.clone(), Boundary::Dead);
+        bench(&format!("world_step/{}", size), filter.as_deref(), || world.step(black_box(&rule)));
+        if size <= 256 {
+            bench(&format!("update_grid_naive/{}", size), filter.as_deref(), || update_grid_naive(black_box(&grid), &rule));
+        }
+        bench(&format!("live_neighbors/{}", size), filter.as_deref(), || {
+            let mut total = 0;
+            for y in 0..size {
+                for x in 0..size {
+                    total += live_neighbors(black_box(&grid), x, y);
+                }
+            }
+            total
+        });
+    }
+}
+
+/// Times a benchmark and prints its median time per iteration.
+///
+/// # Arguments
+///
+/// * `name` - The name of the benchmark, as in `update_grid/256`.
+/// * `filter` - Only benchmarks whose name contains this are run, if given.
+/// * `routine` - The code to time; its result is kept from being optimized away.
+fn bench<T>(name: &str, filter: Option<&str>, mut routine: impl FnMut() -> T) {
+    if filter.is_some_and(|filter| !name.contains(filter)) {
+        return;
+    }
+
+    // Warm up, and find how many iterations make a sample long enough to measure.
+    let mut iterations = 1u32;
+    loop {
+        let started = Instant::now();
+        for _ in 0..iterations {
+            black_box(routine());
+        }
+        if started.elapsed() >= SAMPLE_TIME {
+            break;
+        }
+        iterations *= 2;
+    }
+
+    let mut samples: Vec<Duration> = (0..SAMPLES)
+        .map(|_| {
+            let started = Instant::now();
+            for _ in 0..iterations {
+                black_box(routine());
+            }
+            started.elapsed() / iterations
+        })
+        .collect();
+    samples.sort();
+    let (low, median, high) = (samples[0], samples[SAMPLES / 2], samples[SAMPLES - 1]);
+    println!("{:<24} {:>12?}  [{:?} .. {:?}]", name, median, low, high);
+}
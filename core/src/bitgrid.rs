@@ -0,0 +1,389 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    boundary::Boundary,
+    rule::{Neighborhood, Rule},
+    update_grid_with_boundary, Grid,
+};
+
+/// A grid packed into 64-bit words, one bit per cell, advanced with bitwise arithmetic.
+///
+/// Each row is stored as `words_per_row` words with column `x` in bit `x % 64` of word
+/// `x / 64`. Bits past the right edge of the grid are always zero.
+///
+/// A generation is computed a whole word (64 cells) at a time: the eight neighbours of
+/// every cell in a word are the word itself and the words of the rows above and below,
+/// shifted one column left and right with the carry bit taken from the adjacent word.
+/// These eight one-bit inputs are summed with half and full adders into a four-bit count
+/// held in four words, and the rule is applied by matching the count bits, so no loop
+/// ever visits individual cells.
+///
+/// The cells past the edges are those of the grid's [`Boundary`], which is dead unless
+/// set with [`BitGrid::with_boundary`] and carries over to every later generation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid {
+    /// The number of columns.
+    width: usize,
+    /// The number of rows.
+    height: usize,
+    /// The number of words that hold one row.
+    words_per_row: usize,
+    /// The packed rows, one after another.
+    words: Vec<u64>,
+    /// What lies past the edges.
+    boundary: Boundary,
+}
+
+/// Adds three one-bit inputs, returning the sum bit and the carry bit.
+fn full_adder(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let partial = a ^ b;
+    (partial ^ c, (a & b) | (c & partial))
+}
+
+/// Adds two one-bit inputs, returning the sum bit and the carry bit.
+fn half_adder(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
+
+impl BitGrid {
+    /// Creates an empty grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of columns.
+    /// * `height` - The number of rows.
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        let words_per_row = width.div_ceil(64);
+        BitGrid {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+            boundary: Boundary::Dead,
+        }
+    }
+
+    /// Sets what lies past the edges of the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundary` - The boundary, which the generations stepped from this grid keep.
+    pub fn with_boundary(mut self, boundary: Boundary) -> BitGrid {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Returns what lies past the edges of the grid.
+    pub fn boundary(&self) -> Boundary {
+        self.boundary
+    }
+
+    /// Packs a grid of cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid to pack.
+    pub fn from_grid(grid: &[Vec<bool>]) -> BitGrid {
+        let mut bits = BitGrid::new(grid.first().map_or(0, |row| row.len()), grid.len());
+        bits.pack(grid);
+        bits
+    }
+
+    /// Packs a grid of cells into this one, reusing its words, and keeps the boundary.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid to pack, which may be of another size.
+    pub fn pack(&mut self, grid: &[Vec<bool>]) {
+        self.resize(grid.first().map_or(0, |row| row.len()), grid.len());
+        for (words, row) in self.words.chunks_mut(self.words_per_row.max(1)).zip(grid) {
+            for (word, chunk) in words.iter_mut().zip(row.chunks(64)) {
+                *word = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |word, (i, &cell)| word | (cell as u64) << i);
+            }
+        }
+    }
+
+    /// Unpacks the grid into one `bool` per cell.
+    pub fn to_grid(&self) -> Grid {
+        (0..self.height)
+            .map(|y| {
+                let mut cells = vec![false; self.width];
+                self.unpack_row(y, &mut cells);
+                cells
+            })
+            .collect()
+    }
+
+    /// Unpacks the grid into an existing grid of cells, reusing its rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid to fill, which is resized to this one.
+    pub fn unpack_into(&self, grid: &mut Grid) {
+        grid.resize_with(self.height, Vec::new);
+        for (y, cells) in grid.iter_mut().enumerate() {
+            cells.resize(self.width, false);
+            self.unpack_row(y, cells);
+        }
+    }
+
+    /// Unpacks one row into its cells.
+    fn unpack_row(&self, y: usize, cells: &mut [bool]) {
+        for (chunk, &word) in cells.chunks_mut(64).zip(self.row(y)) {
+            for (bit, cell) in chunk.iter_mut().enumerate() {
+                *cell = word >> bit & 1 == 1;
+            }
+        }
+    }
+
+    /// Changes the size of the grid, keeping its words where it can. The cells are left
+    /// undefined, to be overwritten.
+    fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.words_per_row = width.div_ceil(64);
+        self.words.resize(self.words_per_row * height, 0);
+    }
+
+    /// Returns the width of the grid.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height of the grid.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns whether the cell at the given position is alive.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell.
+    /// * `y` - The row of the cell.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.words[y * self.words_per_row + x / 64] >> (x % 64) & 1 == 1
+    }
+
+    /// Sets the state of the cell at the given position.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell.
+    /// * `y` - The row of the cell.
+    /// * `alive` - The new state of the cell.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        let word = &mut self.words[y * self.words_per_row + x / 64];
+        if alive {
+            *word |= 1 << (x % 64);
+        } else {
+            *word &= !(1 << (x % 64));
+        }
+    }
+
+    /// Returns the number of live cells.
+    pub fn population(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Returns the positions of the cells that differ from another grid of the same size.
+    ///
+    /// Only the set bits of the XOR of the two grids are visited, so this is cheap when
+    /// few cells changed.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The grid to compare with.
+    ///
+    /// # Returns
+    ///
+    /// The positions of the changed cells, as `(x, y)`.
+    pub fn changes<'a>(&'a self, other: &'a BitGrid) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .enumerate()
+            .flat_map(move |(i, (a, b))| {
+                let (y, base) = (i / self.words_per_row, (i % self.words_per_row) * 64);
+                let mut diff = a ^ b;
+                core::iter::from_fn(move || {
+                    if diff == 0 {
+                        return None;
+                    }
+                    let bit = diff.trailing_zeros() as usize;
+                    diff &= diff - 1;
+                    Some((base + bit, y))
+                })
+            })
+    }
+
+    /// Returns the mask of the bits of the last word of a row that are inside the grid.
+    fn last_word_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => !0,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    /// Returns the words of a row.
+    fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Fills a buffer with the rows of the grid and the cells of its boundary around them.
+    ///
+    /// There is one row above and one below the grid, and each row is `words_per_row + 2`
+    /// words: the cell past the left edge in the top bit of the first word, the row itself,
+    /// and the cell past the right edge in the bit just after the last column.
+    fn pad_rows(&self, padded: &mut Vec<u64>) {
+        let stride = self.words_per_row + 2;
+        padded.clear();
+        padded.resize(stride * (self.height + 2), 0);
+        if self.width == 0 {
+            return;
+        }
+
+        let cell = |x: isize, y: isize| match self.boundary.map_cell(x, y, self.width, self.height) {
+            Some((x, y)) => self.row(y)[x / 64] >> (x % 64) & 1,
+            None => (self.boundary == Boundary::Alive) as u64,
+        };
+        for (row, padded_row) in padded.chunks_mut(stride).enumerate() {
+            let y = row as isize - 1;
+            let words = &mut padded_row[1..=self.words_per_row];
+            let past_edge = !(0..self.height as isize).contains(&y);
+            match (self.boundary, self.boundary.map(y, self.height)) {
+                // The rows past the edges of a twisted surface come back mirrored, so they
+                // are copied cell by cell.
+                (boundary, Some(_)) if boundary.is_twisted() && past_edge => {
+                    for x in 0..self.width {
+                        words[x / 64] |= cell(x as isize, y) << (x % 64);
+                    }
+                }
+                (_, Some(source)) => words.copy_from_slice(self.row(source)),
+                (Boundary::Alive, None) => words.fill(!0),
+                (_, None) => continue,
+            }
+            words[self.words_per_row - 1] &= self.last_word_mask();
+
+            padded_row[0] = cell(-1, y) << 63;
+            padded_row[1 + self.width / 64] |= cell(self.width as isize, y) << (self.width % 64);
+        }
+    }
+
+    /// Computes the next generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The birth and survival rule to apply.
+    ///
+    /// # Returns
+    ///
+    /// The next generation, with the same boundary.
+    pub fn step(&self, rule: &Rule) -> BitGrid {
+        let mut next = BitGrid::new(self.width, self.height);
+        self.step_into(rule, &mut next, &mut Vec::new());
+        next
+    }
+
+    /// Computes the next generation into another grid, reusing its words.
+    ///
+    /// Together with [`BitGrid::pack`] and [`BitGrid::unpack_into`], this lets a
+    /// simulation keep the same buffers from one generation to the next, as
+    /// the `World` of the `gameoflife` library does.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The birth and survival rule to apply.
+    /// * `next` - The grid the next generation is written to, with the same boundary.
+    /// * `padded` - Room for the rows with the cells past the edges, kept between calls.
+    pub fn step_into(&self, rule: &Rule, next: &mut BitGrid, padded: &mut Vec<u64>) {
+        next.boundary = self.boundary;
+        // Larger than Life neighbourhoods are too big for the adders, and isotropic rules
+        // need the arrangement of the neighbours, not just their count.
+        let Rule::LifeLike { neighborhood, .. } = *rule else {
+            next.pack(&update_grid_with_boundary(&self.to_grid(), rule, self.boundary));
+            return;
+        };
+
+        next.resize(self.width, self.height);
+        let last_mask = self.last_word_mask();
+        let stride = self.words_per_row + 2;
+        self.pad_rows(padded);
+
+        // Only the neighbour counts that lead to a live cell need to be matched.
+        let mut counts = [(0, false, false); 9];
+        let mut live_counts = 0;
+        for n in 0..=8 {
+            let (birth, survival) = (rule.next_state(false, n), rule.next_state(true, n));
+            if birth || survival {
+                counts[live_counts] = (n, birth, survival);
+                live_counts += 1;
+            }
+        }
+
+        for y in 0..self.height {
+            // The padded rows are one row down, so the row above is padded row `y`.
+            let above = &padded[y * stride..(y + 1) * stride];
+            let middle = &padded[(y + 1) * stride..(y + 2) * stride];
+            let below = &padded[(y + 2) * stride..(y + 3) * stride];
+            let next_row = &mut next.words[y * self.words_per_row..(y + 1) * self.words_per_row];
+
+            for (i, slot) in next_row.iter_mut().enumerate() {
+                // The word and its west and east shifted copies, for one row. The padded
+                // rows are one word right, so the word is padded word `i + 1`.
+                let shifted = |row: &[u64]| {
+                    let (west_word, word, east_word) = (row[i], row[i + 1], row[i + 2]);
+                    (word << 1 | west_word >> 63, word, word >> 1 | east_word << 63)
+                };
+                let (nw, n, ne) = shifted(above);
+                let (w, center, e) = shifted(middle);
+                let (sw, s, se) = shifted(below);
+
+                // Neighbours outside the neighbourhood are left out of the count. Odd
+                // rows of a hex board are shifted right, so they touch the cells above and
+                // below to the east instead of the west.
+                let (nw, ne, sw, se) = match neighborhood {
+                    Neighborhood::Moore => (nw, ne, sw, se),
+                    Neighborhood::VonNeumann => (0, 0, 0, 0),
+                    Neighborhood::Hex if y % 2 == 0 => (nw, 0, sw, 0),
+                    Neighborhood::Hex => (0, ne, 0, se),
+                };
+
+                // Sum the eight neighbours into count bits of weight 1, 2, 4, and 8.
+                let (sum_above, carry_above) = full_adder(nw, n, ne);
+                let (sum_middle, carry_middle) = half_adder(w, e);
+                let (sum_below, carry_below) = full_adder(sw, s, se);
+                let (ones, carry_ones) = full_adder(sum_above, sum_middle, sum_below);
+                let (twos_partial, carry_twos) = full_adder(carry_above, carry_middle, carry_below);
+                let (twos, carry_twos_partial) = half_adder(twos_partial, carry_ones);
+                let (fours, eights) = half_adder(carry_twos, carry_twos_partial);
+
+                // Match the count against every neighbour count the rule cares about.
+                let bit = |word: u64, set: bool| if set { word } else { !word };
+                let mut next_word = 0;
+                for &(count, birth, survival) in &counts[..live_counts] {
+                    let matches = bit(ones, count & 1 != 0)
+                        & bit(twos, count & 2 != 0)
+                        & bit(fours, count & 4 != 0)
+                        & bit(eights, count & 8 != 0);
+                    let alive_after = match (birth, survival) {
+                        (true, true) => !0,
+                        (true, false) => !center,
+                        (false, true) => center,
+                        (false, false) => 0,
+                    };
+                    next_word |= matches & alive_after;
+                }
+
+                // Keep the bits past the right edge dead.
+                if i == self.words_per_row - 1 {
+                    next_word &= last_mask;
+                }
+                *slot = next_word;
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::Grid;
+
+/// What lies past the edges of a finite board, as seen by the cells next to them.
+///
+/// The boundary decides the neighbours that cells on the edge count, and so what
+/// survives near the edges: on a dead boundary gliders die against the wall, on a wrapped
+/// one they fly through it and come back from the other side, and a live boundary keeps
+/// the edges busy with births. The twisted surfaces glue edges together with a flip, so
+/// what crosses them comes back mirrored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// The cells past the edges are always dead.
+    #[default]
+    Dead,
+    /// The board wraps around, so the cells past each edge are those of the opposite edge,
+    /// as on a torus.
+    Wrap,
+    /// The board is mirrored at its edges, so the cells past each edge are the edge cells
+    /// themselves and those next to them.
+    Reflect,
+    /// The cells past the edges are always alive.
+    Alive,
+    /// A Klein bottle: the left and right edges are glued as on a torus, and the top and
+    /// bottom edges with a flip, so what leaves through the top comes back through the
+    /// bottom mirrored left to right.
+    Klein,
+    /// A cross-surface, or real projective plane: both pairs of edges are glued with a
+    /// flip, so what crosses any edge comes back through the opposite one mirrored.
+    Cross,
+}
+
+impl Boundary {
+    /// Parses a boundary name: `dead`, `wrap`, `reflect`, `alive`, `klein`, or `cross`.
+    pub fn parse(name: &str) -> Option<Boundary> {
+        match name {
+            "dead" => Some(Boundary::Dead),
+            "wrap" => Some(Boundary::Wrap),
+            "reflect" => Some(Boundary::Reflect),
+            "alive" => Some(Boundary::Alive),
+            "klein" => Some(Boundary::Klein),
+            "cross" => Some(Boundary::Cross),
+            _ => None,
+        }
+    }
+
+    /// Parses the name of the surface the board is glued into: `plane` for dead edges,
+    /// `torus`, `klein` for a Klein bottle, or `cross` for a cross-surface.
+    pub fn parse_topology(name: &str) -> Option<Boundary> {
+        match name {
+            "plane" => Some(Boundary::Dead),
+            "torus" => Some(Boundary::Wrap),
+            "klein" => Some(Boundary::Klein),
+            "cross" => Some(Boundary::Cross),
+            _ => None,
+        }
+    }
+
+    /// Returns whether crossing an edge flips the board, so the cells past it depend on
+    /// both coordinates and not one at a time.
+    pub fn is_twisted(&self) -> bool {
+        matches!(self, Boundary::Klein | Boundary::Cross)
+    }
+
+    /// Maps a column or row that may be past the edges onto the board.
+    ///
+    /// The twisted surfaces wrap each coordinate like a torus here, and also flip the
+    /// other one, which only [`Boundary::map_cell`] takes into account.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The column or row, which may be negative or past the end.
+    /// * `len` - The number of columns or rows of the board.
+    ///
+    /// # Returns
+    ///
+    /// The column or row on the board whose cells are seen there, or `None` for a dead
+    /// or live boundary.
+    pub fn map(&self, index: isize, len: usize) -> Option<usize> {
+        if (0..len as isize).contains(&index) {
+            return Some(index as usize);
+        }
+        match self {
+            Boundary::Dead | Boundary::Alive => None,
+            _ if len == 0 => None,
+            Boundary::Wrap | Boundary::Klein | Boundary::Cross => Some(index.rem_euclid(len as isize) as usize),
+            Boundary::Reflect => {
+                // A mirrored board repeats every two widths: the board, then its mirror image.
+                let index = index.rem_euclid(2 * len as isize) as usize;
+                Some(if index < len { index } else { 2 * len - 1 - index })
+            }
+        }
+    }
+
+    /// Maps a cell that may be past the edges onto the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column, which may be negative or past the end.
+    /// * `y` - The row, which may be negative or past the end.
+    /// * `width` - The number of columns of the board.
+    /// * `height` - The number of rows of the board.
+    ///
+    /// # Returns
+    ///
+    /// The column and row on the board of the cell seen there, or `None` for a dead or
+    /// live boundary.
+    pub fn map_cell(&self, x: isize, y: isize, width: usize, height: usize) -> Option<(usize, usize)> {
+        let outside = |index: isize, len: usize| !(0..len as isize).contains(&index);
+        let (mut cx, mut cy) = (self.map(x, width)?, self.map(y, height)?);
+        // Crossing the top or bottom edge flips the column, and on a cross-surface crossing
+        // the left or right edge flips the row.
+        if self.is_twisted() && outside(y, height) {
+            cx = width - 1 - cx;
+        }
+        if *self == Boundary::Cross && outside(x, width) {
+            cy = height - 1 - cy;
+        }
+        Some((cx, cy))
+    }
+
+    /// Returns whether a cell is alive, for a position that may be past the edges.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    /// * `x` - The column of the cell.
+    /// * `y` - The row of the cell.
+    pub fn cell(&self, grid: &[Vec<bool>], x: isize, y: isize) -> bool {
+        let width = grid.first().map_or(0, |row| row.len());
+        match self.map_cell(x, y, width, grid.len()) {
+            Some((x, y)) => grid[y][x],
+            None => *self == Boundary::Alive,
+        }
+    }
+
+    /// Surrounds a board with the cells its boundary puts past the edges.
+    ///
+    /// Any engine can then step the larger board as if it had dead edges, and the
+    /// original board cut back out of the result is stepped with this boundary, as long
+    /// as the margins are at least the reach of the rule's neighbourhood.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    /// * `columns` - The number of columns added on the left and on the right.
+    /// * `rows` - The number of rows added above and below.
+    ///
+    /// # Returns
+    ///
+    /// The larger board.
+    pub fn pad(&self, grid: &[Vec<bool>], columns: usize, rows: usize) -> Grid {
+        let width = grid.first().map_or(0, |row| row.len());
+        (-(rows as isize)..(grid.len() + rows) as isize)
+            .map(|y| (-(columns as isize)..(width + columns) as isize).map(|x| self.cell(grid, x, y)).collect())
+            .collect()
+    }
+}
+
+impl fmt::Display for Boundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Boundary::Dead => "dead",
+            Boundary::Wrap => "wrap",
+            Boundary::Reflect => "reflect",
+            Boundary::Alive => "alive",
+            Boundary::Klein => "klein",
+            Boundary::Cross => "cross",
+        })
+    }
+}
@@ -0,0 +1,268 @@
+//! Isotropic non-totalistic rules in Hensel notation, such as `B2-a/S12`.
+//!
+//! These rules look at how the live neighbours of a cell are arranged, not just how many
+//! there are. The eight neighbours of a cell are packed into a byte, one bit each, and
+//! every arrangement of a given count belongs to a class named by a letter: `2a` is two
+//! neighbours side by side, `2i` two on opposite edges, and so on. A class holds every
+//! rotation and reflection of its arrangement, so the rules are isotropic.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::Grid;
+
+/// The offsets of the neighbours, in the order of their bits: NW, N, NE, W, E, SW, S, and SE.
+const OFFSETS: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+
+// The bits of the neighbours.
+const NW: u8 = 1;
+const N: u8 = 1 << 1;
+const NE: u8 = 1 << 2;
+const W: u8 = 1 << 3;
+const E: u8 = 1 << 4;
+const SW: u8 = 1 << 5;
+const S: u8 = 1 << 6;
+const SE: u8 = 1 << 7;
+
+/// The letters of the classes of each neighbour count, in the usual order.
+const LETTERS: [&str; 9] = ["", "ce", "cekain", "cekainyqjr", "cekainyqjrtwz", "cekainyqjr", "cekain", "ce", ""];
+
+/// One arrangement of each class of up to four neighbours, in the order of [`LETTERS`].
+///
+/// The classes of five to eight neighbours are the complements of these: `5c` is every
+/// arrangement whose empty neighbours form a `3c`.
+const REPRESENTATIVES: [&[u8]; 5] = [
+    &[0],
+    // c: a corner; e: an edge.
+    &[NW, N],
+    // c: adjacent corners; e: adjacent edges; k: a knight's move apart; a: a corner and
+    // its edge; i: opposite edges; n: opposite corners.
+    &[NW | NE, N | E, N | SE, NW | N, N | S, NW | SE],
+    // c: corners; e: edges; k: a corner and the two edges away from it; a: a corner
+    // and both its edges; i: a whole side; n: a corner, its edge, and the next edge
+    // round; y: two corners and the edge opposite them; q: opposite corners and an edge;
+    // j: a corner, its neighbouring corner, and the edge beyond; r: a corner, its edge,
+    // and the opposite edge.
+    &[
+        NW | NE | SE,
+        N | E | W,
+        N | E | SW,
+        NW | N | W,
+        NW | N | NE,
+        N | E | SE,
+        N | SW | SE,
+        NW | N | SE,
+        N | NE | SE,
+        N | S | SE,
+    ],
+    &[
+        NW | NE | SW | SE, // c
+        N | W | E | S,     // e
+        NW | W | E | S,    // k
+        NW | N | NE | W,   // a
+        NW | NE | W | E,   // i
+        NW | NE | SW | S,  // n
+        NW | NE | W | S,   // y
+        NW | N | W | SE,   // q
+        NW | N | NE | SE,  // j
+        W | E | SW | S,    // r
+        NW | N | NE | S,   // t
+        NW | N | E | SE,   // w
+        NW | N | S | SE,   // z
+    ],
+];
+
+/// A set of arrangements of the eight neighbours of a cell, one bit per arrangement.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Arrangements([u64; 4]);
+
+impl Arrangements {
+    /// Returns whether an arrangement is in the set.
+    pub fn contains(&self, arrangement: u8) -> bool {
+        self.0[arrangement as usize / 64] >> (arrangement % 64) & 1 == 1
+    }
+
+    /// Adds an arrangement to the set.
+    pub fn insert(&mut self, arrangement: u8) {
+        self.0[arrangement as usize / 64] |= 1 << (arrangement % 64);
+    }
+}
+
+/// Returns the live neighbours of a cell as a byte, with the bit order of [`OFFSETS`].
+///
+/// # Arguments
+///
+/// * `grid` - The grid containing the cells.
+/// * `x` - The x-coordinate of the cell.
+/// * `y` - The y-coordinate of the cell.
+///
+/// # Returns
+///
+/// The arrangement of the live neighbours; cells past the edges are dead.
+pub fn arrangement(grid: &[Vec<bool>], x: usize, y: usize) -> u8 {
+    OFFSETS.iter().enumerate().fold(0, |bits, (bit, &(dx, dy))| {
+        let alive = grid
+            .get((y as isize + dy) as usize)
+            .and_then(|row| row.get((x as isize + dx) as usize))
+            .copied()
+            .unwrap_or(false);
+        bits | u8::from(alive) << bit
+    })
+}
+
+/// Applies a rotation or reflection of the neighbourhood to an arrangement.
+fn transform(bits: u8, map: impl Fn(isize, isize) -> (isize, isize)) -> u8 {
+    OFFSETS.iter().enumerate().filter(|&(bit, _)| bits >> bit & 1 == 1).fold(0, |out, (_, &(dx, dy))| {
+        let target = map(dx, dy);
+        out | 1 << OFFSETS.iter().position(|&offset| offset == target).expect("the neighbourhood maps onto itself")
+    })
+}
+
+/// Returns every arrangement in a class.
+///
+/// # Arguments
+///
+/// * `count` - The number of live neighbours, from 0 to 8.
+/// * `letter` - The letter of the class.
+///
+/// # Returns
+///
+/// The arrangements, or `None` if the count has no class with that letter.
+pub fn class(count: usize, letter: char) -> Option<Vec<u8>> {
+    let index = LETTERS.get(count)?.find(letter)?;
+    let representative = if count <= 4 {
+        REPRESENTATIVES[count][index]
+    } else {
+        !REPRESENTATIVES[8 - count][index]
+    };
+
+    // Every rotation of the arrangement and of its mirror image.
+    let mut arrangements = Vec::new();
+    for start in [representative, transform(representative, |dx, dy| (-dx, dy))] {
+        let mut bits = start;
+        for _ in 0..4 {
+            if !arrangements.contains(&bits) {
+                arrangements.push(bits);
+            }
+            bits = transform(bits, |dx, dy| (-dy, dx));
+        }
+    }
+    Some(arrangements)
+}
+
+/// Returns every arrangement of a number of live neighbours, whatever its class.
+fn all_of(count: usize) -> impl Iterator<Item = u8> {
+    (0..=255u8).filter(move |bits| bits.count_ones() as usize == count)
+}
+
+/// Parses one half of a rulestring, like `2-a` or `34q`, into the arrangements it lists.
+///
+/// Each count is followed by the letters of its classes, or by `-` and the letters to
+/// leave out; a count with no letters takes every arrangement.
+///
+/// # Arguments
+///
+/// * `text` - The counts and letters.
+///
+/// # Returns
+///
+/// The arrangements listed, or `None` if the text is not valid.
+pub fn parse_arrangements(text: &str) -> Option<Arrangements> {
+    let mut table = Arrangements::default();
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        let count = ch.to_digit(10).filter(|&n| n <= 8)? as usize;
+        let negated = chars.next_if_eq(&'-').is_some();
+        let mut letters = Vec::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            letters.push(letter.to_ascii_lowercase());
+        }
+        if negated && letters.is_empty() {
+            return None;
+        }
+
+        let listed: Vec<u8> = if letters.is_empty() || negated {
+            // Start from every arrangement and take away the letters left out.
+            let excluded = letters.iter().map(|&letter| class(count, letter)).collect::<Option<Vec<_>>>()?;
+            all_of(count).filter(|bits| !excluded.iter().any(|class| class.contains(bits))).collect()
+        } else {
+            letters.iter().map(|&letter| class(count, letter)).collect::<Option<Vec<_>>>()?.concat()
+        };
+        for bits in listed {
+            table.insert(bits);
+        }
+    }
+    Some(table)
+}
+
+/// Writes the arrangements of one half of a rule in Hensel notation, like `2-a`.
+///
+/// # Arguments
+///
+/// * `table` - The arrangements listed.
+///
+/// # Returns
+///
+/// The counts and letters, with a count listing more than half its classes written as
+/// the letters it leaves out.
+pub fn format_arrangements(table: &Arrangements) -> String {
+    let mut text = String::new();
+    for (count, letters) in LETTERS.iter().enumerate() {
+        if letters.is_empty() {
+            // Counts 0 and 8 have a single arrangement and no letters.
+            if all_of(count).all(|bits| table.contains(bits)) {
+                text.push_str(&count.to_string());
+            }
+            continue;
+        }
+
+        // Letters are written in alphabetical order, as rulestrings usually are.
+        let mut sorted: Vec<char> = letters.chars().collect();
+        sorted.sort_unstable();
+        let (included, excluded): (String, String) = sorted.into_iter().partition(|&letter| {
+            class(count, letter).expect("the letter belongs to the count").iter().all(|&bits| table.contains(bits))
+        });
+        if included.is_empty() {
+            continue;
+        }
+        text.push_str(&count.to_string());
+        if excluded.is_empty() {
+            continue;
+        }
+        if included.len() > excluded.len() {
+            text.push('-');
+            text.push_str(&excluded);
+        } else {
+            text.push_str(&included);
+        }
+    }
+    text
+}
+
+/// Computes the next generation under an isotropic rule.
+///
+/// # Arguments
+///
+/// * `grid` - The current generation.
+/// * `birth` - The arrangements of live neighbours that bring a dead cell to life.
+/// * `survival` - The arrangements of live neighbours that keep a live cell alive.
+///
+/// # Returns
+///
+/// The next generation.
+pub fn step(grid: &[Vec<bool>], birth: &Arrangements, survival: &Arrangements) -> Grid {
+    grid.iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, &alive)| {
+                    let table = if alive { survival } else { birth };
+                    table.contains(arrangement(grid, x, y))
+                })
+                .collect()
+        })
+        .collect()
+}
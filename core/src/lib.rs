@@ -0,0 +1,192 @@
+//! The stepping engine of the Game of Life: the grid, the rules, the boundaries, and the
+//! bit-parallel kernel that advances a board.
+//!
+//! The engine only needs an allocator, not the standard library, so it runs on embedded
+//! devices as well as under the terminal frontend. It touches no terminal, files, or
+//! threads; the `gameoflife` library builds the rest on top of it and re-exports it.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod bitgrid;
+pub mod boundary;
+pub mod hensel;
+pub mod ltl;
+pub mod rule;
+#[cfg(feature = "serde")]
+mod serialize;
+
+use alloc::{vec, vec::Vec};
+
+use bitgrid::BitGrid;
+use boundary::Boundary;
+use rule::{Neighborhood, Rule};
+
+/// A grid of cells, stored row by row, where `true` means the cell is alive.
+pub type Grid = Vec<Vec<bool>>;
+
+/// Calculates the number of live neighbors of a cell in the grid.
+///
+/// # Arguments
+///
+/// * `grid` - The grid containing the cells.
+/// * `x` - The x-coordinate of the cell.
+/// * `y` - The y-coordinate of the cell.
+///
+/// # Returns
+///
+/// The number of live neighbors.
+pub fn live_neighbors(grid: &[Vec<bool>], x: usize, y: usize) -> usize {
+    // Slice out the rows and columns around the cell once, instead of bounds-checking
+    // each of the eight neighbours on its own.
+    let rows = grid.get(y.saturating_sub(1)..(y + 2).min(grid.len())).unwrap_or_default();
+    let block: usize = rows
+        .iter()
+        .map(|row| {
+            let cells = row.get(x.saturating_sub(1)..(x + 2).min(row.len())).unwrap_or_default();
+            cells.iter().filter(|&&cell| cell).count()
+        })
+        .sum();
+
+    // The block includes the cell itself.
+    let itself = grid.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false);
+    block - itself as usize
+}
+
+/// Calculates the number of live neighbors of a cell out to a given radius.
+///
+/// # Arguments
+///
+/// * `grid` - The grid containing the cells.
+/// * `x` - The x-coordinate of the cell.
+/// * `y` - The y-coordinate of the cell.
+/// * `radius` - How far the neighbourhood reaches from the cell.
+/// * `neighborhood` - The shape of the neighbourhood.
+///
+/// # Returns
+///
+/// The number of live neighbors, not counting the cell itself.
+pub fn live_neighbors_within(grid: &[Vec<bool>], x: usize, y: usize, radius: usize, neighborhood: Neighborhood) -> usize {
+    // Initialize a count for the live neighbors.
+    let mut count = 0;
+    let r = radius as isize;
+
+    // Iterate over the neighbors of the cell.
+    for i in -r..=r {
+        for j in -r..=r {
+            // Skip the cell itself, and the cells outside the shape of the neighbourhood.
+            if (i == 0 && j == 0) || !neighborhood.contains(j, i, radius, y) {
+                continue;
+            }
+
+            // Check if the neighbor is within the grid bounds.
+            if let Some(&cell) = grid
+                .get((y as isize + i) as usize)
+                .and_then(|row| row.get((x as isize + j) as usize))
+            {
+                // Increment the count if the neighbor is live.
+                count += cell as usize;
+            }
+        }
+    }
+
+    // Return the count of live neighbors.
+    count
+}
+
+/// Updates the grid by applying the Game of Life rules.
+///
+/// This packs the grid and advances it with the bit-parallel engine in [`bitgrid`].
+/// Larger than Life rules are counted with the summed-area tables of [`ltl`] instead,
+/// and isotropic rules by looking up the arrangement of each cell's neighbours.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to be updated.
+/// * `rule` - The birth and survival rule to apply.
+///
+/// # Returns
+///
+/// The updated grid.
+pub fn update_grid(grid: &[Vec<bool>], rule: &Rule) -> Grid {
+    match rule {
+        Rule::LifeLike { .. } => BitGrid::from_grid(grid).step(rule).to_grid(),
+        Rule::LargerThanLife(rule) => rule.step(grid),
+        Rule::Isotropic { birth, survival } => hensel::step(grid, birth, survival),
+    }
+}
+
+/// Updates the grid by applying the Game of Life rules, with a given boundary past its
+/// edges.
+///
+/// Life-like rules are stepped with the boundary by the bit-parallel engine. The other
+/// engines only know dead edges, so for them the grid is surrounded by the cells its
+/// boundary puts past the edges, stepped, and cut back out.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to be updated.
+/// * `rule` - The birth and survival rule to apply.
+/// * `boundary` - What lies past the edges of the grid.
+///
+/// # Returns
+///
+/// The updated grid.
+pub fn update_grid_with_boundary(grid: &[Vec<bool>], rule: &Rule, boundary: Boundary) -> Grid {
+    match rule {
+        _ if boundary == Boundary::Dead => update_grid(grid, rule),
+        Rule::LifeLike { .. } => BitGrid::from_grid(grid).with_boundary(boundary).step(rule).to_grid(),
+        _ => {
+            // The margin above and below is kept even, so the rows of a hex board keep
+            // their offsets.
+            let (radius, _) = rule.neighborhood();
+            let (columns, rows) = (radius, radius + radius % 2);
+            let width = grid.first().map_or(0, |row| row.len());
+            update_grid(&boundary.pad(grid, columns, rows), rule)
+                .into_iter()
+                .skip(rows)
+                .take(grid.len())
+                .map(|row| row[columns..columns + width].to_vec())
+                .collect()
+        }
+    }
+}
+
+/// Updates the grid by applying the Game of Life rules one cell at a time.
+///
+/// This is the straightforward reference implementation that the optimized engine is
+/// tested against.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to be updated.
+/// * `rule` - The birth and survival rule to apply.
+///
+/// # Returns
+///
+/// The updated grid.
+pub fn update_grid_naive(grid: &[Vec<bool>], rule: &Rule) -> Grid {
+    let rows = grid.len();
+    let cols = grid.first().map(|row| row.len()).unwrap_or(0);
+    let (radius, neighborhood) = rule.neighborhood();
+
+    // Create a new grid with the same dimensions as the input grid.
+    let mut new_grid = vec![vec![false; cols]; rows];
+
+    // Iterate over each cell in the grid.
+    for i in 0..rows {
+        for j in 0..cols {
+            // Apply the rule to the arrangement of the neighbors if it looks at that,
+            // and otherwise to the number of live neighbors.
+            new_grid[i][j] = if let Rule::Isotropic { .. } = rule {
+                rule.next_state_of(grid[i][j], hensel::arrangement(grid, j, i))
+            } else {
+                rule.next_state(grid[i][j], live_neighbors_within(grid, j, i, radius, neighborhood))
+            };
+        }
+    }
+
+    // Return the updated grid.
+    new_grid
+}
@@ -0,0 +1,215 @@
+use alloc::{boxed::Box, format, vec, vec::Vec};
+use core::{error::Error, fmt, ops::RangeInclusive};
+
+use crate::{rule::Neighborhood, Grid};
+
+/// The largest neighbourhood radius accepted, the same limit as Golly's.
+pub const MAX_RADIUS: usize = 500;
+
+/// A Larger than Life rule, such as Bosco's Rule `R5,C0,M1,S34..58,B34..45,NM`.
+///
+/// Cells count their live neighbours out to a radius beyond 1, and birth and survival
+/// each happen over a range of counts instead of a list of them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LargerThanLife {
+    /// How far the neighbourhood reaches from the cell, in cells.
+    pub radius: usize,
+    /// Whether the cell itself counts as one of its neighbours (`M1`).
+    pub middle: bool,
+    /// The lowest and highest counts at which a live cell stays alive.
+    pub survival: (usize, usize),
+    /// The lowest and highest counts at which a dead cell becomes alive.
+    pub birth: (usize, usize),
+    /// The shape of the neighbourhood: a square (`NM`), a diamond (`NN`), or a hexagon (`NH`).
+    pub neighborhood: Neighborhood,
+}
+
+impl LargerThanLife {
+    /// Parses a rule in Larger than Life notation, like `R5,C0,M1,S34..58,B34..45,NM`.
+    ///
+    /// `R` gives the radius, `S` and `B` the survival and birth ranges (a single count
+    /// is a range of one), `M1` counts the cell itself, and `NM`, `NN`, or `NH` picks a
+    /// square, diamond, or hexagonal neighbourhood. `C` must be 0 or 2, since only
+    /// two-state rules are supported. All but `R`, `S`, and `B` can be left out,
+    /// defaulting to `C0,M0,NM`.
+    ///
+    /// # Arguments
+    ///
+    /// * `rulestring` - The rulestring to parse.
+    ///
+    /// # Returns
+    ///
+    /// The parsed rule.
+    pub fn parse(rulestring: &str) -> Result<LargerThanLife, Box<dyn Error>> {
+        let invalid = || format!("Invalid rule '{}': expected Larger than Life notation like R5,C0,M1,S34..58,B34..45,NM", rulestring);
+
+        let mut radius = None;
+        let mut middle = false;
+        let mut survival = None;
+        let mut birth = None;
+        let mut neighborhood = Neighborhood::Moore;
+
+        for field in rulestring.trim().split(',') {
+            let field = field.trim().to_ascii_uppercase();
+            let (key, value) = field.split_at(field.chars().next().map_or(0, char::len_utf8));
+            match key {
+                "R" => radius = Some(value.parse::<usize>().map_err(|_| invalid())?),
+                "C" => {
+                    if !matches!(value, "0" | "1" | "2") {
+                        return Err(format!("Invalid rule '{}': only two-state rules (C0 or C2) are supported", rulestring).into());
+                    }
+                }
+                "M" => {
+                    middle = match value {
+                        "0" => false,
+                        "1" => true,
+                        _ => return Err(invalid().into()),
+                    }
+                }
+                "S" => survival = Some(parse_range(value).ok_or_else(invalid)?),
+                "B" => birth = Some(parse_range(value).ok_or_else(invalid)?),
+                "N" => {
+                    neighborhood = match value {
+                        "M" => Neighborhood::Moore,
+                        "N" => Neighborhood::VonNeumann,
+                        "H" => Neighborhood::Hex,
+                        _ => return Err(invalid().into()),
+                    }
+                }
+                _ => return Err(invalid().into()),
+            }
+        }
+
+        let radius = radius.ok_or_else(invalid)?;
+        if !(1..=MAX_RADIUS).contains(&radius) {
+            return Err(format!("Invalid rule '{}': the radius must be between 1 and {}", rulestring, MAX_RADIUS).into());
+        }
+        Ok(LargerThanLife {
+            radius,
+            middle,
+            survival: survival.ok_or_else(invalid)?,
+            birth: birth.ok_or_else(invalid)?,
+            neighborhood,
+        })
+    }
+
+    /// Computes the next state of a cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `alive` - Whether the cell is currently alive.
+    /// * `live_neighbors` - The number of live cells in the neighbourhood, not counting the cell itself.
+    ///
+    /// # Returns
+    ///
+    /// Whether the cell is alive in the next generation.
+    pub fn next_state(&self, alive: bool, live_neighbors: usize) -> bool {
+        let count = live_neighbors + usize::from(alive && self.middle);
+        let (low, high) = if alive { self.survival } else { self.birth };
+        (low..=high).contains(&count)
+    }
+
+    /// Computes the next generation.
+    ///
+    /// Square neighbourhoods are counted from a summed-area table, so each cell costs the
+    /// same four lookups whatever the radius. Diamonds and hexagons are summed from
+    /// per-row prefix sums instead, one lookup pair per row of the neighbourhood.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The current generation.
+    ///
+    /// # Returns
+    ///
+    /// The next generation.
+    pub fn step(&self, grid: &[Vec<bool>]) -> Grid {
+        let height = grid.len();
+        let width = grid.first().map_or(0, |row| row.len());
+        let r = self.radius;
+
+        // `table[y][x]` sums the cells above and to the left of (x, y), exclusive.
+        let mut table = vec![vec![0u32; width + 1]; height + 1];
+        // `rows[y][x]` sums the cells of row y left of x, exclusive.
+        let mut rows = vec![vec![0u32; width + 1]; height];
+        for y in 0..height {
+            for x in 0..width {
+                let cell = u32::from(grid[y][x]);
+                rows[y][x + 1] = rows[y][x] + cell;
+                table[y + 1][x + 1] = table[y][x + 1] + rows[y][x + 1];
+            }
+        }
+
+        // The live cells of a row between two columns, clipped to the board.
+        let span = |y: usize, columns: RangeInclusive<isize>| -> u32 {
+            let left = (*columns.start()).max(0) as usize;
+            let right = (*columns.end()).min(width as isize - 1) + 1;
+            if right <= left as isize {
+                return 0;
+            }
+            rows[y][right as usize] - rows[y][left]
+        };
+
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let alive = grid[y][x];
+                        let count = match self.neighborhood {
+                            Neighborhood::Moore => {
+                                let (top, bottom) = (y.saturating_sub(r), (y + r).min(height - 1) + 1);
+                                let (left, right) = (x.saturating_sub(r), (x + r).min(width - 1) + 1);
+                                table[bottom][right] + table[top][left] - table[top][right] - table[bottom][left]
+                            }
+                            Neighborhood::VonNeumann => (y.saturating_sub(r)..=(y + r).min(height - 1))
+                                .map(|row| {
+                                    let reach = (r - row.abs_diff(y)) as isize;
+                                    span(row, x as isize - reach..=x as isize + reach)
+                                })
+                                .sum(),
+                            Neighborhood::Hex => (y.saturating_sub(r)..=(y + r).min(height - 1))
+                                .map(|row| {
+                                    // In axial coordinates a row `dy` away spans `r - |dy|`
+                                    // cells on one side and `r` on the other, and the shift
+                                    // of the odd rows moves the span by half the rows between.
+                                    let (r, dy) = (r as isize, row as isize - y as isize);
+                                    let shift = (row / 2) as isize - (y / 2) as isize;
+                                    let (left, right) = ((-r).max(-r - dy) + shift, r.min(r - dy) + shift);
+                                    span(row, x as isize + left..=x as isize + right)
+                                })
+                                .sum(),
+                        };
+                        // The sums include the cell itself.
+                        self.next_state(alive, count as usize - usize::from(alive))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Parses a range of counts like `34..58`, or a single count like `3`.
+fn parse_range(text: &str) -> Option<(usize, usize)> {
+    let (low, high) = text.split_once("..").unwrap_or((text, text));
+    let (low, high) = (low.parse().ok()?, high.parse().ok()?);
+    (low <= high).then_some((low, high))
+}
+
+impl fmt::Display for LargerThanLife {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "R{},C0,M{},S{}..{},B{}..{},N{}",
+            self.radius,
+            u8::from(self.middle),
+            self.survival.0,
+            self.survival.1,
+            self.birth.0,
+            self.birth.1,
+            match self.neighborhood {
+                Neighborhood::Moore => "M",
+                Neighborhood::VonNeumann => "N",
+                Neighborhood::Hex => "H",
+            }
+        )
+    }
+}
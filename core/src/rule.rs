@@ -0,0 +1,280 @@
+use alloc::{boxed::Box, format, string::{String, ToString}};
+use core::{error::Error, fmt};
+
+use crate::{
+    hensel::{self, Arrangements},
+    ltl::LargerThanLife,
+};
+
+/// The shape of the neighbourhood a cell counts its live neighbours in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The square of cells around the cell, including the diagonals.
+    #[default]
+    Moore,
+    /// The diamond of cells within a Manhattan distance of the cell.
+    VonNeumann,
+    /// The hexagon of cells around the cell, on a board whose odd rows are shifted half
+    /// a cell to the right, like a honeycomb. At radius 1 these are the cells to either
+    /// side and the two cells touching it in each of the rows above and below.
+    Hex,
+}
+
+impl Neighborhood {
+    /// Parses a neighbourhood name: `moore`, `vonneumann`, or `hex`.
+    pub fn parse(name: &str) -> Option<Neighborhood> {
+        match name {
+            "moore" => Some(Neighborhood::Moore),
+            "vonneumann" | "von-neumann" => Some(Neighborhood::VonNeumann),
+            "hex" | "hexagonal" => Some(Neighborhood::Hex),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of neighbours of a cell at radius 1.
+    pub fn size(&self) -> usize {
+        match self {
+            Neighborhood::Moore => 8,
+            Neighborhood::VonNeumann => 4,
+            Neighborhood::Hex => 6,
+        }
+    }
+
+    /// Returns whether a cell at an offset from another is within a radius of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `dx` - The offset in columns.
+    /// * `dy` - The offset in rows.
+    /// * `radius` - The radius of the neighbourhood.
+    /// * `row` - The row of the cell the offset is from; on a hex board, odd and even rows
+    ///   touch different columns of the rows above and below.
+    ///
+    /// # Returns
+    ///
+    /// Whether the offset falls inside the neighbourhood, which includes the cell itself.
+    pub fn contains(&self, dx: isize, dy: isize, radius: usize, row: usize) -> bool {
+        let r = radius as isize;
+        match self {
+            Neighborhood::Moore => dx.abs() <= r && dy.abs() <= r,
+            Neighborhood::VonNeumann => dx.abs() + dy.abs() <= r,
+            Neighborhood::Hex => {
+                // Undo the shift of the rows to get axial coordinates, where the distance
+                // between hexes is the largest of the three cube coordinates.
+                let row = row as isize;
+                let dq = dx - ((row + dy).div_euclid(2) - row.div_euclid(2));
+                dq.abs().max(dy.abs()).max((dq + dy).abs()) <= r
+            }
+        }
+    }
+}
+
+impl fmt::Display for Neighborhood {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Neighborhood::Moore => "moore",
+            Neighborhood::VonNeumann => "vonneumann",
+            Neighborhood::Hex => "hex",
+        })
+    }
+}
+
+/// A rule of a two-state automaton: the neighbor counts that give birth to dead cells and
+/// keep live cells alive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// A life-like rule, counting the neighbors next to a cell.
+    LifeLike {
+        /// `birth[n]` is true if a dead cell with `n` live neighbors becomes alive.
+        birth: [bool; 9],
+        /// `survival[n]` is true if a live cell with `n` live neighbors stays alive.
+        survival: [bool; 9],
+        /// The neighbors counted: eight for Moore, four for von Neumann, or six for hex.
+        neighborhood: Neighborhood,
+    },
+    /// A Larger than Life rule, counting the neighbors out to a radius beyond 1.
+    LargerThanLife(LargerThanLife),
+    /// An isotropic non-totalistic rule, which looks at how the eight neighbors of a cell
+    /// are arranged (see [`hensel`]).
+    Isotropic {
+        /// The arrangements of live neighbors that bring a dead cell to life.
+        birth: Arrangements,
+        /// The arrangements of live neighbors that keep a live cell alive.
+        survival: Arrangements,
+    },
+}
+
+impl Default for Rule {
+    /// Conway's Game of Life, B3/S23.
+    fn default() -> Self {
+        Rule::parse("B3/S23").expect("B3/S23 is a valid rule")
+    }
+}
+
+impl Rule {
+    /// Parses a rulestring in B/S notation (`B36/S23`), the older S/B notation (`23/36`),
+    /// Hensel notation for isotropic rules (`B2-a/S12`), or Larger than Life notation
+    /// (`R5,C0,M1,S34..58,B34..45,NM`).
+    ///
+    /// A B/S rulestring ending in `V` counts the von Neumann neighborhood (`B2/S013V`),
+    /// and one ending in `H` the hexagonal one (`B2/S34H`).
+    ///
+    /// # Arguments
+    ///
+    /// * `rulestring` - The rulestring to parse.
+    ///
+    /// # Returns
+    ///
+    /// The parsed rule.
+    pub fn parse(rulestring: &str) -> Result<Rule, Box<dyn Error>> {
+        // Larger than Life rules start with their radius.
+        let mut chars = rulestring.trim().chars();
+        if matches!((chars.next(), chars.next()), (Some('R' | 'r'), Some(c)) if c.is_ascii_digit()) {
+            return Ok(Rule::LargerThanLife(LargerThanLife::parse(rulestring)?));
+        }
+
+        let invalid = || format!("Invalid rule '{}': expected B/S notation like B3/S23", rulestring);
+
+        // A trailing letter picks the neighborhood; neither `V` nor `H` is a Hensel letter.
+        let trimmed = rulestring.trim();
+        let (trimmed, neighborhood) = match trimmed.chars().last() {
+            Some('V' | 'v') => (&trimmed[..trimmed.len() - 1], Neighborhood::VonNeumann),
+            Some('H' | 'h') => (&trimmed[..trimmed.len() - 1], Neighborhood::Hex),
+            _ => (trimmed, Neighborhood::Moore),
+        };
+
+        let (first, second) = trimmed.split_once('/').ok_or_else(invalid)?;
+
+        // Work out which half is which: `B3/S23` and `S23/B3` are labelled, `23/3` is survival/birth.
+        let (birth, survival) = match (first.chars().next(), second.chars().next()) {
+            (Some('B' | 'b'), Some('S' | 's')) => (&first[1..], &second[1..]),
+            (Some('S' | 's'), Some('B' | 'b')) => (&second[1..], &first[1..]),
+            _ => (second, first),
+        };
+
+        // Letters after the counts make the rule isotropic.
+        if birth.chars().chain(survival.chars()).any(|c| c.is_ascii_alphabetic() || c == '-') {
+            let rule = Rule::Isotropic {
+                birth: hensel::parse_arrangements(birth).ok_or_else(invalid)?,
+                survival: hensel::parse_arrangements(survival).ok_or_else(invalid)?,
+            };
+            return rule.with_neighborhood(neighborhood);
+        }
+
+        Rule::LifeLike {
+            birth: parse_counts(birth).ok_or_else(invalid)?,
+            survival: parse_counts(survival).ok_or_else(invalid)?,
+            neighborhood: Neighborhood::Moore,
+        }
+        .with_neighborhood(neighborhood)
+    }
+
+    /// Returns the rule counting a different neighborhood.
+    ///
+    /// # Arguments
+    ///
+    /// * `neighborhood` - The neighborhood to count.
+    ///
+    /// # Returns
+    ///
+    /// The rule, or an error if it names more neighbors than the neighborhood has, or is
+    /// an isotropic rule, whose letters only describe the Moore neighborhood.
+    pub fn with_neighborhood(self, neighborhood: Neighborhood) -> Result<Rule, Box<dyn Error>> {
+        match self {
+            Rule::LifeLike { birth, survival, .. } => {
+                let size = neighborhood.size();
+                if birth[size + 1..].iter().chain(&survival[size + 1..]).any(|&count| count) {
+                    return Err(format!("Rule {} counts more than the {} neighbors of the {} neighborhood", self, size, neighborhood).into());
+                }
+                Ok(Rule::LifeLike { birth, survival, neighborhood })
+            }
+            Rule::LargerThanLife(rule) => Ok(Rule::LargerThanLife(LargerThanLife { neighborhood, ..rule })),
+            Rule::Isotropic { .. } if neighborhood == Neighborhood::Moore => Ok(self),
+            Rule::Isotropic { .. } => Err(format!("Isotropic rule {} only works with the Moore neighborhood", self).into()),
+        }
+    }
+
+    /// Computes the next state of a cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `alive` - Whether the cell is currently alive.
+    /// * `live_neighbors` - The number of live neighbors of the cell.
+    ///
+    /// # Returns
+    ///
+    /// Whether the cell is alive in the next generation. Isotropic rules depend on the
+    /// arrangement of the neighbors, so under them a count only counts when every
+    /// arrangement of it does; [`Rule::next_state_of`] applies them exactly.
+    pub fn next_state(&self, alive: bool, live_neighbors: usize) -> bool {
+        match self {
+            Rule::LifeLike { birth, survival, .. } => {
+                let counts = if alive { survival } else { birth };
+                counts.get(live_neighbors).copied().unwrap_or(false)
+            }
+            Rule::LargerThanLife(rule) => rule.next_state(alive, live_neighbors),
+            Rule::Isotropic { birth, survival } => {
+                let table = if alive { survival } else { birth };
+                (0..=255u8).filter(|bits| bits.count_ones() as usize == live_neighbors).all(|bits| table.contains(bits))
+            }
+        }
+    }
+
+    /// Computes the next state of a cell from the arrangement of its eight neighbors.
+    ///
+    /// # Arguments
+    ///
+    /// * `alive` - Whether the cell is currently alive.
+    /// * `arrangement` - The live neighbors of the cell, as returned by [`hensel::arrangement`].
+    ///
+    /// # Returns
+    ///
+    /// Whether the cell is alive in the next generation.
+    pub fn next_state_of(&self, alive: bool, arrangement: u8) -> bool {
+        match self {
+            Rule::Isotropic { birth, survival } => (if alive { survival } else { birth }).contains(arrangement),
+            _ => self.next_state(alive, arrangement.count_ones() as usize),
+        }
+    }
+
+    /// Returns how far the neighbourhood of the rule reaches from a cell, and its shape.
+    pub fn neighborhood(&self) -> (usize, Neighborhood) {
+        match self {
+            Rule::LifeLike { neighborhood, .. } => (1, *neighborhood),
+            Rule::Isotropic { .. } => (1, Neighborhood::Moore),
+            Rule::LargerThanLife(rule) => (rule.radius, rule.neighborhood),
+        }
+    }
+}
+
+/// Parses a list of neighbor counts like `236` into a lookup table.
+fn parse_counts(digits: &str) -> Option<[bool; 9]> {
+    let mut counts = [false; 9];
+    for ch in digits.chars() {
+        let n = ch.to_digit(10).filter(|&n| n <= 8)?;
+        counts[n as usize] = true;
+    }
+    Some(counts)
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |counts: &[bool; 9]| -> String {
+            (0..9).filter(|&n| counts[n]).map(|n| n.to_string()).collect()
+        };
+        match self {
+            Rule::LifeLike { birth, survival, neighborhood } => {
+                let suffix = match neighborhood {
+                    Neighborhood::Moore => "",
+                    Neighborhood::VonNeumann => "V",
+                    Neighborhood::Hex => "H",
+                };
+                write!(f, "B{}/S{}{}", digits(birth), digits(survival), suffix)
+            }
+            Rule::LargerThanLife(rule) => rule.fmt(f),
+            Rule::Isotropic { birth, survival } => {
+                write!(f, "B{}/S{}", hensel::format_arrangements(birth), hensel::format_arrangements(survival))
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Serde support for the rules and boundaries, with the `serde` feature.
+//!
+//! Each is stored as the text it is written in on the command line, a rulestring such as
+//! `B36/S23` or a boundary name such as `wrap`, so files stay readable and match what
+//! `--rule` and `--boundary` accept in any serde format. The grid needs nothing of its
+//! own, as its rows of cells already serialize as nested sequences.
+
+use alloc::{format, string::String};
+use core::fmt::Display;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    boundary::Boundary,
+    rule::{Neighborhood, Rule},
+};
+
+/// Reads a value stored as text.
+///
+/// # Arguments
+///
+/// * `deserializer` - Where the text is read from.
+/// * `parse` - Parses the text, or describes what is wrong with it.
+fn from_text<'de, D, T, E>(deserializer: D, parse: impl FnOnce(&str) -> Result<T, E>) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    E: Display,
+{
+    let text = String::deserialize(deserializer)?;
+    parse(&text).map_err(de::Error::custom)
+}
+
+impl Serialize for Rule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Rule {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rule, D::Error> {
+        from_text(deserializer, Rule::parse)
+    }
+}
+
+impl Serialize for Boundary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Boundary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Boundary, D::Error> {
+        from_text(deserializer, |name| {
+            Boundary::parse(name)
+                .or_else(|| Boundary::parse_topology(name))
+                .ok_or_else(|| format!("unknown boundary '{}' (expected dead, wrap, reflect, alive, klein, cross, plane, or torus)", name))
+        })
+    }
+}
+
+impl Serialize for Neighborhood {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Neighborhood {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Neighborhood, D::Error> {
+        from_text(deserializer, |name| {
+            Neighborhood::parse(name).ok_or_else(|| format!("unknown neighbourhood '{}' (expected moore, vonneumann, or hex)", name))
+        })
+    }
+}
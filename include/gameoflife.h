@@ -0,0 +1,71 @@
+/*
+ * The C ABI of the Game of Life engine, built with the `ffi` feature of the `gameoflife`
+ * crate. The declarations match `src/ffi.rs`.
+ *
+ *     cargo rustc --lib --release --no-default-features --features ffi --crate-type cdylib
+ *     cargo rustc --lib --release --no-default-features --features ffi --crate-type staticlib
+ */
+
+#ifndef GAMEOFLIFE_H
+#define GAMEOFLIFE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A board and the rule it follows. */
+typedef struct GolWorld GolWorld;
+
+/*
+ * Creates an empty board of `width` by `height` cells following `rule`, in any notation
+ * `--rule` accepts, or Conway's Life if `rule` is NULL. Returns NULL if the rule is not valid.
+ */
+GolWorld *gol_new(uint32_t width, uint32_t height, const char *rule);
+
+/* Advances a board by a number of generations. */
+void gol_step(GolWorld *world, uint32_t generations);
+
+/*
+ * Copies the cells out, one byte per cell, row by row: 1 for alive and 0 for dead.
+ * Returns the number of cells of the board; nothing is written when `len` is smaller, so
+ * gol_get_cells(world, NULL, 0) asks how large `out` must be.
+ */
+size_t gol_get_cells(const GolWorld *world, uint8_t *out, size_t len);
+
+/* Returns whether a cell is alive. Cells off the board are dead. */
+bool gol_get_cell(const GolWorld *world, uint32_t x, uint32_t y);
+
+/* Brings a cell to life or kills it. Returns false for a cell off the board. */
+bool gol_set_cell(GolWorld *world, uint32_t x, uint32_t y, bool alive);
+
+/*
+ * Switches a board to `rule`, in any notation `--rule` accepts. Returns false, keeping the
+ * old rule, if the rule is not valid.
+ */
+bool gol_set_rule(GolWorld *world, const char *rule);
+
+/*
+ * Copies the rulestring of a board out, NUL-terminated. Returns its size with the NUL;
+ * nothing is written when `len` is smaller, so gol_get_rule(world, NULL, 0) asks how large
+ * `out` must be.
+ */
+size_t gol_get_rule(const GolWorld *world, char *out, size_t len);
+
+/* Returns the width of a board in cells. */
+uint32_t gol_width(const GolWorld *world);
+
+/* Returns the height of a board in cells. */
+uint32_t gol_height(const GolWorld *world);
+
+/* Releases a board. NULL is ignored. */
+void gol_free(GolWorld *world);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,141 @@
+"""Python bindings for the Game of Life engine, over the C ABI of the `ffi` feature.
+
+Build the shared library first:
+
+    cargo rustc --lib --release --no-default-features --features ffi --crate-type cdylib
+
+The library is looked up through the GAMEOFLIFE_LIB environment variable, then in the
+`target/release` directory of the repository this file is in.
+
+    >>> import numpy
+    >>> from gameoflife import Grid
+    >>> grid = Grid(64, 64, "B36/S23")
+    >>> grid[10, 10] = True
+    >>> grid.step(100)
+    >>> grid.rule = "B3/S23"
+    >>> cells = numpy.asarray(grid.cells())  # a 64 by 64 array of uint8, 1 for alive
+"""
+
+import ctypes
+import os
+import sys
+
+__all__ = ["Grid"]
+
+
+def _library_path():
+    if "GAMEOFLIFE_LIB" in os.environ:
+        return os.environ["GAMEOFLIFE_LIB"]
+    if sys.platform == "win32":
+        name = "gameoflife.dll"
+    elif sys.platform == "darwin":
+        name = "libgameoflife.dylib"
+    else:
+        name = "libgameoflife.so"
+    root = os.path.dirname(os.path.dirname(os.path.abspath(__file__)))
+    return os.path.join(root, "target", "release", name)
+
+
+_lib = ctypes.CDLL(_library_path())
+
+# The declarations match `include/gameoflife.h`.
+_lib.gol_new.argtypes = [ctypes.c_uint32, ctypes.c_uint32, ctypes.c_char_p]
+_lib.gol_new.restype = ctypes.c_void_p
+_lib.gol_step.argtypes = [ctypes.c_void_p, ctypes.c_uint32]
+_lib.gol_step.restype = None
+_lib.gol_get_cells.argtypes = [ctypes.c_void_p, ctypes.c_void_p, ctypes.c_size_t]
+_lib.gol_get_cells.restype = ctypes.c_size_t
+_lib.gol_get_cell.argtypes = [ctypes.c_void_p, ctypes.c_uint32, ctypes.c_uint32]
+_lib.gol_get_cell.restype = ctypes.c_bool
+_lib.gol_set_cell.argtypes = [ctypes.c_void_p, ctypes.c_uint32, ctypes.c_uint32, ctypes.c_bool]
+_lib.gol_set_cell.restype = ctypes.c_bool
+_lib.gol_set_rule.argtypes = [ctypes.c_void_p, ctypes.c_char_p]
+_lib.gol_set_rule.restype = ctypes.c_bool
+_lib.gol_get_rule.argtypes = [ctypes.c_void_p, ctypes.c_char_p, ctypes.c_size_t]
+_lib.gol_get_rule.restype = ctypes.c_size_t
+_lib.gol_width.argtypes = [ctypes.c_void_p]
+_lib.gol_width.restype = ctypes.c_uint32
+_lib.gol_height.argtypes = [ctypes.c_void_p]
+_lib.gol_height.restype = ctypes.c_uint32
+_lib.gol_free.argtypes = [ctypes.c_void_p]
+_lib.gol_free.restype = None
+
+
+class Grid:
+    """A board and the rule it follows.
+
+    Cells are indexed as `grid[x, y]`, with the column first.
+    """
+
+    def __init__(self, width, height, rule=None):
+        """Creates an empty board.
+
+        `rule` is a rulestring in any notation `--rule` accepts, or None for Conway's Life.
+        Raises ValueError if the rule is not valid.
+        """
+        encoded = None if rule is None else rule.encode()
+        self._world = _lib.gol_new(width, height, encoded)
+        if not self._world:
+            raise ValueError("Invalid rule '{}'".format(rule))
+
+    def __del__(self):
+        world, self._world = getattr(self, "_world", None), None
+        if world:
+            _lib.gol_free(world)
+
+    @property
+    def width(self):
+        """The width of the board in cells."""
+        return _lib.gol_width(self._world)
+
+    @property
+    def height(self):
+        """The height of the board in cells."""
+        return _lib.gol_height(self._world)
+
+    @property
+    def rule(self):
+        """The rule the board follows, as a rulestring."""
+        size = _lib.gol_get_rule(self._world, None, 0)
+        buffer = ctypes.create_string_buffer(size)
+        _lib.gol_get_rule(self._world, buffer, size)
+        return buffer.value.decode()
+
+    @rule.setter
+    def rule(self, rule):
+        """Switches to another rule, in any notation `--rule` accepts.
+
+        Raises ValueError, keeping the old rule, if the rule is not valid.
+        """
+        if not _lib.gol_set_rule(self._world, rule.encode()):
+            raise ValueError("Invalid rule '{}'".format(rule))
+
+    def step(self, generations=1):
+        """Advances the board by a number of generations."""
+        _lib.gol_step(self._world, generations)
+
+    def cells(self):
+        """Copies the cells out as a `height` by `width` buffer of bytes, 1 for alive.
+
+        The buffer supports the buffer protocol, so `numpy.asarray(grid.cells())` wraps it
+        as a uint8 array without copying it again.
+        """
+        size = _lib.gol_get_cells(self._world, None, 0)
+        buffer = (ctypes.c_uint8 * size)()
+        _lib.gol_get_cells(self._world, buffer, size)
+        return memoryview(buffer).cast("B", (self.height, self.width))
+
+    def population(self):
+        """Returns the number of live cells."""
+        return sum(self.cells().tobytes())
+
+    def __getitem__(self, cell):
+        x, y = cell
+        if not (0 <= x < self.width and 0 <= y < self.height):
+            raise IndexError("cell ({}, {}) is off the board".format(x, y))
+        return _lib.gol_get_cell(self._world, x, y)
+
+    def __setitem__(self, cell, alive):
+        x, y = cell
+        if x < 0 or y < 0 or not _lib.gol_set_cell(self._world, x, y, bool(alive)):
+            raise IndexError("cell ({}, {}) is off the board".format(x, y))
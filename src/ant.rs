@@ -0,0 +1,220 @@
+use rand::Rng;
+use std::{error::Error, fmt};
+
+use crate::{automaton::Simulation, raster::Rgb, variant::StateGrid};
+
+/// The longest turn rule accepted, in colors.
+const MAX_COLORS: usize = 32;
+
+/// The color of the ants themselves.
+const ANT_COLOR: Rgb = Rgb(0xff, 0x33, 0x33);
+
+/// The color of the single painted color of the classic `RL` ant.
+const CLASSIC_COLOR: Rgb = Rgb(0xee, 0xee, 0xee);
+
+/// A turn an ant makes on leaving a cell of some color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+    /// Turn 90 degrees left (`L`).
+    Left,
+    /// Turn 90 degrees right (`R`).
+    Right,
+    /// Keep going straight (`N`).
+    Straight,
+    /// Turn around (`U`).
+    UTurn,
+}
+
+/// The turns of an ant for each color of cell, such as `RL` for Langton's original ant.
+///
+/// An ant on a cell of color `i` makes the `i`-th turn, repaints the cell with the next
+/// color (wrapping around to 0), and steps forward.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TurnRule(Vec<Turn>);
+
+impl TurnRule {
+    /// Parses a turn rule made of the letters `L`, `R`, `N`, and `U`.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The turn rule, such as `RL` or `LLRR`.
+    pub fn parse(text: &str) -> Result<TurnRule, Box<dyn Error>> {
+        let turns = text
+            .chars()
+            .map(|c| match c.to_ascii_uppercase() {
+                'L' => Ok(Turn::Left),
+                'R' => Ok(Turn::Right),
+                'N' => Ok(Turn::Straight),
+                'U' => Ok(Turn::UTurn),
+                c => Err(format!("Invalid turn '{}' in ant rule '{}' (expected L, R, N, or U)", c, text)),
+            })
+            .collect::<Result<Vec<Turn>, String>>()?;
+
+        if !(2..=MAX_COLORS).contains(&turns.len()) {
+            return Err(format!("Ant rule '{}' must have between 2 and {} turns", text, MAX_COLORS).into());
+        }
+        Ok(TurnRule(turns))
+    }
+
+    /// Returns the number of colors a cell can have.
+    pub fn colors(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl Default for TurnRule {
+    /// Returns Langton's original `RL` ant.
+    fn default() -> Self {
+        TurnRule(vec![Turn::Right, Turn::Left])
+    }
+}
+
+impl fmt::Display for TurnRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for turn in &self.0 {
+            f.write_str(match turn {
+                Turn::Left => "L",
+                Turn::Right => "R",
+                Turn::Straight => "N",
+                Turn::UTurn => "U",
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// An ant walking the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ant {
+    /// The column of the ant.
+    pub x: usize,
+    /// The row of the ant.
+    pub y: usize,
+    /// The direction the ant faces: 0 up, 1 right, 2 down, and 3 left.
+    pub heading: u8,
+}
+
+/// Langton's ant: ants that walk a wrap-around board, turning and repainting as they go.
+///
+/// Unlike the cellular automata, a generation only changes the cells under the ants:
+/// every ant makes one move per generation, in order.
+pub struct LangtonsAnt {
+    /// The color of every cell, from 0 to the number of colors of the rule.
+    pub colors: StateGrid,
+    /// The ants.
+    pub ants: Vec<Ant>,
+    /// The turn rule.
+    pub rule: TurnRule,
+    /// The colors of the painted cells, then of the ants.
+    palette: Vec<Rgb>,
+}
+
+impl LangtonsAnt {
+    /// Creates a board with no painted cells.
+    ///
+    /// The first ant starts in the middle, facing up; any others start at random
+    /// positions and headings.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board.
+    /// * `height` - The height of the board.
+    /// * `rule` - The turn rule.
+    /// * `ants` - The number of ants.
+    /// * `rng` - The random number generator that places the extra ants.
+    pub fn new(width: usize, height: usize, rule: TurnRule, ants: usize, rng: &mut impl Rng) -> LangtonsAnt {
+        let width = width.max(1);
+        let height = height.max(1);
+        let ants = (0..ants)
+            .map(|i| match i {
+                0 => Ant { x: width / 2, y: height / 2, heading: 0 },
+                _ => Ant {
+                    x: rng.gen_range(0..width),
+                    y: rng.gen_range(0..height),
+                    heading: rng.gen_range(0..4),
+                },
+            })
+            .collect();
+
+        // The classic ant paints in white; longer rules get a color wheel.
+        let painted = rule.colors() - 1;
+        let mut palette: Vec<Rgb> = if painted == 1 {
+            vec![CLASSIC_COLOR]
+        } else {
+            (0..painted).map(|i| hue(i as f64 / painted as f64)).collect()
+        };
+        palette.push(ANT_COLOR);
+
+        LangtonsAnt {
+            colors: vec![vec![0; width]; height],
+            ants,
+            rule,
+            palette,
+        }
+    }
+}
+
+/// Returns a bright color at a position on the color wheel, from 0 to 1.
+fn hue(position: f64) -> Rgb {
+    let h = position * 6.0;
+    let x = 1.0 - (h % 2.0 - 1.0).abs();
+    let (r, g, b) = match h as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    // Soften the colors towards white so they read well on dark terminals.
+    let channel = |c: f64| (0x55 as f64 + c * (0xff - 0x55) as f64) as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
+
+impl Simulation for LangtonsAnt {
+    fn view(&self) -> StateGrid {
+        // The ants are drawn on top, in the state after the painted colors.
+        let mut view = self.colors.clone();
+        for ant in &self.ants {
+            view[ant.y][ant.x] = self.rule.colors() as u8;
+        }
+        view
+    }
+
+    fn palette(&self) -> &[Rgb] {
+        &self.palette
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "{} {} | rule {}",
+            self.ants.len(),
+            if self.ants.len() == 1 { "ant" } else { "ants" },
+            self.rule
+        )
+    }
+
+    fn step(&mut self) {
+        let height = self.colors.len();
+        let width = self.colors[0].len();
+
+        for ant in &mut self.ants {
+            let color = &mut self.colors[ant.y][ant.x];
+            ant.heading = match self.rule.0[*color as usize] {
+                Turn::Left => (ant.heading + 3) % 4,
+                Turn::Right => (ant.heading + 1) % 4,
+                Turn::Straight => ant.heading,
+                Turn::UTurn => (ant.heading + 2) % 4,
+            };
+            *color = ((*color as usize + 1) % self.rule.colors()) as u8;
+
+            // Step forward, wrapping around the edges of the board.
+            match ant.heading {
+                0 => ant.y = (ant.y + height - 1) % height,
+                1 => ant.x = (ant.x + 1) % width,
+                2 => ant.y = (ant.y + 1) % height,
+                _ => ant.x = (ant.x + width - 1) % width,
+            }
+        }
+    }
+}
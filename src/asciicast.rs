@@ -0,0 +1,115 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Records terminal output into an asciinema v2 cast file.
+///
+/// The file starts with a JSON header line describing the terminal, followed by one
+/// `[time, "o", data]` line per chunk of output. Output is collected as it is written
+/// and turned into an event whenever the terminal is flushed, which the display code
+/// does once per drawing call.
+pub struct CastRecorder {
+    /// The output file.
+    path: PathBuf,
+    /// The writer of the cast file.
+    file: BufWriter<File>,
+    /// When the recording started; event times are relative to it.
+    start: Instant,
+    /// Output written since the last event.
+    pending: Vec<u8>,
+}
+
+/// Escapes text for use in a JSON string.
+fn json_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            ch if (ch as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+impl CastRecorder {
+    /// Creates a cast file and writes its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The output file.
+    /// * `cols` - The width of the recorded terminal.
+    /// * `rows` - The height of the recorded terminal.
+    pub fn new(path: &Path, cols: usize, rows: usize) -> Result<CastRecorder, Box<dyn Error>> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut file = BufWriter::new(file);
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let term = std::env::var("TERM").unwrap_or_else(|_| "xterm-256color".to_string());
+        writeln!(
+            file,
+            "{{\"version\": 2, \"width\": {}, \"height\": {}, \"timestamp\": {}, \"env\": {{\"TERM\": \"{}\"}}}}",
+            cols,
+            rows,
+            timestamp,
+            json_escape(&term)
+        )?;
+
+        Ok(CastRecorder {
+            path: path.to_path_buf(),
+            file,
+            start: Instant::now(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Collects output written to the terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The output.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.pending.extend_from_slice(bytes);
+    }
+
+    /// Writes the output collected so far as one event.
+    ///
+    /// A multi-byte character split across writes is kept back until it is complete.
+    pub fn write_event(&mut self) -> io::Result<()> {
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(e) => e.valid_up_to(),
+        };
+        if valid == 0 {
+            return Ok(());
+        }
+
+        let text = String::from_utf8_lossy(&self.pending[..valid]).into_owned();
+        self.pending.drain(..valid);
+        writeln!(
+            self.file,
+            "[{:.6}, \"o\", \"{}\"]",
+            self.start.elapsed().as_secs_f64(),
+            json_escape(&text)
+        )
+    }
+
+    /// Writes any remaining output and closes the file.
+    ///
+    /// # Returns
+    ///
+    /// The path of the written file.
+    pub fn finish(mut self) -> Result<PathBuf, Box<dyn Error>> {
+        self.write_event()?;
+        self.file.flush()?;
+        Ok(self.path)
+    }
+}
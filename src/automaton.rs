@@ -0,0 +1,255 @@
+use std::{error::Error, fmt, fs, path::Path};
+
+use crate::{brians_brain_step, margolus::MargolusRule, pattern, raster::Rgb, variant::StateGrid, wireworld_step};
+
+/// The colors of the Brian's Brain states: firing, then refractory.
+const BRIANS_BRAIN_PALETTE: [Rgb; 2] = [Rgb(0xdd, 0xee, 0xff), Rgb(0x33, 0x55, 0xcc)];
+
+/// The colors of the Wireworld states: electron head, electron tail, then conductor.
+const WIREWORLD_PALETTE: [Rgb; 3] = [Rgb(0x44, 0x88, 0xff), Rgb(0xff, 0x44, 0x44), Rgb(0xff, 0xbb, 0x22)];
+
+/// A simulation other than Life that runs in the terminal, one generation at a time.
+///
+/// Implementations decide how a generation is computed: per cell, like Brian's Brain, or
+/// by agents walking the board, like Langton's ant. The terminal loop only draws the
+/// states returned by [`Simulation::view`] and steps the simulation.
+pub trait Simulation {
+    /// Returns the state of every cell to draw, with 0 drawn as empty.
+    fn view(&self) -> StateGrid;
+
+    /// Returns the color of each state other than 0, in order.
+    fn palette(&self) -> &[Rgb];
+
+    /// Returns a short summary of the board for the status bar, such as the number of
+    /// cells in each state.
+    fn status(&self) -> String;
+
+    /// Advances the simulation by one generation.
+    fn step(&mut self);
+}
+
+/// A cellular automaton other than Life, chosen with `--automaton`.
+///
+/// Its cells have more than two states, so it runs on a [`StateGrid`] instead of the
+/// Life board; state 0 is the empty or resting state.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Automaton {
+    /// Brian's Brain, with three states: 0 ready, 1 firing, and 2 refractory.
+    BriansBrain,
+    /// Wireworld, with four states: 0 empty, 1 electron head, 2 electron tail, and 3 conductor.
+    Wireworld,
+}
+
+impl Automaton {
+    /// Parses an automaton name: `brians-brain` or `wireworld`.
+    pub fn parse(name: &str) -> Option<Automaton> {
+        match name {
+            "brians-brain" => Some(Automaton::BriansBrain),
+            "wireworld" => Some(Automaton::Wireworld),
+            _ => None,
+        }
+    }
+
+    /// Returns the names of the states other than 0, in order.
+    pub fn state_names(&self) -> &'static [&'static str] {
+        match self {
+            Automaton::BriansBrain => &["firing", "refractory"],
+            Automaton::Wireworld => &["head", "tail", "conductor"],
+        }
+    }
+
+    /// Returns the colors of the states other than 0, in order.
+    pub fn palette(&self) -> &'static [Rgb] {
+        match self {
+            Automaton::BriansBrain => &BRIANS_BRAIN_PALETTE,
+            Automaton::Wireworld => &WIREWORLD_PALETTE,
+        }
+    }
+
+    /// Returns the characters of the states other than 0 in text layout files, in order.
+    pub fn state_glyphs(&self) -> &'static [char] {
+        match self {
+            Automaton::BriansBrain => &['O', 'o'],
+            Automaton::Wireworld => &['@', '~', '#'],
+        }
+    }
+
+    /// Returns whether the automaton needs a layout file rather than a random board.
+    ///
+    /// A random Wireworld board is a tangle of wire with nothing to compute, so it
+    /// only runs circuits.
+    pub fn needs_layout(&self) -> bool {
+        *self == Automaton::Wireworld
+    }
+
+    /// Turns a Life board into a starting board for the automaton.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board, with random cells and placed patterns.
+    ///
+    /// # Returns
+    ///
+    /// The starting states: live cells become state 1 (firing, for Brian's Brain).
+    pub fn seed(&self, grid: &[Vec<bool>]) -> StateGrid {
+        grid.iter().map(|row| row.iter().map(|&cell| u8::from(cell)).collect()).collect()
+    }
+
+    /// Reads a layout file, such as a Wireworld circuit.
+    ///
+    /// Files with an RLE header (or an `.rle` extension) are read as Golly's multi-state
+    /// RLE; other files are drawn as text, with `.` or a space for state 0 and the
+    /// characters of [`Automaton::state_glyphs`] for the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The layout file.
+    ///
+    /// # Returns
+    ///
+    /// The state of each cell of the layout.
+    pub fn load_layout(&self, path: &Path) -> Result<StateGrid, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let is_rle = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rle")) || pattern::looks_like_rle(&text);
+
+        let cells = if is_rle {
+            pattern::parse_multistate_rle(&text)
+        } else {
+            pattern::parse_multistate_plaintext(&text, self.state_glyphs())
+        }
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        // Multi-state RLE can name states the automaton does not have.
+        let states = self.state_names().len() as u8;
+        if let Some(&state) = cells.iter().flatten().find(|&&state| state > states) {
+            return Err(format!("{}: state {} does not exist in {} (states 0 to {})", path.display(), state, self, states).into());
+        }
+        Ok(cells)
+    }
+
+    /// Computes the next generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The states of the cells.
+    ///
+    /// # Returns
+    ///
+    /// The next generation.
+    pub fn step(&self, cells: &[Vec<u8>]) -> StateGrid {
+        match self {
+            Automaton::BriansBrain => brians_brain_step(cells),
+            Automaton::Wireworld => wireworld_step(cells),
+        }
+    }
+}
+
+/// Copies a layout into the middle of a board, clipping whatever does not fit.
+///
+/// # Arguments
+///
+/// * `cells` - The board.
+/// * `layout` - The layout to copy.
+///
+/// # Returns
+///
+/// The number of cells of the layout, other than state 0, that did not fit.
+pub fn place_layout(cells: &mut StateGrid, layout: &[Vec<u8>]) -> usize {
+    let height = cells.len() as isize;
+    let width = cells.first().map_or(0, |row| row.len()) as isize;
+    let top = (height - layout.len() as isize) / 2;
+    let left = (width - layout.first().map_or(0, |row| row.len()) as isize) / 2;
+
+    let mut clipped = 0;
+    for (dy, row) in layout.iter().enumerate() {
+        for (dx, &state) in row.iter().enumerate() {
+            let (x, y) = (left + dx as isize, top + dy as isize);
+            if (0..width).contains(&x) && (0..height).contains(&y) {
+                cells[y as usize][x as usize] = state;
+            } else if state != 0 {
+                clipped += 1;
+            }
+        }
+    }
+    clipped
+}
+
+/// A cellular automaton running on a board, where every cell is updated at once.
+pub struct CellularSimulation {
+    /// The automaton.
+    pub automaton: Automaton,
+    /// The state of every cell.
+    pub cells: StateGrid,
+}
+
+impl Simulation for CellularSimulation {
+    fn view(&self) -> StateGrid {
+        self.cells.clone()
+    }
+
+    fn palette(&self) -> &[Rgb] {
+        self.automaton.palette()
+    }
+
+    fn status(&self) -> String {
+        // Count the cells in each state other than the resting one.
+        self.automaton
+            .state_names()
+            .iter()
+            .enumerate()
+            .map(|(i, name)| format!("{} {}", name, self.cells.iter().flatten().filter(|&&state| state as usize == i + 1).count()))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    fn step(&mut self) {
+        self.cells = self.automaton.step(&self.cells);
+    }
+}
+
+/// What `--automaton` runs instead of Life.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimulationKind {
+    /// A cellular automaton with more than two states.
+    Cellular(Automaton),
+    /// Langton's ant and its generalizations.
+    LangtonsAnt,
+    /// An elementary one-dimensional automaton, scrolling down the board.
+    Elementary,
+    /// A block automaton on the Margolus neighborhood, such as Critters.
+    Margolus(MargolusRule),
+}
+
+impl SimulationKind {
+    /// Parses an `--automaton` name: `brians-brain`, `wireworld`, `langtons-ant`,
+    /// `elementary`, `critters`, or `bbm`.
+    pub fn parse(name: &str) -> Option<SimulationKind> {
+        match name {
+            "langtons-ant" => Some(SimulationKind::LangtonsAnt),
+            "elementary" => Some(SimulationKind::Elementary),
+            name => Automaton::parse(name)
+                .map(SimulationKind::Cellular)
+                .or_else(|| MargolusRule::parse(name).map(SimulationKind::Margolus)),
+        }
+    }
+}
+
+impl fmt::Display for SimulationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SimulationKind::Cellular(automaton) => automaton.fmt(f),
+            SimulationKind::LangtonsAnt => f.write_str("langtons-ant"),
+            SimulationKind::Elementary => f.write_str("elementary"),
+            SimulationKind::Margolus(rule) => rule.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for Automaton {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Automaton::BriansBrain => "brians-brain",
+            Automaton::Wireworld => "wireworld",
+        })
+    }
+}
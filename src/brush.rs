@@ -0,0 +1,120 @@
+//! The brushes the board is painted with by dragging the mouse over it.
+
+use std::fmt;
+
+/// The shape of the cells painted around the mouse.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Brush {
+    /// The one cell under the mouse.
+    #[default]
+    Dot,
+    /// A 3×3 square.
+    Square3,
+    /// A 5×5 square.
+    Square5,
+    /// A disc seven cells across.
+    Circle,
+}
+
+/// The brushes from the smallest to the largest, as the scroll wheel goes through them.
+const SIZES: [Brush; 4] = [Brush::Dot, Brush::Square3, Brush::Square5, Brush::Circle];
+
+impl Brush {
+    /// Parses a brush name: `1x1`, `3x3`, `5x5`, or `circle`.
+    pub fn parse(name: &str) -> Option<Brush> {
+        match name {
+            "1x1" => Some(Brush::Dot),
+            "3x3" => Some(Brush::Square3),
+            "5x5" => Some(Brush::Square5),
+            "circle" => Some(Brush::Circle),
+            _ => None,
+        }
+    }
+
+    /// Returns the next larger brush, or this one if it is the largest.
+    pub fn larger(self) -> Brush {
+        let i = SIZES.iter().position(|&brush| brush == self).unwrap_or(0);
+        SIZES[(i + 1).min(SIZES.len() - 1)]
+    }
+
+    /// Returns the next smaller brush, or this one if it is the smallest.
+    pub fn smaller(self) -> Brush {
+        let i = SIZES.iter().position(|&brush| brush == self).unwrap_or(0);
+        SIZES[i.saturating_sub(1)]
+    }
+
+    /// Returns the cells the brush covers, relative to the cell under the mouse.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let radius: isize = match self {
+            Brush::Dot => 0,
+            Brush::Square3 => 1,
+            Brush::Square5 => 2,
+            Brush::Circle => 3,
+        };
+        (-radius..=radius)
+            .flat_map(|dy| (-radius..=radius).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| *self != Brush::Circle || dx * dx + dy * dy <= radius * radius)
+            .collect()
+    }
+
+    /// Paints the brush on a board around a cell.
+    ///
+    /// The cells of the brush that fall outside the board are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board to paint on.
+    /// * `x` - The column of the middle of the brush.
+    /// * `y` - The row of the middle of the brush.
+    /// * `alive` - Whether the cells are brought to life or erased.
+    ///
+    /// # Returns
+    ///
+    /// The number of cells that changed.
+    pub fn paint(&self, grid: &mut [Vec<bool>], x: isize, y: isize, alive: bool) -> usize {
+        let mut changed = 0;
+        for (dx, dy) in self.offsets() {
+            let (Ok(cx), Ok(cy)) = (usize::try_from(x + dx), usize::try_from(y + dy)) else { continue };
+            if let Some(cell) = grid.get_mut(cy).and_then(|row| row.get_mut(cx)) {
+                changed += (*cell != alive) as usize;
+                *cell = alive;
+            }
+        }
+        changed
+    }
+
+    /// Paints the brush at every cell of a straight line, so a fast drag whose mouse
+    /// reports are cells apart leaves no gaps.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board to paint on.
+    /// * `from` - The column and row the line starts at.
+    /// * `to` - The column and row the line ends at.
+    /// * `alive` - Whether the cells are brought to life or erased.
+    ///
+    /// # Returns
+    ///
+    /// The number of cells that changed.
+    pub fn stroke(&self, grid: &mut [Vec<bool>], from: (isize, isize), to: (isize, isize), alive: bool) -> usize {
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs());
+        (0..=steps)
+            .map(|i| {
+                let along = |start: isize, end: isize| if steps == 0 { start } else { start + ((end - start) * i * 2 + steps).div_euclid(steps * 2) };
+                self.paint(grid, along(from.0, to.0), along(from.1, to.1), alive)
+            })
+            .sum()
+    }
+}
+
+impl fmt::Display for Brush {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Brush::Dot => "1x1",
+            Brush::Square3 => "3x3",
+            Brush::Square5 => "5x5",
+            Brush::Circle => "circle",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::VecDeque;
+
+/// The number of recent generations whose births the camera follows.
+const RECENT_GENERATIONS: usize = 8;
+
+/// How far the followed activity may drift from the middle of the view, as a fraction
+/// of the view, before the camera moves after it.
+const DEAD_ZONE: f64 = 0.15;
+
+/// A view onto part of a board larger than the terminal, which follows the activity
+/// on the board with `--follow`.
+///
+/// The camera tracks the centroid of the cells born over the last few generations. It
+/// stays put while the centroid is near the middle of the view, and otherwise moves just
+/// far enough to bring it back, so gliders and growing patterns stay in view without the
+/// view shaking from one generation to the next.
+pub struct Camera {
+    /// The number of columns and rows of cells in view.
+    view: (usize, usize),
+    /// The number of columns and rows of cells of the board.
+    board: (usize, usize),
+    /// The cell in the middle of the view.
+    center: (f64, f64),
+    /// The summed positions and number of the cells born in each recent generation, oldest first.
+    recent: VecDeque<(f64, f64, usize)>,
+    /// The board seen last, to find the births against.
+    previous: Vec<Vec<bool>>,
+}
+
+impl Camera {
+    /// Creates a camera looking at the live cells of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    /// * `view` - The number of columns and rows of cells that fit in the terminal.
+    pub fn new(grid: &[Vec<bool>], view: (usize, usize)) -> Camera {
+        let board = (grid.first().map_or(0, |row| row.len()), grid.len());
+        let (sum_x, sum_y, count) = live_cells(grid, |_, _| true);
+        let center = if count > 0 {
+            (sum_x / count as f64, sum_y / count as f64)
+        } else {
+            (board.0 as f64 / 2.0, board.1 as f64 / 2.0)
+        };
+        Camera { view, board, center, recent: VecDeque::with_capacity(RECENT_GENERATIONS), previous: grid.to_vec() }
+    }
+
+    /// Follows the births of a new generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board, which must be the size it was when the camera was created.
+    pub fn observe(&mut self, grid: &[Vec<bool>]) {
+        let previous = &self.previous;
+        let births = live_cells(grid, |x, y| !previous.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false));
+        if self.recent.len() == RECENT_GENERATIONS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(births);
+        self.previous = grid.to_vec();
+
+        let (sum_x, sum_y, count) = self.recent.iter().fold((0.0, 0.0, 0), |(x, y, n), &(bx, by, bn)| (x + bx, y + by, n + bn));
+        if count == 0 {
+            return;
+        }
+        let follow = |center: f64, target: f64, view: usize| {
+            let slack = view as f64 * DEAD_ZONE;
+            if target > center + slack {
+                target - slack
+            } else if target < center - slack {
+                target + slack
+            } else {
+                center
+            }
+        };
+        self.center = (
+            follow(self.center.0, sum_x / count as f64, self.view.0),
+            follow(self.center.1, sum_y / count as f64, self.view.1),
+        );
+    }
+
+    /// Returns the cell at the top-left corner of the view.
+    ///
+    /// The view is kept inside the board, so near the edges the followed activity is
+    /// off-center rather than next to empty space.
+    pub fn origin(&self) -> (usize, usize) {
+        let origin = |center: f64, view: usize, board: usize| {
+            let left = (center - view as f64 / 2.0).round().max(0.0) as usize;
+            left.min(board.saturating_sub(view))
+        };
+        // The top row stays even, so the rows of a hex board keep their offsets.
+        let top = origin(self.center.1, self.view.1, self.board.1);
+        (origin(self.center.0, self.view.0, self.board.0), top - top % 2)
+    }
+
+    /// Cuts the part of a board in view out of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cells of the board, or anything else kept per cell, such as states.
+    pub fn window<T: Clone>(&self, cells: &[Vec<T>]) -> Vec<Vec<T>> {
+        let (left, top) = self.origin();
+        cells
+            .iter()
+            .skip(top)
+            .take(self.view.1)
+            .map(|row| row.iter().skip(left).take(self.view.0).cloned().collect())
+            .collect()
+    }
+}
+
+/// Sums the positions of the live cells of a board that pass a filter.
+///
+/// # Returns
+///
+/// The summed columns, the summed rows, and the number of cells.
+fn live_cells(grid: &[Vec<bool>], filter: impl Fn(usize, usize) -> bool) -> (f64, f64, usize) {
+    let mut sums = (0.0, 0.0, 0);
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &alive) in row.iter().enumerate() {
+            if alive && filter(x, y) {
+                sums = (sums.0 + x as f64, sums.1 + y as f64, sums.2 + 1);
+            }
+        }
+    }
+    sums
+}
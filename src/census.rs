@@ -0,0 +1,219 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::OnceLock,
+};
+
+use crate::{library, pattern, rule::Rule, update_grid};
+
+/// What kind of object a known object is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ObjectKind {
+    /// An object that never changes.
+    StillLife,
+    /// An object that returns to its first phase in place.
+    Oscillator,
+    /// An object that returns to its first phase somewhere else.
+    Spaceship,
+}
+
+/// The built-in library patterns that are still lifes, oscillators, or spaceships.
+const LIBRARY_OBJECTS: [(&str, ObjectKind); 11] = [
+    ("block", ObjectKind::StillLife),
+    ("beehive", ObjectKind::StillLife),
+    ("eater", ObjectKind::StillLife),
+    ("blinker", ObjectKind::Oscillator),
+    ("toad", ObjectKind::Oscillator),
+    ("beacon", ObjectKind::Oscillator),
+    ("pulsar", ObjectKind::Oscillator),
+    ("glider", ObjectKind::Spaceship),
+    ("lwss", ObjectKind::Spaceship),
+    ("mwss", ObjectKind::Spaceship),
+    ("hwss", ObjectKind::Spaceship),
+];
+
+/// Common soup debris that is not in the built-in library, as (name, RLE) pairs.
+const EXTRA_OBJECTS: [(&str, &str); 5] = [
+    ("boat", "2o$obo$bo!"),
+    ("ship", "2o$obo$b2o!"),
+    ("tub", "bo$obo$bo!"),
+    ("loaf", "b2o$o2bo$bobo$2bo!"),
+    ("pond", "b2o$o2bo$o2bo$b2o!"),
+];
+
+/// Returns what kind of object a known object is.
+///
+/// # Arguments
+///
+/// * `name` - The name of the object, as returned by [`classify`].
+///
+/// # Returns
+///
+/// The kind of the object, or `None` for [`UNKNOWN_OBJECT`] and other unknown names.
+pub fn kind(name: &str) -> Option<ObjectKind> {
+    // All of the extra objects are still lifes.
+    LIBRARY_OBJECTS
+        .iter()
+        .find(|&&(known, _)| known == name)
+        .map(|&(_, kind)| kind)
+        .or_else(|| EXTRA_OBJECTS.iter().any(|&(known, _)| known == name).then_some(ObjectKind::StillLife))
+}
+
+/// The name given to objects that are not in the known-object table.
+pub const UNKNOWN_OBJECT: &str = "other";
+
+/// The longest period looked for when collecting the phases of a known object.
+const MAX_OBJECT_PERIOD: usize = 30;
+
+/// A shape reduced to a form that is the same at every position, rotation, and reflection.
+type CanonicalShape = Vec<(i64, i64)>;
+
+/// Returns the canonical form of a set of cells.
+///
+/// The cells are transformed by each of the eight rotations and reflections, moved to
+/// the origin, and sorted; the smallest of the eight results is the canonical form.
+fn canonical(cells: &[(i64, i64)]) -> CanonicalShape {
+    // Transposing or not, then flipping either axis, gives all eight transforms.
+    let transform = |index: usize, (x, y): (i64, i64)| {
+        let (x, y) = if index & 4 != 0 { (y, x) } else { (x, y) };
+        (if index & 1 != 0 { -x } else { x }, if index & 2 != 0 { -y } else { y })
+    };
+
+    (0..8)
+        .map(|index| {
+            let mut shape: CanonicalShape = cells.iter().map(|&cell| transform(index, cell)).collect();
+            let min_x = shape.iter().map(|&(x, _)| x).min().unwrap_or(0);
+            let min_y = shape.iter().map(|&(_, y)| y).min().unwrap_or(0);
+            shape.iter_mut().for_each(|cell| *cell = (cell.0 - min_x, cell.1 - min_y));
+            shape.sort_unstable();
+            shape
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Splits the live cells of a board into objects.
+///
+/// Live cells within two cells of each other (in each other's 5×5 neighbourhood) belong
+/// to the same object, so oscillators whose phases fall apart into pieces, like the
+/// beacon and the pulsar, stay whole. Objects closer than that are counted as one.
+///
+/// # Arguments
+///
+/// * `grid` - The board.
+///
+/// # Returns
+///
+/// The cells of each object, as `(x, y)`.
+pub fn objects(grid: &[Vec<bool>]) -> Vec<Vec<(usize, usize)>> {
+    let height = grid.len();
+    let width = grid.first().map_or(0, |row| row.len());
+    let mut visited = vec![vec![false; width]; height];
+    let mut objects = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            if !grid[y][x] || visited[y][x] {
+                continue;
+            }
+
+            // Flood fill the object from its first cell.
+            visited[y][x] = true;
+            let mut cells = Vec::new();
+            let mut stack = vec![(x, y)];
+            while let Some((cx, cy)) = stack.pop() {
+                cells.push((cx, cy));
+                for ny in cy.saturating_sub(2)..(cy + 3).min(height) {
+                    for nx in cx.saturating_sub(2)..(cx + 3).min(width) {
+                        if grid[ny][nx] && !visited[ny][nx] {
+                            visited[ny][nx] = true;
+                            stack.push((nx, ny));
+                        }
+                    }
+                }
+            }
+            objects.push(cells);
+        }
+    }
+
+    objects
+}
+
+/// Returns the table of known objects, keyed by the canonical form of each of their phases.
+fn known_objects() -> &'static HashMap<CanonicalShape, &'static str> {
+    static KNOWN: OnceLock<HashMap<CanonicalShape, &'static str>> = OnceLock::new();
+    KNOWN.get_or_init(|| {
+        let library_objects = LIBRARY_OBJECTS
+            .iter()
+            .filter_map(|&(name, _)| library::get(name).map(|pattern| (name, pattern)));
+        let extra_objects = EXTRA_OBJECTS
+            .iter()
+            .map(|&(name, rle)| (name, pattern::parse_rle(rle).expect("known objects are valid RLE")));
+
+        let mut known = HashMap::new();
+        for (name, pattern) in library_objects.chain(extra_objects) {
+            // Run the object on a board with room to move, collecting each phase.
+            let margin = MAX_OBJECT_PERIOD;
+            let mut grid = vec![vec![false; pattern.width + 2 * margin]; pattern.height + 2 * margin];
+            for (y, row) in pattern.cells.iter().enumerate() {
+                for (x, &cell) in row.iter().enumerate() {
+                    grid[y + margin][x + margin] = cell;
+                }
+            }
+
+            let first = shapes(&grid);
+            for _ in 0..MAX_OBJECT_PERIOD {
+                for shape in shapes(&grid) {
+                    known.entry(shape).or_insert(name);
+                }
+                grid = update_grid(&grid, &Rule::default());
+                if shapes(&grid) == first {
+                    break;
+                }
+            }
+        }
+        known
+    })
+}
+
+/// Returns the canonical forms of the objects on a board.
+fn shapes(grid: &[Vec<bool>]) -> Vec<CanonicalShape> {
+    objects(grid)
+        .iter()
+        .map(|cells| canonical(&cells.iter().map(|&(x, y)| (x as i64, y as i64)).collect::<Vec<_>>()))
+        .collect()
+}
+
+/// Names an object by comparing it with the known-object table.
+///
+/// # Arguments
+///
+/// * `cells` - The cells of the object.
+///
+/// # Returns
+///
+/// The name of the object, or [`UNKNOWN_OBJECT`] if it is not a known still life,
+/// oscillator, or spaceship.
+pub fn classify(cells: &[(usize, usize)]) -> &'static str {
+    let shape = canonical(&cells.iter().map(|&(x, y)| (x as i64, y as i64)).collect::<Vec<_>>());
+    known_objects().get(&shape).copied().unwrap_or(UNKNOWN_OBJECT)
+}
+
+/// Counts the objects on a board by name.
+///
+/// Objects are separated with [`objects`] and named with [`classify`], against a small
+/// table of common Life still lifes, oscillators, and spaceships.
+///
+/// # Arguments
+///
+/// * `grid` - The board.
+///
+/// # Returns
+///
+/// The number of objects of each kind, by name.
+pub fn census(grid: &[Vec<bool>]) -> BTreeMap<&'static str, usize> {
+    let mut counts = BTreeMap::new();
+    for cells in objects(grid) {
+        *counts.entry(classify(&cells)).or_insert(0) += 1;
+    }
+    counts
+}
@@ -0,0 +1,857 @@
+use crossterm::style::Color;
+use std::{error::Error, path::PathBuf, time::Duration};
+
+use gameoflife::{
+    ant::TurnRule,
+    boundary::Boundary,
+    brush::Brush,
+    automaton::SimulationKind,
+    cycle::CyclePolicy,
+    engine::EngineKind,
+    expr::InitExpr,
+    gradient::{Gradient, GradientKey},
+    placement::{FitPolicy, Placement},
+    raster::{RasterStyle, Rgb}, recorder::RecordSettings, settings::MIN_SPEED, seeding::{self, Picture, SeedMode}, soup, symmetry::Symmetry, trails, rule::{Neighborhood, Rule}, variant::Variant};
+
+use crate::{
+    config,
+    fetch,
+    picture,
+    display::{self, DeadFill, DisplayStyle, RenderMode},
+    split::{self, Layout},
+    theme::{Theme, Themes},
+    versus,
+};
+
+/// The default probability that a cell starts alive.
+pub const DEFAULT_INITIAL_GRID_PROBABILITY: f64 = 0.2;
+
+/// The default number of generations computed per second.
+pub const DEFAULT_SPEED: f64 = 10.0;
+
+/// The default number of frames drawn per second.
+pub const DEFAULT_FPS: f64 = 30.0;
+
+/// The lowest frame rate, which like [`MIN_SPEED`] keeps the wait between frames countable.
+pub const MIN_FPS: f64 = 0.001;
+
+/// The most generations computed per tick with `--steps-per-frame` and the `[` and `]` keys.
+pub const MAX_STEPS_PER_FRAME: u32 = 1024;
+
+/// The default Wolfram rule of the elementary automaton.
+pub const DEFAULT_WOLFRAM_RULE: u8 = 110;
+
+/// The default length of a screensaver round, in seconds.
+pub const DEFAULT_SCREENSAVER_ROUND: u64 = 60;
+
+/// The default number of past generations kept for rewinding.
+pub const DEFAULT_HISTORY_DEPTH: usize = 256;
+
+/// The options the program was started with.
+#[derive(Debug)]
+pub struct Options {
+    /// The probability that a cell in a random grid starts alive.
+    pub initial_grid_probability: f64,
+    /// Whether the probability was given explicitly on the command line.
+    pub probability_given: bool,
+    /// Patterns to place on the board, in the order they were given.
+    pub placements: Vec<Placement>,
+    /// A pattern file to watch, re-placing it on a fresh board whenever it changes.
+    pub watch: Option<Placement>,
+    /// What to do when the placed patterns do not fit on the board.
+    pub fit: FitPolicy,
+    /// A scene file of layered automata to run instead of a single board.
+    pub scene: Option<PathBuf>,
+    /// An expression composing shapes and patterns into the initial board.
+    pub init_expr: Option<InitExpr>,
+    /// A file to write the final statistics to when the program exits.
+    pub stats_file: Option<PathBuf>,
+    /// A CSV file to log the population, births, and deaths of every generation to.
+    pub stats_csv: Option<PathBuf>,
+    /// The seed of the random number generator, or `None` to pick one at random.
+    pub seed: Option<u64>,
+    /// The directory screenshots are saved to.
+    pub screenshot_dir: PathBuf,
+    /// The folder of the user's patterns, listed in the picker after the library.
+    pub pattern_dir: Option<PathBuf>,
+    /// The cell size, colors, and border of screenshots; the caption is filled in when
+    /// each one is taken.
+    pub screenshot_style: RasterStyle,
+    /// The rule the simulation runs under.
+    pub rule: Rule,
+    /// The neighborhood the rule counts, where it overrides the one the rule names.
+    pub neighborhood: Option<Neighborhood>,
+    /// The multi-state variant of the rule, if any.
+    pub variant: Variant,
+    /// What lies past the edges of the board.
+    pub boundary: Boundary,
+    /// The engine the generations are computed by.
+    pub engine: EngineKind,
+    /// How the live cells of the random board are spread.
+    pub seed_mode: SeedMode,
+    /// The picture the board is seeded from instead of at random, if any.
+    pub picture: Option<Picture>,
+    /// The most generations in a row a cell may be alive, if there is a limit.
+    pub max_age: Option<u32>,
+    /// The symmetry random soups are made with, if any.
+    pub symmetry: Option<Symmetry>,
+    /// The simulation to run instead of Life, if any.
+    pub automaton: Option<SimulationKind>,
+    /// The turn rule of Langton's ant.
+    pub ant_rule: TurnRule,
+    /// The number of ants.
+    pub ants: usize,
+    /// The Wolfram rule number of the elementary automaton.
+    pub wolfram_rule: u8,
+    /// The probability that each cell flips between generations, or `None` for no noise.
+    pub noise: Option<f64>,
+    /// The layout file to start the automaton from, such as a Wireworld circuit.
+    pub layout: Option<PathBuf>,
+    /// The number of generations computed per second.
+    pub speed: f64,
+    /// The most frames drawn per second.
+    pub fps: f64,
+    /// The number of generations computed each tick, between frames.
+    pub steps_per_frame: u32,
+    /// The glyphs, colors, and render mode used to draw the grid.
+    pub display: DisplayStyle,
+    /// The color themes that can be chosen, including those of the configuration file.
+    pub themes: Themes,
+    /// Whether to start again on a fresh board when every cell has died.
+    pub restart_on_extinction: bool,
+    /// How long each soup runs in screensaver mode, if enabled.
+    pub screensaver: Option<Duration>,
+    /// Whether to run unattended: no quit or editing keys, and restarts after failures.
+    pub kiosk: bool,
+    /// The text that stops the simulator when typed in kiosk mode, if any.
+    pub kiosk_token: Option<String>,
+    /// The file that kiosk mode logs its incidents to.
+    pub kiosk_log: PathBuf,
+    /// Whether to leave this session out of the lifetime statistics.
+    pub no_lifetime_stats: bool,
+    /// What to do when the board settles into a still life or oscillation, if anything.
+    pub on_cycle: Option<CyclePolicy>,
+    /// The number of past generations kept for rewinding.
+    pub history_depth: usize,
+    /// Whether to run flat out and only draw a density summary until paused.
+    pub turbo: bool,
+    /// Whether to show the control panel beside the board.
+    pub panel: bool,
+    /// Whether the starting pattern is read from standard input.
+    pub stdin: bool,
+    /// The text to write on the middle of the board in live cells, if any.
+    pub text: Option<String>,
+    /// The URL of a pattern file to download and start with, if any.
+    pub fetch: Option<String>,
+    /// Whether the starting pattern is read from the system clipboard.
+    pub paste: bool,
+    /// Whether the view of a board too big for the terminal follows its activity.
+    pub follow: bool,
+    /// Whether the statistics include a sparkline of the recent population.
+    pub sparkline: bool,
+    /// Whether the status bar is shown below the board.
+    pub status_bar: bool,
+    /// The brush the board is painted with by dragging the mouse.
+    pub brush: Brush,
+    /// Whether the mouse is left to the terminal, for selecting text, instead of painting.
+    pub no_mouse: bool,
+    /// Whether the timing overlay is shown from the start.
+    pub timings: bool,
+    /// The number of generations dead cells leave a fading trail for, if they do.
+    pub trails: Option<u8>,
+    /// The gradient live cells are colored along, if they are.
+    pub gradient: Option<Gradient>,
+    /// A Unix domain socket that external tools control the simulation through.
+    pub control: Option<PathBuf>,
+    /// The command line of a script told about the run, which answers with commands.
+    pub script: Option<String>,
+    /// Whether a headless run writes every generation to standard output as JSON lines.
+    pub emit: bool,
+    /// Whether the JSON lines list the live cells.
+    pub emit_cells: bool,
+    /// How the terminal is split between independent universes, if it is.
+    pub split: Option<Layout>,
+    /// The rules of the split universes, given to them in turn; empty for `--rule` in all.
+    pub split_rules: Vec<Rule>,
+    /// The length in generations of a two-player match, if one is played.
+    pub versus: Option<u64>,
+    /// The number of cells each player places per round of a match.
+    pub budget: usize,
+    /// The number of generations between the placement rounds of a match.
+    pub round: u64,
+    /// Whether to run without any terminal output and report the simulation speed.
+    pub headless: bool,
+    /// Whether to draw the simulation in a window of its own instead of the terminal.
+    pub gui: bool,
+    /// The number of random soups a headless soup search runs, if one is run.
+    pub soup_search: Option<u64>,
+    /// The number of generations to simulate, if limited.
+    pub generations: Option<u64>,
+    /// The address to stream the simulation on with `--serve`, if any.
+    pub serve: Option<String>,
+    /// The address of a server to draw the stream of with `--connect`, if any.
+    pub connect: Option<String>,
+    /// The width of the grid, where it is not taken from the terminal.
+    pub width: Option<usize>,
+    /// The height of the grid, where it is not taken from the terminal.
+    pub height: Option<usize>,
+    /// An animation file to record the simulation to.
+    pub record: Option<PathBuf>,
+    /// A directory to write every recorded generation to as a PNG file.
+    pub frames: Option<PathBuf>,
+    /// A file to record the terminal output to, in asciinema format.
+    pub asciicast: Option<PathBuf>,
+    /// The file snapshots are saved to and loaded from with F5 and F9.
+    pub snapshot_file: PathBuf,
+    /// A snapshot to resume the simulation from.
+    pub resume: Option<PathBuf>,
+    /// A replay file to record the run and everything done to it to.
+    pub record_replay: Option<PathBuf>,
+    /// A replay file to reproduce a recorded run from.
+    pub replay: Option<PathBuf>,
+    /// Which generations are recorded, and how they are drawn.
+    pub record_settings: RecordSettings,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            initial_grid_probability: DEFAULT_INITIAL_GRID_PROBABILITY,
+            probability_given: false,
+            placements: Vec::new(),
+            watch: None,
+            fit: FitPolicy::default(),
+            scene: None,
+            init_expr: None,
+            stats_file: None,
+            stats_csv: None,
+            seed: None,
+            screenshot_dir: PathBuf::from("screenshots"),
+            pattern_dir: config::Config::default_patterns_dir(),
+            screenshot_style: RasterStyle::default(),
+            rule: Rule::default(),
+            neighborhood: None,
+            variant: Variant::default(),
+            boundary: Boundary::default(),
+            engine: EngineKind::default(),
+            seed_mode: SeedMode::default(),
+            picture: None,
+            max_age: None,
+            symmetry: None,
+            automaton: None,
+            ant_rule: TurnRule::default(),
+            ants: 1,
+            wolfram_rule: DEFAULT_WOLFRAM_RULE,
+            layout: None,
+            noise: None,
+            speed: DEFAULT_SPEED,
+            fps: DEFAULT_FPS,
+            steps_per_frame: 1,
+            display: DisplayStyle::default(),
+            themes: Themes::default(),
+            restart_on_extinction: false,
+            screensaver: None,
+            kiosk: false,
+            kiosk_token: None,
+            kiosk_log: PathBuf::from("kiosk.log"),
+            no_lifetime_stats: false,
+            on_cycle: None,
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            turbo: false,
+            panel: false,
+            stdin: false,
+            text: None,
+            fetch: None,
+            paste: false,
+            follow: false,
+            sparkline: false,
+            status_bar: true,
+            brush: Brush::default(),
+            no_mouse: false,
+            timings: false,
+            trails: None,
+            gradient: None,
+            control: None,
+            script: None,
+            emit: false,
+            emit_cells: false,
+            split: None,
+            split_rules: Vec::new(),
+            versus: None,
+            budget: versus::DEFAULT_BUDGET,
+            round: versus::DEFAULT_ROUND,
+            headless: false,
+            gui: false,
+            soup_search: None,
+            generations: None,
+            serve: None,
+            connect: None,
+            width: None,
+            height: None,
+            record: None,
+            frames: None,
+            asciicast: None,
+            snapshot_file: PathBuf::from("snapshot.life"),
+            resume: None,
+            record_replay: None,
+            replay: None,
+            record_settings: RecordSettings::default(),
+        }
+    }
+}
+
+/// Checks that an initial grid probability is between 0 and 1.
+pub fn check_probability(val: f64) -> Result<f64, Box<dyn Error>> {
+    if !(0.0..=1.0).contains(&val) {
+        return Err("Initial grid probability must be between 0 and 1.".into());
+    }
+    Ok(val)
+}
+
+/// Checks that a speed is a number of generations per second of at least [`MIN_SPEED`].
+pub fn check_speed(val: f64) -> Result<f64, Box<dyn Error>> {
+    if !(val >= MIN_SPEED && val.is_finite()) {
+        return Err(format!("Speed must be at least {} generations per second, got {}", MIN_SPEED, val).into());
+    }
+    Ok(val)
+}
+
+/// Checks that a frame rate is a number of frames per second of at least [`MIN_FPS`].
+pub fn check_fps(val: f64) -> Result<f64, Box<dyn Error>> {
+    if !(val >= MIN_FPS && val.is_finite()) {
+        return Err(format!("The frame rate must be at least {} frames per second, got {}", MIN_FPS, val).into());
+    }
+    Ok(val)
+}
+
+/// Parses a render mode name.
+pub fn parse_render_mode(name: &str) -> Result<RenderMode, Box<dyn Error>> {
+    RenderMode::parse(name)
+        .ok_or_else(|| format!("Unknown render mode '{}' (expected ascii, half-block, sextant, braille, heatmap, sixel, kitty or iterm)", name).into())
+}
+
+/// Parses a fit policy name.
+pub fn parse_fit_policy(name: &str) -> Result<FitPolicy, Box<dyn Error>> {
+    FitPolicy::parse(name).ok_or_else(|| format!("Unknown fit policy '{}' (expected expand, zoom, crop or error)", name).into())
+}
+
+/// Parses a cycle policy name.
+pub fn parse_cycle_policy(name: &str) -> Result<CyclePolicy, Box<dyn Error>> {
+    CyclePolicy::parse(name).ok_or_else(|| format!("Unknown cycle policy '{}' (expected stop, report or restart)", name).into())
+}
+
+/// Parses an `--automaton` name; `life` means the default Life simulation.
+pub fn parse_automaton(name: &str) -> Result<Option<SimulationKind>, Box<dyn Error>> {
+    if name == "life" {
+        return Ok(None);
+    }
+    SimulationKind::parse(name)
+        .map(Some)
+        .ok_or_else(|| format!("Unknown automaton '{}' (expected life, brians-brain, wireworld, langtons-ant, elementary, critters, or bbm)", name).into())
+}
+
+/// Parses a `--neighborhood` name.
+pub fn parse_neighborhood(name: &str) -> Result<Neighborhood, Box<dyn Error>> {
+    Neighborhood::parse(name).ok_or_else(|| format!("Unknown neighborhood '{}' (expected moore, vonneumann, or hex)", name).into())
+}
+
+/// Parses a `--boundary` name.
+pub fn parse_boundary(name: &str) -> Result<Boundary, Box<dyn Error>> {
+    Boundary::parse(name).ok_or_else(|| format!("Unknown boundary '{}' (expected dead, wrap, reflect, alive, klein, or cross)", name).into())
+}
+
+/// Parses an `--engine` name.
+pub fn parse_engine(name: &str) -> Result<EngineKind, Box<dyn Error>> {
+    EngineKind::parse(name).ok_or_else(|| format!("Unknown engine '{}' (expected packed, dense, or sparse)", name).into())
+}
+
+/// Parses a `--brush` name.
+pub fn parse_brush(name: &str) -> Result<Brush, Box<dyn Error>> {
+    Brush::parse(name).ok_or_else(|| format!("Unknown brush '{}' (expected 1x1, 3x3, 5x5, or circle)", name).into())
+}
+
+/// Parses a `--topology` name.
+pub fn parse_topology(name: &str) -> Result<Boundary, Box<dyn Error>> {
+    Boundary::parse_topology(name).ok_or_else(|| format!("Unknown topology '{}' (expected plane, torus, klein, or cross)", name).into())
+}
+
+/// Parses a `--seed-mode` name.
+pub fn parse_seed_mode(name: &str) -> Result<SeedMode, Box<dyn Error>> {
+    SeedMode::parse(name).ok_or_else(|| format!("Unknown seed mode '{}' (expected uniform, radial, horizontal, clusters, or noise)", name).into())
+}
+
+/// Parses a `--symmetry` name.
+pub fn parse_symmetry(name: &str) -> Result<Symmetry, Box<dyn Error>> {
+    Symmetry::parse(name).ok_or_else(|| format!("Unknown symmetry '{}' (expected C2, C4, D2, D4, or D8)", name).into())
+}
+
+/// Looks up a `--theme` by name, among the built-in themes and those of the configuration file.
+pub fn parse_theme(themes: &Themes, name: &str) -> Result<Theme, Box<dyn Error>> {
+    themes.get(name).copied().ok_or_else(|| format!("Unknown theme '{}' (expected {})", name, themes.names()).into())
+}
+
+/// Parses a `--dead-fill` name.
+pub fn parse_dead_fill(name: &str) -> Result<DeadFill, Box<dyn Error>> {
+    DeadFill::parse(name).ok_or_else(|| format!("Unknown dead-cell fill '{}' (expected solid or checker)", name).into())
+}
+
+/// Parses a `--gradient` key.
+pub fn parse_gradient_key(name: &str) -> Result<GradientKey, Box<dyn Error>> {
+    GradientKey::parse(name).ok_or_else(|| format!("Unknown gradient '{}' (expected age or density)", name).into())
+}
+
+/// Parses a `--split` layout, such as `2x2`.
+pub fn parse_split(text: &str) -> Result<Layout, Box<dyn Error>> {
+    let layout = Layout::parse(text).ok_or_else(|| format!("Invalid split '{}' (expected columns x rows, as in 2x2)", text))?;
+    if layout.universes() < 2 || layout.universes() > split::MAX_UNIVERSES {
+        return Err(format!("--split shows from 2 to {} universes, not {}", split::MAX_UNIVERSES, layout.universes()).into());
+    }
+    Ok(layout)
+}
+
+/// Parses a `--variant` name.
+pub fn parse_variant(name: &str) -> Result<Variant, Box<dyn Error>> {
+    Variant::parse(name).ok_or_else(|| format!("Unknown variant '{}' (expected life, immigration, or quadlife)", name).into())
+}
+
+/// Parses a terminal color name, ANSI number, or `#rrggbb` value.
+pub fn parse_color_arg(value: &str) -> Result<Color, Box<dyn Error>> {
+    display::parse_color(value).ok_or_else(|| format!("Invalid color '{}'", value).into())
+}
+
+/// Parses a glyph, which must be a single character.
+pub fn parse_glyph(value: &str) -> Result<char, Box<dyn Error>> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(glyph), None) => Ok(glyph),
+        _ => Err(format!("Glyph must be a single character, got '{}'", value).into()),
+    }
+}
+
+/// Parses a hex RGB color for raster output.
+pub fn parse_rgb(value: &str) -> Result<Rgb, Box<dyn Error>> {
+    Rgb::parse(value).ok_or_else(|| format!("Invalid color '{}': expected a hex color like #33ff66", value).into())
+}
+
+/// Parses the number following a flag.
+fn flag_number<T: std::str::FromStr, I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<T, Box<dyn Error>> {
+    let value = flag_value(args, flag)?;
+    value.parse().map_err(|_| format!("Invalid value for {}: {}", flag, value).into())
+}
+
+/// Returns the value following a flag, or an error naming the flag.
+fn flag_value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, Box<dyn Error>> {
+    args.next().ok_or_else(|| format!("{} requires a value", flag).into())
+}
+
+/// Returns command-line arguments without a flag and its value.
+///
+/// # Arguments
+///
+/// * `args` - The command-line arguments.
+/// * `flag` - The flag to leave out.
+pub fn without_flag(args: &[String], flag: &str) -> Vec<String> {
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            args.next();
+        } else {
+            rest.push(arg.clone());
+        }
+    }
+    rest
+}
+
+/// Parses the command-line arguments (excluding the program name).
+///
+/// The first positional argument is a float value that controls the randomness
+/// of the initial grid. Flags may appear anywhere. Defaults are read from the
+/// configuration file first (see [`config::Config`]), and flags override them.
+///
+/// # Arguments
+///
+/// * `args` - The command-line arguments.
+///
+/// # Returns
+///
+/// The parsed options.
+pub fn parse_args<I: Iterator<Item = String>>(args: I) -> Result<Options, Box<dyn Error>> {
+    let args: Vec<String> = args.collect();
+
+    // Load the configuration file before the flags, so the flags take precedence.
+    let config_path = match args.iter().position(|arg| arg == "--config") {
+        Some(i) => Some(PathBuf::from(args.get(i + 1).ok_or("--config requires a path")?)),
+        None => None,
+    };
+    let mut options = Options::default();
+    config::load_config(config_path.as_deref())?.apply(&mut options)?;
+
+    let mut seen_positional = false;
+    let mut theme = None;
+    let mut alive_color = None;
+    let mut gradient_ends = (None, None);
+    let mut threshold = None;
+    let mut image = None;
+    let mut args = args.into_iter().peekable();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => {
+                // Already handled above.
+                args.next();
+            }
+            "--rule" => options.rule = Rule::parse(&flag_value(&mut args, "--rule")?)?,
+            "--neighborhood" => options.neighborhood = Some(parse_neighborhood(&flag_value(&mut args, "--neighborhood")?)?),
+            "--variant" => options.variant = parse_variant(&flag_value(&mut args, "--variant")?)?,
+            "--boundary" => options.boundary = parse_boundary(&flag_value(&mut args, "--boundary")?)?,
+            "--topology" => options.boundary = parse_topology(&flag_value(&mut args, "--topology")?)?,
+            "--engine" => options.engine = parse_engine(&flag_value(&mut args, "--engine")?)?,
+            "--seed-mode" => options.seed_mode = parse_seed_mode(&flag_value(&mut args, "--seed-mode")?)?,
+            "--threshold" => {
+                let value: f64 = flag_number(&mut args, "--threshold")?;
+                if !(0.0..=1.0).contains(&value) {
+                    return Err("--threshold must be between 0 and 1".into());
+                }
+                threshold = Some(value);
+            }
+            "--image" => image = Some(PathBuf::from(flag_value(&mut args, "--image")?)),
+            "--max-age" => {
+                let max_age = flag_number(&mut args, "--max-age")?;
+                if max_age == 0 {
+                    return Err("--max-age must be at least 1".into());
+                }
+                options.max_age = Some(max_age);
+            }
+            "--symmetry" => options.symmetry = Some(parse_symmetry(&flag_value(&mut args, "--symmetry")?)?),
+            "--automaton" => options.automaton = parse_automaton(&flag_value(&mut args, "--automaton")?)?,
+            "--ant-rule" => options.ant_rule = TurnRule::parse(&flag_value(&mut args, "--ant-rule")?)?,
+            "--ants" => {
+                options.ants = flag_number(&mut args, "--ants")?;
+                if options.ants == 0 {
+                    return Err("--ants must be at least 1".into());
+                }
+            }
+            "--wolfram-rule" => options.wolfram_rule = flag_number(&mut args, "--wolfram-rule")?,
+            "--layout" => options.layout = Some(PathBuf::from(flag_value(&mut args, "--layout")?)),
+            "--noise" => {
+                let noise = flag_value(&mut args, "--noise")?;
+                let noise = noise.parse().map_err(|_| format!("Invalid noise probability: {}", noise))?;
+                if !(0.0..=1.0).contains(&noise) {
+                    return Err("--noise must be a probability between 0 and 1".into());
+                }
+                options.noise = Some(noise);
+            }
+            "--speed" | "--ups" => {
+                let speed = flag_value(&mut args, &arg)?;
+                options.speed = check_speed(speed.parse().map_err(|_| format!("Invalid speed: {}", speed))?)?;
+            }
+            "--render" => options.display.mode = parse_render_mode(&flag_value(&mut args, "--render")?)?,
+            "--theme" => theme = Some(parse_theme(&options.themes, &flag_value(&mut args, "--theme")?)?),
+            "--alive-color" => alive_color = Some(parse_color_arg(&flag_value(&mut args, "--alive-color")?)?),
+            "--dead-color" => options.display.dead_color = Some(parse_color_arg(&flag_value(&mut args, "--dead-color")?)?),
+            "--dead-fill" => options.display.dead_fill = Some(parse_dead_fill(&flag_value(&mut args, "--dead-fill")?)?),
+            "--dead-fill-color" => options.display.dead_fill_color = parse_color_arg(&flag_value(&mut args, "--dead-fill-color")?)?,
+            "--alive-glyph" => options.display.alive_glyph = parse_glyph(&flag_value(&mut args, "--alive-glyph")?)?,
+            "--dead-glyph" => options.display.dead_glyph = parse_glyph(&flag_value(&mut args, "--dead-glyph")?)?,
+            "--place" => {
+                let spec = args.next().ok_or("--place requires a value like pattern.rle@30,10:rot90")?;
+                options.placements.push(Placement::parse(&spec)?);
+            }
+            "--watch" => options.watch = Some(Placement::parse(&flag_value(&mut args, "--watch")?)?),
+            "--fit" => options.fit = parse_fit_policy(&flag_value(&mut args, "--fit")?)?,
+            "--scene" => options.scene = Some(PathBuf::from(flag_value(&mut args, "--scene")?)),
+            "--init-expr" => options.init_expr = Some(InitExpr::parse(&flag_value(&mut args, "--init-expr")?)?),
+            "--headless" => options.headless = true,
+            "--gui" => options.gui = true,
+            "--soup-search" => {
+                // The number of soups is optional, so only a whole number is taken as one.
+                let soups = match args.peek().and_then(|value| value.parse::<u64>().ok()) {
+                    Some(soups) => {
+                        args.next();
+                        soups
+                    }
+                    None => soup::DEFAULT_SOUPS,
+                };
+                if soups == 0 {
+                    return Err("--soup-search needs at least one soup".into());
+                }
+                options.soup_search = Some(soups);
+            }
+            "--turbo" => options.turbo = true,
+            "--panel" => options.panel = true,
+            "--stdin" => options.stdin = true,
+            "--paste" => options.paste = true,
+            "--follow" => options.follow = true,
+            "--text" => {
+                let text = flag_value(&mut args, "--text")?;
+                if text.trim().is_empty() {
+                    return Err("--text needs some text to write".into());
+                }
+                options.text = Some(text);
+            }
+            "--fetch" => options.fetch = Some(flag_value(&mut args, "--fetch")?),
+            "--wiki" => options.fetch = Some(fetch::wiki_url(&flag_value(&mut args, "--wiki")?)),
+            "--fps" => {
+                let fps = flag_value(&mut args, "--fps")?;
+                options.fps = check_fps(fps.parse().map_err(|_| format!("Invalid frame rate: {}", fps))?)?;
+            }
+            "--steps-per-frame" => {
+                options.steps_per_frame = flag_number(&mut args, "--steps-per-frame")?;
+                if !(1..=MAX_STEPS_PER_FRAME).contains(&options.steps_per_frame) {
+                    return Err(format!("--steps-per-frame must be between 1 and {}", MAX_STEPS_PER_FRAME).into());
+                }
+            }
+            "--sparkline" => options.sparkline = true,
+            "--no-status-bar" => options.status_bar = false,
+            "--timings" => options.timings = true,
+            "--brush" => options.brush = parse_brush(&flag_value(&mut args, "--brush")?)?,
+            "--no-mouse" => options.no_mouse = true,
+            "--trails" => {
+                let length: u8 = flag_number(&mut args, "--trails")?;
+                if length == 0 || length > trails::MAX_LENGTH {
+                    return Err(format!("--trails must be between 1 and {} generations", trails::MAX_LENGTH).into());
+                }
+                options.trails = Some(length);
+            }
+            "--gradient" => options.gradient = Some(Gradient::new(parse_gradient_key(&flag_value(&mut args, "--gradient")?)?)),
+            "--gradient-from" => gradient_ends.0 = Some(parse_rgb(&flag_value(&mut args, "--gradient-from")?)?),
+            "--gradient-to" => gradient_ends.1 = Some(parse_rgb(&flag_value(&mut args, "--gradient-to")?)?),
+            "--control" => options.control = Some(PathBuf::from(flag_value(&mut args, "--control")?)),
+            "--script" => options.script = Some(flag_value(&mut args, "--script")?),
+            "--emit" => {
+                let format = flag_value(&mut args, "--emit")?;
+                if format != "ndjson" {
+                    return Err(format!("Unknown output format '{}' (expected ndjson)", format).into());
+                }
+                options.emit = true;
+            }
+            "--emit-cells" => options.emit_cells = true,
+            "--split" => options.split = Some(parse_split(&flag_value(&mut args, "--split")?)?),
+            "--split-rules" => {
+                let rules = flag_value(&mut args, "--split-rules")?;
+                options.split_rules = rules.split(',').map(|rule| Rule::parse(rule.trim())).collect::<Result<_, _>>()?;
+            }
+            "--versus" => options.versus = Some(flag_number(&mut args, "--versus")?),
+            "--budget" => options.budget = flag_number(&mut args, "--budget")?,
+            "--round" => {
+                options.round = flag_number(&mut args, "--round")?;
+                if options.round == 0 {
+                    return Err("--round must be at least 1".into());
+                }
+            }
+            "--restart-on-extinction" => options.restart_on_extinction = true,
+            "--no-lifetime-stats" => options.no_lifetime_stats = true,
+            "--kiosk" => options.kiosk = true,
+            "--kiosk-token" => {
+                let token = flag_value(&mut args, "--kiosk-token")?;
+                if token.is_empty() {
+                    return Err("--kiosk-token must not be empty".into());
+                }
+                options.kiosk_token = Some(token);
+            }
+            "--kiosk-log" => options.kiosk_log = PathBuf::from(flag_value(&mut args, "--kiosk-log")?),
+            "--screensaver" => {
+                // The round length is optional, so only a whole number of seconds is taken as one.
+                let secs = match args.peek().and_then(|value| value.parse::<u64>().ok()) {
+                    Some(secs) => {
+                        args.next();
+                        secs
+                    }
+                    None => DEFAULT_SCREENSAVER_ROUND,
+                };
+                if secs == 0 {
+                    return Err("--screensaver rounds must last at least one second".into());
+                }
+                options.screensaver = Some(Duration::from_secs(secs));
+            }
+            "--on-cycle" => options.on_cycle = Some(parse_cycle_policy(&flag_value(&mut args, "--on-cycle")?)?),
+            "--history" => options.history_depth = flag_number(&mut args, "--history")?,
+            "--generations" => options.generations = Some(flag_number(&mut args, "--generations")?),
+            "--serve" => options.serve = Some(flag_value(&mut args, "--serve")?),
+            "--connect" => options.connect = Some(flag_value(&mut args, "--connect")?),
+            "--width" => options.width = Some(flag_number(&mut args, "--width")?),
+            "--height" => options.height = Some(flag_number(&mut args, "--height")?),
+            "--record" => options.record = Some(PathBuf::from(flag_value(&mut args, "--record")?)),
+            "--frames" => options.frames = Some(PathBuf::from(flag_value(&mut args, "--frames")?)),
+            "--asciicast" => options.asciicast = Some(PathBuf::from(flag_value(&mut args, "--asciicast")?)),
+            "--snapshot-file" => options.snapshot_file = PathBuf::from(flag_value(&mut args, "--snapshot-file")?),
+            "--resume" => options.resume = Some(PathBuf::from(flag_value(&mut args, "--resume")?)),
+            "--record-replay" => options.record_replay = Some(PathBuf::from(flag_value(&mut args, "--record-replay")?)),
+            "--replay" => options.replay = Some(PathBuf::from(flag_value(&mut args, "--replay")?)),
+            "--record-every" => {
+                options.record_settings.every = flag_number(&mut args, "--record-every")?;
+                if options.record_settings.every == 0 {
+                    return Err("--record-every must be at least 1".into());
+                }
+            }
+            "--record-cell-size" => {
+                options.record_settings.style.cell_size = flag_number(&mut args, "--record-cell-size")?;
+                if options.record_settings.style.cell_size == 0 {
+                    return Err("--record-cell-size must be at least 1".into());
+                }
+            }
+            "--record-alive-color" => options.record_settings.style.alive = parse_rgb(&flag_value(&mut args, "--record-alive-color")?)?,
+            "--record-dead-color" => options.record_settings.style.dead = parse_rgb(&flag_value(&mut args, "--record-dead-color")?)?,
+            "--stats-file" => {
+                let path = args.next().ok_or("--stats-file requires a path")?;
+                options.stats_file = Some(PathBuf::from(path));
+            }
+            "--stats-csv" => {
+                let path = args.next().ok_or("--stats-csv requires a path")?;
+                options.stats_csv = Some(PathBuf::from(path));
+            }
+            "--seed" => {
+                let seed = args.next().ok_or("--seed requires a value")?;
+                options.seed = Some(seed.parse().map_err(|_| format!("Invalid seed: {}", seed))?);
+            }
+            "--screenshot-dir" => {
+                let path = args.next().ok_or("--screenshot-dir requires a path")?;
+                options.screenshot_dir = PathBuf::from(path);
+            }
+            "--pattern-dir" => options.pattern_dir = Some(PathBuf::from(flag_value(&mut args, "--pattern-dir")?)),
+            "--no-screenshot-border" => options.screenshot_style.border = 0,
+            "--screenshot-cell-size" => {
+                options.screenshot_style.cell_size = flag_number(&mut args, "--screenshot-cell-size")?;
+                if options.screenshot_style.cell_size == 0 {
+                    return Err("--screenshot-cell-size must be at least 1".into());
+                }
+            }
+            "--screenshot-alive-color" => options.screenshot_style.alive = parse_rgb(&flag_value(&mut args, "--screenshot-alive-color")?)?,
+            "--screenshot-dead-color" => options.screenshot_style.dead = parse_rgb(&flag_value(&mut args, "--screenshot-dead-color")?)?,
+            flag if flag.starts_with("--") => {
+                return Err(format!("Unknown option: {}", flag).into());
+            }
+            value => {
+                if seen_positional {
+                    return Err(format!("Unexpected argument: {}", value).into());
+                }
+
+                if let Ok(val) = value.parse::<f64>() {
+                    // If the value is not between 0 and 1, error and inform the user.
+                    options.initial_grid_probability = check_probability(val)?;
+                    options.probability_given = true;
+                } else {
+                    println!(
+                        "Failed to parse argument as a float value. Using default value: {}",
+                        options.initial_grid_probability
+                    );
+                }
+                seen_positional = true;
+            }
+        }
+    }
+
+    // The neighborhood applies whichever order it was given in relative to the rule.
+    if let Some(neighborhood) = options.neighborhood {
+        options.rule = options.rule.with_neighborhood(neighborhood)?;
+        for rule in options.split_rules.iter_mut() {
+            *rule = rule.with_neighborhood(neighborhood)?;
+        }
+    }
+
+    // The theme also applies whichever order it was given in, and the live-cell color of
+    // --alive-color wins over it.
+    if let Some(theme) = theme {
+        theme.apply(&mut options.display);
+    }
+    if alive_color.is_some() {
+        options.display.alive_color = alive_color;
+    }
+
+    // So do the ends of the gradient.
+    match options.gradient.as_mut() {
+        Some(gradient) => {
+            gradient.from = gradient_ends.0.unwrap_or(gradient.from);
+            gradient.to = gradient_ends.1.unwrap_or(gradient.to);
+        }
+        None if gradient_ends != (None, None) => return Err("--gradient-from and --gradient-to need --gradient".into()),
+        None => {}
+    }
+
+    // Only noise and pictures are cut off at a threshold.
+    match (&mut options.seed_mode, image, threshold) {
+        (SeedMode::Uniform, Some(path), threshold) => {
+            options.picture = Some(picture::load_picture(&path, threshold.unwrap_or(seeding::DEFAULT_LUMINANCE_THRESHOLD))?);
+        }
+        (_, Some(_), _) => return Err("--image and --seed-mode both decide how the board is seeded".into()),
+        (SeedMode::Noise { threshold }, None, Some(value)) => *threshold = value,
+        (_, None, Some(_)) => return Err("--threshold needs --seed-mode noise or --image".into()),
+        _ => {}
+    }
+
+    // Terminals without 24-bit color are drawn to in the 256-color palette.
+    options.display.true_color = display::supports_true_color();
+
+    // The lines go to standard output, which the terminal frontend draws on.
+    if options.emit && !options.headless {
+        return Err("--emit writes to standard output and needs --headless".into());
+    }
+    if options.emit_cells && !options.emit {
+        return Err("--emit-cells needs --emit ndjson".into());
+    }
+    if options.soup_search.is_some() && (!options.headless || options.emit) {
+        return Err("--soup-search needs --headless, and prints a census instead of --emit lines".into());
+    }
+
+    // Trails are drawn one cell per character, over the whole board.
+    if options.trails.is_some() && options.display.mode != RenderMode::Ascii {
+        return Err(format!("--trails needs --render ascii, not {}", options.display.mode).into());
+    }
+    if options.trails.is_some() && options.follow {
+        return Err("--trails cannot be combined with --follow".into());
+    }
+
+    // The gradient colors the cells in place of the colors of trails, heatmaps, and the
+    // states of multi-state variants.
+    if options.gradient.is_some() && (options.trails.is_some() || options.display.mode == RenderMode::Heatmap || options.variant.is_multi_state()) {
+        return Err("--gradient cannot be combined with --trails, --render heatmap, or a multi-state --variant".into());
+    }
+
+    // Split universes are plain boards, drawn without the extras of the main view.
+    if !options.split_rules.is_empty() && options.split.is_none() {
+        return Err("--split-rules needs --split".into());
+    }
+    if options.split.is_some() && (options.trails.is_some() || options.gradient.is_some() || options.display.mode == RenderMode::Heatmap) {
+        return Err("--split cannot be combined with --trails, --gradient, or --render heatmap".into());
+    }
+
+    // Pixel images are drawn in two colors, over the whole board of the main simulation.
+    let pixels = options.display.mode.draws_pixels();
+    let other_screen = options.split.is_some() || options.versus.is_some() || options.scene.is_some() || options.automaton.is_some() || options.connect.is_some();
+    if pixels && (options.gradient.is_some() || options.variant.is_multi_state() || other_screen) {
+        return Err(format!("--render {} cannot be combined with --gradient, a multi-state --variant, --split, --versus, --scene, --automaton, or --connect", options.display.mode).into());
+    }
+
+    // A replay starts from a board of its own, and its run must follow from that start and
+    // the recorded interventions alone, not the clock or files changing on disk.
+    if options.replay.is_some() && (options.record_replay.is_some() || options.resume.is_some()) {
+        return Err("--replay cannot be combined with --record-replay or --resume".into());
+    }
+    let replaying = options.replay.is_some() || options.record_replay.is_some();
+    if replaying && (options.screensaver.is_some() || options.watch.is_some()) {
+        return Err("--record-replay and --replay cannot be combined with --screensaver or --watch".into());
+    }
+    let other_loop = options.headless
+        || options.serve.is_some()
+        || options.scene.is_some()
+        || options.split.is_some()
+        || options.versus.is_some()
+        || options.automaton.is_some();
+    if replaying && (other_loop || options.gui) {
+        return Err("--record-replay and --replay need the main simulation, not --headless, --serve, --gui, --scene, --split, --versus, or --automaton".into());
+    }
+    // Cells are only aged on the unpacked board of the main simulation.
+    if options.max_age.is_some() && (other_loop || options.soup_search.is_some()) {
+        return Err("--max-age needs the main simulation, not --headless, --soup-search, --serve, --scene, --split, --versus, or --automaton".into());
+    }
+    // Only the terminal keeps and draws the colors of the cells.
+    if options.variant.is_multi_state() && (other_loop || options.gui) {
+        return Err(format!("--variant {} needs the main simulation, not --headless, --serve, --gui, --scene, --split, --versus, or --automaton", options.variant).into());
+    }
+
+    // Play recordings back at the simulation speed.
+    options.record_settings.speed = options.speed;
+
+    Ok(options)
+}
@@ -0,0 +1,45 @@
+use std::{error::Error, process::Command};
+
+use gameoflife::pattern::{self, Pattern};
+
+/// The commands that print the system clipboard, tried in order: Wayland, X11 (two
+/// tools), macOS, and Windows.
+const PASTE_COMMANDS: [(&str, &[&str]); 5] = [
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-out"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("pbpaste", &[]),
+    ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+];
+
+/// Reads the text on the system clipboard.
+///
+/// The clipboard is read with the first of the usual clipboard tools that is installed
+/// and succeeds, such as `wl-paste` or `xclip` on Linux and `pbpaste` on macOS.
+pub fn read_text() -> Result<String, Box<dyn Error>> {
+    for (program, args) in PASTE_COMMANDS {
+        // Tools that are not installed, or have no display to talk to, are skipped.
+        let Ok(output) = Command::new(program).args(args).output() else { continue };
+        if output.status.success() {
+            return Ok(String::from_utf8_lossy(&output.stdout).into_owned());
+        }
+    }
+    Err("Cannot read the clipboard: install wl-clipboard, xclip, or xsel".into())
+}
+
+/// Reads a pattern from the system clipboard.
+///
+/// # Returns
+///
+/// The pattern, from RLE or plaintext cells, as copied from LifeWiki.
+pub fn read_pattern() -> Result<Pattern, Box<dyn Error>> {
+    let text = read_text()?;
+    if text.trim().is_empty() {
+        return Err("The clipboard is empty".into());
+    }
+    let pattern = pattern::parse(&text).map_err(|e| format!("The clipboard does not hold a pattern: {}", e))?;
+    if pattern.width == 0 || pattern.height == 0 {
+        return Err("The clipboard does not hold a pattern".into());
+    }
+    Ok(pattern)
+}
@@ -0,0 +1,76 @@
+use std::{error::Error, io::Write, thread, time::Duration};
+
+use gameoflife::scene::Scene;
+
+use crate::{
+    display::{clear_screen, display_colors, display_status, DisplayStyle, Screen, Terminal},
+    input::{self, Command, Input},
+};
+
+/// Runs a composite scene in the terminal until the user quits.
+///
+/// This is a deliberately small loop next to the main one: the layers are stepped and
+/// drawn with their blended colors, and only quitting and pausing are supported.
+///
+/// # Arguments
+///
+/// * `scene` - The seeded scene.
+/// * `speed` - The number of generations per second.
+/// * `style` - The colors to draw with.
+/// * `cols` - The number of terminal columns available for the board.
+/// * `rows` - The number of terminal rows available for the board; the status bar goes below.
+pub fn run(scene: &mut Scene, speed: f64, style: &DisplayStyle, cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+    let mut term = Terminal::new(None);
+    let screen = Screen::enter()?;
+    let raw_mode = input::RawMode::enable().ok();
+    clear_screen(&mut term, style)?;
+
+    let mut generation: u64 = 0;
+    let mut paused = false;
+    let mut prev_colors = Vec::new();
+    let mut running = true;
+
+    while running {
+        let colors = scene.colors();
+        display_colors(&mut term, &colors, &prev_colors, style, cols, rows)?;
+        prev_colors = colors;
+
+        // Show the population of every layer, by automaton.
+        let populations: Vec<String> = scene
+            .layers
+            .iter()
+            .map(|layer| format!("{} {}", layer.automaton.name(), layer.population()))
+            .collect();
+        let status = format!("gen {} | {}{}", generation, populations.join(" | "), if paused { " [paused]" } else { "" });
+        display_status(&mut term, &status, rows, cols, style)?;
+        term.flush()?;
+
+        if !paused {
+            scene.step();
+            generation += 1;
+        }
+
+        let wait = Duration::from_secs_f64(1.0 / speed);
+        let inputs = if raw_mode.is_some() {
+            input::wait_for_input(wait)?
+        } else {
+            thread::sleep(wait);
+            Vec::new()
+        };
+        for input in inputs {
+            if let Input::Key(key) = input {
+                match input::map_key(&key) {
+                    Some(Command::Quit) => running = false,
+                    Some(Command::TogglePause) => paused = !paused,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    drop(raw_mode);
+    drop(screen);
+    println!("Exiting...");
+    println!("gen: {}", generation);
+    Ok(())
+}
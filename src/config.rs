@@ -0,0 +1,251 @@
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    env,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gameoflife::rule::Rule;
+
+use crate::{
+    cli::{self, Options},
+    theme::Theme,
+};
+
+/// Default settings read from a TOML configuration file.
+///
+/// Every key is optional; missing keys keep the built-in defaults, and command-line
+/// flags override whatever the file sets.
+///
+/// ```toml
+/// probability = 0.3
+/// rule = "B36/S23"
+/// speed = 20
+/// render = "braille"
+/// theme = "dusk"
+/// brush = "3x3"
+///
+/// [colors]
+/// alive = "green"
+/// dead = "dark_grey"
+///
+/// [themes.dusk]
+/// alive = "#ff8c42"
+/// dying = "#8c3b12"
+/// background = "#1b0f24"
+/// status = "#1b0f24"
+/// status_background = "#ff8c42"
+///
+/// [glyphs]
+/// alive = "@"
+/// dead = "."
+///
+/// [hud]
+/// status_bar = true
+/// sparkline = true
+/// timings = false
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The probability that a cell in a random grid starts alive.
+    pub probability: Option<f64>,
+    /// The rulestring, e.g. `B3/S23`.
+    pub rule: Option<String>,
+    /// The number of generations per second.
+    pub speed: Option<f64>,
+    /// The most frames drawn per second.
+    pub fps: Option<f64>,
+    /// The render mode name.
+    pub render: Option<String>,
+    /// The name of the color theme, built in or defined under `[themes]`.
+    pub theme: Option<String>,
+    /// The name of the brush the mouse paints with.
+    pub brush: Option<String>,
+    /// The cell colors.
+    pub colors: ColorConfig,
+    /// The cell glyphs.
+    pub glyphs: GlyphConfig,
+    /// Color themes by name, added to the built-in ones.
+    pub themes: BTreeMap<String, ThemeConfig>,
+    /// The parts of the screen shown around the board.
+    pub hud: HudConfig,
+}
+
+/// The `[colors]` table of the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorConfig {
+    /// The color of live cells.
+    pub alive: Option<String>,
+    /// The color of dead-cell glyphs.
+    pub dead: Option<String>,
+}
+
+/// The `[glyphs]` table of the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GlyphConfig {
+    /// The character drawn for live cells.
+    pub alive: Option<String>,
+    /// The character drawn for dead cells.
+    pub dead: Option<String>,
+}
+
+/// The `[hud]` table of the configuration file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HudConfig {
+    /// Whether the status bar is shown below the board.
+    pub status_bar: Option<bool>,
+    /// Whether the statistics include a sparkline of the recent population.
+    pub sparkline: Option<bool>,
+    /// Whether the timing overlay is shown from the start.
+    pub timings: Option<bool>,
+}
+
+/// A table under `[themes]` in the configuration file, defining a color theme.
+///
+/// Colors left out are the terminal's own.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeConfig {
+    /// The color of live cells.
+    pub alive: Option<String>,
+    /// The color the ghost trails of dying cells fade from.
+    pub dying: Option<String>,
+    /// The color behind the board.
+    pub background: Option<String>,
+    /// The color of the status bar text.
+    pub status: Option<String>,
+    /// The color behind the status bar text.
+    pub status_background: Option<String>,
+}
+
+impl ThemeConfig {
+    /// Parses the colors of the theme.
+    fn parse(&self) -> Result<Theme, Box<dyn Error>> {
+        let color = |value: &Option<String>| value.as_deref().map(cli::parse_color_arg).transpose();
+        Ok(Theme {
+            alive: color(&self.alive)?,
+            dying: color(&self.dying)?,
+            background: color(&self.background)?,
+            status: color(&self.status)?,
+            status_background: color(&self.status_background)?,
+        })
+    }
+}
+
+impl Config {
+    /// Returns the default configuration file path, `~/.config/gameoflife/config.toml`.
+    ///
+    /// `$XDG_CONFIG_HOME` is used instead of `~/.config` when it is set.
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+        Some(config_dir.join("gameoflife").join("config.toml"))
+    }
+
+    /// Returns the default folder of the user's patterns, `~/.config/gameoflife/patterns`,
+    /// beside the configuration file.
+    pub fn default_patterns_dir() -> Option<PathBuf> {
+        Config::default_path().and_then(|path| Some(path.parent()?.join("patterns")))
+    }
+
+    /// Reads and parses a configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the configuration file.
+    ///
+    /// # Returns
+    ///
+    /// The parsed configuration.
+    pub fn load(path: &Path) -> Result<Config, Box<dyn Error>> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file {}: {}", path.display(), e))?;
+
+        toml::from_str(&text).map_err(|e| format!("Invalid config file {}: {}", path.display(), e).into())
+    }
+
+    /// Applies the configured values on top of the given options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options to update.
+    pub fn apply(&self, options: &mut Options) -> Result<(), Box<dyn Error>> {
+        if let Some(probability) = self.probability {
+            options.initial_grid_probability = cli::check_probability(probability)?;
+        }
+        if let Some(rule) = &self.rule {
+            options.rule = Rule::parse(rule)?;
+        }
+        if let Some(speed) = self.speed {
+            options.speed = cli::check_speed(speed)?;
+        }
+        if let Some(fps) = self.fps {
+            options.fps = cli::check_fps(fps)?;
+        }
+        if let Some(render) = &self.render {
+            options.display.mode = cli::parse_render_mode(render)?;
+        }
+        if let Some(brush) = &self.brush {
+            options.brush = cli::parse_brush(brush)?;
+        }
+        for (name, theme) in &self.themes {
+            let theme = theme.parse().map_err(|e| format!("Invalid theme '{}': {}", name, e))?;
+            options.themes.insert(name, theme);
+        }
+        if let Some(theme) = &self.theme {
+            cli::parse_theme(&options.themes, theme)?.apply(&mut options.display);
+        }
+        if let Some(color) = &self.colors.alive {
+            options.display.alive_color = Some(cli::parse_color_arg(color)?);
+        }
+        if let Some(color) = &self.colors.dead {
+            options.display.dead_color = Some(cli::parse_color_arg(color)?);
+        }
+        if let Some(glyph) = &self.glyphs.alive {
+            options.display.alive_glyph = cli::parse_glyph(glyph)?;
+        }
+        if let Some(glyph) = &self.glyphs.dead {
+            options.display.dead_glyph = cli::parse_glyph(glyph)?;
+        }
+        if let Some(status_bar) = self.hud.status_bar {
+            options.status_bar = status_bar;
+        }
+        if let Some(sparkline) = self.hud.sparkline {
+            options.sparkline = sparkline;
+        }
+        if let Some(timings) = self.hud.timings {
+            options.timings = timings;
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the configuration file given with `--config`, or the default one if it exists.
+///
+/// # Arguments
+///
+/// * `explicit` - The path given on the command line, if any.
+///
+/// # Returns
+///
+/// The configuration, or the empty configuration if there is no file to read.
+pub fn load_config(explicit: Option<&Path>) -> Result<Config, Box<dyn Error>> {
+    match explicit {
+        Some(path) => Config::load(path),
+        None => match Config::default_path().filter(|path| path.exists()) {
+            Some(path) => Config::load(&path),
+            None => Ok(Config::default()),
+        },
+    }
+}
+
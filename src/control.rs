@@ -0,0 +1,354 @@
+#[cfg(unix)]
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+    thread,
+};
+use std::{
+    error::Error,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+use gameoflife::{rule::Rule, settings::Settings};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::MAX_STEPS_PER_FRAME;
+
+/// A command sent over the control socket, as a JSON object whose `command` field names it
+/// or as words, such as `step 10`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ControlCommand {
+    /// Pause the simulation.
+    Pause,
+    /// Resume the simulation.
+    Resume,
+    /// Advance a number of generations and pause.
+    Step {
+        /// The number of generations, 1 unless given, and at most [`MAX_STEPS_PER_FRAME`]
+        /// since they are computed before the next frame.
+        #[serde(default = "one")]
+        count: u64,
+    },
+    /// Switch to another rule.
+    SetRule {
+        /// The new rule, as a rulestring.
+        rule: Rule,
+    },
+    /// Change the settings of the run, as reported by `status`.
+    Settings(Settings),
+    /// Save the board to an RLE file.
+    Save {
+        /// The file to write.
+        path: PathBuf,
+    },
+    /// Bring a cell to life, or place a pattern with its top-left corner there.
+    Inject {
+        /// The column of the cell.
+        x: isize,
+        /// The row of the cell.
+        y: isize,
+        /// The pattern file or built-in pattern to place, if any.
+        #[serde(default)]
+        pattern: Option<String>,
+    },
+    /// Bring a cell to life or kill it.
+    Set {
+        /// The column of the cell.
+        x: usize,
+        /// The row of the cell.
+        y: usize,
+        /// Whether the cell is alive.
+        alive: bool,
+    },
+    /// Report the generation, population, and settings.
+    Status {
+        /// Whether to list the live cells too.
+        #[serde(default)]
+        cells: bool,
+    },
+    /// Quit the program.
+    Quit,
+}
+
+/// Returns the number of generations a `step` takes when none is given.
+fn one() -> u64 {
+    1
+}
+
+impl ControlCommand {
+    /// Parses one line sent to the control socket, as JSON if it starts with `{` and as
+    /// words otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The command, as a JSON object or as the command and its arguments,
+    ///   separated by spaces.
+    ///
+    /// # Returns
+    ///
+    /// The command, or an explanation of what is wrong with it.
+    pub fn parse(line: &str) -> Result<ControlCommand, String> {
+        let command = if is_json(line) { ControlCommand::parse_json(line)? } else { ControlCommand::parse_words(line)? };
+        match command {
+            ControlCommand::Step { count: 0 } => Err("a step must advance a positive number of generations".to_string()),
+            ControlCommand::Step { count } if count > u64::from(MAX_STEPS_PER_FRAME) => {
+                Err(format!("a step advances at most {} generations, not {}; send several to go further", MAX_STEPS_PER_FRAME, count))
+            }
+            command => Ok(command),
+        }
+    }
+
+    /// Parses a command written as a JSON object.
+    fn parse_json(line: &str) -> Result<ControlCommand, String> {
+        serde_json::from_str(line).map_err(|e| {
+            if e.is_data() {
+                e.to_string()
+            } else {
+                format!("{} (commands are JSON objects, such as {{\"command\": \"status\"}})", e)
+            }
+        })
+    }
+
+    /// Parses a command written as words, the command and then its arguments.
+    fn parse_words(line: &str) -> Result<ControlCommand, String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let number = |word: &str| word.parse::<isize>().map_err(|_| format!("'{}' is not a number", word));
+
+        match words.as_slice() {
+            ["pause"] => Ok(ControlCommand::Pause),
+            ["resume"] => Ok(ControlCommand::Resume),
+            ["step"] => Ok(ControlCommand::Step { count: 1 }),
+            ["step", count] => match count.parse() {
+                Ok(count) => Ok(ControlCommand::Step { count }),
+                Err(_) => Err(format!("'{}' is not a positive number of generations", count)),
+            },
+            ["set-rule", rule] => Rule::parse(rule).map(|rule| ControlCommand::SetRule { rule }).map_err(|e| e.to_string()),
+            ["save", path] => Ok(ControlCommand::Save { path: PathBuf::from(path) }),
+            ["inject", x, y] => Ok(ControlCommand::Inject { x: number(x)?, y: number(y)?, pattern: None }),
+            ["inject", x, y, pattern] => Ok(ControlCommand::Inject { x: number(x)?, y: number(y)?, pattern: Some(pattern.to_string()) }),
+            ["status"] => Ok(ControlCommand::Status { cells: false }),
+            ["quit"] => Ok(ControlCommand::Quit),
+            [] => Err("empty command".to_string()),
+            [name, ..] => Err(format!(
+                "unknown command or arguments for '{}' (expected pause, resume, step [N], set-rule RULE, save PATH, inject X Y [PATTERN], status, quit, or a JSON object)",
+                name
+            )),
+        }
+    }
+}
+
+/// Returns whether a line sent to the control socket is a JSON command, which is also
+/// answered in JSON.
+fn is_json(line: &str) -> bool {
+    line.trim_start().starts_with('{')
+}
+
+/// The state of the simulation, sent back for `status` and `settings`.
+#[derive(Clone, Debug, Serialize)]
+pub struct ControlStatus {
+    /// The generation the board is at.
+    pub generation: u64,
+    /// The number of live cells.
+    pub population: usize,
+    /// The settings of the run.
+    pub settings: Settings,
+    /// The columns and rows of the live cells, when asked for.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cells: Option<Vec<(usize, usize)>>,
+}
+
+/// Returns the columns and rows of the live cells of a board, row by row, for a `status`
+/// that asks for them.
+pub fn live_cells(grid: &[Vec<bool>]) -> Vec<(usize, usize)> {
+    let rows = grid.iter().enumerate();
+    rows.flat_map(|(y, row)| row.iter().enumerate().filter(|(_, &alive)| alive).map(move |(x, _)| (x, y))).collect()
+}
+
+/// The answer to a command, written back as one line in the form of the command.
+#[derive(Debug, Serialize)]
+struct ControlReply {
+    /// Whether the command was carried out.
+    ok: bool,
+    /// What the command did, or why it failed.
+    message: String,
+    /// The state of the simulation, for the commands that report it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<ControlStatus>,
+}
+
+impl ControlReply {
+    /// Returns the reply as the line written back to the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - Whether the command was sent as JSON. Commands sent as words are
+    ///   answered with `ok` or `error` and the message, or for `status` the state of the
+    ///   run, as in `ok generation 120 population 341 rule B3/S23`.
+    fn to_line(&self, json: bool) -> String {
+        if json {
+            return serde_json::to_string(self).expect("replies serialize to JSON");
+        }
+        match (&self.status, self.ok) {
+            (Some(status), true) => format!(
+                "ok generation {} population {} rule {}{}",
+                status.generation,
+                status.population,
+                status.settings.rule,
+                if status.settings.paused { " paused" } else { "" }
+            ),
+            (_, true) => format!("ok {}", self.message),
+            (_, false) => format!("error {}", self.message),
+        }
+    }
+}
+
+/// A command waiting for the main loop, with the way back to the client that sent it.
+pub struct ControlRequest {
+    /// The command.
+    pub command: ControlCommand,
+    /// Where the reply goes.
+    reply: Sender<String>,
+    /// Whether the command was sent as JSON, and is answered in JSON.
+    json: bool,
+}
+
+impl ControlRequest {
+    /// Parses one line of commands into a request for the main loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The command, as a JSON object or as words.
+    ///
+    /// # Returns
+    ///
+    /// The request and where its reply arrives, or the reply line to send back when the
+    /// command is not valid.
+    pub fn from_line(line: &str) -> Result<(ControlRequest, Receiver<String>), String> {
+        let json = is_json(line);
+        let command = ControlCommand::parse(line).map_err(|message| ControlReply { ok: false, message, status: None }.to_line(json))?;
+        let (reply, answer) = mpsc::channel();
+        Ok((ControlRequest { command, reply, json }, answer))
+    }
+
+    /// Answers the client; the reply is written back as one line.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - What the command did, or why it failed.
+    /// * `status` - The state of the simulation, for the commands that report it.
+    pub fn reply(self, result: Result<String, String>, status: Option<ControlStatus>) {
+        let reply = match result {
+            Ok(message) => ControlReply { ok: true, message, status },
+            Err(message) => ControlReply { ok: false, message, status: None },
+        };
+        // The client may have gone away in the meantime.
+        let _ = self.reply.send(reply.to_line(self.json));
+    }
+}
+
+/// A Unix domain socket that external scripts drive the simulation through.
+///
+/// Clients connect and send one command per line, as JSON or as words, and get one line
+/// back for each, in the same form, saying whether it was carried out and what was done or
+/// what went wrong. Every client is served on a thread of its own, but the commands are
+/// only carried out by the main loop, which owns the simulation, between two generations.
+/// The socket file is removed again when the socket is dropped.
+pub struct ControlSocket {
+    /// The path of the socket file.
+    path: PathBuf,
+    /// The commands sent by all clients, in the order they arrived.
+    requests: Receiver<ControlRequest>,
+}
+
+#[cfg(unix)]
+impl ControlSocket {
+    /// Creates the socket and starts accepting clients.
+    ///
+    /// A socket file left behind by an earlier run is replaced, but not one that another
+    /// running simulation still listens on, and nothing at the path that is not a socket.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the socket file, such as `/tmp/gol.sock`.
+    pub fn bind(path: &Path) -> Result<ControlSocket, Box<dyn Error>> {
+        if let Ok(metadata) = fs::symlink_metadata(path) {
+            if !metadata.file_type().is_socket() {
+                return Err(format!("{} already exists and is not a socket", path.display()).into());
+            }
+            if UnixStream::connect(path).is_ok() {
+                return Err(format!("{} is already in use by another simulation", path.display()).into());
+            }
+            fs::remove_file(path).map_err(|e| format!("Cannot replace {}: {}", path.display(), e))?;
+        }
+        let listener = UnixListener::bind(path).map_err(|e| format!("Cannot listen on {}: {}", path.display(), e))?;
+
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for client in listener.incoming().flatten() {
+                let sender = sender.clone();
+                thread::spawn(move || serve_client(client, sender));
+            }
+        });
+
+        Ok(ControlSocket { path: path.to_path_buf(), requests })
+    }
+
+    /// Returns the commands that arrived since the last call.
+    pub fn pending(&self) -> Vec<ControlRequest> {
+        self.requests.try_iter().collect()
+    }
+}
+
+#[cfg(not(unix))]
+impl ControlSocket {
+    /// Fails, because control sockets are Unix domain sockets.
+    pub fn bind(_path: &Path) -> Result<ControlSocket, Box<dyn Error>> {
+        Err("--control needs Unix domain sockets, which this platform does not have".into())
+    }
+
+    /// Returns no commands.
+    pub fn pending(&self) -> Vec<ControlRequest> {
+        Vec::new()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Reads the commands of one client and writes back the replies of the main loop.
+#[cfg(unix)]
+fn serve_client(client: UnixStream, requests: Sender<ControlRequest>) {
+    let Ok(mut out) = client.try_clone() else { return };
+    for line in BufReader::new(client).lines() {
+        let Ok(line) = line else { return };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let reply = match ControlRequest::from_line(&line) {
+            Ok((request, answer)) => {
+                if requests.send(request).is_err() {
+                    return;
+                }
+                match answer.recv() {
+                    Ok(reply) => reply,
+                    Err(_) => return,
+                }
+            }
+            Err(reply) => reply,
+        };
+        if writeln!(out, "{}", reply).is_err() {
+            return;
+        }
+    }
+}
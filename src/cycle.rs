@@ -0,0 +1,147 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use crate::hash::BoardHash;
+
+/// The longest oscillation period that is looked for by default.
+pub const DEFAULT_MAX_PERIOD: usize = 1024;
+
+/// What to do once the board has settled into a still life or an oscillation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CyclePolicy {
+    /// Stop the simulation.
+    Stop,
+    /// Report the period and keep going.
+    Report,
+    /// Start again on a fresh board.
+    Restart,
+}
+
+impl CyclePolicy {
+    /// Parses a cycle policy name (`stop`, `report` or `restart`).
+    pub fn parse(name: &str) -> Option<CyclePolicy> {
+        match name {
+            "stop" => Some(CyclePolicy::Stop),
+            "report" => Some(CyclePolicy::Report),
+            "restart" => Some(CyclePolicy::Restart),
+            _ => None,
+        }
+    }
+}
+
+/// A repeating sequence of boards found by a [`CycleDetector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first generation of the cycle.
+    pub start: u64,
+    /// The number of generations before the board repeats; 1 for a still life.
+    pub period: u64,
+}
+
+impl Cycle {
+    /// Returns whether the board stopped changing altogether.
+    pub fn is_still_life(&self) -> bool {
+        self.period == 1
+    }
+}
+
+impl fmt::Display for Cycle {
+    /// Describes the cycle, as in "period 2 oscillation from generation 40".
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_still_life() {
+            write!(f, "still life from generation {}", self.start)
+        } else {
+            write!(f, "period {} oscillation from generation {}", self.period, self.start)
+        }
+    }
+}
+
+/// Detects when a run starts repeating itself, by remembering the hashes of recent boards.
+///
+/// A board that hashes the same as one seen `p` generations earlier has entered a cycle of
+/// period `p`, since the next generation depends only on the current one. Only the last
+/// `max_period` generations are remembered, which bounds the memory used and the longest
+/// period that can be found.
+#[derive(Debug)]
+pub struct CycleDetector {
+    /// The longest period looked for.
+    max_period: usize,
+    /// The generation each remembered hash was last seen at.
+    seen: HashMap<BoardHash, u64>,
+    /// The remembered hashes and their generations, oldest first.
+    order: VecDeque<(BoardHash, u64)>,
+    /// The cycle found, once there is one.
+    found: Option<Cycle>,
+}
+
+impl CycleDetector {
+    /// Creates a detector that has not seen any boards.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_period` - The longest period looked for.
+    pub fn new(max_period: usize) -> CycleDetector {
+        CycleDetector {
+            max_period,
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+            found: None,
+        }
+    }
+
+    /// Records the board of a generation and checks whether it repeats an earlier one.
+    ///
+    /// Boards seen again at the same or a later generation than before, as when stepping
+    /// forward after a rewind, are not counted as cycles.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash` - The hash of the board.
+    /// * `generation` - The generation of the board.
+    ///
+    /// # Returns
+    ///
+    /// The cycle, the first time one is found.
+    pub fn observe(&mut self, hash: BoardHash, generation: u64) -> Option<Cycle> {
+        if self.found.is_some() {
+            return None;
+        }
+
+        if let Some(&earlier) = self.seen.get(&hash) {
+            if earlier < generation {
+                let cycle = Cycle {
+                    start: earlier,
+                    period: generation - earlier,
+                };
+                self.found = Some(cycle);
+                return Some(cycle);
+            }
+        }
+
+        // Forget the oldest board once the window is full.
+        if self.order.len() == self.max_period {
+            if let Some((old_hash, old_generation)) = self.order.pop_front() {
+                if self.seen.get(&old_hash) == Some(&old_generation) {
+                    self.seen.remove(&old_hash);
+                }
+            }
+        }
+        self.seen.insert(hash, generation);
+        self.order.push_back((hash, generation));
+        None
+    }
+
+    /// Returns the cycle found so far, if any.
+    pub fn found(&self) -> Option<Cycle> {
+        self.found
+    }
+
+    /// Forgets every board seen, for a run that starts over.
+    pub fn reset(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+        self.found = None;
+    }
+}
@@ -0,0 +1,1764 @@
+use crossterm::{
+    cursor,
+    event::DisableBracketedPaste,
+    execute,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    QueueableCommand,
+};
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::File,
+    io::{self, stdout, Read, Stdout, Write},
+    mem, panic,
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver, SyncSender},
+        Once,
+    },
+    thread,
+    time::Duration,
+};
+
+use gameoflife::{
+    heatmap::{self, Heatmap},
+    iterm, kitty,
+    raster::{self, Image, RasterStyle, Rgb},
+    recorder::FrameRecorder,
+    sixel,
+    trails::Trails,
+    Grid,
+};
+
+use crate::asciicast::CastRecorder;
+
+/// The terminal output, optionally copied into an asciicast recording.
+///
+/// All drawing goes through this writer, so a recording sees exactly the commands
+/// sent to the terminal. The commands of a frame are collected in memory and sent to the
+/// terminal in one write when it is flushed, so the terminal never shows a half-drawn
+/// frame. Each flush ends one recorded event.
+pub struct Terminal {
+    /// The real terminal.
+    stdout: Stdout,
+    /// The output of the frame being drawn.
+    frame: Vec<u8>,
+    /// The recording of the output, if one was requested.
+    cast: Option<CastRecorder>,
+    /// Whether the screen was cleared in the frame being drawn, so nothing drawn before
+    /// is on it, not even the fill behind the dead cells.
+    cleared: bool,
+    /// The width and height in pixels of the image of the board on the screen, if it is
+    /// drawn as pixels.
+    image: Option<(usize, usize)>,
+    /// The thread the frames are written to the terminal on, if they are not written
+    /// when flushed.
+    writer: Option<FrameWriter>,
+}
+
+impl Terminal {
+    /// Creates the terminal output.
+    ///
+    /// # Arguments
+    ///
+    /// * `cast` - A recording to copy the output into.
+    pub fn new(cast: Option<CastRecorder>) -> Terminal {
+        Terminal { stdout: stdout(), frame: Vec::new(), cast, cleared: false, image: None, writer: None }
+    }
+
+    /// Creates the terminal output, writing the frames to the terminal on a thread of its
+    /// own.
+    ///
+    /// A flush then only hands the frame over, so a slow terminal holds up the writes
+    /// instead of the loop drawing the frames. That loop asks whether the terminal is
+    /// [ready](Terminal::ready) for another frame, and skips frames while it is not.
+    ///
+    /// # Arguments
+    ///
+    /// * `cast` - A recording to copy the output into.
+    pub fn with_writer_thread(cast: Option<CastRecorder>) -> Result<Terminal, Box<dyn Error>> {
+        Ok(Terminal { writer: Some(FrameWriter::spawn()?), ..Terminal::new(cast) })
+    }
+
+    /// Returns whether another frame can be flushed without waiting for the terminal.
+    ///
+    /// Without a writer thread, flushing writes the frame at once, so this is always true.
+    pub fn ready(&mut self) -> io::Result<bool> {
+        match self.writer.as_mut() {
+            Some(writer) => {
+                writer.collect()?;
+                Ok(writer.in_flight <= FRAMES_QUEUED)
+            }
+            None => Ok(true),
+        }
+    }
+
+    /// Waits until every frame flushed so far is on the terminal, before anything else
+    /// is written to it.
+    pub fn wait(&mut self) -> io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.wait_for(0),
+            None => Ok(()),
+        }
+    }
+
+    /// Finishes the recording, if there is one.
+    ///
+    /// # Returns
+    ///
+    /// The path of the recording.
+    pub fn finish(self) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        self.cast.map(CastRecorder::finish).transpose()
+    }
+}
+
+impl Write for Terminal {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.frame.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if let Some(cast) = self.cast.as_mut() {
+            cast.feed(&self.frame);
+            cast.write_event()?;
+        }
+        match self.writer.as_mut() {
+            Some(writer) => writer.send(&mut self.frame)?,
+            None => {
+                let mut stdout = self.stdout.lock();
+                stdout.write_all(&self.frame)?;
+                stdout.flush()?;
+                self.frame.clear();
+            }
+        }
+        self.cleared = false;
+        Ok(())
+    }
+}
+
+/// The number of finished frames that can wait for the terminal while it writes another.
+const FRAMES_QUEUED: usize = 1;
+
+/// A thread that writes finished frames to the terminal.
+///
+/// The frames go to it through a bounded channel, and their buffers come back emptied once
+/// they are written, to draw later frames into.
+struct FrameWriter {
+    /// The frames to write.
+    frames: SyncSender<Vec<u8>>,
+    /// The buffers of the frames written, or the errors writing them.
+    written: Receiver<io::Result<Vec<u8>>>,
+    /// The number of frames sent and not yet written.
+    in_flight: usize,
+    /// An emptied buffer to draw the next frame into.
+    spare: Vec<u8>,
+}
+
+impl FrameWriter {
+    /// Starts the thread.
+    fn spawn() -> io::Result<FrameWriter> {
+        let (frames, queue) = mpsc::sync_channel::<Vec<u8>>(FRAMES_QUEUED);
+        let (done, written) = mpsc::channel();
+        thread::Builder::new().name("terminal".to_string()).spawn(move || {
+            let mut stdout = stdout();
+            for mut frame in queue {
+                let result = stdout.write_all(&frame).and_then(|()| stdout.flush());
+                frame.clear();
+                if done.send(result.map(|()| frame)).is_err() {
+                    break;
+                }
+            }
+        })?;
+        Ok(FrameWriter { frames, written, in_flight: 0, spare: Vec::new() })
+    }
+
+    /// Takes back the buffers of the frames written so far, without waiting.
+    fn collect(&mut self) -> io::Result<()> {
+        while let Ok(written) = self.written.try_recv() {
+            self.in_flight -= 1;
+            self.spare = written?;
+        }
+        Ok(())
+    }
+
+    /// Waits until at most a given number of frames are left to write.
+    fn wait_for(&mut self, in_flight: usize) -> io::Result<()> {
+        while self.in_flight > in_flight {
+            let written = self.written.recv().map_err(|_| io::Error::other("the terminal writer stopped"))?;
+            self.in_flight -= 1;
+            self.spare = written?;
+        }
+        Ok(())
+    }
+
+    /// Hands a frame over to be written, waiting for room in the queue first.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame, which is left empty to draw the next one into.
+    fn send(&mut self, frame: &mut Vec<u8>) -> io::Result<()> {
+        self.wait_for(FRAMES_QUEUED)?;
+        let frame = mem::replace(frame, mem::take(&mut self.spare));
+        self.frames.send(frame).map_err(|_| io::Error::other("the terminal writer stopped"))?;
+        self.in_flight += 1;
+        Ok(())
+    }
+}
+
+impl Drop for FrameWriter {
+    /// Waits for the frames sent to be written, so they are on the screen they were drawn
+    /// for. The thread ends once the channel closes.
+    fn drop(&mut self) {
+        let _ = self.wait_for(0);
+    }
+}
+
+/// Keeps the simulation on the terminal's alternate screen, with the cursor hidden, for as
+/// long as it is alive.
+///
+/// The alternate screen leaves the user's screen and scrollback as they were, and they
+/// come back when the guard is dropped, which happens when the program exits early with
+/// an error as well. A panic also puts the terminal back, including leaving raw mode,
+/// before its message is printed, so the message is readable and stays on screen.
+pub struct Screen;
+
+impl Screen {
+    /// Switches to the alternate screen and hides the cursor.
+    pub fn enter() -> Result<Screen, Box<dyn Error>> {
+        static PANIC_HOOK: Once = Once::new();
+        PANIC_HOOK.call_once(|| {
+            let hook = panic::take_hook();
+            panic::set_hook(Box::new(move |info| {
+                restore_screen();
+                hook(info);
+            }));
+        });
+
+        execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), ResetColor, cursor::Show, LeaveAlternateScreen);
+    }
+}
+
+/// Puts the terminal back as it was before the simulation, from wherever it stopped.
+fn restore_screen() {
+    let _ = execute!(stdout(), DisableBracketedPaste, ResetColor, cursor::Show, LeaveAlternateScreen);
+    let _ = terminal::disable_raw_mode();
+}
+
+/// Collects the characters drawn on one terminal row into runs.
+///
+/// Characters next to each other share one cursor move, and those of one color one print,
+/// so a frame that redraws most of the screen costs a few commands per row rather than
+/// three or four per character.
+struct RowRun {
+    /// The terminal row.
+    row: usize,
+    /// Whether a single blank column between two characters joins them into one run, as
+    /// between the cells of a hex board.
+    bridge_gaps: bool,
+    /// The color behind the characters that are not given one, or `None` for the
+    /// terminal's default.
+    backdrop: Option<Color>,
+    /// Whether the terminal shows 24-bit colors, or only the 256-color palette.
+    true_color: bool,
+    /// The terminal column just past the last character of the run, if one is open.
+    end: Option<usize>,
+    /// The color of the characters waiting to be printed.
+    color: Option<Color>,
+    /// The color behind the characters waiting to be printed.
+    background: Option<Color>,
+    /// The characters waiting to be printed.
+    text: String,
+}
+
+impl RowRun {
+    /// Starts collecting the characters of a row.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The terminal row.
+    /// * `bridge_gaps` - Whether the columns between the characters are always blank, so
+    ///   they can be printed over to join two characters one column apart.
+    /// * `style` - The style, with the background and the colors the terminal shows.
+    fn new(row: usize, bridge_gaps: bool, style: &DisplayStyle) -> RowRun {
+        let backdrop = style.fit(style.background);
+        RowRun { row, bridge_gaps, backdrop, true_color: style.true_color, end: None, color: None, background: backdrop, text: String::new() }
+    }
+
+    /// Adds a character to the row, continuing the current run if it is next to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `column` - The terminal column of the character.
+    /// * `glyph` - The character.
+    /// * `color` - Its color, or `None` for the terminal's default.
+    fn put(&mut self, out: &mut impl Write, column: usize, glyph: char, color: Option<Color>) -> Result<(), Box<dyn Error>> {
+        self.put_on(out, column, glyph, color, self.backdrop)
+    }
+
+    /// Adds a character to the row on a background of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `column` - The terminal column of the character.
+    /// * `glyph` - The character.
+    /// * `color` - Its color, or `None` for the terminal's default.
+    /// * `background` - The color behind it, or `None` for the terminal's default. A
+    ///   bridged gap before the character is drawn on it too.
+    fn put_on(&mut self, out: &mut impl Write, column: usize, glyph: char, color: Option<Color>, background: Option<Color>) -> Result<(), Box<dyn Error>> {
+        let (color, background) = if self.true_color { (color, background) } else { (color.map(downsample), background.map(downsample)) };
+        let bridged = self.bridge_gaps && self.end == Some(column.wrapping_sub(1));
+        if self.end != Some(column) && !bridged {
+            self.print(out)?;
+            out.queue(cursor::MoveTo(column as u16, self.row as u16))?;
+        } else if self.color != color || self.background != background {
+            self.print(out)?;
+        }
+        if bridged {
+            self.text.push(' ');
+        }
+        self.color = color;
+        self.background = background;
+        self.text.push(glyph);
+        self.end = Some(column + 1);
+        Ok(())
+    }
+
+    /// Prints the characters waiting to be printed.
+    fn print(&mut self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        if self.text.is_empty() {
+            return Ok(());
+        }
+        print_colored(out, &self.text, self.color, self.background)?;
+        self.text.clear();
+        Ok(())
+    }
+}
+
+/// Prints text at the cursor in the given colors, then goes back to the terminal's own.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `text` - The text.
+/// * `color` - The color of the text, or `None` for the terminal's default.
+/// * `background` - The color behind the text, or `None` for the terminal's default.
+fn print_colored(out: &mut impl Write, text: &str, color: Option<Color>, background: Option<Color>) -> Result<(), Box<dyn Error>> {
+    if let Some(color) = color {
+        out.queue(SetForegroundColor(color))?;
+    }
+    if let Some(background) = background {
+        out.queue(SetBackgroundColor(background))?;
+    }
+    out.queue(Print(text))?;
+    if color.is_some() || background.is_some() {
+        out.queue(ResetColor)?;
+    }
+    Ok(())
+}
+
+/// Returns the nearest color of the 256-color palette to a 24-bit color.
+fn downsample(color: Color) -> Color {
+    match color {
+        Color::Rgb { r, g, b } => Color::AnsiValue(Rgb(r, g, b).ansi256()),
+        color => color,
+    }
+}
+
+/// Checks whether the terminal shows 24-bit colors, as terminals that do announce in
+/// `$COLORTERM`.
+pub fn supports_true_color() -> bool {
+    env::var("COLORTERM").is_ok_and(|value| matches!(value.as_str(), "truecolor" | "24bit"))
+}
+
+/// Checks whether the terminal is one known to draw the sextant characters.
+///
+/// Terminals cannot be asked which characters their font has, so this goes by the
+/// terminals that draw the Symbols for Legacy Computing themselves instead of from the
+/// font: kitty, WezTerm, foot, Ghostty, and Contour.
+pub fn supports_sextants() -> bool {
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    term == "xterm-kitty"
+        || term.starts_with("foot")
+        || term.starts_with("contour")
+        || matches!(program.as_str(), "WezTerm" | "ghostty")
+}
+
+/// Clears the whole terminal to the background color of a style.
+///
+/// The screen is cleared with the next frame, so it is never shown blank in between.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `style` - The style whose background fills the screen.
+pub fn clear_screen(out: &mut Terminal, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+    // Terminals erase with the current background color.
+    match style.fit(style.background) {
+        Some(background) => {
+            out.queue(SetBackgroundColor(background))?;
+            out.queue(Clear(ClearType::All))?;
+            out.queue(ResetColor)?;
+        }
+        None => {
+            out.queue(Clear(ClearType::All))?;
+        }
+    }
+    out.cleared = true;
+    Ok(())
+}
+
+/// How cells are mapped to characters on the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    /// One cell per character, drawn with the configured glyphs.
+    Ascii,
+    /// Two vertically stacked cells per character, drawn with half-block characters.
+    HalfBlock,
+    /// A 2×3 block of cells per character, drawn with the sextant characters of Unicode's
+    /// Symbols for Legacy Computing.
+    Sextant,
+    /// A 2×4 block of cells per character, drawn with braille dots.
+    Braille,
+    /// One cell per two characters, with odd rows shifted one character to the right so
+    /// the cells sit like a honeycomb. Used for rules on the hexagonal neighbourhood.
+    Hex,
+    /// One cell per character, colored by how often it has been alive recently.
+    Heatmap,
+    /// A 4×8 block of cells per character, drawn as pixels with sixel graphics.
+    Sixel,
+    /// A 4×8 block of cells per character, drawn as pixels with the kitty graphics
+    /// protocol.
+    Kitty,
+    /// A 4×8 block of cells per character, drawn as pixels with iTerm2's inline images.
+    ITerm,
+}
+
+impl RenderMode {
+    /// Parses a render mode name (`ascii`, `half-block`, `sextant`, `braille`, `heatmap`,
+    /// `sixel`, `kitty` or `iterm`).
+    pub fn parse(name: &str) -> Option<RenderMode> {
+        match name {
+            "ascii" => Some(RenderMode::Ascii),
+            "half-block" | "halfblock" => Some(RenderMode::HalfBlock),
+            "sextant" => Some(RenderMode::Sextant),
+            "braille" => Some(RenderMode::Braille),
+            "heatmap" => Some(RenderMode::Heatmap),
+            "sixel" => Some(RenderMode::Sixel),
+            "kitty" => Some(RenderMode::Kitty),
+            "iterm" | "iterm2" => Some(RenderMode::ITerm),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of cell columns and rows covered by one character.
+    pub fn cells_per_char(&self) -> (usize, usize) {
+        match self {
+            RenderMode::Ascii => (1, 1),
+            RenderMode::HalfBlock => (1, 2),
+            RenderMode::Sextant => (2, 3),
+            RenderMode::Braille => (2, 4),
+            RenderMode::Sixel | RenderMode::Kitty | RenderMode::ITerm => (4, 8),
+            RenderMode::Hex | RenderMode::Heatmap => (1, 1),
+        }
+    }
+
+    /// Returns whether the board is drawn as pixels instead of characters.
+    pub fn draws_pixels(&self) -> bool {
+        matches!(self, RenderMode::Sixel | RenderMode::Kitty | RenderMode::ITerm)
+    }
+
+    /// Returns the number of cell columns that fit in a number of terminal columns.
+    pub fn board_columns(&self, cols: usize) -> usize {
+        match self {
+            RenderMode::Hex => cols / 2,
+            _ => cols * self.cells_per_char().0,
+        }
+    }
+
+    /// Returns the cell in the middle of a terminal character, counted from the cell at the
+    /// top-left corner of the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The terminal column of the character.
+    /// * `row` - The terminal row of the character.
+    pub fn cell_under(&self, column: usize, row: usize) -> (usize, usize) {
+        match self {
+            // Every other row of a hex board is shifted right by half a cell.
+            RenderMode::Hex => (column.saturating_sub(row % 2) / 2, row),
+            _ => {
+                let (cols, rows) = self.cells_per_char();
+                (column * cols + cols / 2, row * rows + rows / 2)
+            }
+        }
+    }
+
+    /// Returns the number of terminal columns needed to draw a number of cell columns.
+    pub fn console_columns(&self, width: usize) -> usize {
+        match self {
+            RenderMode::Hex => width * 2,
+            _ => width.div_ceil(self.cells_per_char().0),
+        }
+    }
+}
+
+/// How the dead cells of a board are filled in with `--dead-fill`, so the extent of the
+/// board shows on any terminal background.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadFill {
+    /// Every dead cell is on the fill color.
+    Solid,
+    /// Every other cell is on the fill color, the rest on the background, like a checkerboard.
+    Checker,
+}
+
+impl DeadFill {
+    /// Parses a fill name: `solid` or `checker`.
+    pub fn parse(name: &str) -> Option<DeadFill> {
+        match name {
+            "solid" => Some(DeadFill::Solid),
+            "checker" | "checkerboard" => Some(DeadFill::Checker),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RenderMode {
+    /// Shows the render mode by the name it is given on the command line.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RenderMode::Ascii => "ascii",
+            RenderMode::HalfBlock => "half-block",
+            RenderMode::Sextant => "sextant",
+            RenderMode::Braille => "braille",
+            RenderMode::Hex => "hex",
+            RenderMode::Heatmap => "heatmap",
+            RenderMode::Sixel => "sixel",
+            RenderMode::Kitty => "kitty",
+            RenderMode::ITerm => "iterm",
+        })
+    }
+}
+
+/// The glyphs, colors, and render mode used to draw the grid and its status bar.
+#[derive(Clone, Debug)]
+pub struct DisplayStyle {
+    /// How cells are mapped to characters.
+    pub mode: RenderMode,
+    /// The character drawn for live cells in ASCII mode.
+    pub alive_glyph: char,
+    /// The character drawn for dead cells in ASCII mode.
+    pub dead_glyph: char,
+    /// The color of live cells, or `None` for the terminal's default color.
+    pub alive_color: Option<Color>,
+    /// The color of dead-cell glyphs, or `None` for the terminal's default color.
+    pub dead_color: Option<Color>,
+    /// The color the ghost trails of dying cells fade from, or `None` for the live-cell color.
+    pub dying_color: Option<Color>,
+    /// The color behind the board, or `None` for the terminal's default color.
+    pub background: Option<Color>,
+    /// The color of the status bar text, or `None` for the terminal's default color.
+    pub status_color: Option<Color>,
+    /// The color behind the status bar, or `None` for the terminal's default color.
+    pub status_background: Option<Color>,
+    /// Whether the terminal shows 24-bit colors. Without them, colors are drawn in the
+    /// nearest of the 256-color palette.
+    pub true_color: bool,
+    /// How the dead cells are filled in, if they are.
+    pub dead_fill: Option<DeadFill>,
+    /// The color the dead cells are filled in with.
+    pub dead_fill_color: Color,
+}
+
+impl DisplayStyle {
+    /// Returns the color behind a character of the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `cx` - The column of the character, counted in cells' characters.
+    /// * `cy` - The row of the character.
+    fn fill_at(&self, cx: usize, cy: usize) -> Option<Color> {
+        match self.dead_fill {
+            Some(DeadFill::Solid) => Some(self.dead_fill_color),
+            Some(DeadFill::Checker) if (cx + cy).is_multiple_of(2) => Some(self.dead_fill_color),
+            _ => self.background,
+        }
+    }
+
+    /// Returns a color as the terminal can show it.
+    fn fit(&self, color: Option<Color>) -> Option<Color> {
+        if self.true_color {
+            color
+        } else {
+            color.map(downsample)
+        }
+    }
+}
+
+impl Default for DisplayStyle {
+    fn default() -> Self {
+        DisplayStyle {
+            mode: RenderMode::Ascii,
+            alive_glyph: '#',
+            dead_glyph: ' ',
+            alive_color: None,
+            dead_color: None,
+            dying_color: None,
+            background: None,
+            status_color: None,
+            status_background: None,
+            true_color: true,
+            dead_fill: None,
+            dead_fill_color: DEFAULT_DEAD_FILL_COLOR,
+        }
+    }
+}
+
+/// The color dead cells are filled in with by default: a gray just above black.
+pub const DEFAULT_DEAD_FILL_COLOR: Color = Color::Rgb { r: 38, g: 38, b: 44 };
+
+/// Parses a terminal color: a name like `green` or `dark_blue`, an ANSI color number
+/// from 0 to 255, or a hex RGB value like `#33ff66`.
+///
+/// # Arguments
+///
+/// * `value` - The color to parse.
+///
+/// # Returns
+///
+/// The color, or `None` if the value is not a valid color.
+pub fn parse_color(value: &str) -> Option<Color> {
+    let value = value.trim();
+
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some(Color::Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        });
+    }
+
+    if let Ok(ansi) = value.parse::<u8>() {
+        return Some(Color::AnsiValue(ansi));
+    }
+
+    Color::try_from(value).ok()
+}
+
+/// Returns the character for a group of cells covered by one character.
+///
+/// # Arguments
+///
+/// * `grid` - The grid.
+/// * `cx` - The column of the character.
+/// * `cy` - The row of the character.
+/// * `style` - The display style.
+///
+/// # Returns
+///
+/// The character, and whether any of its cells are alive.
+pub fn glyph_at(grid: &[Vec<bool>], cx: usize, cy: usize, style: &DisplayStyle) -> (char, bool) {
+    let cell = |x: usize, y: usize| grid.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false);
+
+    match style.mode {
+        RenderMode::Ascii | RenderMode::Hex | RenderMode::Heatmap | RenderMode::Sixel | RenderMode::Kitty | RenderMode::ITerm => {
+            if cell(cx, cy) {
+                (style.alive_glyph, true)
+            } else {
+                (style.dead_glyph, false)
+            }
+        }
+        RenderMode::HalfBlock => {
+            let glyph = match (cell(cx, cy * 2), cell(cx, cy * 2 + 1)) {
+                (false, false) => ' ',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (true, true) => '█',
+            };
+            (glyph, glyph != ' ')
+        }
+        RenderMode::Sextant => {
+            // Sextants are numbered across each row, from the top.
+            let mut bits = 0;
+            for dy in 0..3 {
+                for dx in 0..2 {
+                    if cell(cx * 2 + dx, cy * 3 + dy) {
+                        bits |= 1 << (dy * 2 + dx);
+                    }
+                }
+            }
+            (sextant(bits), bits != 0)
+        }
+        RenderMode::Braille => {
+            // Braille dots are numbered down the left column, then the right, with the bottom row last.
+            const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+
+            let mut bits = 0;
+            for (dy, row) in DOTS.iter().enumerate() {
+                for (dx, &dot) in row.iter().enumerate() {
+                    if cell(cx * 2 + dx, cy * 4 + dy) {
+                        bits |= dot;
+                    }
+                }
+            }
+
+            match bits {
+                0 => (' ', false),
+                _ => (char::from_u32(0x2800 + bits).unwrap_or(' '), true),
+            }
+        }
+    }
+}
+
+/// Returns the sextant character for a 2×3 block of cells.
+///
+/// The sextants run from U+1FB00 in the order of their bits, leaving out the empty and full
+/// blocks and the left and right halves, which are the older space, `█`, `▌`, and `▐`.
+///
+/// # Arguments
+///
+/// * `bits` - The live cells, with bit `2 * row + column` set for each, from the top left.
+fn sextant(bits: u32) -> char {
+    const LEFT: u32 = 0b010101;
+    const RIGHT: u32 = 0b101010;
+    match bits {
+        0 => ' ',
+        LEFT => '▌',
+        RIGHT => '▐',
+        0b111111 => '█',
+        _ => {
+            let skipped = (bits > LEFT) as u32 + (bits > RIGHT) as u32;
+            char::from_u32(0x1FB00 + bits - 1 - skipped).unwrap_or(' ')
+        }
+    }
+}
+
+/// The states of a multi-state board, drawn in color over the live cells.
+pub struct CellStates<'a> {
+    /// The state of each cell: 0 for dead, otherwise the number of its color in the palette, from 1.
+    pub states: &'a [Vec<u8>],
+    /// The states drawn last time; characters whose states changed are redrawn.
+    pub prev_states: &'a [Vec<u8>],
+    /// The color of each live state, in order.
+    pub palette: &'a [Rgb],
+}
+
+impl CellStates<'_> {
+    /// Checks whether the state of any cell covered by a character changed since the last frame.
+    fn changed(&self, cx: usize, cy: usize, mode: RenderMode) -> bool {
+        let (cw, ch) = mode.cells_per_char();
+        (cy * ch..(cy + 1) * ch).any(|y| {
+            (cx * cw..(cx + 1) * cw).any(|x| {
+                let state = |states: &[Vec<u8>]| states.get(y).and_then(|row| row.get(x)).copied();
+                state(self.states) != state(self.prev_states)
+            })
+        })
+    }
+
+    /// Returns the color of the first live cell covered by a character, if any.
+    fn color_at(&self, cx: usize, cy: usize, mode: RenderMode) -> Option<Color> {
+        let (cw, ch) = mode.cells_per_char();
+        (cy * ch..(cy + 1) * ch)
+            .flat_map(|y| (cx * cw..(cx + 1) * cw).map(move |x| (x, y)))
+            .filter_map(|(x, y)| self.states.get(y).and_then(|row| row.get(x)).copied())
+            .find(|&state| state != 0)
+            .and_then(|state| self.palette.get(state as usize - 1))
+            .map(|&Rgb(r, g, b)| Color::Rgb { r, g, b })
+    }
+}
+
+/// Checks whether any cell covered by a character changed between two grids.
+fn char_changed(grid: &[Vec<bool>], prev_grid: &[Vec<bool>], cx: usize, cy: usize, mode: RenderMode) -> bool {
+    let (cw, ch) = mode.cells_per_char();
+    (cy * ch..(cy + 1) * ch).any(|y| {
+        (cx * cw..(cx + 1) * cw).any(|x| {
+            let cell = |grid: &[Vec<bool>]| grid.get(y).and_then(|row| row.get(x)).copied();
+            cell(grid) != cell(prev_grid)
+        })
+    })
+}
+
+/// A rectangle of the terminal that a board is drawn into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// The terminal column of the left edge.
+    pub left: usize,
+    /// The terminal row of the top edge.
+    pub top: usize,
+    /// The number of terminal columns.
+    pub cols: usize,
+    /// The number of terminal rows.
+    pub rows: usize,
+}
+
+impl Region {
+    /// Returns the region from the top-left corner of the terminal.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The number of terminal columns.
+    /// * `rows` - The number of terminal rows.
+    pub fn screen(cols: usize, rows: usize) -> Region {
+        Region { left: 0, top: 0, cols, rows }
+    }
+
+    /// Returns whether the region shares any terminal cell with another.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The other region.
+    pub fn overlaps(&self, other: &Region) -> bool {
+        self.left < other.left + other.cols
+            && other.left < self.left + self.cols
+            && self.top < other.top + other.rows
+            && other.top < self.top + self.rows
+    }
+}
+
+/// A board to draw, with the one drawn before it, so only what changed is redrawn.
+pub struct Frame<'a> {
+    /// The board to draw.
+    pub grid: &'a [Vec<bool>],
+    /// The board drawn last, which the screen still shows.
+    pub prev_grid: &'a [Vec<bool>],
+    /// The cell states of a multi-state variant, whose colors replace the live-cell color.
+    pub states: Option<&'a CellStates<'a>>,
+    /// The part of the terminal available for the board.
+    pub region: Region,
+    /// The board cell at the top-left corner of `grid`, when only part of the board is in
+    /// view.
+    pub origin: (usize, usize),
+}
+
+/// Draws the board on the terminal, in one of the render modes.
+///
+/// The main loop draws every frame through a renderer and knows nothing of how it turns
+/// cells into characters or pixels, so a new render mode only needs a renderer of its own
+/// and a line in [`renderer`]. Renderers that keep something of the generations before,
+/// such as the heatmap, keep it themselves, and [`Renderers`] draws with several at once,
+/// such as the one of the render mode and a [`Recording`].
+pub trait Renderer {
+    /// Prepares the screen before the first frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `style` - The style the frames are drawn with.
+    fn init(&mut self, out: &mut Terminal, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        clear_screen(out, style)
+    }
+
+    /// Sees a generation the simulation computed, whether or not a frame is drawn of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The whole board.
+    /// * `generation` - The generation number of the board.
+    fn record(&mut self, _grid: &[Vec<bool>], _generation: u64) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Draws a frame, redrawing only what changed since the board drawn last unless the
+    /// screen was cleared since.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `frame` - The board to draw and the one drawn last.
+    /// * `style` - The glyphs, colors, and render mode to draw with.
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>>;
+
+    /// Lets the renderer know the terminal changed size. The screen is cleared and the
+    /// next frame drawn from scratch after this.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `style` - The style the frames are drawn with.
+    fn resize(&mut self, _out: &mut Terminal, _style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Takes away whatever the renderer left on the terminal that clearing the screen does
+    /// not, before the program exits.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    fn shutdown(&mut self, _out: &mut Terminal) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    /// Finishes the files the renderer wrote besides the terminal, such as a recording.
+    ///
+    /// # Returns
+    ///
+    /// The paths of the files.
+    fn finish(&mut self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Returns the renderer a render mode draws with.
+///
+/// # Arguments
+///
+/// * `mode` - The render mode.
+/// * `trails` - The number of generations the trails behind dying cells last, if asked
+///   for with `--trails`. Only ASCII boards are drawn with trails.
+pub fn renderer(mode: RenderMode, trails: Option<u8>) -> Box<dyn Renderer> {
+    match (mode, trails) {
+        (RenderMode::Heatmap, _) => Box::new(HeatmapRenderer::default()),
+        (RenderMode::Ascii, Some(length)) => Box::new(TrailsRenderer::new(length)),
+        _ => board_renderer(mode),
+    }
+}
+
+/// Returns the renderer that draws a board as it is, without anything kept of the
+/// generations before it.
+///
+/// # Arguments
+///
+/// * `mode` - The render mode.
+fn board_renderer(mode: RenderMode) -> Box<dyn Renderer> {
+    match graphics_protocol(mode) {
+        Some(protocol) => Box::new(PixelRenderer { protocol }),
+        None => Box::new(CharacterRenderer),
+    }
+}
+
+/// Draws with several renderers in turn, which all see the same generations and frames.
+///
+/// The main loop draws through one of these holding the renderer of the render mode and
+/// a [`Recording`] for every file recorded, so the terminal and the recordings are fed
+/// alike.
+pub struct Renderers {
+    /// The renderers, in the order they draw.
+    renderers: Vec<Box<dyn Renderer>>,
+}
+
+impl Renderers {
+    /// Combines renderers.
+    ///
+    /// # Arguments
+    ///
+    /// * `renderers` - The renderers, in the order they draw.
+    pub fn new(renderers: Vec<Box<dyn Renderer>>) -> Renderers {
+        Renderers { renderers }
+    }
+}
+
+impl Renderer for Renderers {
+    fn init(&mut self, out: &mut Terminal, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        self.renderers.iter_mut().try_for_each(|renderer| renderer.init(out, style))
+    }
+
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        self.renderers.iter_mut().try_for_each(|renderer| renderer.record(grid, generation))
+    }
+
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        self.renderers.iter_mut().try_for_each(|renderer| renderer.draw_frame(out, frame, style))
+    }
+
+    fn resize(&mut self, out: &mut Terminal, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        self.renderers.iter_mut().try_for_each(|renderer| renderer.resize(out, style))
+    }
+
+    fn shutdown(&mut self, out: &mut Terminal) -> Result<(), Box<dyn Error>> {
+        self.renderers.iter_mut().try_for_each(|renderer| renderer.shutdown(out))
+    }
+
+    fn finish(&mut self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        let mut paths = Vec::new();
+        for renderer in self.renderers.iter_mut() {
+            paths.extend(renderer.finish()?);
+        }
+        Ok(paths)
+    }
+}
+
+/// Records every generation into a file, such as an animated GIF with `--record`, and
+/// draws nothing on the terminal.
+pub struct Recording {
+    /// The recorder, until the recording is finished.
+    recorder: Option<Box<dyn FrameRecorder>>,
+}
+
+impl Recording {
+    /// Creates a renderer that feeds a recorder.
+    ///
+    /// # Arguments
+    ///
+    /// * `recorder` - The recorder.
+    pub fn new(recorder: Box<dyn FrameRecorder>) -> Recording {
+        Recording { recorder: Some(recorder) }
+    }
+}
+
+impl Renderer for Recording {
+    fn init(&mut self, _out: &mut Terminal, _style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        match self.recorder.as_mut() {
+            Some(recorder) => recorder.record(grid, generation),
+            None => Ok(()),
+        }
+    }
+
+    fn draw_frame(&mut self, _out: &mut Terminal, _frame: &Frame, _style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        self.recorder.take().map(|recorder| recorder.finish()).into_iter().collect()
+    }
+}
+
+/// Prints the grid to the console with the renderer of the style's render mode.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `grid` - The grid to be printed.
+/// * `prev_grid` - The previous grid state.
+/// * `style` - The glyphs, colors, and render mode to draw with.
+/// * `states` - The cell states of a multi-state variant, whose colors replace the live-cell color.
+/// * `region` - The part of the terminal available for the grid.
+pub fn display_grid(
+    out: &mut Terminal,
+    grid: &[Vec<bool>],
+    prev_grid: &[Vec<bool>],
+    style: &DisplayStyle,
+    states: Option<&CellStates>,
+    region: Region,
+) -> Result<(), Box<dyn Error>> {
+    board_renderer(style.mode).draw_frame(out, &Frame { grid, prev_grid, states, region, origin: (0, 0) }, style)
+}
+
+/// Draws the character render modes: ASCII, half blocks, sextants, braille, and hex.
+///
+/// Only the characters whose cells changed since the previous grid are redrawn, and
+/// with `--dead-fill` also the dead ones on a screen that was just cleared. A grid larger
+/// than the region is shown from its top-left corner.
+pub struct CharacterRenderer;
+
+impl Renderer for CharacterRenderer {
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        let &Frame { grid, prev_grid, states, region, .. } = frame;
+        draw_characters(out, grid, prev_grid, style, states, region)
+    }
+}
+
+/// Draws the grid as characters, for [`CharacterRenderer`].
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `grid` - The grid to be printed.
+/// * `prev_grid` - The previous grid state.
+/// * `style` - The glyphs, colors, and render mode to draw with.
+/// * `states` - The cell states of a multi-state variant, whose colors replace the live-cell color.
+/// * `region` - The part of the terminal available for the grid.
+fn draw_characters(
+    out: &mut Terminal,
+    grid: &[Vec<bool>],
+    prev_grid: &[Vec<bool>],
+    style: &DisplayStyle,
+    states: Option<&CellStates>,
+    region: Region,
+) -> Result<(), Box<dyn Error>> {
+    let (cw, ch) = style.mode.cells_per_char();
+    let rows = grid.len().div_ceil(ch).min(region.rows);
+    let width = grid.first().map(|row| row.len()).unwrap_or(0);
+    let cols = if style.mode == RenderMode::Hex {
+        style.mode.board_columns(region.cols).min(width)
+    } else {
+        width.div_ceil(cw).min(region.cols)
+    };
+
+    // Clearing the screen takes the fill away from the cells that stayed dead.
+    let refill = out.cleared && style.dead_fill.is_some();
+    for cy in 0..rows {
+        let mut run = RowRun::new(region.top + cy, style.mode == RenderMode::Hex, style);
+        for cx in 0..cols {
+            let states_changed = states.is_some_and(|states| states.changed(cx, cy, style.mode));
+            if !refill && !states_changed && !char_changed(grid, prev_grid, cx, cy, style.mode) {
+                continue;
+            }
+
+            let (glyph, alive) = glyph_at(grid, cx, cy, style);
+            // Hex cells are two characters apart, and odd rows start half a cell in.
+            let column = if style.mode == RenderMode::Hex { cx * 2 + cy % 2 } else { cx };
+            let color = match (alive, states) {
+                (true, Some(states)) => states.color_at(cx, cy, style.mode).or(style.alive_color),
+                (true, None) => style.alive_color,
+                (false, _) => style.dead_color,
+            };
+            run.put_on(out, region.left + column, glyph, color, style.fill_at(cx, cy))?;
+        }
+        run.print(out)?;
+    }
+    Ok(())
+}
+
+/// The color live cells are drawn in as pixels, if they use the terminal's default.
+const PIXEL_ALIVE: Rgb = Rgb(220, 220, 220);
+
+/// The size of a cell in pixels, when the terminal does not tell the size of its
+/// characters.
+const PIXEL_CELL_SIZE: usize = 2;
+
+/// Returns the cells of the grid that fit in the region.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to be drawn.
+/// * `mode` - The render mode, which sets how many cells go in a character.
+/// * `region` - The part of the terminal available for the grid.
+fn visible_cells(grid: &[Vec<bool>], mode: RenderMode, region: Region) -> Grid {
+    let (cw, ch) = mode.cells_per_char();
+    grid.iter()
+        .take(region.rows * ch)
+        .map(|row| row.iter().take(region.cols * cw).copied().collect())
+        .collect()
+}
+
+/// Returns how the cells are painted when the board is drawn as pixels.
+///
+/// Each cell is a square of the most pixels that lets the cells of a character fit in it,
+/// so the board lines up with the characters drawn over it.
+///
+/// # Arguments
+///
+/// * `style` - The colors to draw with.
+fn pixel_style(style: &DisplayStyle) -> RasterStyle {
+    let (cw, ch) = style.mode.cells_per_char();
+    let cell_size = match terminal::window_size() {
+        Ok(size) if size.columns > 0 && size.rows > 0 && size.width > 0 && size.height > 0 => {
+            (size.width as usize / size.columns as usize / cw).min(size.height as usize / size.rows as usize / ch).max(1)
+        }
+        _ => PIXEL_CELL_SIZE,
+    };
+    let rgb = |color: Option<Color>, default: Rgb| match color {
+        Some(Color::Rgb { r, g, b }) => Rgb(r, g, b),
+        _ => default,
+    };
+    RasterStyle {
+        cell_size,
+        alive: rgb(style.alive_color, PIXEL_ALIVE),
+        dead: rgb(style.background, Rgb(0, 0, 0)),
+        border: 0,
+        ..RasterStyle::default()
+    }
+}
+
+/// A terminal graphics protocol that the pixel render modes draw the board with.
+///
+/// The board is rasterized the same way for every protocol, and each only turns the image
+/// into its own escape sequences.
+trait GraphicsProtocol {
+    /// Returns the escape sequences that show an image at the cursor, over the one shown
+    /// before.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image of the whole board.
+    fn show(&self, image: &Image) -> Result<String, Box<dyn Error>>;
+
+    /// Returns the escape sequences that paint part of the image shown last, or `None` if
+    /// the protocol can only show whole images.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - The new pixels.
+    /// * `x` - The column of the image the left edge of the patch goes at, in pixels.
+    /// * `y` - The row of the image the top edge of the patch goes at, in pixels.
+    fn update(&self, _patch: &Image, _x: usize, _y: usize) -> Option<String> {
+        None
+    }
+
+    /// Returns the escape sequences that take the image off the terminal, or `None` if
+    /// clearing the screen already does.
+    fn remove(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Sixel graphics, which can only show whole images.
+struct Sixel;
+
+impl GraphicsProtocol for Sixel {
+    fn show(&self, image: &Image) -> Result<String, Box<dyn Error>> {
+        Ok(sixel::encode(image))
+    }
+}
+
+/// The number the terminal knows the image of the board by with kitty graphics.
+const KITTY_IMAGE_ID: u32 = 1;
+
+/// The kitty graphics protocol, which paints changes over the image on the screen.
+struct Kitty;
+
+impl GraphicsProtocol for Kitty {
+    fn show(&self, image: &Image) -> Result<String, Box<dyn Error>> {
+        Ok(kitty::transmit(image, KITTY_IMAGE_ID))
+    }
+
+    fn update(&self, patch: &Image, x: usize, y: usize) -> Option<String> {
+        Some(kitty::update(patch, KITTY_IMAGE_ID, x, y))
+    }
+
+    fn remove(&self) -> Option<String> {
+        Some(kitty::delete(KITTY_IMAGE_ID))
+    }
+}
+
+/// iTerm2's inline images, which can only show whole images.
+struct ITerm;
+
+impl GraphicsProtocol for ITerm {
+    fn show(&self, image: &Image) -> Result<String, Box<dyn Error>> {
+        iterm::inline_image(image)
+    }
+}
+
+/// Returns the graphics protocol a render mode draws with, if it draws pixels.
+///
+/// # Arguments
+///
+/// * `mode` - The render mode.
+fn graphics_protocol(mode: RenderMode) -> Option<&'static dyn GraphicsProtocol> {
+    match mode {
+        RenderMode::Sixel => Some(&Sixel),
+        RenderMode::Kitty => Some(&Kitty),
+        RenderMode::ITerm => Some(&ITerm),
+        _ => None,
+    }
+}
+
+/// Draws the pixel render modes, with sixel graphics, kitty graphics, or iTerm2 inline
+/// images.
+pub struct PixelRenderer {
+    /// The graphics protocol the board is drawn with.
+    protocol: &'static dyn GraphicsProtocol,
+}
+
+impl Renderer for PixelRenderer {
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        display_pixels(out, self.protocol, frame.grid, frame.prev_grid, style, frame.region)
+    }
+
+    fn resize(&mut self, out: &mut Terminal, _style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        // The image stays where it was on the terminal, which may now be off the board.
+        self.shutdown(out)
+    }
+
+    fn shutdown(&mut self, out: &mut Terminal) -> Result<(), Box<dyn Error>> {
+        if let Some(remove) = self.protocol.remove() {
+            out.write_all(remove.as_bytes())?;
+            out.image = None;
+        }
+        Ok(())
+    }
+}
+
+/// Draws the grid as one image over the region.
+///
+/// The whole image is shown when the screen was cleared or the board changed size. After
+/// that, protocols that can paint part of an image are sent the smallest rectangle holding
+/// every cell that changed, and the others the whole image again.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `protocol` - The graphics protocol to draw with.
+/// * `grid` - The grid to be drawn.
+/// * `prev_grid` - The grid drawn last time.
+/// * `style` - The colors to draw with.
+/// * `region` - The part of the terminal available for the grid.
+fn display_pixels(
+    out: &mut Terminal,
+    protocol: &dyn GraphicsProtocol,
+    grid: &[Vec<bool>],
+    prev_grid: &[Vec<bool>],
+    style: &DisplayStyle,
+    region: Region,
+) -> Result<(), Box<dyn Error>> {
+    let visible = visible_cells(grid, style.mode, region);
+    let raster = pixel_style(style);
+    let width = visible.first().map_or(0, |row| row.len());
+    let size = (width * raster.cell_size, visible.len() * raster.cell_size);
+
+    if out.cleared || out.image != Some(size) {
+        out.queue(cursor::MoveTo(region.left as u16, region.top as u16))?;
+        out.write_all(protocol.show(&raster::render_grid(&visible, &raster))?.as_bytes())?;
+        out.image = Some(size);
+        return Ok(());
+    }
+
+    // The columns and rows of the cells that changed, from the first to the last.
+    let was_alive = |x: usize, y: usize| prev_grid.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false);
+    let changed: Vec<(usize, usize)> = visible
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| row.iter().enumerate().filter(move |&(x, &cell)| cell != was_alive(x, y)).map(move |(x, _)| (x, y)))
+        .collect();
+    let (Some(left), Some(right)) = (changed.iter().map(|&(x, _)| x).min(), changed.iter().map(|&(x, _)| x).max()) else {
+        return Ok(());
+    };
+    let (top, bottom) = (changed[0].1, changed[changed.len() - 1].1);
+
+    let patch: Grid = visible[top..=bottom].iter().map(|row| row[left..=right].to_vec()).collect();
+    let update = protocol.update(&raster::render_grid(&patch, &raster), left * raster.cell_size, top * raster.cell_size);
+    match update {
+        Some(update) => out.write_all(update.as_bytes())?,
+        None => {
+            out.queue(cursor::MoveTo(region.left as u16, region.top as u16))?;
+            out.write_all(protocol.show(&raster::render_grid(&visible, &raster))?.as_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// How long the terminal is given to answer a query.
+const QUERY_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Sends a query to the terminal and returns its answer.
+///
+/// The query is followed by a request for the primary device attributes, which every
+/// terminal answers, so the answer is read up to that one whether or not the terminal
+/// knew the query. This is done before the terminal is read for keys, since the answer
+/// comes in as input.
+///
+/// # Arguments
+///
+/// * `query` - The escape sequences to send before the request for the attributes.
+///
+/// # Returns
+///
+/// Everything the terminal sent back, or `None` if there is no terminal or it did not
+/// answer in time.
+fn query_terminal(query: &[u8]) -> Result<Option<String>, Box<dyn Error>> {
+    let mut tty = match File::options().read(true).write(true).open("/dev/tty") {
+        Ok(tty) => tty,
+        Err(_) => return Ok(None),
+    };
+    terminal::enable_raw_mode()?;
+    let asked = tty.write_all(query).and_then(|_| tty.write_all(b"\x1b[c")).and_then(|_| tty.flush());
+    let (sender, answer) = mpsc::channel();
+    // The read blocks until the terminal answers, so it is left behind if it never does.
+    thread::spawn(move || {
+        let mut response = Vec::new();
+        let mut byte = [0];
+        while tty.read_exact(&mut byte).is_ok() {
+            response.push(byte[0]);
+            // The attributes are the last answer, `ESC [ ? ... c`.
+            let last = response.rsplit(|&byte| byte == 0x1b).next().unwrap_or_default();
+            if byte[0] == b'c' && last.starts_with(b"[?") {
+                break;
+            }
+        }
+        let _ = sender.send(response);
+    });
+    let response = answer.recv_timeout(QUERY_TIMEOUT);
+    terminal::disable_raw_mode()?;
+    asked?;
+    Ok(response.ok().map(|response| String::from_utf8_lossy(&response).into_owned()))
+}
+
+/// Checks whether the terminal shows sixel graphics.
+///
+/// The terminal is asked for its primary device attributes, and shows sixels if it lists
+/// attribute 4 among them, as xterm with a VT340 or later terminal type, mlterm, and foot
+/// do.
+pub fn supports_sixel() -> Result<bool, Box<dyn Error>> {
+    // The answer is `ESC [ ? 62 ; 4 ; 6 c`, with the attributes between the `?` and the `c`.
+    Ok(query_terminal(b"")?.is_some_and(|text| {
+        let attributes = text.rsplit("\x1b[?").next().unwrap_or_default().trim_end_matches('c');
+        attributes.split(';').any(|attribute| attribute == "4")
+    }))
+}
+
+/// Checks whether the terminal shows images sent with the kitty graphics protocol.
+///
+/// The terminal is asked to check a one-pixel image without keeping it, which kitty,
+/// WezTerm, and ghostty answer with `OK` and other terminals ignore.
+pub fn supports_kitty_graphics() -> Result<bool, Box<dyn Error>> {
+    Ok(query_terminal(b"\x1b_Gi=31,s=1,v=1,a=q,t=d,f=24;AAAA\x1b\\")?.is_some_and(|text| text.contains("\x1b_Gi=31;OK")))
+}
+
+/// Checks whether the terminal shows iTerm2's inline images.
+///
+/// Terminals cannot be asked whether they do without drawing something, so this goes by
+/// the terminals known to: iTerm2, also over ssh where it sets `LC_TERMINAL`, and WezTerm.
+pub fn supports_inline_images() -> bool {
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    matches!(program.as_str(), "iTerm.app" | "WezTerm") || env::var("LC_TERMINAL").is_ok_and(|terminal| terminal == "iTerm2")
+}
+
+/// The glyphs of a fading trail, from a cell that just died to one that has almost faded.
+const TRAIL_GLYPHS: [char; 3] = ['▓', '▒', '░'];
+
+/// The color live cells are taken to have when fading them, if they use the terminal's default.
+const TRAIL_BASE: Rgb = Rgb(200, 200, 200);
+
+/// Returns the glyph and color of a trail cell.
+///
+/// # Arguments
+///
+/// * `age` - The number of generations since the cell died, from 1.
+/// * `length` - The number of generations a trail lasts.
+/// * `style` - The display style, whose dying or live-cell color the trail fades from.
+fn trail_glyph(age: u8, length: u8, style: &DisplayStyle) -> (char, Color) {
+    // The trail fades from two thirds of the way from the background to the dying color
+    // down to a sixth of the way.
+    let fade = 1.0 - age as f32 / (length as f32 + 1.0);
+    let rgb = |color: Option<Color>, default: Rgb| match color {
+        Some(Color::Rgb { r, g, b }) => Rgb(r, g, b),
+        _ => default,
+    };
+    let Rgb(r, g, b) = rgb(style.dying_color.or(style.alive_color), TRAIL_BASE);
+    let Rgb(r0, g0, b0) = rgb(style.background, Rgb(0, 0, 0));
+    let dim = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * (0.1 + 0.6 * fade)) as u8;
+    let glyph = TRAIL_GLYPHS[((age as usize - 1) * TRAIL_GLYPHS.len() / length as usize).min(TRAIL_GLYPHS.len() - 1)];
+    (glyph, Color::Rgb { r: dim(r0, r), g: dim(g0, g), b: dim(b0, b) })
+}
+
+/// Draws ASCII boards with fading trails behind the cells that died recently, with
+/// `--trails`.
+pub struct TrailsRenderer {
+    /// The number of generations a trail lasts.
+    length: u8,
+    /// The trails of the whole board, once it has been seen.
+    trails: Option<Trails>,
+    /// The trails drawn last and the board cell at the top-left corner of the screen then,
+    /// unless the screen was cleared since.
+    shown: Option<(Trails, (usize, usize))>,
+}
+
+impl TrailsRenderer {
+    /// Creates a renderer for trails that last a number of generations.
+    ///
+    /// # Arguments
+    ///
+    /// * `length` - The number of generations a trail lasts.
+    pub fn new(length: u8) -> TrailsRenderer {
+        TrailsRenderer { length, trails: None, shown: None }
+    }
+}
+
+impl Renderer for TrailsRenderer {
+    fn record(&mut self, grid: &[Vec<bool>], _generation: u64) -> Result<(), Box<dyn Error>> {
+        let length = self.length;
+        self.trails
+            .get_or_insert_with(|| Trails::new(grid.first().map_or(0, |row| row.len()), grid.len(), length))
+            .observe(grid);
+        Ok(())
+    }
+
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        if out.cleared {
+            self.shown = None;
+        }
+        let empty;
+        let trails = match &self.trails {
+            Some(trails) => trails,
+            None => {
+                empty = Trails::new(0, 0, self.length);
+                &empty
+            }
+        };
+        let prev = self.shown.as_ref().map(|(trails, origin)| (trails, *origin));
+        display_trails(out, frame, trails, prev, style)?;
+        self.shown = Some((trails.clone(), frame.origin));
+        Ok(())
+    }
+}
+
+/// Prints the grid to the console, one cell per character, with fading trails behind
+/// the cells that died recently.
+///
+/// Only the characters whose cells or trails changed since the previous frame are redrawn.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `frame` - The part of the board in view and the one drawn last.
+/// * `trails` - The trails of the whole board.
+/// * `prev` - The trails drawn last and the board cell at the top-left corner of the screen
+///   then, or `None` to draw every cell.
+/// * `style` - The glyphs and colors to draw with.
+fn display_trails(
+    out: &mut impl Write,
+    frame: &Frame,
+    trails: &Trails,
+    prev: Option<(&Trails, (usize, usize))>,
+    style: &DisplayStyle,
+) -> Result<(), Box<dyn Error>> {
+    let (left, top) = frame.origin;
+    for (y, row) in frame.grid.iter().enumerate().take(frame.region.rows) {
+        let mut run = RowRun::new(frame.region.top + y, false, style);
+        for (x, &alive) in row.iter().enumerate().take(frame.region.cols) {
+            let age = trails.age(left + x, top + y);
+            let unchanged = prev.is_some_and(|(prev_trails, (prev_left, prev_top))| {
+                frame.prev_grid.get(y).and_then(|row| row.get(x)) == Some(&alive) && prev_trails.age(prev_left + x, prev_top + y) == age
+            });
+            if unchanged {
+                continue;
+            }
+
+            let (glyph, color) = match age {
+                _ if alive => (style.alive_glyph, style.alive_color),
+                Some(age) => {
+                    let (glyph, color) = trail_glyph(age, trails.length(), style);
+                    (glyph, Some(color))
+                }
+                None => (style.dead_glyph, style.dead_color),
+            };
+            run.put_on(out, frame.region.left + x, glyph, color, style.fill_at(x, y))?;
+        }
+        run.print(out)?;
+    }
+    Ok(())
+}
+
+/// Draws the activity heatmap of `--render heatmap`, one cell per character.
+#[derive(Default)]
+pub struct HeatmapRenderer {
+    /// The activity of the whole board, once it has been seen.
+    heatmap: Option<Heatmap>,
+    /// The colors on screen, unless the screen was cleared since.
+    shown: Vec<Vec<Option<Rgb>>>,
+}
+
+impl Renderer for HeatmapRenderer {
+    fn record(&mut self, grid: &[Vec<bool>], _generation: u64) -> Result<(), Box<dyn Error>> {
+        self.heatmap
+            .get_or_insert_with(|| Heatmap::new(grid.first().map_or(0, |row| row.len()), grid.len(), heatmap::DEFAULT_DECAY))
+            .observe(grid);
+        Ok(())
+    }
+
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        if out.cleared {
+            self.shown.clear();
+        }
+        // Only the part of the heatmap under the part of the board in view is drawn.
+        let (left, top) = frame.origin;
+        let width = frame.grid.first().map_or(0, |row| row.len());
+        let colors: Vec<Vec<Option<Rgb>>> = match &self.heatmap {
+            Some(heatmap) => heatmap
+                .colors()
+                .iter()
+                .skip(top)
+                .take(frame.grid.len())
+                .map(|row| row.iter().skip(left).take(width).copied().collect())
+                .collect(),
+            None => vec![vec![None; width]; frame.grid.len()],
+        };
+        display_colors(out, &colors, &self.shown, style, frame.region.cols, frame.region.rows)?;
+        self.shown = colors;
+        Ok(())
+    }
+}
+
+/// Prints a board of blended layer colors to the console, one cell per character.
+///
+/// Only the characters whose color changed since the previous board are redrawn.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `colors` - The color of each cell, or `None` for an empty cell.
+/// * `prev_colors` - The previously drawn colors.
+/// * `style` - The style, whose background or dead-cell fill is drawn behind the empty cells.
+/// * `cols` - The number of terminal columns available for the board.
+/// * `rows` - The number of terminal rows available for the board.
+pub fn display_colors(
+    out: &mut impl Write,
+    colors: &[Vec<Option<Rgb>>],
+    prev_colors: &[Vec<Option<Rgb>>],
+    style: &DisplayStyle,
+    cols: usize,
+    rows: usize,
+) -> Result<(), Box<dyn Error>> {
+    for (y, row) in colors.iter().enumerate().take(rows) {
+        let mut run = RowRun::new(y, false, style);
+        for (x, &color) in row.iter().enumerate().take(cols) {
+            if prev_colors.get(y).and_then(|row| row.get(x)) == Some(&color) {
+                continue;
+            }
+            match color {
+                Some(Rgb(r, g, b)) => run.put(out, x, '█', Some(Color::Rgb { r, g, b }))?,
+                None => run.put_on(out, x, ' ', None, style.fill_at(x, y))?,
+            }
+        }
+        run.print(out)?;
+    }
+    Ok(())
+}
+
+/// Prints the status bar on the given row.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `line` - The status text.
+/// * `row` - The terminal row of the status bar.
+/// * `cols` - The width of the terminal.
+/// * `style` - The style, with the colors of the status bar.
+pub fn display_status(out: &mut impl Write, line: &str, row: usize, cols: usize, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+    // Pad or truncate the line so it always covers the whole row.
+    let line: String = line.chars().chain(std::iter::repeat(' ')).take(cols).collect();
+
+    out.queue(cursor::MoveTo(0, row as u16))?;
+    print_colored(out, &line, style.fit(style.status_color), style.fit(style.status_background))?;
+    Ok(())
+}
+
+/// The characters of the density view, from empty to fully alive.
+const DENSITY_SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// The number of terminal characters summarized by one character of the density view,
+/// in each direction.
+const DENSITY_BLOCK: usize = 4;
+
+/// Returns the shading character for the fraction of live cells in a block.
+///
+/// Typical soups are sparse, so any live cell shows up and the darker shades are
+/// reserved for the densest regions.
+fn density_shade(alive: usize, total: usize) -> char {
+    let density = alive as f64 / total.max(1) as f64;
+    let level = match density {
+        _ if alive == 0 => 0,
+        d if d <= 0.1 => 1,
+        d if d <= 0.25 => 2,
+        d if d <= 0.5 => 3,
+        _ => 4,
+    };
+    DENSITY_SHADES[level]
+}
+
+/// Draws the density minimap of turbo mode, which summarizes the whole board.
+#[derive(Default)]
+pub struct DensityRenderer {
+    /// The minimap on screen, unless the screen was cleared since.
+    shown: Vec<Vec<char>>,
+}
+
+impl Renderer for DensityRenderer {
+    fn draw_frame(&mut self, out: &mut Terminal, frame: &Frame, style: &DisplayStyle) -> Result<(), Box<dyn Error>> {
+        if out.cleared {
+            self.shown.clear();
+        }
+        display_density(out, frame.grid, &mut self.shown, frame.region.cols, frame.region.rows, style)
+    }
+}
+
+/// Prints a low-resolution density summary of the grid in a framed minimap.
+///
+/// The minimap is a quarter of the terminal area in each direction, centered, and each
+/// of its characters shades the live-cell density of the block of the grid beneath it.
+/// It is much cheaper to draw than the full grid, so the engine can run flat out.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `grid` - The grid to summarize.
+/// * `shown` - The minimap currently on screen; only changed characters are redrawn.
+///   Pass an empty map to draw everything, including the frame.
+/// * `cols` - The width of the terminal area.
+/// * `rows` - The height of the terminal area.
+/// * `style` - The colors to draw with.
+fn display_density(
+    out: &mut impl Write,
+    grid: &[Vec<bool>],
+    shown: &mut Vec<Vec<char>>,
+    cols: usize,
+    rows: usize,
+    style: &DisplayStyle,
+) -> Result<(), Box<dyn Error>> {
+    let grid_rows = grid.len();
+    let grid_cols = grid.first().map_or(0, |row| row.len());
+    let map_cols = cols.div_ceil(DENSITY_BLOCK).min(cols.saturating_sub(2));
+    let map_rows = rows.div_ceil(DENSITY_BLOCK).min(rows.saturating_sub(2));
+    if map_cols == 0 || map_rows == 0 || grid_cols == 0 || grid_rows == 0 {
+        return Ok(());
+    }
+
+    // The range of cells covered by map position `i` along an axis of `len` cells.
+    let span = |i: usize, map_len: usize, len: usize| {
+        let start = i * len / map_len;
+        start..((i + 1) * len / map_len).max(start + 1).min(len)
+    };
+
+    // Summarize each block of cells as one shading character.
+    let map: Vec<Vec<char>> = (0..map_rows)
+        .map(|my| {
+            let ys = span(my, map_rows, grid_rows);
+            (0..map_cols)
+                .map(|mx| {
+                    let xs = span(mx, map_cols, grid_cols);
+                    let alive = grid[ys.clone()]
+                        .iter()
+                        .map(|row| row[xs.clone()].iter().filter(|&&cell| cell).count())
+                        .sum();
+                    density_shade(alive, ys.len() * xs.len())
+                })
+                .collect()
+        })
+        .collect();
+
+    let left = (cols - map_cols - 2) / 2;
+    let top = (rows - map_rows - 2) / 2;
+
+    // Draw the frame the first time the minimap is shown.
+    if shown.is_empty() {
+        let title: String = " turbo: space pauses ".chars().take(map_cols).collect();
+        let fill = "─".repeat(map_cols - title.chars().count());
+        out.queue(cursor::MoveTo(left as u16, top as u16))?;
+        print_colored(out, &format!("┌{}{}┐", title, fill), None, style.fit(style.background))?;
+        for my in 0..map_rows {
+            out.queue(cursor::MoveTo(left as u16, (top + 1 + my) as u16))?;
+            print_colored(out, "│", None, style.fit(style.background))?;
+            out.queue(cursor::MoveTo((left + 1 + map_cols) as u16, (top + 1 + my) as u16))?;
+            print_colored(out, "│", None, style.fit(style.background))?;
+        }
+        out.queue(cursor::MoveTo(left as u16, (top + 1 + map_rows) as u16))?;
+        print_colored(out, &format!("└{}┘", "─".repeat(map_cols)), None, style.fit(style.background))?;
+    }
+
+    for (my, row) in map.iter().enumerate() {
+        let mut run = RowRun::new(top + 1 + my, false, style);
+        for (mx, &shade) in row.iter().enumerate() {
+            if shown.get(my).and_then(|row| row.get(mx)) == Some(&shade) {
+                continue;
+            }
+            run.put(out, left + 1 + mx, shade, style.alive_color)?;
+        }
+        run.print(out)?;
+    }
+
+    *shown = map;
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    QueueableCommand,
+};
+use std::{error::Error, io::Write};
+
+use gameoflife::pattern::{self, ParseError, Pattern};
+
+use crate::display::Region;
+
+/// The largest size of the editor pane, including its frame.
+const MAX_PANE_WIDTH: usize = 60;
+const MAX_PANE_HEIGHT: usize = 16;
+
+/// What the main loop should do after the editor handled a key.
+pub enum EditorAction {
+    /// Keep the editor open.
+    Continue,
+    /// Close the editor and stamp the pattern onto the board.
+    Commit(Pattern),
+    /// Close the editor without changing the board.
+    Cancel,
+}
+
+/// A small text editor pane for typing or pasting RLE onto the board.
+///
+/// The text is parsed after every edit, so the pane always shows whether the
+/// pattern is valid and which line holds the first error.
+pub struct Editor {
+    /// The lines of text.
+    lines: Vec<String>,
+    /// The line the cursor is on.
+    row: usize,
+    /// The character position of the cursor within its line.
+    col: usize,
+    /// The first line shown in the pane.
+    scroll: usize,
+    /// The result of parsing the current text.
+    parsed: Result<Pattern, ParseError>,
+}
+
+impl Editor {
+    /// Creates an empty editor.
+    pub fn new() -> Editor {
+        let mut editor = Editor {
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+            scroll: 0,
+            parsed: Ok(Pattern::from_rows(Vec::new())),
+        };
+        editor.reparse();
+        editor
+    }
+
+    /// Parses the current text as RLE.
+    fn reparse(&mut self) {
+        self.parsed = pattern::parse_rle(&self.lines.join("\n"));
+    }
+
+    /// Returns the byte offset of the cursor in its line.
+    fn byte_col(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(self.col).map_or(line.len(), |(i, _)| i)
+    }
+
+    /// Inserts a character at the cursor.
+    fn insert_char(&mut self, ch: char) {
+        let at = self.byte_col();
+        self.lines[self.row].insert(at, ch);
+        self.col += 1;
+    }
+
+    /// Splits the current line at the cursor.
+    fn insert_newline(&mut self) {
+        let at = self.byte_col();
+        let rest = self.lines[self.row].split_off(at);
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    /// Inserts pasted text at the cursor and reparses once.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The pasted text.
+    pub fn paste(&mut self, text: &str) {
+        for ch in text.chars() {
+            match ch {
+                '\n' => self.insert_newline(),
+                '\r' => {}
+                ch => self.insert_char(ch),
+            }
+        }
+        self.reparse();
+    }
+
+    /// Handles a key press.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key event.
+    ///
+    /// # Returns
+    ///
+    /// What the main loop should do next.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> EditorAction {
+        let line_len = |editor: &Editor, row: usize| editor.lines[row].chars().count();
+
+        match key.code {
+            KeyCode::Esc => return EditorAction::Cancel,
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return match &self.parsed {
+                    Ok(pattern) => EditorAction::Commit(pattern.clone()),
+                    Err(_) => EditorAction::Continue,
+                };
+            }
+            KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => self.insert_char(ch),
+            KeyCode::Enter => self.insert_newline(),
+            KeyCode::Backspace => {
+                if self.col > 0 {
+                    self.col -= 1;
+                    let at = self.byte_col();
+                    self.lines[self.row].remove(at);
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = line_len(self, self.row);
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Delete => {
+                if self.col < line_len(self, self.row) {
+                    let at = self.byte_col();
+                    self.lines[self.row].remove(at);
+                } else if self.row + 1 < self.lines.len() {
+                    let line = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Left => {
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = line_len(self, self.row);
+                }
+            }
+            KeyCode::Right => {
+                if self.col < line_len(self, self.row) {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(line_len(self, self.row));
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(line_len(self, self.row));
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = line_len(self, self.row),
+            _ => return EditorAction::Continue,
+        }
+
+        self.reparse();
+        EditorAction::Continue
+    }
+
+    /// Returns the position and size of the pane, centered in the terminal area.
+    fn pane(cols: usize, rows: usize) -> (usize, usize, usize, usize) {
+        let width = cols.min(MAX_PANE_WIDTH);
+        let height = rows.min(MAX_PANE_HEIGHT);
+        ((cols - width) / 2, (rows - height) / 2, width, height)
+    }
+
+    /// Returns the part of the terminal area the pane covers.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The width of the terminal area.
+    /// * `rows` - The height of the terminal area.
+    pub fn area(cols: usize, rows: usize) -> Region {
+        let (left, top, cols, rows) = Editor::pane(cols, rows);
+        Region { left, top, cols, rows }
+    }
+
+    /// Draws the editor pane over the grid.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `cols` - The width of the terminal area.
+    /// * `rows` - The height of the terminal area.
+    pub fn draw(&mut self, out: &mut impl Write, cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+        let (left, top, width, height) = Editor::pane(cols, rows);
+        if width < 4 || height < 4 {
+            return Ok(());
+        }
+        let inner_width = width - 2;
+
+        // The parse result goes under the text, wrapped onto a second line if it is long.
+        let (status, color) = match &self.parsed {
+            Ok(pattern) => (
+                format!(
+                    " OK: {}x{}, {} cells",
+                    pattern.width,
+                    pattern.height,
+                    pattern.cells.iter().flatten().filter(|&&cell| cell).count()
+                ),
+                Color::Green,
+            ),
+            Err(e) => (format!(" Error {}", e), Color::Red),
+        };
+        let status: Vec<char> = status.chars().collect();
+        let status_rows = if status.len() > inner_width && height >= 5 { 2 } else { 1 };
+        let text_rows = height - 2 - status_rows;
+
+        // Keep the cursor line in view.
+        if self.row < self.scroll {
+            self.scroll = self.row;
+        } else if self.row >= self.scroll + text_rows {
+            self.scroll = self.row + 1 - text_rows;
+        }
+
+        let error_line = self.parsed.as_ref().err().map(|e| e.line - 1);
+        let fit = |text: &str| -> String { text.chars().chain(std::iter::repeat(' ')).take(inner_width).collect() };
+
+        let title: String = " RLE editor: Ctrl-S place, Esc cancel ".chars().take(inner_width).collect();
+        let fill = "─".repeat(inner_width - title.chars().count());
+        out.queue(cursor::MoveTo(left as u16, top as u16))?;
+        out.queue(Print(format!("┌{}{}┐", title, fill)))?;
+
+        for i in 0..text_rows {
+            let row = self.scroll + i;
+            out.queue(cursor::MoveTo(left as u16, (top + 1 + i) as u16))?;
+            out.queue(Print("│"))?;
+
+            let text = self.lines.get(row).map(String::as_str).unwrap_or("");
+            if error_line == Some(row) {
+                out.queue(SetBackgroundColor(Color::DarkRed))?;
+            }
+
+            // Draw the line, showing the cursor as a reversed character.
+            let chars: Vec<char> = fit(text).chars().collect();
+            for (col, ch) in chars.iter().enumerate() {
+                if row == self.row && col == self.col {
+                    out.queue(SetAttribute(Attribute::Reverse))?;
+                    out.queue(Print(ch))?;
+                    out.queue(SetAttribute(Attribute::NoReverse))?;
+                } else {
+                    out.queue(Print(ch))?;
+                }
+            }
+
+            out.queue(ResetColor)?;
+            out.queue(Print("│"))?;
+        }
+
+        // Show the parse result on the last lines inside the frame.
+        for (i, line) in status.chunks(inner_width).take(status_rows).enumerate() {
+            out.queue(cursor::MoveTo(left as u16, (top + 1 + text_rows + i) as u16))?;
+            out.queue(Print("│"))?;
+            out.queue(SetForegroundColor(color))?;
+            out.queue(Print(fit(&line.iter().collect::<String>())))?;
+            out.queue(ResetColor)?;
+            out.queue(Print("│"))?;
+        }
+
+        out.queue(cursor::MoveTo(left as u16, (top + height - 1) as u16))?;
+        out.queue(Print(format!("└{}┘", "─".repeat(inner_width))))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//! The undo and redo stacks of the changes made to the board by hand, kept apart from the
+//! history of generations the simulation went through.
+
+/// The most edits that can be undone; older ones are forgotten.
+pub const MAX_EDITS: usize = 256;
+
+/// A change made to the board by hand, as the cells it flipped.
+///
+/// Flipping the same cells again takes the change back, so one list of cells both undoes
+/// the edit on the board after it and redoes it on the board before it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Edit {
+    /// The column and row of every cell the edit flipped.
+    cells: Vec<(usize, usize)>,
+}
+
+impl Edit {
+    /// Returns the edit that turns one board into another of the same size.
+    ///
+    /// # Arguments
+    ///
+    /// * `before` - The board before the change.
+    /// * `after` - The board after the change.
+    pub fn between(before: &[Vec<bool>], after: &[Vec<bool>]) -> Edit {
+        let cells = before
+            .iter()
+            .zip(after)
+            .enumerate()
+            .flat_map(|(y, (old, new))| old.iter().zip(new).enumerate().filter(|(_, (old, new))| old != new).map(move |(x, _)| (x, y)))
+            .collect();
+        Edit { cells }
+    }
+
+    /// Returns whether the edit changed no cells.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Flips the cells of the edit on a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board to flip the cells on.
+    fn flip(&self, grid: &mut [Vec<bool>]) {
+        for &(x, y) in &self.cells {
+            if let Some(cell) = grid.get_mut(y).and_then(|row| row.get_mut(x)) {
+                *cell = !*cell;
+            }
+        }
+    }
+}
+
+/// The edits made to the board, which can be undone and redone in order.
+///
+/// Edits only make sense on the generation they were made on, so once the board has moved
+/// on to another one, the edits made before are forgotten.
+#[derive(Clone, Debug, Default)]
+pub struct EditStack {
+    /// The edits that can be undone, the latest last.
+    undo: Vec<Edit>,
+    /// The edits that were undone and can be redone, the latest undone last.
+    redo: Vec<Edit>,
+    /// The generation the edits were made on.
+    generation: u64,
+}
+
+impl EditStack {
+    /// Records an edit, forgetting the edits that were undone before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit` - The edit that was made.
+    /// * `generation` - The generation of the board it was made on.
+    pub fn record(&mut self, edit: Edit, generation: u64) {
+        self.forget_other(generation);
+        if edit.is_empty() {
+            return;
+        }
+        self.redo.clear();
+        self.undo.push(edit);
+        if self.undo.len() > MAX_EDITS {
+            self.undo.remove(0);
+        }
+    }
+
+    /// Undoes the latest edit.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board the edit was made on.
+    /// * `generation` - The generation of the board.
+    ///
+    /// # Returns
+    ///
+    /// Whether there was an edit to undo.
+    pub fn undo(&mut self, grid: &mut [Vec<bool>], generation: u64) -> bool {
+        self.forget_other(generation);
+        let Some(edit) = self.undo.pop() else { return false };
+        edit.flip(grid);
+        self.redo.push(edit);
+        true
+    }
+
+    /// Redoes the latest edit that was undone.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board the edit was undone on.
+    /// * `generation` - The generation of the board.
+    ///
+    /// # Returns
+    ///
+    /// Whether there was an edit to redo.
+    pub fn redo(&mut self, grid: &mut [Vec<bool>], generation: u64) -> bool {
+        self.forget_other(generation);
+        let Some(edit) = self.redo.pop() else { return false };
+        edit.flip(grid);
+        self.undo.push(edit);
+        true
+    }
+
+    /// Returns the number of edits that can be undone and redone.
+    pub fn counts(&self) -> (usize, usize) {
+        (self.undo.len(), self.redo.len())
+    }
+
+    /// Forgets every edit if they were made on another generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation of the board now.
+    fn forget_other(&mut self, generation: u64) {
+        if generation != self.generation {
+            self.undo.clear();
+            self.redo.clear();
+            self.generation = generation;
+        }
+    }
+}
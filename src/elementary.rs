@@ -0,0 +1,113 @@
+use rand::Rng;
+use std::collections::VecDeque;
+
+use crate::{automaton::Simulation, raster::Rgb, variant::StateGrid};
+
+/// The color of live cells.
+const PALETTE: [Rgb; 1] = [Rgb(0xee, 0xee, 0xee)];
+
+/// An elementary one-dimensional cellular automaton, shown as a scrolling history.
+///
+/// Each generation is one row of cells, computed from the row before it by a Wolfram
+/// rule number: bit `n` of the rule gives the next state of a cell whose left neighbour,
+/// self, and right neighbour spell `n` in binary. The row wraps around at the edges.
+/// Rows are drawn from the top down, one per generation, and once the board is full
+/// the older rows scroll up and off it, like text in a terminal.
+pub struct Elementary {
+    /// The Wolfram rule number.
+    pub rule: u8,
+    /// The rows on the board, oldest first.
+    pub rows: VecDeque<Vec<bool>>,
+    /// The number of rows that fit on the board.
+    pub height: usize,
+}
+
+impl Elementary {
+    /// Creates an automaton whose first row has a single live cell in the middle.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of cells in a row.
+    /// * `height` - The number of rows on the board.
+    /// * `rule` - The Wolfram rule number.
+    pub fn new(width: usize, height: usize, rule: u8) -> Elementary {
+        let mut row = vec![false; width.max(1)];
+        let middle = row.len() / 2;
+        row[middle] = true;
+        Elementary::with_row(row, height, rule)
+    }
+
+    /// Creates an automaton whose first row is random.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of cells in a row.
+    /// * `height` - The number of rows on the board.
+    /// * `rule` - The Wolfram rule number.
+    /// * `probability` - The probability that a cell starts alive.
+    /// * `rng` - The random number generator.
+    pub fn random(width: usize, height: usize, rule: u8, probability: f64, rng: &mut impl Rng) -> Elementary {
+        let row = (0..width.max(1)).map(|_| rng.gen_bool(probability)).collect();
+        Elementary::with_row(row, height, rule)
+    }
+
+    /// Creates an automaton from its first row.
+    fn with_row(row: Vec<bool>, height: usize, rule: u8) -> Elementary {
+        Elementary {
+            rule,
+            rows: VecDeque::from([row]),
+            height: height.max(1),
+        }
+    }
+
+    /// Computes the row after a given one.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The current row.
+    ///
+    /// # Returns
+    ///
+    /// The next row.
+    pub fn next_row(&self, row: &[bool]) -> Vec<bool> {
+        let width = row.len();
+        (0..width)
+            .map(|x| {
+                let left = row[(x + width - 1) % width] as u8;
+                let right = row[(x + 1) % width] as u8;
+                let pattern = (left << 2) | ((row[x] as u8) << 1) | right;
+                self.rule >> pattern & 1 == 1
+            })
+            .collect()
+    }
+}
+
+impl Simulation for Elementary {
+    fn view(&self) -> StateGrid {
+        // Rows that have not been computed yet are blank.
+        let width = self.rows.front().map_or(0, Vec::len);
+        let blank = self.height.saturating_sub(self.rows.len());
+        self.rows
+            .iter()
+            .map(|row| row.iter().map(|&cell| u8::from(cell)).collect())
+            .chain(std::iter::repeat_n(vec![0; width], blank))
+            .collect()
+    }
+
+    fn palette(&self) -> &[Rgb] {
+        &PALETTE
+    }
+
+    fn status(&self) -> String {
+        format!("rule {}", self.rule)
+    }
+
+    fn step(&mut self) {
+        // Only the newest row is computed; the oldest scrolls off once the board is full.
+        let next = self.next_row(self.rows.back().expect("there is always a row"));
+        self.rows.push_back(next);
+        if self.rows.len() > self.height {
+            self.rows.pop_front();
+        }
+    }
+}
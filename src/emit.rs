@@ -0,0 +1,154 @@
+//! Machine-readable generation output, written by `--emit ndjson`.
+//!
+//! Each generation becomes one line of JSON, such as
+//! `{"generation":1,"population":5,"births":2,"deaths":2}`, so the output can be piped
+//! into `jq` or read line by line by analysis tools. With the live-cell list enabled, each
+//! line also has `"cells":[[x,y],...]`, listed row by row.
+//!
+//! `--stats-csv` writes the same counts to a CSV file instead, one row per generation.
+
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{recorder::FrameRecorder, Grid};
+
+/// One line of the output.
+#[derive(Serialize)]
+struct Line<'a> {
+    generation: u64,
+    population: usize,
+    births: usize,
+    deaths: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cells: Option<&'a [(usize, usize)]>,
+}
+
+/// Writes every generation as a line of JSON.
+///
+/// The first generation has no births or deaths, since there is nothing to compare it to.
+pub struct NdjsonRecorder<W: Write> {
+    /// Where the lines are written.
+    out: W,
+    /// Whether each line lists the live cells.
+    cells: bool,
+    /// The previous generation, to count births and deaths against.
+    previous: Option<Grid>,
+}
+
+impl<W: Write> NdjsonRecorder<W> {
+    /// Creates a recorder writing to `out`.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where the lines are written, usually standard output.
+    /// * `cells` - Whether each line lists the live cells.
+    pub fn new(out: W, cells: bool) -> NdjsonRecorder<W> {
+        NdjsonRecorder { out, cells, previous: None }
+    }
+}
+
+impl<W: Write> FrameRecorder for NdjsonRecorder<W> {
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        let (births, deaths) = churn(self.previous.as_deref(), grid);
+        let live: Vec<(usize, usize)> = if self.cells {
+            grid.iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.iter().enumerate().filter(|&(_, &alive)| alive).map(move |(x, _)| (x, y)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let line = Line {
+            generation,
+            population: population(grid),
+            births,
+            deaths,
+            cells: self.cells.then_some(live.as_slice()),
+        };
+        serde_json::to_writer(&mut self.out, &line)?;
+        self.out.write_all(b"\n")?;
+
+        self.previous = Some(grid.to_vec());
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<PathBuf, Box<dyn Error>> {
+        self.out.flush()?;
+        Ok(PathBuf::from("standard output"))
+    }
+}
+
+/// Writes the population, births, and deaths of every generation to a CSV file.
+///
+/// The rows are buffered and only reach the file in large blocks, so logging every
+/// generation costs the simulation next to nothing.
+pub struct CsvRecorder {
+    /// The buffered file.
+    out: BufWriter<File>,
+    /// The path of the file.
+    path: PathBuf,
+    /// The previous generation, to count births and deaths against.
+    previous: Option<Grid>,
+}
+
+impl CsvRecorder {
+    /// Creates the CSV file and writes its header row.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file, which is replaced if it exists.
+    pub fn create(path: &Path) -> Result<CsvRecorder, Box<dyn Error>> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut out = BufWriter::new(file);
+        writeln!(out, "generation,population,births,deaths")?;
+        Ok(CsvRecorder { out, path: path.to_path_buf(), previous: None })
+    }
+}
+
+impl FrameRecorder for CsvRecorder {
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        let (births, deaths) = churn(self.previous.as_deref(), grid);
+        writeln!(self.out, "{},{},{},{}", generation, population(grid), births, deaths)?;
+        self.previous = Some(grid.to_vec());
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<PathBuf, Box<dyn Error>> {
+        self.out.flush().map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        Ok(self.path)
+    }
+}
+
+/// Returns the number of live cells of a board.
+fn population(grid: &[Vec<bool>]) -> usize {
+    grid.iter().flatten().filter(|&&alive| alive).count()
+}
+
+/// Counts the cells born and the cells that died since the previous generation.
+///
+/// # Arguments
+///
+/// * `previous` - The previous generation, or `None` for the first one, which has neither.
+/// * `grid` - The new generation.
+///
+/// # Returns
+///
+/// The number of births and the number of deaths.
+fn churn(previous: Option<&[Vec<bool>]>, grid: &[Vec<bool>]) -> (usize, usize) {
+    let (mut births, mut deaths) = (0, 0);
+    if let Some(previous) = previous {
+        for (row, previous_row) in grid.iter().zip(previous) {
+            for (&alive, &was_alive) in row.iter().zip(previous_row) {
+                births += (alive && !was_alive) as usize;
+                deaths += (!alive && was_alive) as usize;
+            }
+        }
+    }
+    (births, deaths)
+}
@@ -0,0 +1,386 @@
+//! Interchangeable backends that advance a board, picked with `--engine`.
+//!
+//! A [`World`](crate::world::World) keeps its board in an [`Engine`] and only reads the
+//! cells back when they are looked at, so the simulation runs the same whichever engine
+//! does the stepping. Other crates can implement the trait to plug in engines of their own.
+
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    bitgrid::BitGrid,
+    boundary::Boundary,
+    rule::{Neighborhood, Rule},
+    update_grid_naive, update_grid_with_boundary, Grid,
+};
+
+/// Something that stores a board and advances it by a rule.
+///
+/// The board is finite, `bounds` cells across, with the cells past its edges given by the
+/// boundary the engine was created with. Engines are `Send`, so a world can be moved to
+/// another thread with its board.
+pub trait Engine: fmt::Debug + Send {
+    /// Returns the width and height of the board in cells.
+    fn bounds(&self) -> (usize, usize);
+
+    /// Returns whether a cell is alive.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell, within the bounds.
+    /// * `y` - The row of the cell, within the bounds.
+    fn get(&self, x: usize, y: usize) -> bool;
+
+    /// Brings a cell to life or kills it.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell, within the bounds.
+    /// * `y` - The row of the cell, within the bounds.
+    /// * `alive` - Whether the cell is alive.
+    fn set(&mut self, x: usize, y: usize, alive: bool);
+
+    /// Returns the number of live cells.
+    fn population(&self) -> usize {
+        let (width, height) = self.bounds();
+        (0..height).map(|y| (0..width).filter(|&x| self.get(x, y)).count()).sum()
+    }
+
+    /// Advances the board by one generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The birth and survival rule to apply.
+    /// * `changes` - Cleared, then given the cells that were born or died, as `(x, y)`.
+    fn step(&mut self, rule: &Rule, changes: &mut Vec<(usize, usize)>);
+
+    /// Replaces the board, taking the size of the new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The new board.
+    fn load(&mut self, grid: &[Vec<bool>]);
+
+    /// Writes the board into a grid, resizing it to fit.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid written to, whose buffers are reused.
+    fn store(&self, grid: &mut Grid) {
+        let (width, height) = self.bounds();
+        grid.resize_with(height, Vec::new);
+        for (y, row) in grid.iter_mut().enumerate() {
+            row.clear();
+            row.extend((0..width).map(|x| self.get(x, y)));
+        }
+    }
+}
+
+/// The engines built in, as named with `--engine`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EngineKind {
+    /// The bit-parallel engine of [`bitgrid`](crate::bitgrid), 64 cells at a time.
+    #[default]
+    Packed,
+    /// One cell at a time over the unpacked board, the reference the others are tested
+    /// against.
+    Dense,
+    /// Only the live cells and their neighbours, for large boards that are mostly empty.
+    Sparse,
+}
+
+impl EngineKind {
+    /// Parses an engine name: `packed`, `dense`, or `sparse`.
+    pub fn parse(name: &str) -> Option<EngineKind> {
+        match name {
+            "packed" => Some(EngineKind::Packed),
+            "dense" => Some(EngineKind::Dense),
+            "sparse" => Some(EngineKind::Sparse),
+            _ => None,
+        }
+    }
+
+    /// Creates an engine of this kind with an empty board.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundary` - What lies past the edges of the board.
+    pub fn create(&self, boundary: Boundary) -> Box<dyn Engine> {
+        match self {
+            EngineKind::Packed => Box::new(Packed::new(boundary)),
+            EngineKind::Dense => Box::new(Dense::new(boundary)),
+            EngineKind::Sparse => Box::new(Sparse::new(boundary)),
+        }
+    }
+}
+
+impl fmt::Display for EngineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EngineKind::Packed => "packed",
+            EngineKind::Dense => "dense",
+            EngineKind::Sparse => "sparse",
+        })
+    }
+}
+
+/// The bit-parallel engine, keeping its buffers from one generation to the next.
+#[derive(Debug)]
+pub struct Packed {
+    /// The current generation.
+    cells: BitGrid,
+    /// The buffer the next generation is written to.
+    next: BitGrid,
+    /// The rows with the cells past the edges, kept between steps.
+    padded: Vec<u64>,
+}
+
+impl Packed {
+    /// Creates the engine with an empty board.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundary` - What lies past the edges of the board.
+    pub fn new(boundary: Boundary) -> Packed {
+        Packed {
+            cells: BitGrid::new(0, 0).with_boundary(boundary),
+            next: BitGrid::new(0, 0).with_boundary(boundary),
+            padded: Vec::new(),
+        }
+    }
+}
+
+impl Engine for Packed {
+    fn bounds(&self) -> (usize, usize) {
+        (self.cells.width(), self.cells.height())
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.cells.get(x, y)
+    }
+
+    fn set(&mut self, x: usize, y: usize, alive: bool) {
+        self.cells.set(x, y, alive);
+    }
+
+    fn population(&self) -> usize {
+        self.cells.population()
+    }
+
+    fn step(&mut self, rule: &Rule, changes: &mut Vec<(usize, usize)>) {
+        self.cells.step_into(rule, &mut self.next, &mut self.padded);
+        changes.clear();
+        changes.extend(self.cells.changes(&self.next));
+        std::mem::swap(&mut self.cells, &mut self.next);
+    }
+
+    fn load(&mut self, grid: &[Vec<bool>]) {
+        self.cells.pack(grid);
+    }
+
+    fn store(&self, grid: &mut Grid) {
+        self.cells.unpack_into(grid);
+    }
+}
+
+/// The reference engine, counting the neighbours of every cell one at a time.
+#[derive(Debug)]
+pub struct Dense {
+    /// The cells of the board.
+    cells: Grid,
+    /// What lies past the edges of the board.
+    boundary: Boundary,
+}
+
+impl Dense {
+    /// Creates the engine with an empty board.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundary` - What lies past the edges of the board.
+    pub fn new(boundary: Boundary) -> Dense {
+        Dense { cells: Vec::new(), boundary }
+    }
+}
+
+impl Engine for Dense {
+    fn bounds(&self) -> (usize, usize) {
+        (self.cells.first().map_or(0, |row| row.len()), self.cells.len())
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.cells[y][x]
+    }
+
+    fn set(&mut self, x: usize, y: usize, alive: bool) {
+        self.cells[y][x] = alive;
+    }
+
+    fn step(&mut self, rule: &Rule, changes: &mut Vec<(usize, usize)>) {
+        let next: Grid = if self.boundary == Boundary::Dead {
+            update_grid_naive(&self.cells, rule)
+        } else {
+            // As in `update_grid_with_boundary`, the board is stepped inside the cells its
+            // boundary puts past the edges, with an even margin above and below for hex rows.
+            let (radius, _) = rule.neighborhood();
+            let (columns, rows) = (radius, radius + radius % 2);
+            let (width, height) = self.bounds();
+            update_grid_naive(&self.boundary.pad(&self.cells, columns, rows), rule)
+                .into_iter()
+                .skip(rows)
+                .take(height)
+                .map(|row| row[columns..columns + width].to_vec())
+                .collect()
+        };
+        changes.clear();
+        for (y, (old, new)) in self.cells.iter().zip(&next).enumerate() {
+            changes.extend((0..old.len()).filter(|&x| old[x] != new[x]).map(|x| (x, y)));
+        }
+        self.cells = next;
+    }
+
+    fn load(&mut self, grid: &[Vec<bool>]) {
+        self.cells = grid.to_vec();
+    }
+
+    fn store(&self, grid: &mut Grid) {
+        grid.clone_from(&self.cells);
+    }
+}
+
+/// The engine that keeps only the live cells, visiting them and their neighbours each
+/// generation rather than the whole board.
+///
+/// Life-like rules in which dead cells with no live neighbours stay dead are stepped this
+/// way. Rules with `B0`, Larger than Life and isotropic rules, and live boundaries are
+/// stepped over the whole board instead, since there every cell may change.
+#[derive(Debug)]
+pub struct Sparse {
+    /// The width of the board.
+    width: usize,
+    /// The height of the board.
+    height: usize,
+    /// The live cells, as columns and rows.
+    alive: HashSet<(usize, usize)>,
+    /// What lies past the edges of the board.
+    boundary: Boundary,
+}
+
+impl Sparse {
+    /// Creates the engine with an empty board.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundary` - What lies past the edges of the board.
+    pub fn new(boundary: Boundary) -> Sparse {
+        Sparse { width: 0, height: 0, alive: HashSet::new(), boundary }
+    }
+
+    /// Returns the cell of the board seen at a position that may be past the edges, or
+    /// `None` if the boundary puts no cell of the board there.
+    fn map(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        self.boundary.map_cell(x, y, self.width, self.height)
+    }
+
+    /// Steps the board by visiting only the live cells and their neighbours, for life-like
+    /// rules in which a dead cell with no live neighbours stays dead.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule to apply.
+    /// * `neighborhood` - The neighbours the rule counts.
+    ///
+    /// # Returns
+    ///
+    /// The live cells of the next generation.
+    fn step_live_cells(&self, rule: &Rule, neighborhood: Neighborhood) -> HashSet<(usize, usize)> {
+        // Only live cells and the cells next to them can be alive in the next generation.
+        let mut candidates = HashSet::with_capacity(self.alive.len() * 9);
+        for &(x, y) in &self.alive {
+            for dy in -1..=1 {
+                for dx in -1..=1 {
+                    candidates.extend(self.map(x as isize + dx, y as isize + dy));
+                }
+            }
+        }
+
+        candidates
+            .into_iter()
+            .filter(|&(x, y)| {
+                let mut count = 0;
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        if (dx, dy) != (0, 0) && neighborhood.contains(dx, dy, 1, y) {
+                            count += self.map(x as isize + dx, y as isize + dy).is_some_and(|cell| self.alive.contains(&cell)) as usize;
+                        }
+                    }
+                }
+                rule.next_state(self.alive.contains(&(x, y)), count)
+            })
+            .collect()
+    }
+
+    /// Steps the board over every cell, for the rules and boundaries that can change
+    /// cells far from any live one.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule to apply.
+    ///
+    /// # Returns
+    ///
+    /// The live cells of the next generation.
+    fn step_whole_board(&self, rule: &Rule) -> HashSet<(usize, usize)> {
+        let mut grid = Vec::new();
+        self.store(&mut grid);
+        let next = update_grid_with_boundary(&grid, rule, self.boundary);
+        next.iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().filter(|&(_, &cell)| cell).map(move |(x, _)| (x, y)))
+            .collect()
+    }
+}
+
+impl Engine for Sparse {
+    fn bounds(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.alive.contains(&(x, y))
+    }
+
+    fn set(&mut self, x: usize, y: usize, alive: bool) {
+        if alive {
+            self.alive.insert((x, y));
+        } else {
+            self.alive.remove(&(x, y));
+        }
+    }
+
+    fn population(&self) -> usize {
+        self.alive.len()
+    }
+
+    fn step(&mut self, rule: &Rule, changes: &mut Vec<(usize, usize)>) {
+        let next = match *rule {
+            Rule::LifeLike { neighborhood, .. } if !rule.next_state(false, 0) && self.boundary != Boundary::Alive => {
+                self.step_live_cells(rule, neighborhood)
+            }
+            _ => self.step_whole_board(rule),
+        };
+        // The cells that changed are those alive in one generation but not the other.
+        changes.clear();
+        changes.extend(self.alive.symmetric_difference(&next));
+        self.alive = next;
+    }
+
+    fn load(&mut self, grid: &[Vec<bool>]) {
+        self.width = grid.first().map_or(0, |row| row.len());
+        self.height = grid.len();
+        self.alive = grid
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().filter(|&(_, &cell)| cell).map(move |(x, _)| (x, y)))
+            .collect();
+    }
+}
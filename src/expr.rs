@@ -0,0 +1,346 @@
+//! A tiny expression language for composing initial boards on the command line.
+//!
+//! An expression is a `+`-separated list of terms, applied left to right:
+//!
+//! ```text
+//! rect(10,10,30,20,density=0.5) + glider@5,5 + gun@40,10:rot90
+//! ```
+//!
+//! Terms are either primitives or pattern placements:
+//!
+//! * `rect(x, y, width, height[, density=d])` fills a rectangle.
+//! * `circle(cx, cy, radius[, density=d])` fills a disc.
+//! * `random([density=d])` fills the whole board.
+//! * Anything else is a placement as accepted by `--place`: a pattern file or
+//!   built-in pattern name, optional `@x,y` coordinates, and `:` modifiers.
+//!
+//! The density defaults to 1 (every cell alive) for shapes and to 0.5 for `random`.
+
+use rand::Rng;
+use std::{error::Error, fmt};
+
+use crate::placement::{self, Placement};
+
+/// An error in an initial-state expression, with the position it was found at.
+#[derive(Debug)]
+pub struct ExprError {
+    /// The expression that failed to parse.
+    source: String,
+    /// The character offset of the error.
+    position: usize,
+    /// A description of the error.
+    message: String,
+}
+
+impl fmt::Display for ExprError {
+    /// Shows the message followed by the expression with a caret under the error.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid --init-expr at column {}: {}", self.position + 1, self.message)?;
+        writeln!(f, "  {}", self.source)?;
+        write!(f, "  {}^", " ".repeat(self.position))
+    }
+}
+
+impl Error for ExprError {}
+
+/// A single term of an expression.
+#[derive(Clone, Debug)]
+enum Term {
+    /// A filled rectangle.
+    Rect {
+        x: isize,
+        y: isize,
+        width: usize,
+        height: usize,
+        density: f64,
+    },
+    /// A filled disc.
+    Circle {
+        cx: isize,
+        cy: isize,
+        radius: usize,
+        density: f64,
+    },
+    /// The whole board filled at random.
+    Random { density: f64 },
+    /// A pattern placement.
+    Pattern(Placement),
+}
+
+/// A parsed initial-state expression.
+#[derive(Clone, Debug)]
+pub struct InitExpr {
+    terms: Vec<Term>,
+}
+
+/// The parser state: the expression and the current character offset.
+struct Parser<'a> {
+    source: &'a str,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Creates an error at the given position.
+    fn error(&self, position: usize, message: impl Into<String>) -> ExprError {
+        ExprError {
+            source: self.source.to_string(),
+            position,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the current character without consuming it.
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    /// Skips whitespace.
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes the expected character or fails.
+    fn expect(&mut self, expected: char) -> Result<(), ExprError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(ch) if ch == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(ch) => Err(self.error(self.pos, format!("expected '{}' but found '{}'", expected, ch))),
+            None => Err(self.error(self.pos, format!("expected '{}' but the expression ended", expected))),
+        }
+    }
+
+    /// Consumes characters while the predicate holds and returns them.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&predicate) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// Parses the whole expression.
+    fn parse_expr(&mut self) -> Result<InitExpr, ExprError> {
+        let mut terms = vec![self.parse_term()?];
+
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some('+') => {
+                    self.pos += 1;
+                    terms.push(self.parse_term()?);
+                }
+                Some(ch) => return Err(self.error(self.pos, format!("expected '+' between terms but found '{}'", ch))),
+            }
+        }
+
+        Ok(InitExpr { terms })
+    }
+
+    /// Parses a primitive call or a pattern placement.
+    fn parse_term(&mut self) -> Result<Term, ExprError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let name = self.take_while(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+
+        if self.peek() == Some('(') {
+            return self.parse_primitive(&name, start);
+        }
+
+        // Everything up to the next `+` is a placement in `--place` syntax.
+        self.pos = start;
+        let spec = self.take_while(|ch| ch != '+' && !ch.is_whitespace());
+        if spec.is_empty() {
+            return Err(match self.peek() {
+                Some(ch) => self.error(self.pos, format!("expected a term but found '{}'", ch)),
+                None => self.error(self.pos, "expected a term but the expression ended"),
+            });
+        }
+
+        Placement::parse(&spec)
+            .map(Term::Pattern)
+            .map_err(|e| self.error(start, e.to_string()))
+    }
+
+    /// Parses the argument list of a primitive and builds the term.
+    fn parse_primitive(&mut self, name: &str, start: usize) -> Result<Term, ExprError> {
+        self.expect('(')?;
+
+        let mut positional: Vec<(f64, usize)> = Vec::new();
+        let mut density: Option<f64> = None;
+
+        self.skip_whitespace();
+        if self.peek() != Some(')') {
+            loop {
+                self.skip_whitespace();
+                let arg_start = self.pos;
+                let key = self.take_while(|ch| ch.is_ascii_alphabetic());
+
+                if !key.is_empty() {
+                    // Named argument; `density` is the only one.
+                    if key != "density" {
+                        return Err(self.error(arg_start, format!("unknown argument '{}' (only density=... is supported)", key)));
+                    }
+                    self.expect('=')?;
+                    let (value, value_start) = self.parse_number()?;
+                    if !(0.0..=1.0).contains(&value) {
+                        return Err(self.error(value_start, "density must be between 0 and 1"));
+                    }
+                    density = Some(value);
+                } else {
+                    if density.is_some() {
+                        return Err(self.error(arg_start, "positional arguments must come before density=..."));
+                    }
+                    positional.push(self.parse_number()?);
+                }
+
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(',') => self.pos += 1,
+                    _ => break,
+                }
+            }
+        }
+        self.expect(')')?;
+
+        // Check the number of positional arguments and convert them.
+        let arity = |expected: usize, usage: &str| -> Result<(), ExprError> {
+            if positional.len() != expected {
+                return Err(self.error(
+                    start,
+                    format!("{} takes {} positional arguments, got {}: {}", name, expected, positional.len(), usage),
+                ));
+            }
+            Ok(())
+        };
+        let int = |i: usize| -> Result<isize, ExprError> {
+            let (value, at) = positional[i];
+            if value.fract() != 0.0 {
+                return Err(self.error(at, "expected a whole number"));
+            }
+            Ok(value as isize)
+        };
+        let size = |i: usize| -> Result<usize, ExprError> {
+            let value = int(i)?;
+            usize::try_from(value).map_err(|_| self.error(positional[i].1, "expected a non-negative size"))
+        };
+
+        match name {
+            "rect" => {
+                arity(4, "rect(x, y, width, height[, density=d])")?;
+                Ok(Term::Rect {
+                    x: int(0)?,
+                    y: int(1)?,
+                    width: size(2)?,
+                    height: size(3)?,
+                    density: density.unwrap_or(1.0),
+                })
+            }
+            "circle" => {
+                arity(3, "circle(cx, cy, radius[, density=d])")?;
+                Ok(Term::Circle {
+                    cx: int(0)?,
+                    cy: int(1)?,
+                    radius: size(2)?,
+                    density: density.unwrap_or(1.0),
+                })
+            }
+            "random" => {
+                arity(0, "random([density=d])")?;
+                Ok(Term::Random {
+                    density: density.unwrap_or(0.5),
+                })
+            }
+            _ => Err(self.error(start, format!("unknown primitive '{}' (expected rect, circle or random)", name))),
+        }
+    }
+
+    /// Parses a (possibly negative, possibly fractional) number.
+    fn parse_number(&mut self) -> Result<(f64, usize), ExprError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let text = self.take_while(|ch| ch.is_ascii_digit() || ch == '.' || ch == '-');
+        if text.is_empty() {
+            return Err(match self.peek() {
+                Some(ch) => self.error(start, format!("expected a number but found '{}'", ch)),
+                None => self.error(start, "expected a number but the expression ended"),
+            });
+        }
+        text.parse()
+            .map(|value| (value, start))
+            .map_err(|_| self.error(start, format!("'{}' is not a valid number", text)))
+    }
+}
+
+impl InitExpr {
+    /// Parses an initial-state expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The expression.
+    ///
+    /// # Returns
+    ///
+    /// The parsed expression, or an error pointing at the offending column.
+    pub fn parse(source: &str) -> Result<InitExpr, ExprError> {
+        let mut parser = Parser {
+            source,
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        parser.parse_expr()
+    }
+
+    /// Applies every term of the expression to the grid, left to right.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid to draw on.
+    /// * `rng` - The random number generator for densities below 1.
+    ///
+    /// # Returns
+    ///
+    /// The messages produced by pattern placements.
+    pub fn apply(&self, grid: &mut [Vec<bool>], rng: &mut impl Rng) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut messages = Vec::new();
+        let rows = grid.len() as isize;
+        let cols = grid.first().map_or(0, |row| row.len()) as isize;
+
+        // Sets the cells accepted by `inside` with the given density.
+        let mut fill = |grid: &mut [Vec<bool>], inside: &dyn Fn(isize, isize) -> bool, density: f64| {
+            for y in 0..rows {
+                for x in 0..cols {
+                    if inside(x, y) && rng.gen_bool(density) {
+                        grid[y as usize][x as usize] = true;
+                    }
+                }
+            }
+        };
+
+        for term in &self.terms {
+            match term {
+                &Term::Rect { x, y, width, height, density } => {
+                    let inside = |cx: isize, cy: isize| cx >= x && cx < x + width as isize && cy >= y && cy < y + height as isize;
+                    fill(grid, &inside, density);
+                }
+                &Term::Circle { cx, cy, radius, density } => {
+                    let r = radius as isize;
+                    let inside = |x: isize, y: isize| (x - cx).pow(2) + (y - cy).pow(2) <= r * r;
+                    fill(grid, &inside, density);
+                }
+                &Term::Random { density } => fill(grid, &|_, _| true, density),
+                Term::Pattern(pattern) => {
+                    messages.extend(placement::apply_placements(grid, std::slice::from_ref(pattern))?);
+                }
+            }
+        }
+
+        Ok(messages)
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(feature = "fetch")]
+use std::{
+    fs,
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    path::PathBuf,
+    process::Command,
+    time::Duration,
+};
+use std::error::Error;
+
+use gameoflife::placement::Placement;
+#[cfg(feature = "fetch")]
+use gameoflife::pattern::Pattern;
+
+#[cfg(feature = "fetch")]
+use crate::config::Config;
+
+/// Where `--wiki` downloads patterns from, by name.
+pub const WIKI_PATTERN_URL: &str = "https://conwaylife.com/patterns/";
+
+/// How long a download may take before it is given up on.
+#[cfg(feature = "fetch")]
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The most redirects followed for one download.
+#[cfg(feature = "fetch")]
+const MAX_REDIRECTS: usize = 5;
+
+/// Returns the URL of a LifeWiki pattern, as in `https://conwaylife.com/patterns/gosperglidergun.rle`.
+///
+/// # Arguments
+///
+/// * `name` - The name of the pattern file on LifeWiki, with or without `.rle`.
+pub fn wiki_url(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    if name.ends_with(".rle") || name.ends_with(".cells") {
+        format!("{}{}", WIKI_PATTERN_URL, name)
+    } else {
+        format!("{}{}.rle", WIKI_PATTERN_URL, name)
+    }
+}
+
+/// Downloads a pattern file, or reads it from the download cache.
+///
+/// Downloads are kept in `~/.config/gameoflife/patterns/`, so a pattern is only
+/// downloaded the first time it is used. `http://` URLs are downloaded directly;
+/// `https://` URLs are handed to the `curl` command, since the program has no TLS
+/// implementation of its own.
+///
+/// # Arguments
+///
+/// * `url` - The URL of an RLE or plaintext pattern file.
+///
+/// # Returns
+///
+/// The pattern, placed at the top-left corner of the board.
+#[cfg(feature = "fetch")]
+pub fn fetch_pattern(url: &str) -> Result<Placement, Box<dyn Error>> {
+    let cache = cache_path(url);
+    let path = match cache {
+        Some(path) if path.exists() => path,
+        cache => {
+            let body = download(url, MAX_REDIRECTS).map_err(|e| format!("Failed to download {}: {}", url, e))?;
+            let path = cache.unwrap_or_else(|| std::env::temp_dir().join(cache_name(url)));
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            fs::write(&path, body).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            path
+        }
+    };
+
+    let pattern = Pattern::from_file(&path).map_err(|e| format!("{} is not a pattern file: {}", url, e))?;
+    Ok(Placement::from_pattern(url, pattern))
+}
+
+/// Fails, because the program was built without network support.
+#[cfg(not(feature = "fetch"))]
+pub fn fetch_pattern(_url: &str) -> Result<Placement, Box<dyn Error>> {
+    Err("--fetch and --wiki need network support; rebuild with `cargo build --features fetch`".into())
+}
+
+/// Returns the path a download is cached at, or `None` if there is no configuration
+/// directory.
+#[cfg(feature = "fetch")]
+fn cache_path(url: &str) -> Option<PathBuf> {
+    let config = Config::default_path()?;
+    Some(config.parent()?.join("patterns").join(cache_name(url)))
+}
+
+/// Returns the file name a download is cached under: a hash of the whole URL, which
+/// tells apart files of the same name from different places, and the name itself.
+#[cfg(feature = "fetch")]
+fn cache_name(url: &str) -> String {
+    // FNV-1a, which unlike the standard library's hasher stays the same between builds.
+    let hash = url.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    let name: String = url
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    format!("{:016x}-{}", hash, name)
+}
+
+/// Downloads a file.
+///
+/// # Arguments
+///
+/// * `url` - The URL of the file.
+/// * `redirects` - The number of further redirects that may be followed.
+///
+/// # Returns
+///
+/// The contents of the file.
+#[cfg(feature = "fetch")]
+fn download(url: &str, redirects: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    if url.starts_with("https://") {
+        return curl(url);
+    }
+    let Some(rest) = url.strip_prefix("http://") else {
+        return Err("only http:// and https:// URLs can be downloaded".into());
+    };
+
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let path = if path.is_empty() { "/" } else { path };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, port.parse::<u16>().map_err(|_| format!("'{}' is not a port", port))?),
+        None => (authority, 80),
+    };
+
+    let address = (host, port).to_socket_addrs()?.next().ok_or_else(|| format!("{} has no address", host))?;
+    let mut stream = TcpStream::connect_timeout(&address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    // HTTP/1.0 keeps the response free of chunked encoding, and the server closes the
+    // connection after it.
+    write!(stream, "GET {} HTTP/1.0\r\nHost: {}\r\nUser-Agent: gameoflife\r\n\r\n", path, authority)?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+
+    let split = response.windows(4).position(|window| window == b"\r\n\r\n").ok_or("the response has no headers")?;
+    let head = String::from_utf8_lossy(&response[..split]).to_string();
+    let mut lines = head.lines();
+    let status: u16 = lines.next().and_then(|line| line.split_whitespace().nth(1)).and_then(|code| code.parse().ok()).ok_or("the response has no status")?;
+    match status {
+        200 => Ok(response[split + 4..].to_vec()),
+        301 | 302 | 303 | 307 | 308 if redirects > 0 => {
+            let location = lines
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("location"))
+                .map(|(_, value)| value.trim().to_string())
+                .ok_or("the redirect has no location")?;
+            let location = if location.starts_with('/') { format!("http://{}{}", authority, location) } else { location };
+            download(&location, redirects - 1)
+        }
+        _ => Err(format!("the server answered with status {}", status).into()),
+    }
+}
+
+/// Downloads a file with the `curl` command.
+///
+/// # Arguments
+///
+/// * `url` - The URL of the file.
+///
+/// # Returns
+///
+/// The contents of the file.
+#[cfg(feature = "fetch")]
+fn curl(url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--max-redirs", &MAX_REDIRECTS.to_string(), "--max-time", &TIMEOUT.as_secs().to_string()])
+        .arg(url)
+        .output()
+        .map_err(|e| format!("https:// downloads need the curl command ({})", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string().into());
+    }
+    Ok(output.stdout)
+}
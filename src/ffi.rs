@@ -0,0 +1,256 @@
+//! A C ABI over the engine, for embedding it in C and C++ visualizers and game engines.
+//!
+//! The functions are declared in `include/gameoflife.h`. A board is created with
+//! [`gol_new`], used through the pointer it returns, and released with [`gol_free`]; the
+//! cells are read out as one byte per cell, row by row.
+
+use std::{
+    ffi::{c_char, CStr},
+    ptr, slice,
+};
+
+use crate::{bitgrid::BitGrid, rule::Rule};
+
+/// A board and the rule it follows, opaque to C.
+pub struct GolWorld {
+    /// The cells of the board.
+    cells: BitGrid,
+    /// The rule the board follows.
+    rule: Rule,
+}
+
+/// Creates an empty board.
+///
+/// # Arguments
+///
+/// * `width` - The width of the board in cells.
+/// * `height` - The height of the board in cells.
+/// * `rule` - The rulestring, in any notation `--rule` accepts, or null for Conway's Life.
+///
+/// # Returns
+///
+/// The board, or null if the rule is not valid.
+///
+/// # Safety
+///
+/// `rule` must be null or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gol_new(width: u32, height: u32, rule: *const c_char) -> *mut GolWorld {
+    let rule = if rule.is_null() {
+        Rule::default()
+    } else {
+        // SAFETY: the caller passes a NUL-terminated string.
+        match unsafe { CStr::from_ptr(rule) }.to_str().map(Rule::parse) {
+            Ok(Ok(rule)) => rule,
+            _ => return ptr::null_mut(),
+        }
+    };
+    let world = GolWorld { cells: BitGrid::new(width as usize, height as usize), rule };
+    Box::into_raw(Box::new(world))
+}
+
+/// Advances a board.
+///
+/// # Arguments
+///
+/// * `world` - The board.
+/// * `generations` - The number of generations to compute.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn gol_step(world: *mut GolWorld, generations: u32) {
+    // SAFETY: the caller passes a live board.
+    let Some(world) = (unsafe { world.as_mut() }) else { return };
+    for _ in 0..generations {
+        world.cells = world.cells.step(&world.rule);
+    }
+}
+
+/// Copies the cells of a board out, one byte per cell, row by row: 1 for alive and 0 for
+/// dead.
+///
+/// # Arguments
+///
+/// * `world` - The board.
+/// * `out` - Where the cells are written.
+/// * `len` - The number of bytes `out` has room for.
+///
+/// # Returns
+///
+/// The number of cells of the board. Nothing is written when `len` is smaller, so a call
+/// with a null buffer asks how large it must be.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed, and `out` must be null or
+/// have room for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn gol_get_cells(world: *const GolWorld, out: *mut u8, len: usize) -> usize {
+    // SAFETY: the caller passes a live board.
+    let Some(world) = (unsafe { world.as_ref() }) else { return 0 };
+    let (width, height) = (world.cells.width(), world.cells.height());
+    let cells = width * height;
+    if out.is_null() || len < cells {
+        return cells;
+    }
+    // SAFETY: the caller gave room for `len` bytes, which is at least `cells`.
+    let out = unsafe { slice::from_raw_parts_mut(out, cells) };
+    for (i, cell) in out.iter_mut().enumerate() {
+        *cell = world.cells.get(i % width, i / width) as u8;
+    }
+    cells
+}
+
+/// Returns whether a cell of a board is alive.
+///
+/// # Arguments
+///
+/// * `world` - The board.
+/// * `x` - The column of the cell.
+/// * `y` - The row of the cell.
+///
+/// # Returns
+///
+/// Whether the cell is alive; cells off the board are dead.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn gol_get_cell(world: *const GolWorld, x: u32, y: u32) -> bool {
+    // SAFETY: the caller passes a live board.
+    let Some(world) = (unsafe { world.as_ref() }) else { return false };
+    let (x, y) = (x as usize, y as usize);
+    x < world.cells.width() && y < world.cells.height() && world.cells.get(x, y)
+}
+
+/// Brings a cell of a board to life or kills it.
+///
+/// # Arguments
+///
+/// * `world` - The board.
+/// * `x` - The column of the cell.
+/// * `y` - The row of the cell.
+/// * `alive` - Whether the cell is alive.
+///
+/// # Returns
+///
+/// Whether the cell is on the board; cells off it are left alone.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn gol_set_cell(world: *mut GolWorld, x: u32, y: u32, alive: bool) -> bool {
+    // SAFETY: the caller passes a live board.
+    let Some(world) = (unsafe { world.as_mut() }) else { return false };
+    let (x, y) = (x as usize, y as usize);
+    if x >= world.cells.width() || y >= world.cells.height() {
+        return false;
+    }
+    world.cells.set(x, y, alive);
+    true
+}
+
+/// Switches a board to another rule.
+///
+/// # Arguments
+///
+/// * `world` - The board.
+/// * `rule` - The rulestring, in any notation `--rule` accepts.
+///
+/// # Returns
+///
+/// Whether the rule is valid; the board keeps its rule when it is not.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed, and `rule` must point to a
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gol_set_rule(world: *mut GolWorld, rule: *const c_char) -> bool {
+    // SAFETY: the caller passes a live board.
+    let Some(world) = (unsafe { world.as_mut() }) else { return false };
+    if rule.is_null() {
+        return false;
+    }
+    // SAFETY: the caller passes a NUL-terminated string.
+    match unsafe { CStr::from_ptr(rule) }.to_str().map(Rule::parse) {
+        Ok(Ok(rule)) => {
+            world.rule = rule;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Copies the rulestring of a board out, NUL-terminated.
+///
+/// # Arguments
+///
+/// * `world` - The board.
+/// * `out` - Where the rulestring is written.
+/// * `len` - The number of bytes `out` has room for.
+///
+/// # Returns
+///
+/// The number of bytes of the rulestring with its NUL. Nothing is written when `len` is
+/// smaller, so a call with a null buffer asks how large it must be.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed, and `out` must be null or
+/// have room for `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn gol_get_rule(world: *const GolWorld, out: *mut c_char, len: usize) -> usize {
+    // SAFETY: the caller passes a live board.
+    let Some(world) = (unsafe { world.as_ref() }) else { return 0 };
+    let rule = world.rule.to_string();
+    let size = rule.len() + 1;
+    if out.is_null() || len < size {
+        return size;
+    }
+    // SAFETY: the caller gave room for `len` bytes, which is at least `size`.
+    let out = unsafe { slice::from_raw_parts_mut(out.cast::<u8>(), size) };
+    out[..rule.len()].copy_from_slice(rule.as_bytes());
+    out[rule.len()] = 0;
+    size
+}
+
+/// Returns the width of a board in cells.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn gol_width(world: *const GolWorld) -> u32 {
+    // SAFETY: the caller passes a live board.
+    unsafe { world.as_ref() }.map_or(0, |world| world.cells.width() as u32)
+}
+
+/// Returns the height of a board in cells.
+///
+/// # Safety
+///
+/// `world` must be a board from [`gol_new`] that was not freed.
+#[no_mangle]
+pub unsafe extern "C" fn gol_height(world: *const GolWorld) -> u32 {
+    // SAFETY: the caller passes a live board.
+    unsafe { world.as_ref() }.map_or(0, |world| world.cells.height() as u32)
+}
+
+/// Releases a board; null is ignored.
+///
+/// # Safety
+///
+/// `world` must be null or a board from [`gol_new`] that was not freed, and is not used
+/// again after.
+#[no_mangle]
+pub unsafe extern "C" fn gol_free(world: *mut GolWorld) {
+    if !world.is_null() {
+        // SAFETY: the board was boxed by `gol_new` and is freed only once.
+        drop(unsafe { Box::from_raw(world) });
+    }
+}
@@ -0,0 +1,128 @@
+use crate::pattern::Pattern;
+
+/// The width of a glyph in the bitmap font.
+pub const GLYPH_WIDTH: usize = 5;
+
+/// The height of a glyph in the bitmap font.
+pub const GLYPH_HEIGHT: usize = 7;
+
+/// A glyph of the bitmap font: one byte per row, with the lowest five bits set
+/// for the lit pixels (the most significant of them is the leftmost pixel).
+pub type Glyph = [u8; GLYPH_HEIGHT];
+
+/// Returns the glyph for a character of the embedded 5×7 bitmap font.
+///
+/// Lowercase letters are drawn as uppercase, and characters without a glyph are
+/// drawn as `?`.
+///
+/// # Arguments
+///
+/// * `ch` - The character to look up.
+///
+/// # Returns
+///
+/// The glyph.
+pub fn glyph(ch: char) -> Glyph {
+    match ch.to_ascii_uppercase() {
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        'A' => [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x00, 0x00, 0x04],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+/// Checks whether a pixel of a glyph is lit.
+///
+/// # Arguments
+///
+/// * `glyph` - The glyph.
+/// * `x` - The column of the pixel, from 0 to `GLYPH_WIDTH - 1`.
+/// * `y` - The row of the pixel, from 0 to `GLYPH_HEIGHT - 1`.
+pub fn is_lit(glyph: &Glyph, x: usize, y: usize) -> bool {
+    glyph[y] & (1 << (GLYPH_WIDTH - 1 - x)) != 0
+}
+
+/// Renders text in the bitmap font as a pattern of live cells.
+///
+/// Characters are one dead column apart and lines one dead row apart. Lines are split at
+/// newlines, or at a literal `\n` as typed on the command line, and each line is centered
+/// on the widest one.
+///
+/// # Arguments
+///
+/// * `text` - The text to render.
+///
+/// # Returns
+///
+/// The pattern, with a live cell for every lit pixel.
+pub fn text_pattern(text: &str) -> Pattern {
+    let lines: Vec<Vec<char>> = text.replace("\\n", "\n").lines().map(|line| line.chars().collect()).collect();
+    let line_width = |line: &[char]| (line.len() * (GLYPH_WIDTH + 1)).saturating_sub(1);
+    let width = lines.iter().map(|line| line_width(line)).max().unwrap_or(0);
+
+    let mut rows = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            rows.push(vec![false; width]);
+        }
+        let indent = (width - line_width(line)) / 2;
+        for y in 0..GLYPH_HEIGHT {
+            let mut row = vec![false; width];
+            for (n, &ch) in line.iter().enumerate() {
+                let glyph = glyph(ch);
+                for x in 0..GLYPH_WIDTH {
+                    row[indent + n * (GLYPH_WIDTH + 1) + x] = is_lit(&glyph, x, y);
+                }
+            }
+            rows.push(row);
+        }
+    }
+    Pattern::from_rows(rows)
+}
@@ -0,0 +1,150 @@
+//! The color gradient that `--gradient` draws live cells along, by how long they have
+//! been alive or how crowded they are.
+
+use std::fmt;
+
+use crate::raster::Rgb;
+
+/// The number of colors along a gradient. Cells only change color when they move to
+/// another step, so a settled board is not redrawn every generation.
+pub const LEVELS: usize = 16;
+
+/// The age, in generations, at which a cell reaches the end of an age gradient.
+pub const MAX_AGE: u16 = 32;
+
+/// The color of newborn or lonely cells by default.
+pub const DEFAULT_FROM: Rgb = Rgb(255, 236, 110);
+
+/// The color of old or crowded cells by default.
+pub const DEFAULT_TO: Rgb = Rgb(48, 80, 220);
+
+/// What places a live cell along the gradient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GradientKey {
+    /// The number of generations the cell has been alive, up to [`MAX_AGE`].
+    Age,
+    /// The number of live cells in the 3×3 block around the cell.
+    Density,
+}
+
+impl GradientKey {
+    /// Parses a gradient key name: `age` or `density`.
+    pub fn parse(name: &str) -> Option<GradientKey> {
+        match name {
+            "age" => Some(GradientKey::Age),
+            "density" => Some(GradientKey::Density),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GradientKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GradientKey::Age => "age",
+            GradientKey::Density => "density",
+        })
+    }
+}
+
+/// A gradient between two colors, and what places cells along it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Gradient {
+    /// What places a live cell along the gradient.
+    pub key: GradientKey,
+    /// The color at the start, for newborn or lonely cells.
+    pub from: Rgb,
+    /// The color at the end, for old or crowded cells.
+    pub to: Rgb,
+}
+
+impl Gradient {
+    /// Creates a gradient between the default colors.
+    pub fn new(key: GradientKey) -> Gradient {
+        Gradient { key, from: DEFAULT_FROM, to: DEFAULT_TO }
+    }
+
+    /// Returns the [`LEVELS`] colors of the gradient, from start to end.
+    pub fn palette(&self) -> Vec<Rgb> {
+        let (Rgb(r0, g0, b0), Rgb(r1, g1, b1)) = (self.from, self.to);
+        (0..LEVELS)
+            .map(|level| {
+                let t = level as f32 / (LEVELS - 1) as f32;
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                Rgb(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+            })
+            .collect()
+    }
+}
+
+/// Where every live cell of a board is along a gradient.
+///
+/// The levels are kept like the states of a multi-state board: 0 for a dead cell, and
+/// otherwise the number of the cell's color in the [palette](Shading::palette), from 1.
+pub struct Shading {
+    /// What places a live cell along the gradient.
+    key: GradientKey,
+    /// The colors of the gradient.
+    palette: Vec<Rgb>,
+    /// The number of generations each cell has been alive, or 0 for a dead cell.
+    ages: Vec<Vec<u16>>,
+    /// The level of each cell.
+    levels: Vec<Vec<u8>>,
+}
+
+impl Shading {
+    /// Creates the shading of a board on which no cell has been seen yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `gradient` - The gradient to place the cells along.
+    pub fn new(gradient: &Gradient) -> Shading {
+        Shading { key: gradient.key, palette: gradient.palette(), ages: Vec::new(), levels: Vec::new() }
+    }
+
+    /// Places the cells of a new generation along the gradient.
+    ///
+    /// Cells that are alive in consecutive generations grow older, so this is called
+    /// once per generation. A board of a different size than the last one starts over.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    pub fn observe(&mut self, grid: &[Vec<bool>]) {
+        let width = grid.first().map_or(0, |row| row.len());
+        if self.ages.len() != grid.len() || self.ages.first().map_or(0, |row| row.len()) != width {
+            self.ages = vec![vec![0; width]; grid.len()];
+            self.levels = vec![vec![0; width]; grid.len()];
+        }
+
+        let steps = LEVELS - 1;
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &alive) in row.iter().enumerate() {
+                let age = &mut self.ages[y][x];
+                *age = if alive { age.saturating_add(1) } else { 0 };
+                self.levels[y][x] = match self.key {
+                    _ if !alive => 0,
+                    GradientKey::Age => {
+                        let age = (*age).min(MAX_AGE) as usize;
+                        (1 + (age - 1) * steps / (MAX_AGE as usize - 1)) as u8
+                    }
+                    GradientKey::Density => {
+                        // The block includes the cell itself, so it holds from 1 to 9 live cells.
+                        let block = crate::live_neighbors(grid, x, y) + 1;
+                        (1 + (block - 1) * steps / 8) as u8
+                    }
+                };
+            }
+        }
+    }
+
+    /// Returns the level of every cell.
+    pub fn levels(&self) -> &[Vec<u8>] {
+        &self.levels
+    }
+
+    /// Returns the colors of the gradient, from start to end.
+    pub fn palette(&self) -> &[Rgb] {
+        &self.palette
+    }
+}
@@ -0,0 +1,213 @@
+//! The native window of `--gui`, which draws the board as pixels instead of characters.
+//!
+//! The window takes the keys of the terminal frontend that make sense without a status
+//! bar, and the mouse paints cells: the left button brings them to life and the right
+//! one kills them.
+
+use std::error::Error;
+#[cfg(all(unix, feature = "gui"))]
+use std::{
+    env,
+    time::{Duration, Instant},
+};
+
+#[cfg(all(unix, feature = "gui"))]
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+#[cfg(all(unix, feature = "gui"))]
+use gameoflife::{
+    raster::{self, RasterStyle},
+    x11::{Event, Window},
+};
+use gameoflife::{recorder::FrameRecorder, world::World, Grid};
+
+use crate::cli;
+#[cfg(all(unix, feature = "gui"))]
+use crate::input::{self, Command};
+
+/// The largest cell the window starts with, in pixels.
+#[cfg(all(unix, feature = "gui"))]
+const MAX_CELL_SIZE: usize = 8;
+
+/// The most generations simulated between two frames when the window falls behind.
+#[cfg(all(unix, feature = "gui"))]
+const MAX_CATCH_UP_STEPS: u32 = 32;
+
+/// Runs the simulation in a window until it is closed.
+///
+/// The cells are drawn in the colors of the recordings, as large as the screen allows.
+/// The window redraws at most `--fps` times a second, and every generation is passed to
+/// the recorders.
+///
+/// # Arguments
+///
+/// * `world` - The board, stepped in place.
+/// * `options` - The parsed command-line options, for the rule, speed, and colors.
+/// * `recorders` - Recorders that receive every generation, including the initial one.
+/// * `reseed` - Creates the fresh board the `r` key starts over on.
+///
+/// # Returns
+///
+/// The generation the board was at when the window was closed.
+#[cfg(all(unix, feature = "gui"))]
+pub fn run(
+    world: &mut World,
+    options: &cli::Options,
+    recorders: &mut [Box<dyn FrameRecorder>],
+    reseed: &mut dyn FnMut() -> Result<Grid, Box<dyn Error>>,
+) -> Result<u64, Box<dyn Error>> {
+    let display = env::var("DISPLAY").map_err(|_| "--gui needs an X display, but DISPLAY is not set")?;
+    let (width, height) = world.bounds();
+    let mut style = RasterStyle { border: 0, caption: None, ..options.record_settings.style.clone() };
+    let mut window = Window::open(&display, 1, 1, &title(options, 0, world, false), style.dead)?;
+
+    // The cells start as large as fits on most of the screen.
+    let (screen_width, screen_height) = window.screen_size();
+    style.cell_size = (screen_width as usize * 9 / 10 / width.max(1)).min(screen_height as usize * 9 / 10 / height.max(1)).clamp(1, MAX_CELL_SIZE);
+    window.resize((width * style.cell_size) as u16, (height * style.cell_size) as u16)?;
+
+    let tick = Duration::from_secs_f64(1.0 / options.speed);
+    let frame = Duration::from_secs_f64(1.0 / options.fps);
+    let mut steps_per_frame = options.steps_per_frame;
+    let mut generation = 0;
+    let mut paused = false;
+    let mut next_update = Instant::now() + tick;
+    let mut next_frame = Instant::now();
+    let mut dirty = true;
+    for recorder in recorders.iter_mut() {
+        recorder.record(world, generation)?;
+    }
+
+    loop {
+        let now = Instant::now();
+        let deadline = if dirty { next_frame } else if paused { now + frame } else { next_update.min(now + frame) };
+        let mut step = false;
+        if let Some(event) = window.next_event(deadline.saturating_duration_since(now))? {
+            match event {
+                Event::Close => return Ok(generation),
+                Event::Expose => dirty = true,
+                Event::Resize { width: window_width, height: window_height } => {
+                    // The cells grow and shrink with the window.
+                    let fits = (window_width as usize / width.max(1)).min(window_height as usize / height.max(1)).max(1);
+                    if fits != style.cell_size {
+                        // A smaller board would leave the edges of the larger one behind.
+                        window.clear()?;
+                        style.cell_size = fits;
+                        dirty = true;
+                    }
+                }
+                Event::Button { button, x, y } => {
+                    let (x, y) = (x as usize / style.cell_size, y as usize / style.cell_size);
+                    if matches!(button, 1 | 3) && x < width && y < height && world.get(x, y) != (button == 1) {
+                        world.set(x, y, button == 1);
+                        dirty = true;
+                    }
+                }
+                Event::Key { keysym, control } => match key_event(keysym, control).as_ref().and_then(input::map_key) {
+                    Some(Command::Quit) => return Ok(generation),
+                    Some(Command::TogglePause) => {
+                        paused = !paused;
+                        next_update = Instant::now() + tick;
+                        dirty = true;
+                    }
+                    Some(Command::StepForward) if paused => step = true,
+                    Some(command @ (Command::Clear | Command::Fill | Command::Invert)) => {
+                        world.edit(|board| {
+                            for cell in board.iter_mut().flatten() {
+                                *cell = match command {
+                                    Command::Clear => false,
+                                    Command::Fill => true,
+                                    _ => !*cell,
+                                };
+                            }
+                        });
+                        dirty = true;
+                    }
+                    Some(Command::Reseed) => {
+                        world.replace(reseed()?);
+                        generation = 0;
+                        for recorder in recorders.iter_mut() {
+                            recorder.record(world, generation)?;
+                        }
+                        dirty = true;
+                    }
+                    Some(Command::FewerSteps) => steps_per_frame = (steps_per_frame / 2).max(1),
+                    Some(Command::MoreSteps) => steps_per_frame = (steps_per_frame * 2).min(cli::MAX_STEPS_PER_FRAME),
+                    _ => {}
+                },
+            }
+        }
+
+        // A window that falls behind makes up for a few missed batches at once, and skips the rest.
+        let now = Instant::now();
+        let mut steps = 0;
+        if step {
+            steps = 1;
+        } else if !paused && now >= next_update {
+            let missed = (now - next_update).as_secs_f64() / tick.as_secs_f64();
+            steps = steps_per_frame.saturating_mul(1 + missed as u32).min(MAX_CATCH_UP_STEPS * steps_per_frame);
+            next_update = now + tick;
+        }
+        for _ in 0..steps {
+            world.step(&options.rule);
+            generation += 1;
+            for recorder in recorders.iter_mut() {
+                recorder.record(world, generation)?;
+            }
+            if options.generations == Some(generation) {
+                window.draw(&raster::render_grid(world, &style))?;
+                return Ok(generation);
+            }
+            dirty = true;
+        }
+
+        if dirty && Instant::now() >= next_frame {
+            window.draw(&raster::render_grid(world, &style))?;
+            window.set_title(&title(options, generation, world, paused))?;
+            next_frame = Instant::now() + frame;
+            dirty = false;
+        }
+    }
+}
+
+/// Fails, because the program was built without window support.
+#[cfg(not(all(unix, feature = "gui")))]
+pub fn run(
+    _world: &mut World,
+    _options: &cli::Options,
+    _recorders: &mut [Box<dyn FrameRecorder>],
+    _reseed: &mut dyn FnMut() -> Result<Grid, Box<dyn Error>>,
+) -> Result<u64, Box<dyn Error>> {
+    Err("--gui needs window support; rebuild with `cargo build --features gui` on a system with X11".into())
+}
+
+/// Returns the title of the window: the rule, generation, and population.
+#[cfg(all(unix, feature = "gui"))]
+fn title(options: &cli::Options, generation: u64, world: &World, paused: bool) -> String {
+    let paused = if paused { " (paused)" } else { "" };
+    format!("Game of Life - {} - generation {} - population {}{}", options.rule, generation, world.population(), paused)
+}
+
+/// Returns the terminal key event of an X keysym, so the window takes the same keys as the
+/// terminal.
+///
+/// # Arguments
+///
+/// * `keysym` - The keysym of the key.
+/// * `control` - Whether Control was held.
+///
+/// # Returns
+///
+/// The key event, or `None` for keys the terminal frontend has no binding for.
+#[cfg(all(unix, feature = "gui"))]
+fn key_event(keysym: u32, control: bool) -> Option<KeyEvent> {
+    let code = match keysym {
+        0x20..=0x7e => KeyCode::Char(char::from(keysym as u8)),
+        0xff1b => KeyCode::Esc,
+        0xff51 => KeyCode::Left,
+        0xff53 => KeyCode::Right,
+        0xffbe..=0xffc9 => KeyCode::F((keysym - 0xffbe + 1) as u8),
+        _ => return None,
+    };
+    let modifiers = if control { KeyModifiers::CONTROL } else { KeyModifiers::NONE };
+    Some(KeyEvent::new(code, modifiers))
+}
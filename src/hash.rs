@@ -0,0 +1,108 @@
+use std::fmt;
+
+/// A hash of the live cells of a board that can be updated incrementally.
+///
+/// This is Zobrist hashing: every cell position has a pseudo-random 128-bit key, and the
+/// hash of a board is the XOR of the keys of its live cells. Toggling a cell XORs its key
+/// in or out again, so a generation only costs as much as the cells that changed, and two
+/// boards with the same live cells always hash the same no matter how they were reached.
+///
+/// The keys depend only on the cell coordinates, not on the size of the grid, so a pattern
+/// hashes differently at every position it is placed at. With 128 bits, two different
+/// boards of a run sharing a hash is too unlikely to be worth checking for, so an equal
+/// hash is taken as an equal board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BoardHash(u128);
+
+/// Returns the key of the cell at the given position.
+///
+/// The key is the SplitMix64 finalizer applied to the packed coordinates twice, from two
+/// different offsets, which spreads neighbouring positions over the whole 128-bit range
+/// without a lookup table.
+///
+/// # Arguments
+///
+/// * `x` - The column of the cell.
+/// * `y` - The row of the cell.
+pub fn cell_key(x: usize, y: usize) -> u128 {
+    let packed = (y as u64) << 32 | x as u64;
+    let mix = |offset: u64| {
+        let mut z = packed.wrapping_add(offset);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    (mix(0x9e37_79b9_7f4a_7c15) as u128) << 64 | mix(0x3c6e_f372_fe94_f82a) as u128
+}
+
+impl BoardHash {
+    /// Hashes a whole board from scratch.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board to hash.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the board's live cells.
+    pub fn of(grid: &[Vec<bool>]) -> BoardHash {
+        let mut hash = BoardHash::default();
+        for (y, row) in grid.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                if cell {
+                    hash.toggle(x, y);
+                }
+            }
+        }
+        hash
+    }
+
+    /// Updates the hash for a cell that was born or died.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell.
+    /// * `y` - The row of the cell.
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        self.0 ^= cell_key(x, y);
+    }
+
+    /// Updates the hash for a list of cells that changed state.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - The positions of the cells that were born or died, as `(x, y)`.
+    pub fn apply_changes(&mut self, changes: impl IntoIterator<Item = (usize, usize)>) {
+        for (x, y) in changes {
+            self.toggle(x, y);
+        }
+    }
+
+    /// Updates the hash from one generation to the next, toggling only the cells that differ.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The board the hash currently describes.
+    /// * `next` - The new board, of the same size.
+    pub fn update(&mut self, prev: &[Vec<bool>], next: &[Vec<bool>]) {
+        for (y, (old_row, new_row)) in prev.iter().zip(next).enumerate() {
+            for (x, (old, new)) in old_row.iter().zip(new_row).enumerate() {
+                if old != new {
+                    self.toggle(x, y);
+                }
+            }
+        }
+    }
+
+    /// Returns the hash as a number.
+    pub fn value(&self) -> u128 {
+        self.0
+    }
+}
+
+impl fmt::Display for BoardHash {
+    /// Formats the hash as 32 hex digits.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:032x}", self.0)
+    }
+}
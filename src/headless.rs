@@ -0,0 +1,126 @@
+use std::{
+    error::Error,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    cycle::{Cycle, CycleDetector, CyclePolicy, DEFAULT_MAX_PERIOD},
+    hash::BoardHash,
+    noise::Noise,
+    recorder::FrameRecorder,
+    rule::Rule,
+    world::World,
+    Grid,
+};
+
+/// The default size of the grid in headless mode.
+pub const DEFAULT_WIDTH: usize = 256;
+pub const DEFAULT_HEIGHT: usize = 256;
+
+/// The default number of generations simulated in headless mode.
+pub const DEFAULT_GENERATIONS: u64 = 1000;
+
+/// The outcome of a headless run.
+pub struct HeadlessReport {
+    /// The number of generations simulated.
+    pub generations: u64,
+    /// The wall-clock time spent simulating.
+    pub elapsed: Duration,
+    /// The number of live cells after the last generation.
+    pub final_population: usize,
+    /// The hash of the board after the last generation, for comparing runs.
+    pub final_hash: BoardHash,
+    /// The cycles found, one for each board when restarting on cycles.
+    pub cycles: Vec<Cycle>,
+}
+
+impl HeadlessReport {
+    /// Returns the achieved simulation speed.
+    pub fn generations_per_second(&self) -> f64 {
+        self.generations as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Runs the simulation without any terminal output, as fast as possible.
+///
+/// The board stays in the world's engine for the whole run, which keeps its hash up to date
+/// from the cells each step changes; the board is only unpacked for recorders.
+///
+/// # Arguments
+///
+/// * `world` - The initial board, in the engine and with the boundary it is stepped with.
+/// * `rule` - The rule to apply.
+/// * `generations` - The number of generations to simulate.
+/// * `recorders` - Recorders that receive every generation, including the initial one.
+/// * `on_cycle` - What to do when the board settles into a cycle, or `None` not to look for cycles.
+/// * `reseed` - Creates the fresh board a run restarts on.
+/// * `noise` - Random flips made before each generation, if any.
+///
+/// # Returns
+///
+/// The timing, final population, final state hash, and cycles of the run.
+pub fn run(
+    mut world: World,
+    rule: &Rule,
+    generations: u64,
+    recorders: &mut [Box<dyn FrameRecorder>],
+    on_cycle: Option<CyclePolicy>,
+    reseed: &mut dyn FnMut() -> Result<Grid, Box<dyn Error>>,
+    mut noise: Option<&mut Noise>,
+) -> Result<HeadlessReport, Box<dyn Error>> {
+    let start = Instant::now();
+    for recorder in recorders.iter_mut() {
+        recorder.record(&world, 0)?;
+    }
+
+    let mut detector = on_cycle.map(|_| CycleDetector::new(DEFAULT_MAX_PERIOD));
+    if let Some(detector) = detector.as_mut() {
+        detector.observe(world.hash(), 0);
+    }
+    let mut cycles = Vec::new();
+    // The generation the current board started at, which moves forward on each restart.
+    let mut board_start = 0;
+
+    let mut simulated = 0;
+    for generation in 1..=generations {
+        if let Some(noise) = noise.as_deref_mut() {
+            let (width, height) = world.bounds();
+            for (x, y) in noise.flips(width, height) {
+                world.toggle(x, y);
+            }
+        }
+        world.step(rule);
+        simulated = generation;
+
+        // Cycle generations are counted from the start of the current board.
+        let cycle = detector.as_mut().and_then(|detector| detector.observe(world.hash(), generation - board_start));
+        if let (Some(cycle), Some(policy)) = (cycle, on_cycle) {
+            cycles.push(cycle);
+            match policy {
+                CyclePolicy::Stop => break,
+                CyclePolicy::Report => {}
+                CyclePolicy::Restart => {
+                    world.replace(reseed()?);
+                    board_start = generation;
+                    if let Some(detector) = detector.as_mut() {
+                        detector.reset();
+                        detector.observe(world.hash(), 0);
+                    }
+                }
+            }
+        }
+
+        for recorder in recorders.iter_mut() {
+            recorder.record(&world, generation)?;
+        }
+    }
+    let elapsed = start.elapsed();
+
+    Ok(HeadlessReport {
+        generations: simulated,
+        elapsed,
+        final_population: world.population(),
+        final_hash: world.hash(),
+        cycles,
+    })
+}
@@ -0,0 +1,77 @@
+//! The activity heatmap drawn by `--render heatmap`.
+//!
+//! Every cell keeps an exponentially decayed count of how often it has been alive, so the
+//! places where the board is busy stay lit for a while after their cells die.
+
+use crate::raster::Rgb;
+
+/// The fraction of a cell's activity kept from one generation to the next by default.
+pub const DEFAULT_DECAY: f32 = 0.9;
+
+/// The number of distinct colors the heatmap is drawn in, which keeps a settled board
+/// from being redrawn every generation as the activity fades.
+const LEVELS: usize = 32;
+
+/// The colors of the heatmap from cold to hot, spread evenly over the activity.
+const GRADIENT: [Rgb; 5] = [Rgb(20, 24, 82), Rgb(52, 84, 214), Rgb(214, 48, 64), Rgb(255, 196, 40), Rgb(255, 255, 240)];
+
+/// The decayed activity of every cell of a board.
+pub struct Heatmap {
+    /// The activity of each cell, from 0 up to the activity of a cell that is always alive.
+    activity: Vec<Vec<f32>>,
+    /// The fraction of the activity kept each generation.
+    decay: f32,
+}
+
+impl Heatmap {
+    /// Creates a heatmap with no activity.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of columns of the board.
+    /// * `height` - The number of rows of the board.
+    /// * `decay` - The fraction of the activity kept each generation, below 1.
+    pub fn new(width: usize, height: usize, decay: f32) -> Heatmap {
+        Heatmap { activity: vec![vec![0.0; width]; height], decay }
+    }
+
+    /// Adds a generation to the heatmap.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board, which must be the size of the heatmap.
+    pub fn observe(&mut self, grid: &[Vec<bool>]) {
+        for (activity, row) in self.activity.iter_mut().zip(grid) {
+            for (activity, &alive) in activity.iter_mut().zip(row) {
+                *activity = *activity * self.decay + alive as u8 as f32;
+            }
+        }
+    }
+
+    /// Returns the color of every cell, or `None` for cells that have been quiet for long.
+    pub fn colors(&self) -> Vec<Vec<Option<Rgb>>> {
+        // A cell that is always alive settles at 1 / (1 - decay).
+        let full = 1.0 / (1.0 - self.decay);
+        self.activity
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&activity| {
+                        let level = ((activity / full).min(1.0) * LEVELS as f32).round() as usize;
+                        (level > 0).then(|| color_of(level as f32 / LEVELS as f32))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Returns the color of the gradient at a fraction along it.
+fn color_of(fraction: f32) -> Rgb {
+    let position = fraction * (GRADIENT.len() - 1) as f32;
+    let index = (position.floor() as usize).min(GRADIENT.len() - 2);
+    let t = position - index as f32;
+    let (Rgb(r0, g0, b0), Rgb(r1, g1, b1)) = (GRADIENT[index], GRADIENT[index + 1]);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    Rgb(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+}
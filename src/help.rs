@@ -0,0 +1,109 @@
+use crossterm::{
+    cursor,
+    style::{Attribute, Print, SetAttribute},
+    QueueableCommand,
+};
+use std::{error::Error, io::Write};
+
+use crate::display::Region;
+
+/// Every key of the main simulation, as (keys, action) pairs.
+const KEYS: [(&str, &str); 23] = [
+    ("q Esc ^C", "quit"),
+    ("Space", "pause or resume"),
+    ("← → , .", "step back, step forward while paused"),
+    ("[ ]", "halve or double the generations per frame"),
+    ("s F12", "save a screenshot"),
+    ("F5 F9", "save a snapshot, load it"),
+    ("e", "open the RLE editor"),
+    ("p", "paste a pattern from the clipboard"),
+    ("t", "pick a pattern to stamp while paused"),
+    ("u ^R", "undo, redo an edit"),
+    ("mouse", "paint, erase with ^ or right, wheel for brush"),
+    ("f", "show or hide the timings"),
+    ("b", "show or hide the status bar"),
+    ("g", "show or hide the sparkline"),
+    ("i", "name the objects of the paused board"),
+    ("c F x", "clear, fill, invert the board"),
+    ("r", "reseed with a new soup"),
+    ("< >", "lower or raise the soup probability"),
+    ("R", "type a rule to switch to"),
+    ("↑ ↓", "pick a pattern (--panel)"),
+    ("Enter", "place the pattern (--panel)"),
+    ("n", "next rule (--panel)"),
+    ("?", "show or hide this help"),
+];
+
+/// The width of the column of keys.
+const KEY_WIDTH: usize = 10;
+
+/// Returns the lines inside the frame of the overlay.
+///
+/// # Arguments
+///
+/// * `settings` - The settings of the run, as (name, value) pairs.
+fn lines(settings: &[(&str, String)]) -> Vec<String> {
+    let mut lines: Vec<String> = settings.iter().map(|(name, value)| format!(" {:<KEY_WIDTH$}{}", name, value)).collect();
+    lines.push(String::new());
+    lines.extend(KEYS.iter().map(|(keys, action)| format!(" {:<KEY_WIDTH$}{}", keys, action)));
+    lines
+}
+
+/// Returns the part of the terminal area the overlay covers, frame included, if it fits.
+///
+/// # Arguments
+///
+/// * `settings` - The settings of the run, as (name, value) pairs.
+/// * `cols` - The width of the terminal area.
+/// * `rows` - The height of the terminal area.
+pub fn area(settings: &[(&str, String)], cols: usize, rows: usize) -> Option<Region> {
+    let lines = lines(settings);
+    let inner_width = lines.iter().map(|line| line.chars().count() + 1).max().unwrap_or(0).min(cols.saturating_sub(2));
+    let height = (lines.len() + 2).min(rows);
+    if inner_width < 4 || height < 3 {
+        return None;
+    }
+    let width = inner_width + 2;
+    Some(Region { left: (cols - width) / 2, top: (rows - height) / 2, cols: width, rows: height })
+}
+
+/// Draws the help overlay in the middle of the screen, with the keys and the settings of
+/// the run.
+///
+/// The overlay covers the board, which is left as it was beneath it; the main loop redraws
+/// the covered cells from the board when the overlay is dismissed.
+///
+/// # Arguments
+///
+/// * `out` - The terminal to draw on.
+/// * `settings` - The settings of the run, as (name, value) pairs.
+/// * `cols` - The width of the terminal area.
+/// * `rows` - The height of the terminal area.
+pub fn draw(out: &mut impl Write, settings: &[(&str, String)], cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+    let Some(Region { left, top, cols: width, rows: height }) = area(settings, cols, rows) else {
+        return Ok(());
+    };
+    let lines = lines(settings);
+    let inner_width = width - 2;
+    let fit = |text: &str| -> String { text.chars().chain(std::iter::repeat(' ')).take(inner_width).collect() };
+
+    let title: String = " Help: any key to close ".chars().take(inner_width).collect();
+    out.queue(cursor::MoveTo(left as u16, top as u16))?;
+    out.queue(Print(format!("┌{}{}┐", title, "─".repeat(inner_width - title.chars().count()))))?;
+    for (i, line) in lines.iter().take(height - 2).enumerate() {
+        out.queue(cursor::MoveTo(left as u16, (top + 1 + i) as u16))?;
+        out.queue(Print("│"))?;
+        // The settings are set apart from the keys.
+        if i < settings.len() {
+            out.queue(SetAttribute(Attribute::Bold))?;
+            out.queue(Print(fit(line)))?;
+            out.queue(SetAttribute(Attribute::NormalIntensity))?;
+        } else {
+            out.queue(Print(fit(line)))?;
+        }
+        out.queue(Print("│"))?;
+    }
+    out.queue(cursor::MoveTo(left as u16, (top + height - 1) as u16))?;
+    out.queue(Print(format!("└{}┘", "─".repeat(inner_width))))?;
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use std::collections::VecDeque;
+
+use crate::{bitgrid::BitGrid, Grid};
+
+/// A bounded record of the most recent generations, for stepping backwards in time.
+///
+/// Boards are stored packed, one bit per cell, so a deep history of a large board stays
+/// small. When the history is full, the oldest generation is dropped to make room.
+#[derive(Debug)]
+pub struct History {
+    /// The most generations kept.
+    depth: usize,
+    /// The kept generations and their boards, oldest first.
+    entries: VecDeque<(u64, BitGrid)>,
+}
+
+impl History {
+    /// Creates an empty history.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The most generations kept; zero keeps none.
+    pub fn new(depth: usize) -> History {
+        History {
+            depth,
+            entries: VecDeque::with_capacity(depth.min(1024)),
+        }
+    }
+
+    /// Records a generation, dropping the oldest one if the history is full.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation number of the board.
+    /// * `grid` - The board.
+    pub fn push(&mut self, generation: u64, grid: &[Vec<bool>]) {
+        if self.depth == 0 {
+            return;
+        }
+        // The oldest board's words are reused for the new one once the history is full.
+        let bits = match self.entries.len() == self.depth {
+            true => self.entries.pop_front().map(|(_, mut bits)| {
+                bits.pack(grid);
+                bits
+            }),
+            false => None,
+        };
+        self.entries.push_back((generation, bits.unwrap_or_else(|| BitGrid::from_grid(grid))));
+    }
+
+    /// Removes and returns the most recent generation.
+    ///
+    /// # Returns
+    ///
+    /// The generation number and its board, or `None` if the history is empty.
+    pub fn pop(&mut self) -> Option<(u64, Grid)> {
+        self.entries.pop_back().map(|(generation, bits)| (generation, bits.to_grid()))
+    }
+
+    /// Returns the number of generations kept.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether no generations are kept.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Forgets every kept generation.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{cli::Options, display::Region};
+
+/// The parts of the screen around the board that can be shown or hidden while the
+/// simulation runs.
+///
+/// The status bar keeps its row below the board while hidden, so hiding it never moves
+/// the board; messages such as a saved screenshot still show there until they expire.
+pub struct Hud {
+    /// Whether the status bar is shown, toggled with `b`.
+    pub status_bar: bool,
+    /// Whether the statistics include a sparkline of the population, toggled with `g`.
+    pub sparkline: bool,
+    /// Whether the timing overlay is shown, toggled with `f`.
+    pub timings: bool,
+}
+
+impl Hud {
+    /// Creates the display with the parts the options and configuration file ask for.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The parsed command-line options.
+    pub fn new(options: &Options) -> Hud {
+        Hud {
+            status_bar: options.status_bar,
+            sparkline: options.sparkline,
+            timings: options.timings,
+        }
+    }
+}
+
+/// Finds a corner of the board for an overlay, where it covers none of the regions other
+/// overlays were drawn in.
+///
+/// The corners are tried clockwise from the top right, and the overlay is left out when
+/// none of them is free.
+///
+/// # Arguments
+///
+/// * `cols` - The width of the overlay.
+/// * `rows` - The height of the overlay.
+/// * `board` - The part of the terminal the board is drawn in.
+/// * `reserved` - The regions taken by other overlays.
+///
+/// # Returns
+///
+/// Where the overlay goes, or `None` if it does not fit.
+pub fn place(cols: usize, rows: usize, board: Region, reserved: &[Region]) -> Option<Region> {
+    if cols > board.cols || rows > board.rows {
+        return None;
+    }
+    let (right, bottom) = (board.left + board.cols - cols, board.top + board.rows - rows);
+    [(right, board.top), (right, bottom), (board.left, bottom), (board.left, board.top)]
+        .into_iter()
+        .map(|(left, top)| Region { left, top, cols, rows })
+        .find(|region| !reserved.iter().any(|taken| region.overlaps(taken)))
+}
@@ -0,0 +1,213 @@
+use crossterm::{
+    event::{self, DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent},
+    execute, terminal,
+};
+use std::{
+    error::Error,
+    io::stdout,
+    time::{Duration, Instant},
+};
+
+/// An action requested by the user from the keyboard.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// Exit the program.
+    Quit,
+    /// Save a styled PNG screenshot of the board.
+    Screenshot,
+    /// Open the RLE editor pane.
+    OpenEditor,
+    /// Pause or resume the simulation.
+    TogglePause,
+    /// Go back one generation in the history, pausing the simulation.
+    StepBack,
+    /// Advance one generation while paused.
+    StepForward,
+    /// Save a snapshot of the simulation.
+    SaveSnapshot,
+    /// Restore the simulation from the saved snapshot.
+    LoadSnapshot,
+    /// Show or hide the timing overlay.
+    ToggleTimings,
+    /// Kill every cell of the board.
+    Clear,
+    /// Bring every cell of the board to life.
+    Fill,
+    /// Flip every cell of the board.
+    Invert,
+    /// Replace the board with a new random soup.
+    Reseed,
+    /// Lower the probability that the cells of the next soup start alive.
+    LowerProbability,
+    /// Raise the probability that the cells of the next soup start alive.
+    RaiseProbability,
+    /// Type a rule to switch the board to.
+    PromptRule,
+    /// Show or hide the status bar.
+    ToggleStatusBar,
+    /// Show or hide the sparkline of the population.
+    ToggleSparkline,
+    /// Name the recognized objects of the paused board, or hide their names.
+    IdentifyObjects,
+    /// Stamp the pattern on the system clipboard onto the board.
+    Paste,
+    /// Halve the number of generations computed between frames.
+    FewerSteps,
+    /// Double the number of generations computed between frames.
+    MoreSteps,
+    /// Show the keys and the settings of the run.
+    Help,
+    /// Open the picker to stamp a pattern on the paused board.
+    PickPattern,
+    /// Take back the latest change made to the board by hand.
+    Undo,
+    /// Make the latest change that was taken back again.
+    Redo,
+}
+
+/// Input delivered by the terminal.
+#[derive(Clone, Debug)]
+pub enum Input {
+    /// A key press.
+    Key(KeyEvent),
+    /// Text pasted into the terminal in one piece.
+    Paste(String),
+    /// A mouse button, drag, or scroll over the terminal, while the mouse is captured.
+    Mouse(MouseEvent),
+    /// The terminal changed size, to a number of columns and rows.
+    Resize(u16, u16),
+}
+
+/// Keeps the terminal in raw mode for as long as it is alive.
+///
+/// Raw mode delivers key presses immediately instead of line by line, and
+/// bracketed paste delivers pasted text as a single event. Dropping the guard
+/// restores the terminal, including when the program exits early with an error.
+pub struct RawMode;
+
+impl RawMode {
+    /// Switches the terminal to raw mode.
+    pub fn enable() -> Result<RawMode, Box<dyn Error>> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout(), EnableBracketedPaste)?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), DisableBracketedPaste);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Captures the mouse for as long as it is alive, so clicks, drags, and the scroll wheel
+/// reach the program instead of selecting text in the terminal.
+///
+/// Dropping the guard gives the mouse back to the terminal.
+pub struct MouseCapture;
+
+impl MouseCapture {
+    /// Starts reporting mouse events.
+    pub fn enable() -> Result<MouseCapture, Box<dyn Error>> {
+        execute!(stdout(), EnableMouseCapture)?;
+        Ok(MouseCapture)
+    }
+}
+
+impl Drop for MouseCapture {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), DisableMouseCapture);
+    }
+}
+
+/// Checks whether a key press is Ctrl-C.
+///
+/// Ctrl-C no longer raises SIGINT in raw mode, so it is handled as a key.
+pub fn is_ctrl_c(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c')
+}
+
+/// Maps a key press to a command.
+///
+/// # Arguments
+///
+/// * `key` - The key event.
+///
+/// # Returns
+///
+/// The command bound to the key, if any.
+pub fn map_key(key: &KeyEvent) -> Option<Command> {
+    if is_ctrl_c(key) {
+        return Some(Command::Quit);
+    }
+
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('r') {
+        return Some(Command::Redo);
+    }
+
+    match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => Some(Command::Quit),
+        KeyCode::Char('s') | KeyCode::F(12) => Some(Command::Screenshot),
+        KeyCode::Char('e') => Some(Command::OpenEditor),
+        KeyCode::Char(' ') => Some(Command::TogglePause),
+        KeyCode::Char('p') => Some(Command::Paste),
+        KeyCode::Left | KeyCode::Char(',') => Some(Command::StepBack),
+        KeyCode::Right | KeyCode::Char('.') => Some(Command::StepForward),
+        KeyCode::F(5) => Some(Command::SaveSnapshot),
+        KeyCode::F(9) => Some(Command::LoadSnapshot),
+        KeyCode::Char('f') => Some(Command::ToggleTimings),
+        KeyCode::Char('c') => Some(Command::Clear),
+        KeyCode::Char('F') => Some(Command::Fill),
+        KeyCode::Char('x') => Some(Command::Invert),
+        KeyCode::Char('r') => Some(Command::Reseed),
+        KeyCode::Char('<') => Some(Command::LowerProbability),
+        KeyCode::Char('>') => Some(Command::RaiseProbability),
+        KeyCode::Char('R') => Some(Command::PromptRule),
+        KeyCode::Char('b') => Some(Command::ToggleStatusBar),
+        KeyCode::Char('g') => Some(Command::ToggleSparkline),
+        KeyCode::Char('i') => Some(Command::IdentifyObjects),
+        KeyCode::Char('[') => Some(Command::FewerSteps),
+        KeyCode::Char(']') => Some(Command::MoreSteps),
+        KeyCode::Char('?') => Some(Command::Help),
+        KeyCode::Char('t') => Some(Command::PickPattern),
+        KeyCode::Char('u') => Some(Command::Undo),
+        _ => None,
+    }
+}
+
+/// Waits for the given duration while collecting the input typed by the user.
+///
+/// This replaces a plain sleep in the main loop, so the simulation speed stays the
+/// same while key presses are handled as soon as the tick ends.
+///
+/// # Arguments
+///
+/// * `timeout` - How long to wait.
+///
+/// # Returns
+///
+/// The key presses, pastes, and mouse events received during the wait, in order. Input that is
+/// already waiting is collected even when the timeout is zero.
+pub fn wait_for_input(timeout: Duration) -> Result<Vec<Input>, Box<dyn Error>> {
+    let deadline = Instant::now() + timeout;
+    let mut inputs = Vec::new();
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if !event::poll(remaining)? {
+            break;
+        }
+
+        match event::read()? {
+            // Ignore key releases reported by some terminals.
+            Event::Key(key) if key.kind == KeyEventKind::Press => inputs.push(Input::Key(key)),
+            Event::Paste(text) => inputs.push(Input::Paste(text)),
+            Event::Mouse(mouse) => inputs.push(Input::Mouse(mouse)),
+            Event::Resize(cols, rows) => inputs.push(Input::Resize(cols, rows)),
+            _ => {}
+        }
+    }
+
+    Ok(inputs)
+}
@@ -0,0 +1,26 @@
+//! The escape sequence of iTerm2's inline images, which `--render iterm` draws the board
+//! with on iTerm2 and WezTerm.
+
+use std::error::Error;
+
+use crate::{kitty, raster::Image};
+
+/// Returns the escape sequence that shows an image at the cursor.
+///
+/// The image is sent as a PNG file, stretched over exactly its own size in pixels so the
+/// terminal does not scale it to the width of the screen.
+///
+/// # Arguments
+///
+/// * `image` - The image to show.
+pub fn inline_image(image: &Image) -> Result<String, Box<dyn Error>> {
+    let mut png = Vec::new();
+    image.write_png(&mut png)?;
+    Ok(format!(
+        "\x1b]1337;File=inline=1;size={};width={}px;height={}px;preserveAspectRatio=0:{}\x07",
+        png.len(),
+        image.width,
+        image.height,
+        kitty::base64(&png),
+    ))
+}
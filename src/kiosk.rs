@@ -0,0 +1,181 @@
+use crossterm::{
+    cursor, execute,
+    event::DisableBracketedPaste,
+    style::ResetColor,
+    terminal::LeaveAlternateScreen,
+};
+use std::{
+    env,
+    error::Error,
+    fs::OpenOptions,
+    io::{stdout, Write},
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The environment variable that marks the simulator started by the kiosk supervisor.
+const CHILD_ENV: &str = "GAMEOFLIFE_KIOSK_CHILD";
+
+/// How long the supervisor waits before starting the simulator again after a failure.
+const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+/// The number of lines of error output kept in the incident log for each failure.
+const INCIDENT_LINES: usize = 10;
+
+/// Returns whether this process is the simulator started by the kiosk supervisor.
+pub fn is_child() -> bool {
+    env::var_os(CHILD_ENV).is_some()
+}
+
+/// Watches the keys typed in kiosk mode for the stop token.
+pub struct StopToken {
+    /// The token that stops the simulator, if one is configured.
+    token: Option<String>,
+    /// The most recently typed characters, as long as the token at most.
+    typed: String,
+}
+
+impl StopToken {
+    /// Creates a watcher for a stop token.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - The token, or `None` if the kiosk can only be stopped from outside.
+    pub fn new(token: Option<String>) -> StopToken {
+        StopToken { token, typed: String::new() }
+    }
+
+    /// Adds a typed character.
+    ///
+    /// # Returns
+    ///
+    /// Whether the characters typed so far end with the stop token.
+    pub fn push(&mut self, c: char) -> bool {
+        let Some(token) = &self.token else {
+            return false;
+        };
+
+        self.typed.push(c);
+        let excess = self.typed.chars().count().saturating_sub(token.chars().count());
+        self.typed = self.typed.chars().skip(excess).collect();
+        self.typed == *token
+    }
+}
+
+/// Formats a time as `YYYY-MM-DD HH:MM:SS` in UTC.
+fn timestamp(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let (days, rest) = (secs / 86400, secs % 86400);
+
+    // Convert the day count to a civil date (Howard Hinnant's algorithm).
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// Appends a line to the incident log, with the time.
+///
+/// Failing to write the log never stops the kiosk.
+fn log(path: &Path, message: &str) {
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{} {}", timestamp(SystemTime::now()), message);
+    }
+}
+
+/// Saves the terminal settings with `stty -g`, so they can be restored after a crash.
+///
+/// The simulator's own raw mode guard cannot run when it is killed, and the supervisor
+/// never enabled raw mode itself, so it restores the settings it saw at startup instead.
+fn save_terminal() -> Option<String> {
+    let output = Command::new("stty").arg("-g").stdin(Stdio::inherit()).stderr(Stdio::null()).output().ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Puts the terminal back in a usable state after the simulator stopped without doing so.
+///
+/// # Arguments
+///
+/// * `settings` - The terminal settings saved at startup, if any.
+fn restore_terminal(settings: Option<&str>) {
+    let _ = execute!(stdout(), DisableBracketedPaste, ResetColor, cursor::Show, LeaveAlternateScreen);
+    if let Some(settings) = settings {
+        let _ = Command::new("stty").arg(settings).stdin(Stdio::inherit()).stderr(Stdio::null()).status();
+    }
+}
+
+/// Runs the simulator in kiosk mode, starting it again whenever it fails.
+///
+/// The simulator runs in a child process with the same arguments. When it exits
+/// successfully, because the stop token was typed or the options are invalid, the kiosk
+/// stops. Any other exit, including a panic or a crash, is written to the incident log
+/// with the end of its error output, the terminal is restored, and the simulator is
+/// started again after a short delay.
+///
+/// # Arguments
+///
+/// * `log_path` - The incident log file.
+pub fn supervise(log_path: &Path) -> Result<(), Box<dyn Error>> {
+    // Ctrl-C does not stop the kiosk; only the stop token does.
+    ctrlc::set_handler(|| {}).map_err(|e| format!("Failed to set the Ctrl-C handler: {}", e))?;
+
+    let program = env::current_exe()?;
+    let args: Vec<_> = env::args_os().skip(1).collect();
+    let settings = save_terminal();
+    log(log_path, "kiosk started");
+
+    let mut restarts: u64 = 0;
+    loop {
+        let output = Command::new(&program)
+            .args(&args)
+            .env(CHILD_ENV, "1")
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .stderr(Stdio::piped())
+            .output();
+
+        let output = match output {
+            Ok(output) if output.status.success() => {
+                log(log_path, "kiosk stopped");
+                return Ok(());
+            }
+            Ok(output) => output,
+            Err(e) => {
+                log(log_path, &format!("failed to start the simulator: {}", e));
+                return Err(format!("Failed to start {}: {}", program.display(), e).into());
+            }
+        };
+
+        restore_terminal(settings.as_deref());
+        restarts += 1;
+
+        // Keep the end of the error output, which holds the error or panic message.
+        let errors = String::from_utf8_lossy(&output.stderr);
+        let lines: Vec<&str> = errors.lines().filter(|line| !line.trim().is_empty()).collect();
+        let tail = lines[lines.len().saturating_sub(INCIDENT_LINES)..].join(" | ");
+        log(
+            log_path,
+            &format!("restart {}: the simulator exited with {}{}", restarts, output.status, if tail.is_empty() { String::new() } else { format!(": {}", tail) }),
+        );
+
+        println!("The simulator stopped unexpectedly; restarting in {} seconds...", RESTART_DELAY.as_secs());
+        thread::sleep(RESTART_DELAY);
+    }
+}
@@ -0,0 +1,92 @@
+//! The escape sequences of the kitty graphics protocol, which `--render kitty` draws the
+//! board with on kitty and WezTerm.
+
+use std::fmt::Write;
+
+use crate::raster::Image;
+
+/// The most base64 bytes sent in one escape sequence; longer images are sent in chunks.
+const CHUNK_SIZE: usize = 4096;
+
+/// Returns the escape sequences that send an image and show it at the cursor.
+///
+/// An image sent with the id of one already on the screen replaces it. The cursor is left
+/// where it was, so text can be drawn over the image.
+///
+/// # Arguments
+///
+/// * `image` - The image to show.
+/// * `id` - The number the terminal knows the image by.
+pub fn transmit(image: &Image, id: u32) -> String {
+    let keys = format!("a=T,f=24,s={},v={},i={},q=2,C=1", image.width, image.height, id);
+    chunked(&keys, &image.pixels)
+}
+
+/// Returns the escape sequences that paint part of an image already on the screen.
+///
+/// Only the pixels that changed are sent, so a board where few cells changed is updated
+/// with a small message instead of the whole image.
+///
+/// # Arguments
+///
+/// * `patch` - The new pixels.
+/// * `id` - The number the terminal knows the image by.
+/// * `x` - The column of the image the left edge of the patch goes at, in pixels.
+/// * `y` - The row of the image the top edge of the patch goes at, in pixels.
+pub fn update(patch: &Image, id: u32, x: usize, y: usize) -> String {
+    let keys = format!("a=f,r=1,f=24,s={},v={},x={},y={},i={},q=2", patch.width, patch.height, x, y, id);
+    chunked(&keys, &patch.pixels)
+}
+
+/// Returns the escape sequence that takes an image off the screen and frees its pixels,
+/// which clearing the screen leaves in place.
+///
+/// # Arguments
+///
+/// * `id` - The number the terminal knows the image by.
+pub fn delete(id: u32) -> String {
+    format!("\x1b_Ga=d,d=I,i={},q=2\x1b\\", id)
+}
+
+/// Splits the base64 of a payload into escape sequences of at most [`CHUNK_SIZE`] bytes,
+/// with the keys on the first and `m=1` on all but the last.
+///
+/// # Arguments
+///
+/// * `keys` - The keys of the command.
+/// * `payload` - The data to send.
+fn chunked(keys: &str, payload: &[u8]) -> String {
+    let data = base64(payload);
+    let chunks: Vec<&[u8]> = if data.is_empty() { vec![&[]] } else { data.as_bytes().chunks(CHUNK_SIZE).collect() };
+
+    let mut out = String::with_capacity(data.len() + chunks.len() * 16 + keys.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = (i + 1 < chunks.len()) as u8;
+        let keys = if i == 0 { format!("{},m={}", keys, more) } else { format!("m={},q=2", more) };
+        // The chunks are ASCII, so they are valid UTF-8.
+        let _ = write!(out, "\x1b_G{};{}\x1b\\", keys, String::from_utf8_lossy(chunk));
+    }
+    out
+}
+
+/// Encodes bytes in standard base64, with padding.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to encode.
+pub fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for group in bytes.chunks(3) {
+        let word = group.iter().enumerate().fold(0u32, |word, (i, &byte)| word | (byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= group.len() {
+                out.push(ALPHABET[(word >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
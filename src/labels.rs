@@ -0,0 +1,119 @@
+use crossterm::{
+    cursor,
+    style::{Attribute, Print, SetAttribute},
+    QueueableCommand,
+};
+use std::{collections::BTreeMap, error::Error, io::Write};
+
+use gameoflife::{
+    census::{self, UNKNOWN_OBJECT},
+    hash::BoardHash,
+};
+
+use crate::display::RenderMode;
+
+/// A recognized object and where it is on the board.
+struct Label {
+    /// The name of the object, as in "glider".
+    name: &'static str,
+    /// The leftmost column of the object.
+    left: usize,
+    /// The top row of the object.
+    top: usize,
+    /// The bottom row of the object.
+    bottom: usize,
+}
+
+/// The names of the recognized objects on a paused board, drawn next to each object.
+///
+/// Objects are told apart and named with the known-object table of [`census`]; objects
+/// that are not in the table are left unlabeled. The labels belong to one board and
+/// should be dropped once it changes.
+pub struct ObjectLabels {
+    /// The labels of the recognized objects.
+    labels: Vec<Label>,
+    /// The number of recognized objects of each kind, by name.
+    counts: BTreeMap<&'static str, usize>,
+    /// The hash of the board the labels were made for.
+    board: BoardHash,
+}
+
+impl ObjectLabels {
+    /// Finds and names the objects on a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    pub fn scan(grid: &[Vec<bool>]) -> ObjectLabels {
+        let mut labels = Vec::new();
+        let mut counts = BTreeMap::new();
+        for cells in census::objects(grid) {
+            let name = census::classify(&cells);
+            if name == UNKNOWN_OBJECT {
+                continue;
+            }
+            *counts.entry(name).or_insert(0) += 1;
+            labels.push(Label {
+                name,
+                left: cells.iter().map(|&(x, _)| x).min().unwrap_or(0),
+                top: cells.iter().map(|&(_, y)| y).min().unwrap_or(0),
+                bottom: cells.iter().map(|&(_, y)| y).max().unwrap_or(0),
+            });
+        }
+        ObjectLabels { labels, counts, board: BoardHash::of(grid) }
+    }
+
+    /// Returns whether the labels were made for a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board shown now.
+    pub fn is_for(&self, grid: &[Vec<bool>]) -> bool {
+        BoardHash::of(grid) == self.board
+    }
+
+    /// Returns a one-line summary of the recognized objects, as in "2 block, 1 glider".
+    pub fn summary(&self) -> String {
+        if self.counts.is_empty() {
+            return "No known objects found".to_string();
+        }
+        let counts: Vec<String> = self.counts.iter().map(|(name, count)| format!("{} {}", count, name)).collect();
+        format!("Found {}", counts.join(", "))
+    }
+
+    /// Draws the labels on the board, each on the row above its object, or below it for
+    /// objects at the top edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `mode` - How the board is drawn, which decides where each cell is on the screen.
+    /// * `origin` - The cell at the top-left corner of the screen.
+    /// * `cols` - The number of terminal columns of the board.
+    /// * `rows` - The number of terminal rows of the board.
+    pub fn draw(&self, out: &mut impl Write, mode: RenderMode, origin: (usize, usize), cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+        let (cell_cols, cell_rows) = mode.cells_per_char();
+        out.queue(SetAttribute(Attribute::Reverse))?;
+        for label in &self.labels {
+            // Objects out of view are left unlabeled.
+            let (Some(left), Some(top)) = (label.left.checked_sub(origin.0), label.top.checked_sub(origin.1)) else {
+                continue;
+            };
+            let (top, bottom) = (top / cell_rows, (label.bottom - origin.1) / cell_rows);
+            let row = if top > 0 { top - 1 } else { bottom + 1 };
+            let col = match mode {
+                RenderMode::Hex => left * 2 + top % 2,
+                _ => left / cell_cols,
+            };
+            if row >= rows || col >= cols {
+                continue;
+            }
+
+            let text: String = label.name.chars().take(cols - col).collect();
+            out.queue(cursor::MoveTo(col as u16, row as u16))?;
+            out.queue(Print(text))?;
+        }
+        out.queue(SetAttribute(Attribute::NoReverse))?;
+        Ok(())
+    }
+}
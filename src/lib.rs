@@ -0,0 +1,149 @@
+//! The simulation core of the Game of Life: the grid, the rules, patterns, and exporters.
+//!
+//! The terminal frontend in `main.rs` is built on top of this library, and the same
+//! modules can be used directly by other tools. The stepping engine itself, with the grid,
+//! rules, and boundaries, lives in the `no_std` crate `gameoflife-core` and is re-exported
+//! here under the same paths.
+
+pub mod ant;
+pub mod automaton;
+pub mod brush;
+pub mod census;
+pub mod cycle;
+pub mod edits;
+pub mod elementary;
+pub mod emit;
+pub mod engine;
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod font;
+pub mod gradient;
+pub mod hash;
+pub mod heatmap;
+pub mod headless;
+pub mod history;
+pub mod iterm;
+pub mod kitty;
+pub mod library;
+pub mod margolus;
+pub mod noise;
+pub mod pattern;
+pub mod placement;
+pub mod raster;
+pub mod recorder;
+pub mod replay;
+pub mod scene;
+pub mod seeding;
+pub mod settings;
+pub mod sixel;
+pub mod runlog;
+pub mod snapshot;
+pub mod soup;
+pub mod stats;
+pub mod stream;
+pub mod symmetry;
+pub mod trails;
+pub mod variant;
+pub mod world;
+#[cfg(all(unix, feature = "gui"))]
+pub mod x11;
+
+pub use gameoflife_core::{
+    bitgrid, boundary, hensel, live_neighbors, live_neighbors_within, ltl, rule, update_grid, update_grid_naive,
+    update_grid_with_boundary, Grid,
+};
+use rand::Rng;
+
+/// Generates a grid of the given size with a random pattern of live and dead cells.
+///
+/// # Arguments
+///
+/// * `cols` - The number of columns in the grid.
+/// * `rows` - The number of rows in the grid.
+/// * `initial_grid_probability` - The probability that a cell starts alive.
+/// * `rng` - The random number generator.
+///
+/// # Returns
+///
+/// The random grid.
+pub fn random_grid(cols: usize, rows: usize, initial_grid_probability: f64, rng: &mut impl Rng) -> Grid {
+    // Create a 2D vector with the correct dimensions
+    // and initialize all cells to `false`.
+    let mut grid = vec![vec![false; cols]; rows];
+
+    // Set randomly generated live cells in the grid.
+    for row in grid.iter_mut() {
+        for cell in row.iter_mut() {
+            *cell = rng.gen_bool(initial_grid_probability); // Reduced the probability to make the grid less crowded.
+        }
+    }
+
+    grid
+}
+
+/// Computes the next generation of Brian's Brain.
+///
+/// A ready cell (0) starts firing (1) when exactly two of its eight neighbours are firing,
+/// a firing cell becomes refractory (2), and a refractory cell becomes ready again.
+///
+/// # Arguments
+///
+/// * `cells` - The states of the cells.
+///
+/// # Returns
+///
+/// The next generation.
+pub fn brians_brain_step(cells: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let firing: Vec<Vec<bool>> = cells.iter().map(|row| row.iter().map(|&state| state == 1).collect()).collect();
+
+    cells
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, &state)| match state {
+                    0 if live_neighbors(&firing, x, y) == 2 => 1,
+                    0 => 0,
+                    1 => 2,
+                    _ => 0,
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Computes the next generation of Wireworld.
+///
+/// An electron head (1) becomes a tail (2), a tail becomes a conductor (3), and a
+/// conductor becomes a head when one or two of its eight neighbours are heads. Empty
+/// cells (0) stay empty. The states are numbered as in Golly, so its circuits load as-is.
+///
+/// # Arguments
+///
+/// * `cells` - The states of the cells.
+///
+/// # Returns
+///
+/// The next generation.
+pub fn wireworld_step(cells: &[Vec<u8>]) -> Vec<Vec<u8>> {
+    let heads: Vec<Vec<bool>> = cells.iter().map(|row| row.iter().map(|&state| state == 1).collect()).collect();
+
+    cells
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(x, &state)| match state {
+                    1 => 2,
+                    2 => 3,
+                    3 if matches!(live_neighbors(&heads, x, y), 1 | 2) => 1,
+                    3 => 3,
+                    _ => 0,
+                })
+                .collect()
+        })
+        .collect()
+}
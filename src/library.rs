@@ -0,0 +1,47 @@
+use crate::pattern::{self, Pattern};
+
+/// The built-in pattern library, as (name, RLE) pairs.
+const PATTERNS: &[(&str, &str)] = &[
+    ("block", "2o$2o!"),
+    ("beehive", "b2o$o2bo$b2o!"),
+    ("eater", "2o2b$obob$2bob$2b2o!"),
+    ("blinker", "3o!"),
+    ("toad", "b3o$3o!"),
+    ("beacon", "2o2b$2o2b$2b2o$2b2o!"),
+    (
+        "pulsar",
+        "2b3o3b3o2b2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2b2$2b3o3b3o2b$o4bobo4bo$o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+    ),
+    ("glider", "bob$2bo$3o!"),
+    ("lwss", "bo2bo$o4b$o3bo$4o!"),
+    ("mwss", "3bo2b$bo3bo$o5b$o4bo$5o!"),
+    ("hwss", "3b2o2b$bo4bo$o6b$o5bo$6o!"),
+    ("r-pentomino", "b2o$2o$bo!"),
+    ("acorn", "bo5b$3bo3b$2o2b3o!"),
+    ("diehard", "6bob$2o6b$bo3b3o!"),
+    (
+        "gun",
+        "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$11bo3bo$12b2o!",
+    ),
+];
+
+/// Looks up a pattern in the built-in library.
+///
+/// # Arguments
+///
+/// * `name` - The name of the pattern, e.g. `glider` or `gun`.
+///
+/// # Returns
+///
+/// The pattern, or `None` if the library has no pattern with that name.
+pub fn get(name: &str) -> Option<Pattern> {
+    PATTERNS
+        .iter()
+        .find(|(pattern_name, _)| pattern_name.eq_ignore_ascii_case(name))
+        .map(|(_, rle)| pattern::parse_rle(rle).expect("built-in patterns are valid RLE"))
+}
+
+/// Returns the names of all patterns in the built-in library.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    PATTERNS.iter().map(|(name, _)| *name)
+}
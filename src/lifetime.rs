@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env,
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use gameoflife::stats::GenerationDiff;
+
+/// Statistics collected over every session, stored between runs.
+///
+/// They are kept as JSON in `~/.local/share/gameoflife/stats.json` and updated when a
+/// session in the terminal ends. Headless runs are not counted.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LifetimeStats {
+    /// The number of sessions played.
+    pub sessions: u64,
+    /// The number of generations simulated over all sessions.
+    pub total_generations: u64,
+    /// The most generations simulated in one session.
+    pub longest_run: u64,
+    /// The largest population seen in any session.
+    pub biggest_population: usize,
+    /// The number of sessions each rule was used in, by rulestring.
+    pub rules_tried: BTreeMap<String, u64>,
+}
+
+/// What happened during the current session, to be added to the lifetime statistics.
+#[derive(Debug, Default)]
+pub struct Session {
+    /// The number of generations simulated.
+    pub generations: u64,
+    /// The largest population seen.
+    pub peak_population: usize,
+    /// The rules used.
+    pub rules: BTreeSet<String>,
+}
+
+impl Session {
+    /// Counts a simulated generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The changes of the new generation.
+    pub fn observe(&mut self, diff: &GenerationDiff) {
+        self.generations += 1;
+        self.peak_population = self.peak_population.max(diff.population);
+    }
+}
+
+impl LifetimeStats {
+    /// Returns the default statistics file path, `~/.local/share/gameoflife/stats.json`.
+    ///
+    /// `$XDG_DATA_HOME` is used instead of `~/.local/share` when it is set.
+    pub fn default_path() -> Option<PathBuf> {
+        let data_dir = env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share")))?;
+
+        Some(data_dir.join("gameoflife").join("stats.json"))
+    }
+
+    /// Reads the statistics file, or returns empty statistics if there is none yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The statistics file.
+    pub fn load(path: &Path) -> Result<LifetimeStats, Box<dyn Error>> {
+        if !path.exists() {
+            return Ok(LifetimeStats::default());
+        }
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        serde_json::from_str(&text).map_err(|e| format!("Invalid statistics file {}: {}", path.display(), e).into())
+    }
+
+    /// Writes the statistics file, creating its directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The statistics file.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?).map_err(|e| format!("Failed to write {}: {}", path.display(), e).into())
+    }
+
+    /// Adds a finished session to the statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `session` - The session that ended.
+    pub fn add(&mut self, session: &Session) {
+        self.sessions += 1;
+        self.total_generations += session.generations;
+        self.longest_run = self.longest_run.max(session.generations);
+        self.biggest_population = self.biggest_population.max(session.peak_population);
+        for rule in &session.rules {
+            *self.rules_tried.entry(rule.clone()).or_insert(0) += 1;
+        }
+    }
+}
+
+impl fmt::Display for LifetimeStats {
+    /// Shows the statistics as a report of several lines.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Sessions: {}", self.sessions)?;
+        writeln!(f, "Total generations: {}", self.total_generations)?;
+        writeln!(f, "Longest run: {} generations", self.longest_run)?;
+        writeln!(f, "Biggest population: {}", self.biggest_population)?;
+
+        // List the most used rules first.
+        let mut rules: Vec<(&String, &u64)> = self.rules_tried.iter().collect();
+        rules.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let rules: Vec<String> = rules
+            .iter()
+            .map(|(rule, sessions)| format!("{} ({} {})", rule, sessions, if **sessions == 1 { "session" } else { "sessions" }))
+            .collect();
+        write!(f, "Rules tried: {}", if rules.is_empty() { "none".to_string() } else { rules.join(", ") })
+    }
+}
+
+/// Prints the lifetime statistics, for `gameoflife stats`.
+pub fn print() -> Result<(), Box<dyn Error>> {
+    let Some(path) = LifetimeStats::default_path() else {
+        println!("No statistics file: neither $XDG_DATA_HOME nor $HOME is set");
+        return Ok(());
+    };
+
+    match LifetimeStats::load(&path) {
+        Ok(stats) => {
+            println!("Lifetime statistics ({})", path.display());
+            println!("{}", stats);
+        }
+        Err(e) => println!("{}", e),
+    }
+    Ok(())
+}
+
+/// Adds a finished session to the statistics file.
+///
+/// # Arguments
+///
+/// * `session` - The session that ended.
+pub fn record(session: &Session) -> Result<(), Box<dyn Error>> {
+    let Some(path) = LifetimeStats::default_path() else {
+        return Ok(());
+    };
+    let mut stats = LifetimeStats::load(&path)?;
+    stats.add(session);
+    stats.save(&path)
+}
@@ -1,14 +1,94 @@
+mod asciicast;
+mod camera;
+mod cli;
+mod clipboard;
+mod composite;
+mod config;
+mod control;
+mod display;
+mod editor;
+mod fetch;
+mod gui;
+mod help;
+mod hud;
+mod input;
+mod kiosk;
+mod labels;
+mod lifetime;
+mod multistate;
+mod paint;
+mod panel;
+mod picker;
+mod picture;
+mod prompt;
+mod screenshot;
+mod script;
+mod serve;
+mod split;
+mod theme;
+mod timing;
+mod versus;
+mod watch;
+
 use crossterm::{
-    cursor, execute,
-    style::Print,
-    terminal::{Clear, ClearType},
-    ExecutableCommand,
+    event::{KeyCode, KeyEvent},
+    style::Color,
+};
+use camera::Camera;
+use control::{live_cells, ControlCommand, ControlSocket, ControlStatus};
+use script::{Script, ScriptEvent};
+use display::{clear_screen, display_status, CellStates, DensityRenderer, Frame, Recording, Region, RenderMode, Renderer, Renderers, Screen, Terminal};
+use editor::{Editor, EditorAction};
+use picker::{Picker, PickerAction};
+use prompt::{Prompt, PromptAction};
+use gameoflife::{
+    ant::LangtonsAnt,
+    edits::{Edit, EditStack},
+    elementary::Elementary,
+    emit::{CsvRecorder, NdjsonRecorder},
+    font,
+    automaton::{self, CellularSimulation, Simulation, SimulationKind},
+    cycle::{CycleDetector, CyclePolicy, DEFAULT_MAX_PERIOD},
+    gradient::Shading,
+    headless,
+    history::History,
+    margolus::Margolus,
+    noise::Noise,
+    pattern::{parse_plaintext, parse_rle_within, Pattern},
+    placement::{self, FitPolicy},
+    recorder,
+    replay::{Intervention, Replay, ReplayRecorder},
+    rule::{Neighborhood, Rule},
+    runlog::{self, RunLog},
+    scene::Scene,
+    seeding::Seeder,
+    settings::Settings,
+    snapshot::Snapshot,
+    soup::{self, SoupSearch},
+    stats,
+    world::World,
+    Grid,
 };
-use rand::{self, Rng};
-use std::{error::Error, f64, io::{stdout, Write}, sync::{atomic::AtomicBool, Arc}};
+use hud::Hud;
+use input::{Command, Input};
+use labels::ObjectLabels;
+use paint::{PaintAction, Painter};
+use panel::{Panel, PanelAction, PanelInfo, PANEL_WIDTH};
+use timing::{FrameTimer, OVERLAY_HEIGHT, OVERLAY_WIDTH};
+use rand::{rngs::OsRng, Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use std::{error::Error, io::{stderr, stdout, BufWriter, IsTerminal, Read, Write}, path::Path, sync::{atomic::AtomicBool, Arc}};
 use std::thread;
-use std::time::Duration;
-use termsize;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long a message replaces the status bar.
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(3);
+
+/// The most generations computed between two frames when the simulation falls behind.
+const MAX_CATCH_UP_STEPS: u32 = 8;
+
+/// How long the engine runs between two redraws of the turbo density view.
+const TURBO_FRAME_TIME: Duration = Duration::from_millis(100);
 
 /// Represents the size of the console.
 ///
@@ -20,142 +100,594 @@ struct ConsoleSize {
     cols: usize,
 }
 
-/// Generates an initial grid for the Game of Life.
+impl ConsoleSize {
+    /// Returns the part of the terminal a board is drawn on: as much as the board needs,
+    /// less the bottom row, which is kept for the status bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The render mode, which sets how many cells go in a character.
+    /// * `board` - The width and height of the board.
+    /// * `terminal` - The size of the terminal in characters, as `(cols, rows)`.
+    fn fit(mode: RenderMode, (width, height): (usize, usize), terminal: (usize, usize)) -> ConsoleSize {
+        ConsoleSize {
+            rows: height.div_ceil(mode.cells_per_char().1).min(terminal.1.saturating_sub(1)),
+            cols: mode.console_columns(width).min(terminal.0),
+        }
+    }
+}
+
+/// How long an extinct board stays on screen before `--restart-on-extinction` reseeds it.
+const EXTINCTION_PAUSE: Duration = Duration::from_secs(2);
+
+/// How much `<` and `>` change the probability of the soups `r` reseeds the board with.
+const PROBABILITY_STEP: f64 = 0.05;
+
+/// How long the last board of a screensaver round stays on screen before the next soup.
+const SCREENSAVER_PAUSE: Duration = Duration::from_secs(1);
+
+/// The rules that screensaver rounds after the first cycle through; all of them keep a soup busy.
+const SCREENSAVER_RULES: [&str; 4] = ["B3/S23", "B36/S23", "B3678/S34678", "B368/S245"];
+
+/// The live-cell colors that screensaver rounds after the first cycle through.
+const SCREENSAVER_COLORS: [Color; 6] = [Color::Green, Color::Cyan, Color::Yellow, Color::Magenta, Color::Red, Color::Blue];
+
+/// The terminal size assumed when it cannot be detected.
+const FALLBACK_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+/// Picks a random seed for a run.
+///
+/// The seed comes from the operating system's random number generator. Where that
+/// is unavailable, as in some containers and early-boot environments, the current
+/// time is used instead and a warning is printed to standard error, which keeps standard
+/// output clean for `--emit`.
+fn random_seed() -> u64 {
+    let mut bytes = [0u8; 8];
+    match OsRng.try_fill_bytes(&mut bytes) {
+        Ok(()) => u64::from_le_bytes(bytes),
+        Err(e) => {
+            eprintln!("Warning: the system random number generator failed ({}); seeding from the clock", e);
+            SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64)
+        }
+    }
+}
+
+/// Returns the size of the terminal in characters, less the control panel if it is shown.
 ///
-/// The grid is initialized with a random pattern of live and dead cells.
+/// Where the terminal size cannot be detected, the size of the grid given with `--width`
+/// and `--height` is used, or an 80x24 terminal is assumed with a warning.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options, for the render mode and grid size.
 ///
 /// # Returns
 ///
-/// * `grid` - The initial grid.
-/// * `console_size` - The size of the console.
-fn initialize_grid(initial_grid_probability: f64) -> Result<(Vec<Vec<bool>>, ConsoleSize), Box<dyn Error>> {
-    // Get the current terminal size.
-    let size = termsize::get().ok_or("Failed to get terminal size")?;
+/// The number of columns and rows, as `(cols, rows)`.
+fn terminal_size(options: &cli::Options) -> (usize, usize) {
+    let (_, cells_per_row) = options.display.mode.cells_per_char();
+    let panel = if options.panel { PANEL_WIDTH } else { 0 };
 
-    // Create a random number generator.
-    let mut rng = rand::thread_rng();
-
-    // Create a 2D vector with the correct dimensions
-    // and initialize all cells to `false`.
-    let mut grid = vec![vec![false; size.cols as usize]; size.rows as usize];
-
-    // Set randomly generated live cells in the grid.
-    for i in 0..size.cols as usize {
-        for j in 0..size.rows as usize {
-            grid[j][i] = rng.gen_bool(initial_grid_probability); // Reduced the probability to make the grid less crowded.
+    match (termsize::get(), options.width, options.height) {
+        (Some(size), _, _) => ((size.cols as usize).saturating_sub(panel), size.rows as usize),
+        (None, Some(width), Some(height)) => (options.display.mode.console_columns(width), height.div_ceil(cells_per_row) + 1),
+        (None, _, _) => {
+            let (cols, rows) = FALLBACK_TERMINAL_SIZE;
+            eprintln!("Warning: could not detect the terminal size; assuming {}x{}", cols, rows);
+            (cols.saturating_sub(panel), rows)
         }
     }
+}
 
-    Ok((
-        grid,
-        ConsoleSize {
-            rows: size.rows as usize,
-            cols: size.cols as usize,
-        },
-    ))
+/// Returns the size of the grid in cells.
+///
+/// The bottom row of the terminal is reserved for the status bar, and the rest is
+/// filled with as many cells as the render mode fits into it. `--width` and `--height`
+/// override the size; without a terminal, the headless defaults are used instead.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options, for the render mode and grid size.
+/// * `terminal` - The size of the terminal in characters, or `None` without a terminal.
+///
+/// # Returns
+///
+/// The width and height of the grid, as `(width, height)`.
+fn board_size(options: &cli::Options, terminal: Option<(usize, usize)>) -> (usize, usize) {
+    match terminal {
+        Some((cols, rows)) => {
+            let (_, cells_per_row) = options.display.mode.cells_per_char();
+            (
+                options.width.unwrap_or(options.display.mode.board_columns(cols)),
+                options.height.unwrap_or(rows.saturating_sub(1) * cells_per_row),
+            )
+        }
+        None => (
+            options.width.unwrap_or(headless::DEFAULT_WIDTH),
+            options.height.unwrap_or(headless::DEFAULT_HEIGHT),
+        ),
+    }
 }
 
-/// Prints the grid to the console.
+/// Adjusts the grid size or render mode so the placed patterns fit, following `--fit`.
 ///
 /// # Arguments
 ///
-/// * `grid` - The grid to be printed.
-/// * `prev_grid` - The previous grid state.
-fn display_grid(grid: &[Vec<bool>], prev_grid: &[Vec<bool>]) -> Result<(), Box<dyn Error>> {
-    let mut stdout = stdout();
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &cell) in row.iter().enumerate() {
-            if cell != prev_grid[y][x] {
-                stdout.execute(cursor::MoveTo(x as u16, y as u16))?;
-                if cell {
-                    stdout
-                        .execute(Print("#"))?;
-                } else {
-                    stdout
-                        .execute(Print(" "))?;
+/// * `options` - The parsed command-line options, updated with the new size or render mode.
+/// * `placements` - The patterns to place on the board.
+/// * `terminal` - The size of the terminal in characters, or `None` without a terminal.
+///
+/// # Returns
+///
+/// A message describing the adjustment, if one was made.
+fn fit_board(
+    options: &mut cli::Options,
+    placements: &[placement::Placement],
+    terminal: Option<(usize, usize)>,
+) -> Result<Option<String>, Box<dyn Error>> {
+    if placements.is_empty() || options.fit == FitPolicy::Crop {
+        return Ok(None);
+    }
+    let (width, height) = board_size(options, terminal);
+    // Only a pattern that loads but is too big gets the hints about --fit.
+    let Some(unfit) = placement::first_unfit(placements, width, height)? else {
+        return Ok(None);
+    };
+
+    match (options.fit, terminal) {
+        (FitPolicy::Crop, _) => Ok(None),
+        (FitPolicy::Error, _) => Err(format!("{}; pass --fit expand, zoom or crop to load it anyway", unfit).into()),
+        (FitPolicy::Zoom, Some(_)) => {
+            // Try the render modes that draw more cells per character than the current one,
+            // with sextants only on terminals known to draw them. Hex boards only draw in
+            // hex mode, so they cannot zoom out.
+            let current = options.display.mode;
+            if current == RenderMode::Hex {
+                return Err(format!("{}; hex boards cannot zoom out, pass --fit expand to grow the grid instead", unfit).into());
+            }
+            let cells = |mode: RenderMode| mode.cells_per_char().0 * mode.cells_per_char().1;
+            for mode in [RenderMode::Ascii, RenderMode::HalfBlock, RenderMode::Sextant, RenderMode::Braille] {
+                if cells(mode) <= cells(current) || (mode == RenderMode::Sextant && !display::supports_sextants()) {
+                    continue;
+                }
+                options.display.mode = mode;
+                let (width, height) = board_size(options, terminal);
+                if placement::first_unfit(placements, width, height)?.is_none() {
+                    return Ok(Some(format!("Zoomed out to {} rendering to fit the patterns", mode)));
                 }
             }
+            options.display.mode = current;
+            Err(format!("{} even when zoomed out; pass --fit expand to grow the grid instead", unfit).into())
+        }
+        // Without a terminal there is nothing to zoom, so the grid grows instead.
+        (FitPolicy::Expand, _) | (FitPolicy::Zoom, None) => {
+            let (needed_width, needed_height) = placement::required_size(placements)?;
+            let size = (width.max(needed_width), height.max(needed_height));
+            options.width = Some(size.0);
+            options.height = Some(size.1);
+            Ok(Some(format!("Expanded the grid to {}x{} to fit the patterns", size.0, size.1)))
         }
     }
-    stdout.flush()?;
-    Ok(())
 }
 
-/// Calculates the number of live neighbors of a cell in the grid.
+/// Generates an initial grid for the Game of Life.
+///
+/// The grid is initialized with a random pattern of live and dead cells, sized by
+/// [`board_size`]. A grid larger than the terminal is shown from its top-left corner.
 ///
 /// # Arguments
 ///
-/// * `grid` - The grid containing the cells.
-/// * `x` - The x-coordinate of the cell.
-/// * `y` - The y-coordinate of the cell.
+/// * `initial_grid_probability` - The probability that a cell starts alive.
+/// * `rng` - The random number generator.
+/// * `options` - The parsed command-line options, for the render mode, grid size, and
+///   symmetry.
+/// * `terminal` - The size of the terminal in characters.
 ///
 /// # Returns
 ///
-/// The number of live neighbors.
-fn live_neighbors(grid: &[Vec<bool>], x: usize, y: usize) -> usize {
-    // Initialize a count for the live neighbors.
-    let mut count = 0;
+/// * `grid` - The initial grid.
+/// * `console_size` - The size of the console area used for the grid.
+fn initialize_grid(
+    initial_grid_probability: f64,
+    rng: &mut impl Rng,
+    options: &cli::Options,
+    terminal: (usize, usize),
+) -> (Grid, ConsoleSize) {
+    let (width, height) = board_size(options, Some(terminal));
+    let grid = random_board(width, height, initial_grid_probability, options, rng);
 
-    // Iterate over the neighbors of the cell.
-    for i in -1..=1 {
-        for j in -1..=1 {
-            // Skip the cell itself.
-            if i == 0 && j == 0 {
-                continue;
-            }
+    (grid, ConsoleSize::fit(options.display.mode, (width, height), terminal))
+}
 
-            // Check if the neighbor is within the grid bounds.
-            if let Some(&cell) = grid
-                .get((y as isize + i) as usize)
-                .and_then(|row| row.get((x as isize + j) as usize))
-            {
-                // Increment the count if the neighbor is live.
-                count += cell as usize;
-            }
-        }
+/// Generates the random base board in the requested seed mode and symmetry, or from the
+/// picture given with `--image`.
+///
+/// # Arguments
+///
+/// * `width` - The width of the board.
+/// * `height` - The height of the board.
+/// * `probability` - The probability that a cell starts alive.
+/// * `options` - The parsed command-line options, for the seed mode, picture, and symmetry.
+/// * `rng` - The random number generator.
+///
+/// # Returns
+///
+/// The random board.
+fn random_board(width: usize, height: usize, probability: f64, options: &cli::Options, rng: &mut impl Rng) -> Grid {
+    let mut grid = match &options.picture {
+        Some(picture) => picture.seed(width, height, probability, rng),
+        None => options.seed_mode.seeder().seed(width, height, probability, rng),
+    };
+    // Mirror and turn the soup into the requested symmetry, before any patterns are placed.
+    if let Some(symmetry) = options.symmetry {
+        symmetry.apply(&mut grid);
     }
+    grid
+}
 
-    // Return the count of live neighbors.
-    count
+/// Returns the camera that follows the activity with `--follow`, if the board is too big
+/// for the terminal.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options, for `--follow` and the render mode.
+/// * `grid` - The board.
+/// * `console_size` - The size of the console area used for the board.
+fn follow_camera(options: &cli::Options, grid: &[Vec<bool>], console_size: &ConsoleSize) -> Option<Camera> {
+    let view_size = (options.display.mode.board_columns(console_size.cols), console_size.rows * options.display.mode.cells_per_char().1);
+    let too_big = grid.len() > view_size.1 || grid.first().is_some_and(|row| row.len() > view_size.0);
+    (options.follow && too_big).then(|| Camera::new(grid, view_size))
+}
+
+/// Returns the probability of the random base board.
+///
+/// Patterns and init expressions start on an empty board unless a probability is given.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options.
+/// * `placements` - The patterns to place on the board.
+fn base_probability(options: &cli::Options, placements: &[placement::Placement]) -> f64 {
+    if (placements.is_empty() && options.init_expr.is_none()) || options.probability_given {
+        options.initial_grid_probability
+    } else {
+        0.0
+    }
 }
 
-/// Updates the grid by applying the Game of Life rules.
+/// Applies the init expression and then the placements to the board.
 ///
 /// # Arguments
 ///
-/// * `grid` - The grid to be updated.
-/// * `size` - The size of the grid.
+/// * `grid` - The board to draw on.
+/// * `options` - The parsed command-line options.
+/// * `placements` - The patterns to place on the board.
+/// * `rng` - The random number generator for the expression's densities.
 ///
 /// # Returns
 ///
-/// The updated grid.
-fn update_grid(grid: &mut [Vec<bool>], size: &ConsoleSize) -> Vec<Vec<bool>> {
-    // Create a new grid with the same dimensions as the input grid.
-    let mut new_grid = vec![vec![false; size.cols]; size.rows];
-
-    // Iterate over each cell in the grid.
-    for i in 0..size.rows {
-        for j in 0..size.cols {
-            // Calculate the number of live neighbors of the cell.
-            let live_neighbors = live_neighbors(grid, j, i);
-
-            // Apply the Game of Life rules to determine the next state of the cell.
-            if grid[i][j] {
-                // If the cell is alive:
-                // - If it has 2 or 3 live neighbors, it remains alive.
-                // - Otherwise, it dies.
-                new_grid[i][j] = live_neighbors == 2 || live_neighbors == 3;
-            } else {
-                // If the cell is dead:
-                // - If it has exactly 3 live neighbors, it becomes alive.
-                // - Otherwise, it remains dead.
-                new_grid[i][j] = live_neighbors == 3;
-            }
+/// The messages produced by the placements.
+fn seed_board(
+    grid: &mut [Vec<bool>],
+    options: &cli::Options,
+    placements: &[placement::Placement],
+    rng: &mut impl Rng,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut messages = match &options.init_expr {
+        Some(init_expr) => init_expr.apply(grid, rng)?,
+        None => Vec::new(),
+    };
+    messages.extend(placement::apply_placements(grid, placements)?);
+    Ok(messages)
+}
+
+/// Creates a board like the initial one: a random soup with the init expression and
+/// patterns applied on top.
+///
+/// # Arguments
+///
+/// * `width` - The width of the board.
+/// * `height` - The height of the board.
+/// * `probability` - The probability that a cell of the soup starts alive.
+/// * `options` - The parsed command-line options.
+/// * `placements` - The patterns to place on the board.
+/// * `rng` - The random number generator.
+fn fresh_board(
+    width: usize,
+    height: usize,
+    probability: f64,
+    options: &cli::Options,
+    placements: &[placement::Placement],
+    rng: &mut impl Rng,
+) -> Result<Grid, Box<dyn Error>> {
+    let mut grid = random_board(width, height, probability, options, rng);
+    seed_board(&mut grid, options, placements, rng)?;
+    Ok(grid)
+}
+
+/// Runs the simulation in headless mode and prints the timing results.
+///
+/// The grid size comes from `--width` and `--height` rather than the terminal,
+/// so this works without a TTY.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options.
+/// * `placements` - The patterns to place on the board.
+/// * `resumed` - The snapshot to start from instead of a new board, if any.
+fn run_headless(
+    options: &cli::Options,
+    placements: &[placement::Placement],
+    resumed: Option<&Snapshot>,
+) -> Result<(), Box<dyn Error>> {
+    let seed = options.seed.unwrap_or_else(random_seed);
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+
+    // Patterns start on an empty board unless a probability is given, as in the terminal.
+    let probability = base_probability(options, placements);
+    let (width, height) = board_size(options, None);
+    let mut grid = random_board(width, height, probability, options, &mut rng);
+    match resumed {
+        Some(snapshot) => grid = snapshot.grid()?,
+        None => {
+            seed_board(&mut grid, options, placements, &mut rng)?;
         }
     }
 
-    // Return the updated grid.
-    new_grid
+    let mut recorders = open_recorders(options)?;
+    // The JSON lines take standard output, so the report goes to standard error instead.
+    if options.emit {
+        recorders.push(Box::new(NdjsonRecorder::new(BufWriter::new(stdout()), options.emit_cells)));
+    }
+    let generations = options.generations.unwrap_or(headless::DEFAULT_GENERATIONS);
+    let mut reseed = || fresh_board(width, height, probability, options, placements, &mut rng);
+    let mut noise = options.noise.map(|probability| Noise::new(probability, seed));
+    let world = World::new(grid, options.boundary).with_engine(options.engine.create(options.boundary));
+    let report = headless::run(world, &options.rule, generations, &mut recorders, options.on_cycle, &mut reseed, noise.as_mut())?;
+    if options.emit {
+        recorders.pop().expect("the emitter was added last").finish()?;
+    }
+
+    let mut log: Box<dyn Write> = if options.emit { Box::new(stderr()) } else { Box::new(stdout()) };
+    writeln!(
+        log,
+        "Simulated {} generations of a {}x{} grid ({}, seed {})",
+        report.generations,
+        width,
+        height,
+        options.rule,
+        seed
+    )?;
+    writeln!(log, "Total time: {:.3}s", report.elapsed.as_secs_f64())?;
+    writeln!(log, "Generations/second: {:.1}", report.generations_per_second())?;
+    writeln!(log, "Final population: {}", report.final_population)?;
+    writeln!(log, "Final state hash: {}", report.final_hash)?;
+    match (options.on_cycle, report.cycles.last()) {
+        (Some(CyclePolicy::Restart), _) => writeln!(log, "Restarts: {}", report.cycles.len())?,
+        (Some(_), Some(cycle)) => writeln!(log, "Cycle: {}", cycle)?,
+        (Some(_), None) => writeln!(log, "Cycle: none found")?,
+        (None, _) => {}
+    }
+    finish_recorders(recorders, &mut log)
+}
+
+/// Runs the simulation in a window of its own, set up like a headless run.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options.
+/// * `placements` - The patterns to place on the board.
+/// * `resumed` - The snapshot to start from instead of a new board, if any.
+fn run_gui(options: &cli::Options, placements: &[placement::Placement], resumed: Option<&Snapshot>) -> Result<(), Box<dyn Error>> {
+    let seed = options.seed.unwrap_or_else(random_seed);
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    println!("Seed: {}", seed);
+
+    let probability = base_probability(options, placements);
+    let (width, height) = board_size(options, None);
+    let grid = match resumed {
+        Some(snapshot) => snapshot.grid()?,
+        None => fresh_board(width, height, probability, options, placements, &mut rng)?,
+    };
+    let mut world = World::new(grid, options.boundary)
+        .with_engine(options.engine.create(options.boundary))
+        .with_lookahead(options.engine.create(options.boundary));
+    if let Some(max_age) = options.max_age {
+        world = world.with_max_age(max_age);
+    }
+
+    let mut recorders = open_recorders(options)?;
+    let mut reseed = || fresh_board(width, height, probability, options, placements, &mut rng);
+    let generation = gui::run(&mut world, options, &mut recorders, &mut reseed)?;
+    println!("Closed at generation {} with a population of {}", generation, world.population());
+    finish_recorders(recorders, &mut stdout())
+}
+
+/// Loads the snapshot file for the current board.
+///
+/// # Arguments
+///
+/// * `path` - The snapshot file.
+/// * `grid` - The current board, which the snapshot must match in size.
+///
+/// # Returns
+///
+/// The snapshot's board and the snapshot itself.
+fn load_snapshot_for(path: &std::path::Path, grid: &[Vec<bool>]) -> Result<(Grid, Snapshot), Box<dyn Error>> {
+    let snapshot = Snapshot::load(path)?;
+    let (width, height) = (grid.first().map_or(0, |row| row.len()), grid.len());
+    if (snapshot.width, snapshot.height) != (width, height) {
+        return Err(format!(
+            "{} is {}x{}, but the board is {}x{}",
+            path.display(),
+            snapshot.width,
+            snapshot.height,
+            width,
+            height
+        )
+        .into());
+    }
+
+    Ok((snapshot.grid()?, snapshot))
+}
+
+/// Streams the simulation to TCP clients, set up like a headless run.
+///
+/// # Arguments
+///
+/// * `address` - The address to listen on.
+/// * `options` - The parsed command-line options.
+/// * `placements` - The patterns to place on the board.
+/// * `resumed` - The snapshot to start from instead of a new board, if any.
+fn run_server(
+    address: &str,
+    options: &cli::Options,
+    placements: &[placement::Placement],
+    resumed: Option<&Snapshot>,
+) -> Result<(), Box<dyn Error>> {
+    let seed = options.seed.unwrap_or_else(random_seed);
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    println!("Seed: {}", seed);
+
+    let probability = base_probability(options, placements);
+    let (width, height) = board_size(options, None);
+    let grid = match resumed {
+        Some(snapshot) => snapshot.grid()?,
+        None => fresh_board(width, height, probability, options, placements, &mut rng)?,
+    };
+    let mut noise = options.noise.map(|probability| Noise::new(probability, seed));
+    let world = World::new(grid, options.boundary).with_engine(options.engine.create(options.boundary));
+    if let Err(e) = serve::serve(address, world, &options.rule, options.speed, options.generations, noise.as_mut()) {
+        println!("{}", e);
+    }
+    Ok(())
+}
+
+/// Advances the simulation by one generation.
+///
+/// The replaced board is kept in the history, and the new one is observed by the
+/// statistics and the renderers.
+///
+/// # Arguments
+///
+/// * `grid` - The board, advanced to its next generation.
+/// * `rule` - The rule to apply.
+/// * `generation` - The generation counter, incremented.
+/// * `stats` - The statistics collectors.
+/// * `history` - The history of past generations.
+/// * `renderer` - The renderers, which see every generation for the heatmap, the trails,
+///   and the recordings.
+/// * `session` - The session totals for the lifetime statistics.
+fn advance(
+    grid: &mut World,
+    rule: &Rule,
+    generation: &mut u64,
+    stats: &mut stats::Stats,
+    history: &mut History,
+    renderer: &mut dyn Renderer,
+    session: &mut lifetime::Session,
+) -> Result<(), Box<dyn Error>> {
+    history.push(*generation, grid);
+    grid.step(rule);
+    *generation += 1;
+    let diff = grid.diff(*generation);
+    stats.observe(&diff);
+    session.observe(&diff);
+    renderer.record(grid, *generation)
+}
+
+/// Records something done to the run into its replay, if one is being recorded.
+///
+/// # Arguments
+///
+/// * `recorder` - The replay recorder, if any.
+/// * `generation` - The generation the board is at.
+/// * `intervention` - What was done.
+fn record_intervention(recorder: &mut Option<ReplayRecorder>, generation: u64, intervention: Intervention) -> Result<(), Box<dyn Error>> {
+    match recorder {
+        Some(recorder) => recorder.record(generation, intervention),
+        None => Ok(()),
+    }
+}
+
+/// Runs a headless soup search and prints the census of the objects found.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options, for the rule, first seed, and the
+///   number of generations each soup may take to settle.
+/// * `soups` - The number of soups to run.
+fn run_soup_search(options: &cli::Options, soups: u64) -> Result<(), Box<dyn Error>> {
+    let search = SoupSearch {
+        soups,
+        first_seed: options.seed.unwrap_or_else(random_seed),
+        max_generations: options.generations.unwrap_or(soup::DEFAULT_MAX_GENERATIONS),
+        symmetry: options.symmetry,
+    };
+
+    let start = Instant::now();
+    let census = search.run(&options.rule);
+    println!("{}", census);
+    println!("Time: {:.3}s ({})", start.elapsed().as_secs_f64(), options.rule);
+    Ok(())
+}
+
+/// Creates the statistics collectors for a new board.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options, which can add collectors.
+/// * `hud` - The parts of the screen shown, which decide whether there is a sparkline.
+///
+/// # Returns
+///
+/// The built-in collectors and the requested ones.
+fn new_stats(options: &cli::Options, hud: &Hud) -> stats::Stats {
+    let mut stats = stats::Stats::new();
+    if hud.sparkline {
+        stats.register(Box::new(stats::PopulationSparkline::default()));
+    }
+    if options.generations.is_some() {
+        stats.register(Box::new(stats::PeakPopulation::default()));
+    }
+    stats
+}
+
+/// Opens the recorders requested on the command line.
+///
+/// # Arguments
+///
+/// * `options` - The parsed command-line options.
+///
+/// # Returns
+///
+/// The recorders, which receive every simulated generation.
+fn open_recorders(options: &cli::Options) -> Result<Vec<Box<dyn recorder::FrameRecorder>>, Box<dyn Error>> {
+    let mut recorders = Vec::new();
+    if let Some(path) = &options.record {
+        recorders.push(recorder::open(path, &options.record_settings)?);
+    }
+    if let Some(dir) = &options.frames {
+        recorders.push(Box::new(recorder::PngSequenceRecorder::new(dir, &options.record_settings)?));
+    }
+    if let Some(path) = &options.stats_csv {
+        recorders.push(Box::new(CsvRecorder::create(path)?));
+    }
+    Ok(recorders)
+}
+
+/// Finishes the recorders and reports the files they wrote.
+///
+/// # Arguments
+///
+/// * `recorders` - The recorders to finish.
+/// * `log` - Where to report the files.
+fn finish_recorders(recorders: Vec<Box<dyn recorder::FrameRecorder>>, log: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    for recorder in recorders {
+        let path = recorder.finish()?;
+        writeln!(log, "Recorded to {}", path.display())?;
+    }
+    Ok(())
 }
 
 /// The main entry point of the program.
@@ -166,63 +698,1505 @@ fn update_grid(grid: &mut [Vec<bool>], size: &ConsoleSize) -> Vec<Vec<bool>> {
 /// # Returns
 ///
 /// This function does not return anything.
+/// Runs a composite scene of layered automata in the terminal.
+///
+/// # Arguments
+///
+/// * `path` - The scene file.
+/// * `options` - The parsed command-line options, for the seed, speed, and grid size.
+/// * `terminal` - The size of the terminal in characters.
+fn run_scene(path: &Path, options: &cli::Options, terminal: (usize, usize)) -> Result<(), Box<dyn Error>> {
+    let mut scene = match Scene::load(path) {
+        Ok(scene) => scene,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+
+    let seed = options.seed.unwrap_or_else(random_seed);
+    println!("Seed: {}", seed);
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+
+    // Scenes are drawn one cell per character, with the bottom row left for the status bar.
+    let (cols, rows) = (terminal.0, terminal.1.saturating_sub(1));
+    scene.seed(options.width.unwrap_or(cols), options.height.unwrap_or(rows), &mut rng);
+    composite::run(&mut scene, options.speed, &options.display, cols, rows)
+}
+
+/// Runs several universes side by side, each in its own part of the terminal.
+///
+/// With `--split-rules`, the universes take the rules in turn and all start from the same
+/// seed, so the rules can be compared on the same soup. Otherwise they all follow `--rule`,
+/// each from the next seed.
+///
+/// # Arguments
+///
+/// * `layout` - How the terminal is split between the universes.
+/// * `options` - The parsed command-line options, for the rules, seed, and board contents.
+/// * `placements` - The patterns to place on every board.
+/// * `terminal` - The size of the terminal in characters.
+fn run_split(layout: split::Layout, options: &cli::Options, placements: &[placement::Placement], terminal: (usize, usize)) -> Result<(), Box<dyn Error>> {
+    let regions = match layout.regions(terminal) {
+        Ok(regions) => regions,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    let seed = options.seed.unwrap_or_else(random_seed);
+    println!("Seed: {}", seed);
+
+    let probability = base_probability(options, placements);
+    let (_, cells_per_row) = options.display.mode.cells_per_char();
+    let mut universes = Vec::new();
+    for (i, region) in regions.into_iter().enumerate() {
+        let (rule, seed) = match options.split_rules.len() {
+            0 => (options.rule, seed.wrapping_add(i as u64)),
+            rules => (options.split_rules[i % rules], seed),
+        };
+        let (width, height) = (options.display.mode.board_columns(region.cols), region.rows * cells_per_row);
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let grid = fresh_board(width, height, probability, options, placements, &mut rng)?;
+        universes.push(split::Universe::new(rule, seed, grid, region));
+    }
+    split::run(&mut universes, options.boundary, options.speed, &options.display, terminal)
+}
+
+/// Compares two recorded runs and prints the report.
+///
+/// The program exits with status 1 when the runs differ, so the comparison can be used
+/// in scripts.
+///
+/// # Arguments
+///
+/// * `args` - The arguments after `diff`: the two run log files.
+fn run_diff(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [a, b] = args else {
+        println!("Usage: gameoflife diff run1.golrec run2.golrec");
+        return Ok(());
+    };
+
+    let diff = RunLog::load(Path::new(a)).and_then(|a| runlog::compare(&a, &RunLog::load(Path::new(b))?));
+    match diff {
+        Ok(diff) => {
+            println!("{}", diff);
+            if !diff.identical() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => println!("{}", e),
+    }
+    Ok(())
+}
+
+/// Reads the starting pattern piped in on standard input, as lines of `#` or `O` for live
+/// cells and `.` for dead ones.
+///
+/// Keys are still read from the terminal, which crossterm opens as `/dev/tty` when
+/// standard input is not one.
+///
+/// # Returns
+///
+/// A placement of the pattern at the top-left corner of the board.
+fn read_stdin_pattern() -> Result<placement::Placement, Box<dyn Error>> {
+    let mut input = std::io::stdin();
+    if input.is_terminal() {
+        return Err("--stdin reads a pattern piped in, as in: cat pattern.txt | gameoflife --stdin".into());
+    }
+    let mut text = String::new();
+    input.read_to_string(&mut text).map_err(|e| format!("Failed to read the pattern from standard input: {}", e))?;
+    let pattern = parse_plaintext(&text).map_err(|e| format!("stdin: {}", e))?;
+    if pattern.width == 0 || pattern.height == 0 {
+        return Err("No pattern was piped in on standard input".into());
+    }
+    Ok(placement::Placement::from_pattern("stdin", pattern))
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut initial_grid_probability = 0.2;
-
-    // The first argument to the program is a float value that controls the randomness of the initial grid.
-    if let Some(arg) = std::env::args().nth(1) {
-        if let Some(val) = arg.parse::<f64>().ok() {
-            // If the value is not between 0 and 1, error and inform the user.
-            if val < 0.0 || val > 1.0 {
-                println!("Initial grid probability must be between 0 and 1.");
+    // `gameoflife diff` compares two recorded runs instead of running the simulation.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("diff") {
+        return run_diff(&args[1..]);
+    }
+    // `gameoflife stats` shows the lifetime statistics of earlier sessions.
+    if args.first().map(String::as_str) == Some("stats") {
+        return lifetime::print();
+    }
+
+    // Parse the command-line arguments, informing the user if they are invalid.
+    let mut options = match cli::parse_args(args.clone().into_iter()) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+
+    // A replay runs with the options it was recorded with, followed by any given along with
+    // it, from the state the recorded run started in.
+    let mut replay = match options.replay.as_deref().map(Replay::load).transpose() {
+        Ok(replay) => replay,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    if let Some(replay) = &replay {
+        let args = replay.args.iter().cloned().chain(cli::without_flag(&args, "--replay"));
+        options = match cli::parse_args(args) {
+            Ok(options) => options,
+            Err(e) => {
+                println!("{}", e);
                 return Ok(());
             }
-            initial_grid_probability = val;
-            println!("Initial grid probability: {}", initial_grid_probability);
-        } else {
-            println!("Failed to parse argument as a float value. Using default value: {}", initial_grid_probability);
+        };
+    }
+
+    // A restarted simulator would find standard input already used up.
+    if options.kiosk && options.stdin {
+        println!("--stdin cannot be combined with --kiosk, which restarts the simulator");
+        return Ok(());
+    }
+
+    // In kiosk mode, this process only watches over the simulator and restarts it.
+    if options.kiosk && !kiosk::is_child() {
+        return kiosk::supervise(&options.kiosk_log);
+    }
+
+    // A client only draws the board a server streams, so no board is set up here.
+    if let Some(address) = &options.connect {
+        if let Err(e) = serve::connect(address, &options.display, terminal_size(&options)) {
+            println!("{}", e);
         }
-    } else {
-        println!("Default initial grid probability: {}", initial_grid_probability);
+        return Ok(());
+    }
+
+    // Piped-in, pasted, and downloaded patterns are placed first and the watched pattern last.
+    let mut placements: Vec<placement::Placement> = Vec::new();
+    if options.stdin {
+        match read_stdin_pattern() {
+            Ok(placement) => placements.push(placement),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
+            }
+        }
+    }
+    if options.paste {
+        match clipboard::read_pattern() {
+            Ok(pattern) => placements.push(placement::Placement::from_pattern("clipboard", pattern)),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
+            }
+        }
+    }
+    if let Some(url) = &options.fetch {
+        match fetch::fetch_pattern(url) {
+            Ok(placement) => placements.push(placement),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
+            }
+        }
+    }
+    // The text is centered once the size of the board is settled, below.
+    let text_placement = options.text.as_ref().map(|text| {
+        placements.push(placement::Placement::from_pattern("text", font::text_pattern(text)));
+        placements.len() - 1
+    });
+    placements.extend(options.placements.iter().chain(options.watch.as_ref()).cloned());
+
+    // A resumed snapshot decides the board size, seed, and rule instead of the options, and
+    // so does the start of a replay.
+    let loaded = match &replay {
+        Some(replay) => Ok(Some(replay.start.clone())),
+        None => options.resume.as_deref().map(Snapshot::load).transpose(),
+    };
+    let resumed = match loaded {
+        Ok(resumed) => resumed,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    if let Some(snapshot) = &resumed {
+        options.width = Some(snapshot.width);
+        options.height = Some(snapshot.height);
+        options.seed = Some(snapshot.seed);
+        options.rule = snapshot.rule;
+    }
+
+    // Hex rules draw their board as a honeycomb.
+    if options.automaton.is_none() && options.rule.neighborhood().1 == Neighborhood::Hex {
+        options.display.mode = RenderMode::Hex;
+    }
+
+    // Terminals without kitty graphics or inline images get the board in characters
+    // instead, before it is sized for them.
+    let on_screen = !options.headless && options.serve.is_none() && !options.gui;
+    let unsupported = match options.display.mode {
+        RenderMode::Kitty => on_screen && !display::supports_kitty_graphics().unwrap_or(false),
+        RenderMode::ITerm => on_screen && !display::supports_inline_images(),
+        _ => false,
+    };
+    if unsupported {
+        println!("This terminal does not show --render {} images; drawing with --render half-block instead", options.display.mode);
+        options.display.mode = RenderMode::HalfBlock;
+    }
+
+    // Grow the grid or zoom out so the placed patterns fit, as requested with `--fit`.
+    let terminal = if on_screen { Some(terminal_size(&options)) } else { None };
+    if resumed.is_none() {
+        match fit_board(&mut options, &placements, terminal) {
+            // Standard output is kept for the JSON lines of `--emit`.
+            Ok(message) if options.emit => message.iter().for_each(|message| eprintln!("{}", message)),
+            Ok(message) => message.iter().for_each(|message| println!("{}", message)),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
+            }
+        }
+    }
+    if let Some(index) = text_placement {
+        let (width, height) = board_size(&options, terminal);
+        placements[index].center(width, height)?;
+    }
+
+    // Benchmark the engine without touching the terminal.
+    if options.headless && options.scene.is_some() {
+        println!("--scene needs a terminal and cannot be combined with --headless");
+        return Ok(());
+    }
+    if options.headless && options.versus.is_some() {
+        println!("--versus needs a terminal and cannot be combined with --headless");
+        return Ok(());
+    }
+    if options.headless && options.split.is_some() {
+        println!("--split needs a terminal and cannot be combined with --headless");
+        return Ok(());
+    }
+    if options.headless && options.automaton.is_some() {
+        println!("--automaton needs a terminal and cannot be combined with --headless");
+        return Ok(());
+    }
+    // The window only draws a single board of the main simulation.
+    if options.gui && (options.headless || options.serve.is_some() || options.scene.is_some() || options.split.is_some() || options.versus.is_some() || options.automaton.is_some()) {
+        println!("--gui draws a single board and cannot be combined with --headless, --serve, --scene, --split, --versus, or --automaton");
+        return Ok(());
+    }
+    // The script is told about the board of the main simulation only.
+    let other_loop = options.headless || options.serve.is_some() || options.gui || options.scene.is_some() || options.split.is_some() || options.versus.is_some() || options.automaton.is_some();
+    if options.script.is_some() && other_loop {
+        println!("--script needs the main simulation, not --headless, --serve, --gui, --scene, --split, --versus, or --automaton");
+        return Ok(());
+    }
+    if let Some(soups) = options.soup_search {
+        return run_soup_search(&options, soups);
+    }
+    if options.headless {
+        return run_headless(&options, &placements, resumed.as_ref());
+    }
+    if let Some(address) = &options.serve {
+        return run_server(address, &options, &placements, resumed.as_ref());
+    }
+    if options.gui {
+        if let Err(e) = run_gui(&options, &placements, resumed.as_ref()) {
+            println!("{}", e);
+        }
+        return Ok(());
+    }
+
+    // Composite scenes run in their own, simpler loop.
+    if let Some(path) = &options.scene {
+        return run_scene(path, &options, terminal.unwrap_or(FALLBACK_TERMINAL_SIZE));
+    }
+    // So do split screens, with a board of their own for every universe.
+    if let Some(layout) = options.split {
+        return run_split(layout, &options, &placements, terminal.unwrap_or(FALLBACK_TERMINAL_SIZE));
+    }
+
+    if let Some(replay) = &replay {
+        println!("Replaying {} interventions from generation {}", replay.remaining(), replay.start.generation);
+    } else if let Some(snapshot) = &resumed {
+        println!("Resuming at generation {}", snapshot.generation);
+    } else if options.probability_given {
+        println!("Initial grid probability: {}", options.initial_grid_probability);
+    } else if placements.is_empty() && options.init_expr.is_none() {
+        println!("Default initial grid probability: {}", options.initial_grid_probability);
         println!("To change the initial grid probability, pass it as an argument to the program.");
         println!("Example: <program_name> 0.5");
     }
+
+    // Initialize the grid with a random pattern of live and dead cells and get the size of the console.
+    // When patterns are placed without an explicit probability, they start on an empty board.
+    let mut probability = base_probability(&options, &placements);
+    // Seed the random number generator so runs can be reproduced with `--seed`.
+    let mut seed = options.seed.unwrap_or_else(random_seed);
+    let mut rng = ChaCha12Rng::seed_from_u64(seed);
+    println!("Seed: {}", seed);
+    let mut noise = options.noise.map(|probability| Noise::new(probability, seed));
+
+    let (mut grid, mut console_size) = initialize_grid(probability, &mut rng, &options, terminal.unwrap_or(FALLBACK_TERMINAL_SIZE));
+
+    // Stamp any requested shapes and patterns onto the board, or restore the snapshot.
+    if let Some(snapshot) = &resumed {
+        grid = snapshot.grid()?;
+        rng = snapshot.rng();
+    } else {
+        match seed_board(&mut grid, &options, &placements, &mut rng) {
+            Ok(messages) => messages.iter().for_each(|message| println!("{}", message)),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
+            }
+        }
+    }
+    // A two-player match starts on an empty board of the same size.
+    if let Some(generations) = options.versus {
+        let settings = versus::Match { generations, budget: options.budget, round: options.round };
+        let size = (grid.first().map_or(0, |row| row.len()), grid.len());
+        if let Err(e) = versus::run(settings, &options.rule, options.speed, &options.display, size, (console_size.cols, console_size.rows)) {
+            println!("{}", e);
+        }
+        return Ok(());
+    }
+
+    // Other simulations run in their own loop, starting from the same random board and patterns.
+    if let Some(kind) = options.automaton {
+        let (width, height) = (grid.first().map_or(0, |row| row.len()), grid.len());
+        let mut simulation: Box<dyn Simulation> = match kind {
+            SimulationKind::Cellular(automaton) => {
+                let cells = match &options.layout {
+                    Some(path) => {
+                        let layout = match automaton.load_layout(path) {
+                            Ok(layout) => layout,
+                            Err(e) => {
+                                println!("{}", e);
+                                return Ok(());
+                            }
+                        };
+                        let mut cells = vec![vec![0; width]; height];
+                        let clipped = automaton::place_layout(&mut cells, &layout);
+                        if clipped > 0 {
+                            println!("{} cells of {} did not fit on the board", clipped, path.display());
+                        }
+                        cells
+                    }
+                    None if automaton.needs_layout() => {
+                        println!("{} needs a layout file: pass --layout circuit.txt", automaton);
+                        return Ok(());
+                    }
+                    None => automaton.seed(&grid),
+                };
+                Box::new(CellularSimulation { automaton, cells })
+            }
+            SimulationKind::LangtonsAnt => Box::new(LangtonsAnt::new(width, height, options.ant_rule.clone(), options.ants, &mut rng)),
+            // The board is one row per generation, so its width comes from the terminal too.
+            SimulationKind::Elementary if options.probability_given => {
+                Box::new(Elementary::random(width, height, options.wolfram_rule, options.initial_grid_probability, &mut rng))
+            }
+            SimulationKind::Elementary => Box::new(Elementary::new(width, height, options.wolfram_rule)),
+            SimulationKind::Margolus(rule) => Box::new(Margolus::new(rule, &grid)),
+        };
+        thread::sleep(Duration::from_millis(2000));
+        return multistate::run(simulation.as_mut(), options.speed, &options.display, console_size.cols, console_size.rows);
+    }
+
+    // The rule can change when a snapshot is loaded.
+    let mut rule = options.rule;
+    let mut session = lifetime::Session::default();
+    session.rules.insert(rule.to_string());
+    let mut watcher = options.watch.clone().map(watch::PatternWatcher::new);
     thread::sleep(Duration::from_millis(2000));
 
     // Create an atomic flag to track if the user has requested to exit the program.
     let should_exit = Arc::new(AtomicBool::new(false));
     let should_exit_clone = Arc::clone(&should_exit);
 
-    // Initialize the ctrlc handler.
+    // Initialize the ctrlc handler. Kiosk mode ignores Ctrl-C.
+    let kiosk = options.kiosk;
     ctrlc::set_handler(move || {
-        // Clear the screen before exiting.
-        execute!(stdout(), Clear(ClearType::All)).expect("Error clearing screen");
-        println!();
-        println!("Exiting...");
+        if kiosk {
+            return;
+        }
 
         // Set the atomic flag to true to indicate that the user has requested to exit the program.
         should_exit_clone.store(true, std::sync::atomic::Ordering::Relaxed);
-        
     }).expect("Error setting Ctrl-C handler");
 
-    // Initialize the grid with a random pattern of live and dead cells and get the size of the console.
-    let (mut grid, console_size) = initialize_grid(initial_grid_probability)?;
-    let mut prev_grid = grid.clone();
+    // The board is stepped in place from here on, reusing its buffers every generation. The
+    // generations are computed ahead on a thread of their own while frames are drawn, except
+    // with noise, which edits the board before every step.
+    let mut grid = World::new(grid, options.boundary).with_engine(options.engine.create(options.boundary));
+    if options.noise.is_none() {
+        grid = grid.with_lookahead(options.engine.create(options.boundary));
+    }
+    if let Some(max_age) = options.max_age {
+        grid = grid.with_max_age(max_age);
+    }
+
+    // Start from an empty previous grid so the first frame draws every live cell.
+    let mut prev_grid = vec![vec![false; grid.first().map_or(0, |row| row.len())]; grid.len()];
 
-    // Clear the screen before starting the loop.
-    execute!(stdout(), Clear(ClearType::All))?;
+    // Register the statistics collectors and feed them the initial state.
+    let mut hud = Hud::new(&options);
+    let mut stats = new_stats(&options, &hud);
+    let mut generation = resumed.as_ref().map_or(0, |snapshot| snapshot.generation);
+    stats.observe(&stats::GenerationDiff::between(&prev_grid, &grid, generation));
+
+    // Record how the run starts and everything done to it, if requested, so it can be replayed.
+    let mut replay_recorder = match &options.record_replay {
+        Some(path) => {
+            let start = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+            match ReplayRecorder::new(path, cli::without_flag(&args, "--record-replay"), start) {
+                Ok(recorder) => Some(recorder),
+                Err(e) => {
+                    println!("{}", e);
+                    return Ok(());
+                }
+            }
+        }
+        None => None,
+    };
+
+    // Keep the most recent generations so they can be stepped back through while paused.
+    let mut history = History::new(options.history_depth);
+    // The cells changed by hand, which `u` and `Ctrl-R` undo and redo.
+    let mut edits = EditStack::default();
+    // The brush the mouse paints the board with, changed with the scroll wheel.
+    let mut painter = Painter::new(options.brush);
+
+    // Multi-state variants keep the state of every cell next to the board.
+    let variant = options.variant;
+    let mut states = variant.is_multi_state().then(|| variant.color(&grid, &mut rng));
+    let mut prev_states = Vec::new();
+
+    // Watch for the board settling into a still life or oscillation, if requested.
+    // Screensaver rounds also end when the soup settles, and the summary of a run limited
+    // with `--generations` says whether it did.
+    let mut cycles = (options.on_cycle.is_some() || options.screensaver.is_some() || options.generations.is_some()).then(|| CycleDetector::new(DEFAULT_MAX_PERIOD));
+    if let Some(detector) = cycles.as_mut() {
+        detector.observe(grid.hash(), generation);
+    }
+
+    // The board is drawn by the renderer of the render mode, and every generation also goes
+    // to the recordings beside it, which start with the initial state. Zooming out to fit
+    // the patterns turns the trails off.
+    let recorders = match open_recorders(&options) {
+        Ok(recorders) => recorders,
+        Err(e) => {
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    let trails = options.trails.filter(|_| options.display.mode == RenderMode::Ascii);
+    let mut renderers = vec![display::renderer(options.display.mode, trails)];
+    renderers.extend(recorders.into_iter().map(|recorder| Box::new(Recording::new(recorder)) as Box<dyn Renderer>));
+    let mut renderer = Renderers::new(renderers);
+    renderer.record(&grid, generation)?;
+    // Turbo mode draws a density minimap of the board instead.
+    let mut density = DensityRenderer::default();
+
+    // A message that temporarily replaces the status bar, and when it was shown.
+    let mut status_message: Option<(String, Instant)> = None;
+
+    // Sixels are only drawn on terminals that say they show them.
+    if options.display.mode == RenderMode::Sixel && !display::supports_sixel().unwrap_or(false) {
+        println!("This terminal does not show sixel graphics; try --render braille or --render sextant instead");
+        return Ok(());
+    }
+
+    // All drawing goes through the terminal writer, which also feeds any asciicast recording.
+    // The status bar is the extra row below the grid.
+    let cast = match &options.asciicast {
+        Some(path) => match asciicast::CastRecorder::new(path, console_size.cols, console_size.rows + 1) {
+            Ok(cast) => Some(cast),
+            Err(e) => {
+                println!("{}", e);
+                return Ok(());
+            }
+        },
+        None => None,
+    };
+
+    // Draw on the alternate screen, leaving the user's screen and scrollback alone, and
+    // switch to raw mode so key presses are delivered immediately. Without a usable
+    // terminal the simulation still runs, and Ctrl-C still stops it.
+    let screen = Screen::enter()?;
+    let raw_mode = match input::RawMode::enable() {
+        Ok(raw_mode) => Some(raw_mode),
+        Err(e) => {
+            status_message = Some((format!("Keyboard input unavailable ({}); press Ctrl-C to quit", e), Instant::now()));
+            None
+        }
+    };
+    // The mouse paints the board, unless `--no-mouse` leaves it to the terminal for
+    // selecting text.
+    let _mouse = raw_mode.as_ref().filter(|_| !options.no_mouse && !options.kiosk).and_then(|_| input::MouseCapture::enable().ok());
+
+    // Frames are written to the terminal on a thread of their own, so a slow terminal
+    // skips frames instead of slowing the simulation down. The terminal is dropped before
+    // the screen, once its last frames are written.
+    let mut term = Terminal::with_writer_thread(cast)?;
+
+    // The renderer clears the screen before the loop starts.
+    renderer.init(&mut term, &options.display)?;
+
+    // The text that stops a kiosk, as it is typed.
+    let mut stop_token = kiosk::StopToken::new(options.kiosk_token.clone());
+
+    // The RLE editor pane, while it is open. The simulation pauses while editing.
+    let mut editor: Option<Editor> = None;
+    // The pattern picker, while it is open on the paused board.
+    let mut picker: Option<Picker> = None;
+    // Whether the help overlay is shown over the board.
+    let mut show_help = false;
+    // The prompt for a rule to switch to, while open with `R`.
+    let mut rule_prompt: Option<Prompt> = None;
+
+    // When the board is due to be replaced by a fresh one, after extinction or a cycle.
+    let mut restart_due: Option<Instant> = None;
+
+    // The current screensaver round, and when it started.
+    let mut round: usize = 0;
+    let mut round_started = Instant::now();
+
+    // The control panel beside the board, if requested.
+    let mut panel = options.panel.then(|| Panel::new(&rule));
+
+    // The socket external tools send commands to, if requested.
+    let control = match options.control.as_deref().map(ControlSocket::bind).transpose() {
+        Ok(control) => control,
+        Err(e) => {
+            drop(raw_mode);
+            drop(screen);
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+
+    // The script told about the run, if one is given, which has its first turn before the
+    // first generation.
+    let mut script = match options.script.as_deref().map(Script::spawn).transpose() {
+        Ok(script) => script,
+        Err(e) => {
+            drop(raw_mode);
+            drop(screen);
+            println!("{}", e);
+            return Ok(());
+        }
+    };
+    if let Some(script) = script.as_mut() {
+        let (width, height) = grid.bounds();
+        script.push(ScriptEvent::Init { width, height, generation, rule });
+    }
+
+    // Whether the simulation is paused, and whether the last frame was drawn in turbo mode.
+    let mut paused = false;
+    let mut was_turbo = false;
+    // With `--follow`, the part of a board too big for the terminal that is in view, and
+    // the cells of it on screen.
+    let mut camera = follow_camera(&options, &grid, &console_size);
+    let mut prev_view: Grid = Vec::new();
+    // Where the live cells are along the gradient with `--gradient`, and the generation
+    // they were last placed at.
+    let mut shading = options.gradient.as_ref().map(Shading::new);
+    let mut shading_generation = None;
+    // How fast the loop runs, shown in an overlay while toggled on with `f`, and where the
+    // overlay was last drawn.
+    let mut timer = FrameTimer::default();
+    let mut timings_at: Option<Region> = None;
+    // The number of generations computed each tick, changed with `[` and `]`.
+    let mut steps_per_frame = options.steps_per_frame;
+    // The names of the objects on the paused board, while shown with `i`.
+    let mut labels: Option<ObjectLabels> = None;
+    // Whether the next frame is drawn from scratch, as after the overlay is hidden.
+    let mut full_redraw = false;
+
+    // The simulation advances on a fixed timestep of its own, and frames are drawn at most
+    // `--fps` times a second in between, so slow drawing does not slow the simulation.
+    let mut tick = Duration::from_secs_f64(1.0 / options.speed);
+    let frame_interval = Duration::from_secs_f64(1.0 / options.fps);
+    let mut next_update = Instant::now();
+    let mut next_frame = Instant::now();
+    // The time spent computing generations since the last frame was drawn.
+    let mut frame_update_time = Duration::ZERO;
 
     // Enter an infinite loop to continuously update and display the grid.
     while !should_exit.load(std::sync::atomic::Ordering::Relaxed) {
-        // Display the current state of the grid to the console.
-        display_grid(&grid, &prev_grid)?;
+        // Turbo mode only applies while the simulation is running.
+        let turbo = options.turbo && !paused && editor.is_none() && restart_due.is_none();
+        if turbo != was_turbo || full_redraw {
+            // Switching views: clear the screen and redraw the new view from scratch.
+            clear_screen(&mut term, &options.display)?;
+            prev_grid = vec![vec![false; grid.first().map_or(0, |row| row.len())]; grid.len()];
+            prev_view.clear();
+            was_turbo = turbo;
+            full_redraw = false;
+        }
+
+        // Take the interventions of a replay in the order they were recorded, each once the
+        // board reaches the generation it was made at.
+        while let Some(intervention) = replay.as_mut().and_then(|replay| replay.next_due(generation)) {
+            match intervention {
+                Intervention::Pause => paused = true,
+                Intervention::Resume => paused = false,
+                Intervention::Step => {
+                    advance(&mut grid, &rule, &mut generation, &mut stats, &mut history, &mut renderer, &mut session)?;
+                    if let Some(states) = states.as_mut() {
+                        *states = variant.step(states, &grid, &rule, options.boundary);
+                    }
+                }
+                Intervention::Place { x, y, cells } => {
+                    // A pasted region may be as large as the board, past the limit of pattern files.
+                    let (width, height) = grid.bounds();
+                    let pattern = parse_rle_within(&cells, width, height)?;
+                    grid.edit(|board| placement::place_pattern(board, &pattern, x, y));
+                    cycles.iter_mut().for_each(CycleDetector::reset);
+                }
+                Intervention::SetRule { rule: next } => {
+                    rule = next;
+                    session.rules.insert(rule.to_string());
+                    cycles.iter_mut().for_each(CycleDetector::reset);
+                }
+                Intervention::Restore { state } => {
+                    let board = state.grid()?;
+                    generation = state.generation;
+                    stats.observe(&stats::GenerationDiff::between(&grid, &board, generation));
+                    grid.replace(board);
+                    rule = state.rule;
+                    seed = state.seed;
+                    rng = state.rng();
+                    cycles.iter_mut().for_each(CycleDetector::reset);
+                }
+            }
+            if replay.as_ref().is_some_and(|replay| replay.remaining() == 0) {
+                status_message = Some(("End of the replay".to_string(), Instant::now()));
+            }
+        }
 
-        // Update the grid by applying the Game of Life rules.
-        prev_grid = grid.clone();
-        grid = update_grid(&mut grid, &console_size);
+        // Cells changed by the editor, a restart, or stepping back get their states here.
+        if let Some(states) = states.as_mut() {
+            variant.reconcile(states, &grid, &mut rng);
+        }
+
+        // Draw a frame once one is due; frames are skipped while the simulation catches up,
+        // and while the terminal is still writing the earlier ones.
+        let now = Instant::now();
+        if now >= next_frame && !term.ready()? {
+            next_frame = now + frame_interval;
+            timer.skip();
+        } else if now >= next_frame {
+            next_frame = now + frame_interval;
+            // Display the current state of the grid to the console.
+            let render_started = Instant::now();
+            // Cells are colored by their gradient level or their state, if either is kept.
+            if let Some(shading) = shading.as_mut() {
+                if shading_generation != Some(generation) {
+                    shading.observe(&grid);
+                    shading_generation = Some(generation);
+                }
+            }
+            let (cell_levels, palette) = match &shading {
+                Some(shading) => (Some(shading.levels()), shading.palette()),
+                None => (states.as_deref(), variant.palette()),
+            };
+            let region = Region::screen(console_size.cols, console_size.rows);
+            if turbo {
+                let frame = Frame { grid: &grid, prev_grid: &prev_grid, states: None, region, origin: (0, 0) };
+                density.draw_frame(&mut term, &frame, &options.display)?;
+            } else if let Some(camera) = &camera {
+                let view = camera.window(&grid);
+                let view_states = cell_levels.map(|states| camera.window(states));
+                let cell_states = view_states.as_ref().map(|states| CellStates {
+                    states,
+                    prev_states: &prev_states,
+                    palette,
+                });
+                let frame = Frame { grid: &view, prev_grid: &prev_view, states: cell_states.as_ref(), region, origin: camera.origin() };
+                renderer.draw_frame(&mut term, &frame, &options.display)?;
+                prev_view = view;
+                prev_grid.clone_from(&grid);
+                prev_states = view_states.unwrap_or_default();
+            } else {
+                let cell_states = cell_levels.map(|states| CellStates {
+                    states,
+                    prev_states: &prev_states,
+                    palette,
+                });
+                let frame = Frame { grid: &grid, prev_grid: &prev_grid, states: cell_states.as_ref(), region, origin: (0, 0) };
+                renderer.draw_frame(&mut term, &frame, &options.display)?;
+                prev_grid.clone_from(&grid);
+                prev_states = cell_levels.map(<[_]>::to_vec).unwrap_or_default();
+            }
+            // The regions of the board the overlays cover, which the timings keep clear of.
+            let mut reserved = Vec::new();
+            if let Some(editor) = editor.as_mut() {
+                editor.draw(&mut term, console_size.cols, console_size.rows)?;
+                reserved.push(Editor::area(console_size.cols, console_size.rows));
+            }
+            if let Some(picker) = &picker {
+                let origin = camera.as_ref().map_or((0, 0), Camera::origin);
+                picker.draw(&mut term, &options.display, origin, console_size.cols, console_size.rows)?;
+                reserved.extend(picker.list_area(console_size.cols, console_size.rows));
+            }
+            if show_help {
+                let settings = [
+                    ("Rule", rule.to_string()),
+                    ("Topology", options.boundary.to_string()),
+                    ("Engine", options.engine.to_string()),
+                    ("Speed", format!("{} generations/s, {} per frame", options.speed, steps_per_frame)),
+                    ("Render", options.display.mode.to_string()),
+                    ("Board", format!("{}x{}, seed {}", grid.first().map_or(0, |row| row.len()), grid.len(), seed)),
+                ];
+                help::draw(&mut term, &settings, console_size.cols, console_size.rows)?;
+                reserved.extend(help::area(&settings, console_size.cols, console_size.rows));
+            }
+            // The names only hold for the board they were found on.
+            if labels.as_ref().is_some_and(|labels| !paused || !labels.is_for(&grid)) {
+                labels = None;
+                full_redraw = true;
+            }
+            if let Some(labels) = &labels {
+                let origin = camera.as_ref().map_or((0, 0), Camera::origin);
+                labels.draw(&mut term, options.display.mode, origin, console_size.cols, console_size.rows)?;
+            }
 
-        // Sleep for a short duration to control the speed of the simulation.
-        thread::sleep(Duration::from_millis(100));
+            // Show the latest message until it expires, then go back to the statistics, or
+            // to a blank row with the status bar hidden.
+            if status_message.as_ref().is_some_and(|(_, shown)| shown.elapsed() >= STATUS_MESSAGE_DURATION) {
+                status_message = None;
+                full_redraw |= !hud.status_bar;
+            }
+            let cycle_tag = match cycles.as_ref().and_then(CycleDetector::found) {
+                Some(cycle) if cycle.is_still_life() => " [still]".to_string(),
+                Some(cycle) => format!(" [period {}]", cycle.period),
+                None => String::new(),
+            };
+            let cycle_tag = match steps_per_frame {
+                1 => cycle_tag,
+                steps => format!("{} [{} gens/frame]", cycle_tag, steps),
+            };
+            let status = match &status_message {
+                Some((message, _)) => message.clone(),
+                None if paused => format!("{}{} [paused]", stats.status_line(), cycle_tag),
+                None if turbo => format!("{}{} [turbo]", stats.status_line(), cycle_tag),
+                None => format!("{}{}", stats.status_line(), cycle_tag),
+            };
+            if let Some(prompt) = &rule_prompt {
+                prompt.draw(&mut term, console_size.rows, console_size.cols)?;
+            } else if hud.status_bar || status_message.is_some() {
+                display_status(&mut term, &status, console_size.rows, console_size.cols, &options.display)?;
+            }
+            if let Some(panel) = &panel {
+                let info = PanelInfo { stats: &stats.values(), rule: &rule, paused };
+                // The panel runs down the whole height, beside the status bar too.
+                panel.draw(&mut term, console_size.cols, console_size.rows + 1, &info)?;
+            }
+            // The timings move out of the way of the other overlays, and the spot they left
+            // is redrawn from the board on the next frame.
+            let board = Region::screen(console_size.cols, console_size.rows);
+            let placed = hud.timings.then(|| hud::place(OVERLAY_WIDTH, OVERLAY_HEIGHT, board, &reserved)).flatten();
+            if let Some(region) = placed {
+                timer.draw(&mut term, region)?;
+            }
+            if timings_at.is_some() && placed != timings_at {
+                full_redraw = true;
+            }
+            timings_at = placed;
+            // The whole frame goes to the terminal in one write.
+            term.flush()?;
+            let render_time = render_started.elapsed();
+            timer.record(generation, frame_update_time, render_time, (!turbo).then_some(frame_interval));
+            frame_update_time = Duration::ZERO;
+        }
+
+        // Update the grid by applying the Game of Life rules for every timestep that is due,
+        // up to a limit per frame. In turbo mode, keep stepping until the frame time is used up.
+        let update_started = Instant::now();
+        let running = editor.is_none() && !paused && restart_due.is_none() && !script.as_ref().is_some_and(Script::is_due);
+        if running && (turbo || update_started >= next_update) {
+            let mut steps = 0;
+            loop {
+                // A replay stops here for the interventions made at this generation.
+                if replay.as_ref().is_some_and(|replay| replay.is_due(generation)) {
+                    break;
+                }
+                if let Some(noise) = noise.as_mut() {
+                    let (width, height) = grid.bounds();
+                    for (x, y) in noise.flips(width, height) {
+                        grid.toggle(x, y);
+                    }
+                }
+                advance(&mut grid, &rule, &mut generation, &mut stats, &mut history, &mut renderer, &mut session)?;
+                if let Some(states) = states.as_mut() {
+                    *states = variant.step(states, &grid, &rule, options.boundary);
+                }
+                if let Some(script) = script.as_mut() {
+                    script.push(ScriptEvent::Generation { generation, population: grid.population() });
+                }
+                // The gradient sees every generation, even those no frame shows, as the
+                // renderers do.
+                if let Some(shading) = shading.as_mut() {
+                    shading.observe(&grid);
+                    shading_generation = Some(generation);
+                }
+                if let Some(camera) = camera.as_mut() {
+                    camera.observe(&grid);
+                }
+
+                // Start over on a fresh board a moment after everything has died, if requested.
+                if options.restart_on_extinction && grid.iter().flatten().all(|&cell| !cell) {
+                    status_message = Some((format!("Generation died at {}", generation), Instant::now()));
+                    restart_due = Some(Instant::now() + EXTINCTION_PAUSE);
+                    break;
+                }
+
+                // Act on a detected cycle as requested with `--on-cycle`.
+                let cycle = cycles.as_mut().and_then(|detector| detector.observe(grid.hash(), generation));
+                if let (Some(cycle), Some(policy)) = (cycle, options.on_cycle) {
+                    let message = match policy {
+                        CyclePolicy::Stop => {
+                            paused = true;
+                            format!("Reached a {}; paused", cycle)
+                        }
+                        CyclePolicy::Report => format!("Reached a {}", cycle),
+                        CyclePolicy::Restart => {
+                            restart_due = Some(Instant::now());
+                            format!("Reached a {}; restarted", cycle)
+                        }
+                    };
+                    status_message = Some((message, Instant::now()));
+                    break;
+                }
+
+                // End the screensaver round once the soup dies out, settles, or runs out of time.
+                if let Some(length) = options.screensaver {
+                    if cycle.is_some() || round_started.elapsed() >= length || grid.iter().flatten().all(|&cell| !cell) {
+                        restart_due = Some(Instant::now() + SCREENSAVER_PAUSE);
+                        break;
+                    }
+                }
+
+                // Stop once the number of generations asked for with `--generations` is reached.
+                if options.generations.is_some_and(|limit| generation >= limit) {
+                    should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                    break;
+                }
+
+                // Each tick computes a batch of generations, and the next is due a tick later.
+                steps += 1;
+                let batch_done = steps % steps_per_frame == 0;
+                if batch_done {
+                    next_update += tick;
+                }
+                let caught_up = (batch_done && Instant::now() < next_update) || steps >= MAX_CATCH_UP_STEPS * steps_per_frame;
+                // A script has its turn for every generation before the next is computed.
+                if (turbo && update_started.elapsed() >= TURBO_FRAME_TIME) || (!turbo && caught_up) || script.is_some() {
+                    break;
+                }
+            }
+        }
+        // While the simulation is stopped, or too slow to keep up, the timestep starts over
+        // from now instead of making up for the lost time later.
+        next_update = next_update.max(Instant::now());
+        frame_update_time += update_started.elapsed();
+
+        // Replace the board with a fresh one once a restart is due.
+        if restart_due.is_some_and(|due| Instant::now() >= due) {
+            restart_due = None;
+            let (width, height) = (grid.first().map_or(0, |row| row.len()), grid.len());
+
+            // A new screensaver round starts on a clear screen with a fresh seed, rule, and color.
+            if options.screensaver.is_some() {
+                round += 1;
+                round_started = Instant::now();
+                seed = random_seed();
+                rng = ChaCha12Rng::seed_from_u64(seed);
+                rule = Rule::parse(SCREENSAVER_RULES[round % SCREENSAVER_RULES.len()])?;
+                session.rules.insert(rule.to_string());
+                options.display.alive_color = Some(SCREENSAVER_COLORS[round % SCREENSAVER_COLORS.len()]);
+                clear_screen(&mut term, &options.display)?;
+                prev_grid = vec![vec![false; width]; height];
+                prev_view.clear();
+                status_message = Some((format!("Round {}: {}, seed {}", round + 1, rule, seed), Instant::now()));
+            }
+            grid.replace(fresh_board(width, height, probability, &options, &placements, &mut rng)?);
+            generation = 0;
+            history.clear();
+            stats = new_stats(&options, &hud);
+            stats.observe(&stats::GenerationDiff::between(&prev_grid, &grid, generation));
+            if let Some(detector) = cycles.as_mut() {
+                detector.reset();
+                detector.observe(grid.hash(), generation);
+            }
+        }
+
+        // Reset the board to the watched pattern whenever its file changes.
+        if let Some(watcher) = watcher.as_mut() {
+            if watcher.changed() {
+                let mut board = vec![vec![false; grid.first().map_or(0, |row| row.len())]; grid.len()];
+                let message = match seed_board(&mut board, &options, &placements, &mut rng) {
+                    Ok(_) => {
+                        grid.replace(board);
+                        generation = 0;
+                        history.clear();
+                        cycles.iter_mut().for_each(CycleDetector::reset);
+                        stats = new_stats(&options, &hud);
+                        stats.observe(&stats::GenerationDiff::between(&prev_grid, &grid, generation));
+                        format!("Reloaded {}", watcher.placement.source)
+                    }
+                    Err(e) => format!("Reload failed: {}", e),
+                };
+                status_message = Some((message, Instant::now()));
+            }
+        }
+
+        // Wait for the next timestep or frame, whichever comes first, handling key presses.
+        // Turbo mode only checks for input that is already waiting.
+        let wake = if running { next_update.min(next_frame) } else { next_frame };
+        let wait = if turbo { Duration::ZERO } else { wake.saturating_duration_since(Instant::now()) };
+        let inputs = if raw_mode.is_some() {
+            input::wait_for_input(wait)?
+        } else {
+            thread::sleep(wait);
+            Vec::new()
+        };
+        for input in inputs {
+            // Resizing the terminal can wrap or wipe what is on it, so the next frame is
+            // drawn from scratch, on as much of the new size as the board needs.
+            if let Input::Resize(cols, rows) = input {
+                let panel = if options.panel { PANEL_WIDTH } else { 0 };
+                console_size = ConsoleSize::fit(options.display.mode, grid.bounds(), (usize::from(cols).saturating_sub(panel), usize::from(rows)));
+                camera = follow_camera(&options, &grid, &console_size);
+                renderer.resize(&mut term, &options.display)?;
+                full_redraw = true;
+                continue;
+            }
+
+            // Kiosk mode ignores every key; only typing the stop token quits.
+            if options.kiosk {
+                if let Input::Key(KeyEvent { code: KeyCode::Char(c), .. }) = input {
+                    if stop_token.push(c) {
+                        clear_screen(&mut term, &options.display)?;
+                        should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                    }
+                }
+                continue;
+            }
+
+            // While the editor is open it receives all input except Ctrl-C.
+            if let Some(pane) = editor.as_mut() {
+                let action = match &input {
+                    Input::Key(key) if input::is_ctrl_c(key) => {
+                        should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                        EditorAction::Continue
+                    }
+                    Input::Key(key) => pane.handle_key(key),
+                    Input::Paste(text) => {
+                        pane.paste(text);
+                        EditorAction::Continue
+                    }
+                    Input::Mouse(_) | Input::Resize(..) => EditorAction::Continue,
+                };
+
+                match action {
+                    EditorAction::Continue => {}
+                    EditorAction::Commit(pattern) => {
+                        // Stamp the pattern in the middle of the board.
+                        let x = (grid.first().map_or(0, |row| row.len()) as isize - pattern.width as isize) / 2;
+                        let y = (grid.len() as isize - pattern.height as isize) / 2;
+                        let before = grid.to_vec();
+                        let report = grid.edit(|board| placement::place_pattern(board, &pattern, x, y));
+                        edits.record(Edit::between(&before, &grid), generation);
+                        record_intervention(&mut replay_recorder, generation, Intervention::Place { x, y, cells: pattern.to_rle() })?;
+                        status_message = Some((format!("Placed {} cells from the editor ({} clipped)", report.placed, report.clipped), Instant::now()));
+                        editor = None;
+                        cycles.iter_mut().for_each(CycleDetector::reset);
+                    }
+                    EditorAction::Cancel => editor = None,
+                }
+
+                // Redraw the whole board once the pane closes.
+                if editor.is_none() {
+                    clear_screen(&mut term, &options.display)?;
+                    prev_grid = vec![vec![false; grid.first().map_or(0, |row| row.len())]; grid.len()];
+                    prev_view.clear();
+                }
+                continue;
+            }
+
+            let key = match input {
+                Input::Key(key) => key,
+                // The mouse paints the board while no overlay is open over it.
+                Input::Mouse(mouse) if !show_help && picker.is_none() => {
+                    let origin = camera.as_ref().map_or((0, 0), Camera::origin);
+                    let cell = (usize::from(mouse.column) < console_size.cols && usize::from(mouse.row) < console_size.rows).then(|| {
+                        let (x, y) = options.display.mode.cell_under(mouse.column.into(), mouse.row.into());
+                        ((x + origin.0) as isize, (y + origin.1) as isize)
+                    });
+                    match grid.edit(|board| painter.handle_mouse(&mouse, cell, board, generation)) {
+                        PaintAction::None => {}
+                        PaintAction::Painted => cycles.iter_mut().for_each(CycleDetector::reset),
+                        PaintAction::Finished { before, generation: started } => {
+                            // A stroke that spanned generations is not an edit of one board.
+                            if started == generation {
+                                edits.record(Edit::between(&before, &grid), generation);
+                            }
+                            if before != *grid {
+                                let state = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+                                record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                            }
+                        }
+                        PaintAction::Brush(brush) => status_message = Some((format!("Brush: {}", brush), Instant::now())),
+                    }
+                    continue;
+                }
+                Input::Paste(text) => {
+                    if let Some(prompt) = rule_prompt.as_mut() {
+                        prompt.paste(&text);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            // Any key closes the help, and the board is drawn again where it covered it.
+            if show_help {
+                if input::is_ctrl_c(&key) {
+                    should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                show_help = false;
+                full_redraw = true;
+                continue;
+            }
+
+            // While the rule prompt is open it receives all keys except Ctrl-C, and the board
+            // keeps running beneath it.
+            if let Some(prompt) = rule_prompt.as_mut() {
+                if input::is_ctrl_c(&key) {
+                    should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                    continue;
+                }
+                match prompt.handle_key(&key) {
+                    PromptAction::Continue => {}
+                    PromptAction::Submit(text) => match Rule::parse(&text) {
+                        Ok(next) => {
+                            rule = next;
+                            record_intervention(&mut replay_recorder, generation, Intervention::SetRule { rule })?;
+                            session.rules.insert(rule.to_string());
+                            cycles.iter_mut().for_each(CycleDetector::reset);
+                            status_message = Some((format!("Switched to {}", rule), Instant::now()));
+                            rule_prompt = None;
+                        }
+                        Err(e) => prompt.reject(e.to_string()),
+                    },
+                    PromptAction::Cancel => rule_prompt = None,
+                }
+                // With the status bar hidden, nothing else draws over the row the prompt was on.
+                full_redraw |= rule_prompt.is_none() && !hud.status_bar;
+                continue;
+            }
+
+            // While the picker is open it receives all keys except Ctrl-C.
+            if let Some(open) = picker.as_mut() {
+                if input::is_ctrl_c(&key) {
+                    should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                    continue;
+                }
+                match open.handle_key(&key) {
+                    PickerAction::Continue => {}
+                    PickerAction::Stamp { pattern, x, y } => {
+                        let before = grid.to_vec();
+                        let report = grid.edit(|board| placement::place_pattern(board, &pattern, x, y));
+                        edits.record(Edit::between(&before, &grid), generation);
+                        record_intervention(&mut replay_recorder, generation, Intervention::Place { x, y, cells: pattern.to_rle() })?;
+                        status_message = Some((format!("Stamped {} cells ({} clipped)", report.placed, report.clipped), Instant::now()));
+                        cycles.iter_mut().for_each(CycleDetector::reset);
+                        picker = None;
+                    }
+                    PickerAction::Cancel => picker = None,
+                }
+                // The preview moved, or the picker closed, so nothing of the old one may stay.
+                full_redraw = true;
+                continue;
+            }
+
+            // The keys that reach the board are passed on to the script as well.
+            if let (Some(script), Some(event)) = (script.as_mut(), ScriptEvent::key(&key)) {
+                script.push(event);
+            }
+
+            // The panel's keys come first while it is shown.
+            if let Some(action) = panel.as_mut().and_then(|panel| panel.handle_key(&key)) {
+                match action {
+                    PanelAction::Select => {}
+                    PanelAction::SetRule(next) => {
+                        rule = next;
+                        record_intervention(&mut replay_recorder, generation, Intervention::SetRule { rule })?;
+                        session.rules.insert(rule.to_string());
+                        cycles.iter_mut().for_each(CycleDetector::reset);
+                        status_message = Some((format!("Switched to {}", rule), Instant::now()));
+                    }
+                    PanelAction::Place(pattern) => {
+                        let x = (grid.first().map_or(0, |row| row.len()) as isize - pattern.width as isize) / 2;
+                        let y = (grid.len() as isize - pattern.height as isize) / 2;
+                        let before = grid.to_vec();
+                        let report = grid.edit(|board| placement::place_pattern(board, &pattern, x, y));
+                        edits.record(Edit::between(&before, &grid), generation);
+                        record_intervention(&mut replay_recorder, generation, Intervention::Place { x, y, cells: pattern.to_rle() })?;
+                        let name = panel.as_ref().map_or("pattern", Panel::selected_pattern);
+                        status_message = Some((format!("Placed {} ({} cells clipped)", name, report.clipped), Instant::now()));
+                        cycles.iter_mut().for_each(CycleDetector::reset);
+                    }
+                }
+                continue;
+            }
+
+            match input::map_key(&key) {
+                Some(Command::Quit) => {
+                    clear_screen(&mut term, &options.display)?;
+                    should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                }
+                Some(Command::Screenshot) => {
+                    let caption = screenshot::Caption {
+                        rule: &rule.to_string(),
+                        generation,
+                        seed,
+                    };
+                    let message = match screenshot::save_screenshot(&grid, &options.screenshot_dir, &options.screenshot_style, &caption) {
+                        Ok(path) => format!("Saved screenshot to {}", path.display()),
+                        Err(e) => format!("Screenshot failed: {}", e),
+                    };
+                    status_message = Some((message, Instant::now()));
+                }
+                Some(Command::OpenEditor) => editor = Some(Editor::new()),
+                Some(Command::ToggleTimings) => hud.timings = !hud.timings,
+                Some(Command::ToggleStatusBar) => {
+                    hud.status_bar = !hud.status_bar;
+                    full_redraw = !hud.status_bar;
+                }
+                Some(Command::ToggleSparkline) => {
+                    // The sparkline starts over from the generation it is shown again at.
+                    hud.sparkline = !hud.sparkline;
+                    if hud.sparkline {
+                        stats.register(Box::new(stats::PopulationSparkline::default()));
+                    } else {
+                        stats.unregister("trend");
+                    }
+                }
+                Some(Command::TogglePause) => {
+                    paused = !paused;
+                    record_intervention(&mut replay_recorder, generation, if paused { Intervention::Pause } else { Intervention::Resume })?;
+                }
+                Some(command @ (Command::FewerSteps | Command::MoreSteps)) => {
+                    steps_per_frame = match command {
+                        Command::FewerSteps => (steps_per_frame / 2).max(1),
+                        _ => (steps_per_frame * 2).min(cli::MAX_STEPS_PER_FRAME),
+                    };
+                    status_message = Some((format!("{} generations per frame", steps_per_frame), Instant::now()));
+                }
+                Some(Command::Paste) => {
+                    let message = match clipboard::read_pattern() {
+                        Ok(pattern) => {
+                            // Stamp the pattern in the middle of the board, like the editor does.
+                            let x = (grid.first().map_or(0, |row| row.len()) as isize - pattern.width as isize) / 2;
+                            let y = (grid.len() as isize - pattern.height as isize) / 2;
+                            let before = grid.to_vec();
+                            let report = grid.edit(|board| placement::place_pattern(board, &pattern, x, y));
+                            edits.record(Edit::between(&before, &grid), generation);
+                            record_intervention(&mut replay_recorder, generation, Intervention::Place { x, y, cells: pattern.to_rle() })?;
+                            cycles.iter_mut().for_each(CycleDetector::reset);
+                            format!("Pasted {} cells from the clipboard ({} clipped)", report.placed, report.clipped)
+                        }
+                        Err(e) => e.to_string(),
+                    };
+                    status_message = Some((message, Instant::now()));
+                }
+                Some(Command::Help) => show_help = true,
+                Some(command @ (Command::Clear | Command::Fill | Command::Invert)) => {
+                    let before = grid.to_vec();
+                    grid.edit(|board| {
+                        for cell in board.iter_mut().flatten() {
+                            *cell = match command {
+                                Command::Clear => false,
+                                Command::Fill => true,
+                                _ => !*cell,
+                            };
+                        }
+                    });
+                    edits.record(Edit::between(&before, &grid), generation);
+                    let state = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+                    record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                    cycles.iter_mut().for_each(CycleDetector::reset);
+                    // Every cell may have changed, so the board is drawn again from scratch.
+                    full_redraw = true;
+                    let verb = match command {
+                        Command::Clear => "Cleared",
+                        Command::Fill => "Filled",
+                        _ => "Inverted",
+                    };
+                    status_message = Some((format!("{} the board", verb), Instant::now()));
+                }
+                Some(Command::Reseed) => {
+                    // A new seed, so the soup differs from every one before it.
+                    seed = random_seed();
+                    rng = ChaCha12Rng::seed_from_u64(seed);
+                    let (width, height) = (grid.first().map_or(0, |row| row.len()), grid.len());
+                    grid.replace(fresh_board(width, height, probability, &options, &placements, &mut rng)?);
+                    generation = 0;
+                    history.clear();
+                    // The edits were made on the old board, which may also have been at generation 0.
+                    edits = EditStack::default();
+                    stats = new_stats(&options, &hud);
+                    stats.observe(&stats::GenerationDiff::between(&prev_grid, &grid, generation));
+                    if let Some(detector) = cycles.as_mut() {
+                        detector.reset();
+                        detector.observe(grid.hash(), generation);
+                    }
+                    let state = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+                    record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                    status_message = Some((format!("Reseeded at probability {:.2} with seed {}", probability, seed), Instant::now()));
+                }
+                Some(command @ (Command::LowerProbability | Command::RaiseProbability)) => {
+                    let step = if command == Command::LowerProbability { -PROBABILITY_STEP } else { PROBABILITY_STEP };
+                    // Kept on the steps, so repeated presses do not pile up rounding errors.
+                    probability = (((probability + step) / PROBABILITY_STEP).round() * PROBABILITY_STEP).clamp(0.0, 1.0);
+                    status_message = Some((format!("Probability {:.2}; r reseeds with it", probability), Instant::now()));
+                }
+                Some(Command::PromptRule) => rule_prompt = Some(Prompt::new("Rule", &rule.to_string())),
+                Some(Command::PickPattern) if paused => {
+                    let (width, height) = (grid.first().map_or(0, |row| row.len()), grid.len());
+                    let (opened, unreadable) = Picker::new(options.pattern_dir.as_deref(), width, height);
+                    if unreadable > 0 {
+                        status_message = Some((format!("Skipped unreadable files in the pattern folder: {}", unreadable), Instant::now()));
+                    }
+                    picker = Some(opened);
+                }
+                Some(Command::PickPattern) => {
+                    status_message = Some(("Pause with Space to pick a pattern".to_string(), Instant::now()));
+                }
+                Some(Command::IdentifyObjects) if labels.is_some() => {
+                    labels = None;
+                    full_redraw = true;
+                }
+                Some(Command::IdentifyObjects) if paused => {
+                    let found = ObjectLabels::scan(&grid);
+                    status_message = Some((found.summary(), Instant::now()));
+                    labels = Some(found);
+                }
+                Some(Command::IdentifyObjects) => {
+                    status_message = Some(("Pause with Space to name the objects".to_string(), Instant::now()));
+                }
+                Some(Command::StepBack) => {
+                    if !paused {
+                        record_intervention(&mut replay_recorder, generation, Intervention::Pause)?;
+                    }
+                    paused = true;
+                    let message = match history.pop() {
+                        Some((earlier, board)) => {
+                            let state = Snapshot::capture(&board, earlier, &rule, seed, &rng);
+                            record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                            generation = earlier;
+                            stats.observe(&stats::GenerationDiff::between(&grid, &board, generation));
+                            grid.replace(board);
+                            format!("Rewound to generation {} ({} earlier kept)", generation, history.len())
+                        }
+                        None if options.history_depth == 0 => "Rewinding is disabled (--history 0)".to_string(),
+                        None => "No earlier generations in the history".to_string(),
+                    };
+                    status_message = Some((message, Instant::now()));
+                }
+                Some(command @ (Command::Undo | Command::Redo)) => {
+                    let (done, verb) = match command {
+                        Command::Undo => (grid.edit(|board| edits.undo(board, generation)), "Undid"),
+                        _ => (grid.edit(|board| edits.redo(board, generation)), "Redid"),
+                    };
+                    let message = if done {
+                        let state = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+                        record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                        cycles.iter_mut().for_each(CycleDetector::reset);
+                        let (undo, redo) = edits.counts();
+                        format!("{} an edit ({} to undo, {} to redo)", verb, undo, redo)
+                    } else if command == Command::Undo {
+                        "No edits of this generation to undo".to_string()
+                    } else {
+                        "No undone edits of this generation to redo".to_string()
+                    };
+                    status_message = Some((message, Instant::now()));
+                }
+                Some(Command::StepForward) if paused => {
+                    record_intervention(&mut replay_recorder, generation, Intervention::Step)?;
+                    advance(&mut grid, &rule, &mut generation, &mut stats, &mut history, &mut renderer, &mut session)?;
+                    if let Some(states) = states.as_mut() {
+                        *states = variant.step(states, &grid, &rule, options.boundary);
+                    }
+                    if let Some(script) = script.as_mut() {
+                        script.push(ScriptEvent::Generation { generation, population: grid.population() });
+                    }
+                }
+                Some(Command::StepForward) => {}
+                Some(Command::SaveSnapshot) => {
+                    let snapshot = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+                    let message = match snapshot.save(&options.snapshot_file) {
+                        Ok(()) => format!("Saved snapshot to {}", options.snapshot_file.display()),
+                        Err(e) => format!("Snapshot failed: {}", e),
+                    };
+                    status_message = Some((message, Instant::now()));
+                }
+                Some(Command::LoadSnapshot) => {
+                    let message = match load_snapshot_for(&options.snapshot_file, &grid) {
+                        Ok((board, snapshot)) => {
+                            let state = Snapshot::capture(&board, snapshot.generation, &snapshot.rule, snapshot.seed, &snapshot.rng());
+                            record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                            grid.replace(board);
+                            generation = snapshot.generation;
+                            rule = snapshot.rule;
+                            session.rules.insert(rule.to_string());
+                            seed = snapshot.seed;
+                            rng = snapshot.rng();
+                            history.clear();
+                            cycles.iter_mut().for_each(CycleDetector::reset);
+                            stats = new_stats(&options, &hud);
+                            stats.observe(&stats::GenerationDiff::between(&prev_grid, &grid, generation));
+                            format!("Loaded snapshot from {} at generation {}", options.snapshot_file.display(), generation)
+                        }
+                        Err(e) => format!("Loading the snapshot failed: {}", e),
+                    };
+                    status_message = Some((message, Instant::now()));
+                }
+                None => {}
+            }
+        }
+
+        // Carry out the commands sent over the control socket, between two generations, then
+        // those the script answers its events with.
+        let requests = control.as_ref().map(ControlSocket::pending).unwrap_or_default();
+        for request in requests.into_iter().chain(script.as_mut().map(Script::turn).into_iter().flatten()) {
+            let result = match &request.command {
+                ControlCommand::Pause => {
+                    if !paused {
+                        record_intervention(&mut replay_recorder, generation, Intervention::Pause)?;
+                    }
+                    paused = true;
+                    Ok("paused".to_string())
+                }
+                ControlCommand::Resume => {
+                    if paused {
+                        record_intervention(&mut replay_recorder, generation, Intervention::Resume)?;
+                    }
+                    paused = false;
+                    Ok("resumed".to_string())
+                }
+                ControlCommand::Step { count } => {
+                    if !paused {
+                        record_intervention(&mut replay_recorder, generation, Intervention::Pause)?;
+                    }
+                    paused = true;
+                    for _ in 0..*count {
+                        record_intervention(&mut replay_recorder, generation, Intervention::Step)?;
+                        advance(&mut grid, &rule, &mut generation, &mut stats, &mut history, &mut renderer, &mut session)?;
+                        if let Some(states) = states.as_mut() {
+                            *states = variant.step(states, &grid, &rule, options.boundary);
+                        }
+                    }
+                    Ok(format!("generation {}", generation))
+                }
+                ControlCommand::SetRule { rule: next } => {
+                    rule = *next;
+                    record_intervention(&mut replay_recorder, generation, Intervention::SetRule { rule })?;
+                    session.rules.insert(rule.to_string());
+                    cycles.iter_mut().for_each(CycleDetector::reset);
+                    status_message = Some((format!("Switched to {}", rule), Instant::now()));
+                    Ok(format!("rule {}", rule))
+                }
+                ControlCommand::Settings(settings) => match settings.check(cli::MAX_STEPS_PER_FRAME) {
+                    Ok(()) => {
+                        if settings.paused != paused {
+                            let intervention = if settings.paused { Intervention::Pause } else { Intervention::Resume };
+                            record_intervention(&mut replay_recorder, generation, intervention)?;
+                            paused = settings.paused;
+                        }
+                        if settings.rule != rule {
+                            rule = settings.rule;
+                            record_intervention(&mut replay_recorder, generation, Intervention::SetRule { rule })?;
+                            session.rules.insert(rule.to_string());
+                            cycles.iter_mut().for_each(CycleDetector::reset);
+                        }
+                        options.speed = settings.speed;
+                        tick = Duration::from_secs_f64(1.0 / options.speed);
+                        steps_per_frame = settings.steps_per_frame;
+                        Ok("settings changed".to_string())
+                    }
+                    Err(e) => Err(e),
+                },
+                ControlCommand::Save { path } => {
+                    // The header also names the rule, so other Life tools run the board the same way.
+                    let rle = Pattern::from_rows(grid.clone()).to_rle().replacen('\n', &format!(", rule = {}\n", rule), 1);
+                    std::fs::write(path, rle)
+                        .map(|()| format!("saved generation {} to {}", generation, path.display()))
+                        .map_err(|e| format!("cannot write {}: {}", path.display(), e))
+                }
+                ControlCommand::Inject { x, y, pattern } => {
+                    let pattern = match pattern {
+                        Some(source) => Pattern::load(source).map_err(|e| e.to_string()),
+                        None => Ok(Pattern::from_rows(vec![vec![true]])),
+                    };
+                    match pattern {
+                        Ok(pattern) => {
+                            let before = grid.to_vec();
+                            let report = grid.edit(|board| placement::place_pattern(board, &pattern, *x, *y));
+                            edits.record(Edit::between(&before, &grid), generation);
+                            record_intervention(&mut replay_recorder, generation, Intervention::Place { x: *x, y: *y, cells: pattern.to_rle() })?;
+                            cycles.iter_mut().for_each(CycleDetector::reset);
+                            Ok(format!("placed {} cells ({} clipped)", report.placed, report.clipped))
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+                ControlCommand::Set { x, y, alive } => {
+                    let (width, height) = grid.bounds();
+                    if *x >= width || *y >= height {
+                        Err(format!("({}, {}) is off the {}x{} board", x, y, width, height))
+                    } else {
+                        if grid.get(*x, *y) != *alive {
+                            let before = grid.to_vec();
+                            grid.set(*x, *y, *alive);
+                            edits.record(Edit::between(&before, &grid), generation);
+                            let state = Snapshot::capture(&grid, generation, &rule, seed, &rng);
+                            record_intervention(&mut replay_recorder, generation, Intervention::Restore { state })?;
+                            cycles.iter_mut().for_each(CycleDetector::reset);
+                        }
+                        Ok(format!("({}, {}) is {}", x, y, if *alive { "alive" } else { "dead" }))
+                    }
+                }
+                ControlCommand::Status { .. } => Ok(format!("generation {}", generation)),
+                ControlCommand::Quit => {
+                    clear_screen(&mut term, &options.display)?;
+                    should_exit.store(true, std::sync::atomic::Ordering::Relaxed);
+                    Ok("quitting".to_string())
+                }
+            };
+            let status = matches!(request.command, ControlCommand::Status { .. } | ControlCommand::Settings(_)).then(|| ControlStatus {
+                generation,
+                population: grid.population(),
+                settings: Settings { rule, speed: options.speed, steps_per_frame, paused },
+                cells: matches!(request.command, ControlCommand::Status { cells: true }).then(|| live_cells(&grid)),
+            });
+            request.reply(result, status);
+        }
+
+        if let Some(ended) = script.as_ref().filter(|script| script.ended()) {
+            let message = if ended.timed_out() {
+                format!("The script took longer than {} seconds over a turn and was stopped", script::TURN_TIMEOUT.as_secs())
+            } else {
+                "The script ended".to_string()
+            };
+            status_message = Some((message, Instant::now()));
+            script = None;
+        }
 
         // If the user has requested to exit the program, break out of the loop.
         if should_exit.load(std::sync::atomic::Ordering::Relaxed) {
@@ -230,5 +2204,39 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     }
 
+    // Restore the terminal before printing the summary, once the last frame is on it.
+    renderer.shutdown(&mut term)?;
+    term.flush()?;
+    term.wait()?;
+    drop(raw_mode);
+    drop(screen);
+    println!("Exiting...");
+
+    // Print the exit summary and write the stats file, if requested.
+    println!("{}", stats.summary());
+    if options.generations.is_some() {
+        match cycles.as_ref().and_then(CycleDetector::found) {
+            Some(cycle) => println!("stabilized: yes, {}", cycle),
+            None => println!("stabilized: no"),
+        }
+    }
+    if let Some(path) = &options.stats_file {
+        stats.write_file(path)?;
+    }
+    if !options.no_lifetime_stats {
+        if let Err(e) = lifetime::record(&session) {
+            println!("Failed to update the lifetime statistics: {}", e);
+        }
+    }
+    for path in renderer.finish()? {
+        println!("Recorded to {}", path.display());
+    }
+    if let Some(recorder) = replay_recorder {
+        println!("Recorded the replay to {}", recorder.finish()?.display());
+    }
+    if let Some(path) = term.finish()? {
+        println!("Recorded to {}", path.display());
+    }
+
     Ok(())
 }
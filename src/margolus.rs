@@ -0,0 +1,159 @@
+use std::fmt;
+
+use crate::{automaton::Simulation, raster::Rgb, variant::StateGrid};
+
+/// The color of live cells.
+const PALETTE: [Rgb; 1] = [Rgb(0xff, 0xcc, 0x55)];
+
+/// A block rule of the Margolus neighborhood.
+///
+/// The board is cut into 2×2 blocks, and each block is replaced as a whole according to
+/// the cells in it. The partition shifts by one cell diagonally every generation, so
+/// blocks overlap from one generation to the next. Both rules here are reversible: every
+/// block maps to a different block, so no information is ever lost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MargolusRule {
+    /// Critters: a block with exactly two live cells stays the same, and every other block
+    /// is inverted, with a block of three live cells also turned half a turn.
+    Critters,
+    /// The billiard ball model: a lone live cell moves to the opposite corner of its
+    /// block, two cells on one diagonal bounce onto the other, and every other block stays
+    /// the same.
+    BilliardBalls,
+}
+
+/// The weights of the cells of a block: top left, top right, bottom left, and bottom right.
+const BLOCK: [(usize, usize, u8); 4] = [(0, 0, 1), (1, 0, 2), (0, 1, 4), (1, 1, 8)];
+
+impl MargolusRule {
+    /// Parses a block rule name: `critters` or `bbm` (also `billiard-balls`).
+    pub fn parse(name: &str) -> Option<MargolusRule> {
+        match name {
+            "critters" => Some(MargolusRule::Critters),
+            "bbm" | "billiard-balls" => Some(MargolusRule::BilliardBalls),
+            _ => None,
+        }
+    }
+
+    /// Computes the next contents of a block.
+    ///
+    /// # Arguments
+    ///
+    /// * `block` - The live cells of the block, weighted as in [`BLOCK`].
+    ///
+    /// # Returns
+    ///
+    /// The live cells of the block in the next generation.
+    pub fn apply(&self, block: u8) -> u8 {
+        // Turning a block half a turn swaps opposite corners.
+        let half_turn = |block: u8| (block & 1) << 3 | (block & 2) << 1 | (block & 4) >> 1 | (block & 8) >> 3;
+        match self {
+            MargolusRule::Critters => match block.count_ones() {
+                2 => block,
+                3 => half_turn(!block & 0xf),
+                _ => !block & 0xf,
+            },
+            MargolusRule::BilliardBalls => match block {
+                1 | 2 | 4 | 8 => half_turn(block),
+                0b1001 => 0b0110,
+                0b0110 => 0b1001,
+                _ => block,
+            },
+        }
+    }
+
+    /// Returns whether every generation of the rule inverts the empty background.
+    ///
+    /// Critters turns an empty block into a full one and back, so odd generations are
+    /// drawn inverted to keep the background still.
+    pub fn inverts_background(&self) -> bool {
+        self.apply(0) == 0xf
+    }
+}
+
+impl fmt::Display for MargolusRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MargolusRule::Critters => "critters",
+            MargolusRule::BilliardBalls => "bbm",
+        })
+    }
+}
+
+/// A block cellular automaton on the Margolus neighborhood, on a wrap-around board.
+///
+/// The blocks of even generations start at even rows and columns, and those of odd
+/// generations one cell down and to the right, wrapping around the edges; the board is
+/// trimmed to an even width and height so the blocks tile it.
+pub struct Margolus {
+    /// The block rule.
+    pub rule: MargolusRule,
+    /// Whether each cell is alive, as the rule sees it.
+    pub cells: Vec<Vec<bool>>,
+    /// The number of generations computed, whose parity picks the partition.
+    pub generation: u64,
+}
+
+impl Margolus {
+    /// Creates a board from a Life board.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The block rule.
+    /// * `grid` - The board, with random cells and placed patterns.
+    pub fn new(rule: MargolusRule, grid: &[Vec<bool>]) -> Margolus {
+        let height = (grid.len() & !1).max(2);
+        let width = (grid.first().map_or(0, |row| row.len()) & !1).max(2);
+        let cells = (0..height)
+            .map(|y| (0..width).map(|x| grid.get(y).and_then(|row| row.get(x)).copied().unwrap_or(false)).collect())
+            .collect();
+        Margolus { rule, cells, generation: 0 }
+    }
+
+    /// Returns whether the cells are drawn inverted this generation; see
+    /// [`MargolusRule::inverts_background`].
+    fn inverted(&self) -> bool {
+        self.rule.inverts_background() && self.generation % 2 == 1
+    }
+}
+
+impl Simulation for Margolus {
+    fn view(&self) -> StateGrid {
+        let inverted = self.inverted();
+        self.cells.iter().map(|row| row.iter().map(|&cell| u8::from(cell != inverted)).collect()).collect()
+    }
+
+    fn palette(&self) -> &[Rgb] {
+        &PALETTE
+    }
+
+    fn status(&self) -> String {
+        let inverted = self.inverted();
+        let population = self.cells.iter().flatten().filter(|&&cell| cell != inverted).count();
+        format!("{} | pop {}", self.rule, population)
+    }
+
+    fn step(&mut self) {
+        let height = self.cells.len();
+        let width = self.cells[0].len();
+        let offset = (self.generation % 2) as usize;
+
+        for top in (0..height).step_by(2) {
+            for left in (0..width).step_by(2) {
+                // The cells of the block, wrapping around the edges on odd generations.
+                let at = |dx: usize, dy: usize| ((left + offset + dx) % width, (top + offset + dy) % height);
+                let block = BLOCK.iter().fold(0, |block, &(dx, dy, weight)| {
+                    let (x, y) = at(dx, dy);
+                    block | if self.cells[y][x] { weight } else { 0 }
+                });
+
+                let next = self.rule.apply(block);
+                for &(dx, dy, weight) in &BLOCK {
+                    let (x, y) = at(dx, dy);
+                    self.cells[y][x] = next & weight != 0;
+                }
+            }
+        }
+        self.generation += 1;
+    }
+}
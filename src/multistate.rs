@@ -0,0 +1,83 @@
+use std::{error::Error, io::Write, thread, time::Duration};
+
+use gameoflife::automaton::Simulation;
+
+use crate::{
+    display::{clear_screen, display_grid, display_status, CellStates, DisplayStyle, Region, Screen, Terminal},
+    input::{self, Command, Input},
+};
+
+/// Runs a simulation other than Life in the terminal until the user quits.
+///
+/// Like composite scenes, these simulations run in a small loop of their own: the board
+/// is drawn in the colors of its states, and quitting, pausing, and stepping forward
+/// while paused are supported.
+///
+/// # Arguments
+///
+/// * `simulation` - The simulation, such as a cellular automaton or Langton's ant.
+/// * `speed` - The number of generations per second.
+/// * `style` - The render mode and glyphs to draw with.
+/// * `cols` - The number of terminal columns available for the board.
+/// * `rows` - The number of terminal rows available for the board; the status bar goes below.
+pub fn run(simulation: &mut dyn Simulation, speed: f64, style: &DisplayStyle, cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+    let mut term = Terminal::new(None);
+    let screen = Screen::enter()?;
+    let raw_mode = input::RawMode::enable().ok();
+    clear_screen(&mut term, style)?;
+
+    let mut generation: u64 = 0;
+    let mut paused = false;
+    let mut prev_grid = Vec::new();
+    let mut prev_cells = Vec::new();
+    let mut running = true;
+
+    while running {
+        let cells = simulation.view();
+        let grid: Vec<Vec<bool>> = cells.iter().map(|row| row.iter().map(|&state| state != 0).collect()).collect();
+        let states = CellStates {
+            states: &cells,
+            prev_states: &prev_cells,
+            palette: simulation.palette(),
+        };
+        display_grid(&mut term, &grid, &prev_grid, style, Some(&states), Region::screen(cols, rows))?;
+        prev_grid = grid;
+        prev_cells = cells;
+
+        let status = format!("gen {} | {}{}", generation, simulation.status(), if paused { " [paused]" } else { "" });
+        display_status(&mut term, &status, rows, cols, style)?;
+        term.flush()?;
+
+        if !paused {
+            simulation.step();
+            generation += 1;
+        }
+
+        let wait = Duration::from_secs_f64(1.0 / speed);
+        let inputs = if raw_mode.is_some() {
+            input::wait_for_input(wait)?
+        } else {
+            thread::sleep(wait);
+            Vec::new()
+        };
+        for input in inputs {
+            if let Input::Key(key) = input {
+                match input::map_key(&key) {
+                    Some(Command::Quit) => running = false,
+                    Some(Command::TogglePause) => paused = !paused,
+                    Some(Command::StepForward) if paused => {
+                        simulation.step();
+                        generation += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    drop(raw_mode);
+    drop(screen);
+    println!("Exiting...");
+    println!("gen: {}", generation);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+/// The stream of the seed's generator that noise draws from, apart from the one that
+/// fills the board, so adding noise leaves the starting board of a seed unchanged.
+const NOISE_STREAM: u64 = 1;
+
+/// Random flips of single cells between generations, as spontaneous births and deaths.
+///
+/// Every cell flips independently with the same small probability each generation,
+/// which keeps a board that has settled into still lifes and oscillators from freezing.
+pub struct Noise {
+    /// The probability that a cell flips each generation.
+    pub probability: f64,
+    /// The generator picking the cells to flip.
+    rng: ChaCha12Rng,
+}
+
+impl Noise {
+    /// Creates the noise of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `probability` - The probability that a cell flips each generation, from 0 to 1.
+    /// * `seed` - The seed of the run; the same seed flips the same cells.
+    pub fn new(probability: f64, seed: u64) -> Noise {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        rng.set_stream(NOISE_STREAM);
+        Noise { probability, rng }
+    }
+
+    /// Picks the cells to flip this generation.
+    ///
+    /// Rather than rolling for every cell, the gaps between flipped cells are drawn from
+    /// a geometric distribution, so a low probability costs little on a large board.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board.
+    /// * `height` - The height of the board.
+    ///
+    /// # Returns
+    ///
+    /// The coordinates of the cells to flip, in row order.
+    pub fn flips(&mut self, width: usize, height: usize) -> Vec<(usize, usize)> {
+        let cells = width * height;
+        if self.probability <= 0.0 || cells == 0 {
+            return Vec::new();
+        }
+        if self.probability >= 1.0 {
+            return (0..cells).map(|i| (i % width, i / width)).collect();
+        }
+
+        let mut flips = Vec::new();
+        let scale = (1.0 - self.probability).ln();
+        let mut i = 0;
+        loop {
+            let gap = ((1.0 - self.rng.gen::<f64>()).ln() / scale).floor();
+            if gap >= (cells - i) as f64 {
+                return flips;
+            }
+            i += gap as usize;
+            flips.push((i % width, i / width));
+            i += 1;
+        }
+    }
+
+    /// Flips random cells of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board to disturb.
+    pub fn apply(&mut self, grid: &mut [Vec<bool>]) {
+        let width = grid.first().map_or(0, |row| row.len());
+        for (x, y) in self.flips(width, grid.len()) {
+            grid[y][x] = !grid[y][x];
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+
+use gameoflife::{brush::Brush, Grid};
+
+/// What the main loop should do after the painter handled a mouse event.
+pub enum PaintAction {
+    /// Nothing changed.
+    None,
+    /// Cells of the board were painted or erased.
+    Painted,
+    /// The button was let go, ending a stroke.
+    Finished {
+        /// The board before the stroke.
+        before: Grid,
+        /// The generation the stroke started on.
+        generation: u64,
+    },
+    /// The scroll wheel picked another brush.
+    Brush(Brush),
+}
+
+/// A drag of the mouse with a button held, from the press to the release.
+struct Stroke {
+    /// The board before the stroke.
+    before: Grid,
+    /// The generation the stroke started on.
+    generation: u64,
+    /// The cell the brush was last painted at, if the mouse was over the board.
+    last: Option<(isize, isize)>,
+    /// Whether the stroke brings cells to life or erases them.
+    alive: bool,
+}
+
+/// Paints the board by dragging the mouse over it.
+///
+/// The left button brings cells to life, and erases them with Ctrl, Alt, or Shift held
+/// or with the right button. The scroll wheel makes the brush larger or smaller.
+pub struct Painter {
+    /// The brush painted with.
+    brush: Brush,
+    /// The stroke being painted, while a button is held.
+    stroke: Option<Stroke>,
+}
+
+impl Painter {
+    /// Creates a painter.
+    ///
+    /// # Arguments
+    ///
+    /// * `brush` - The brush to start with.
+    pub fn new(brush: Brush) -> Painter {
+        Painter { brush, stroke: None }
+    }
+
+    /// Handles a mouse event.
+    ///
+    /// # Arguments
+    ///
+    /// * `mouse` - The mouse event.
+    /// * `cell` - The cell of the board under the mouse, or `None` if it is off the board.
+    /// * `grid` - The board to paint on.
+    /// * `generation` - The generation of the board.
+    ///
+    /// # Returns
+    ///
+    /// What the main loop should do next.
+    pub fn handle_mouse(&mut self, mouse: &MouseEvent, cell: Option<(isize, isize)>, grid: &mut [Vec<bool>], generation: u64) -> PaintAction {
+        match mouse.kind {
+            MouseEventKind::Down(button @ (MouseButton::Left | MouseButton::Right)) => {
+                let erase = button == MouseButton::Right || mouse.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+                let mut stroke = Stroke { before: grid.to_vec(), generation, last: None, alive: !erase };
+                let painted = self.paint(&mut stroke, cell, grid);
+                self.stroke = Some(stroke);
+                painted
+            }
+            MouseEventKind::Drag(_) => match self.stroke.take() {
+                Some(mut stroke) => {
+                    let painted = self.paint(&mut stroke, cell, grid);
+                    self.stroke = Some(stroke);
+                    painted
+                }
+                None => PaintAction::None,
+            },
+            MouseEventKind::Up(_) => match self.stroke.take() {
+                Some(Stroke { before, generation, .. }) => PaintAction::Finished { before, generation },
+                None => PaintAction::None,
+            },
+            MouseEventKind::ScrollUp => {
+                self.brush = self.brush.larger();
+                PaintAction::Brush(self.brush)
+            }
+            MouseEventKind::ScrollDown => {
+                self.brush = self.brush.smaller();
+                PaintAction::Brush(self.brush)
+            }
+            _ => PaintAction::None,
+        }
+    }
+
+    /// Paints the brush along the line from where a stroke last was to the cell under the
+    /// mouse.
+    ///
+    /// # Arguments
+    ///
+    /// * `stroke` - The stroke being painted.
+    /// * `cell` - The cell under the mouse, or `None` if it is off the board.
+    /// * `grid` - The board to paint on.
+    fn paint(&self, stroke: &mut Stroke, cell: Option<(isize, isize)>, grid: &mut [Vec<bool>]) -> PaintAction {
+        let Some(cell) = cell else {
+            // The line starts over where the mouse comes back onto the board.
+            stroke.last = None;
+            return PaintAction::None;
+        };
+        let changed = self.brush.stroke(grid, stroke.last.unwrap_or(cell), cell, stroke.alive);
+        stroke.last = Some(cell);
+        if changed > 0 { PaintAction::Painted } else { PaintAction::None }
+    }
+}
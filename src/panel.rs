@@ -0,0 +1,182 @@
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    style::{Attribute, Print, SetAttribute},
+    QueueableCommand,
+};
+use std::{error::Error, io::Write};
+
+use gameoflife::{library, pattern::Pattern, rule::Rule};
+
+/// The width of the control panel in characters, including the border on its left.
+pub const PANEL_WIDTH: usize = 26;
+
+/// The rules the panel switches between, as (name, rulestring) pairs.
+const RULES: [(&str, &str); 6] = [
+    ("Life", "B3/S23"),
+    ("HighLife", "B36/S23"),
+    ("Seeds", "B2/S"),
+    ("Day & Night", "B3678/S34678"),
+    ("Morley", "B368/S245"),
+    ("Life without death", "B3/S012345678"),
+];
+
+/// The keys listed in the panel's help, as (keys, action) pairs.
+const KEYS: [(&str, &str); 17] = [
+    ("Space", "pause"),
+    ("← → , .", "step back, forth"),
+    ("n", "next rule"),
+    ("R", "type a rule"),
+    ("↑ ↓", "pick a pattern"),
+    ("Enter", "place it"),
+    ("e", "RLE editor"),
+    ("p", "paste"),
+    ("t", "pick a pattern"),
+    ("u ^R", "undo, redo"),
+    ("s F12", "screenshot"),
+    ("F5 F9", "save, load"),
+    ("f b g", "timings, bars"),
+    ("[ ]", "gens per frame"),
+    ("i", "name objects"),
+    ("?", "all keys"),
+    ("q", "quit"),
+];
+
+/// What the main loop should do after the panel handled a key.
+pub enum PanelAction {
+    /// Redraw the panel with another pattern selected.
+    Select,
+    /// Switch to another rule.
+    SetRule(Rule),
+    /// Stamp a pattern in the middle of the board.
+    Place(Pattern),
+}
+
+/// What the panel shows about the running simulation.
+pub struct PanelInfo<'a> {
+    /// The name and value of every statistic.
+    pub stats: &'a [(String, String)],
+    /// The current rule.
+    pub rule: &'a Rule,
+    /// Whether the simulation is paused.
+    pub paused: bool,
+}
+
+/// A control panel beside the board, with the statistics, the rule, the pattern library,
+/// and the keys.
+///
+/// Patterns are picked from the library with the arrow keys and placed with Enter, and
+/// `n` cycles through a few well-known rules.
+pub struct Panel {
+    /// The selected pattern of the library.
+    pattern: usize,
+    /// The rule of [`RULES`] switched to last.
+    rule: usize,
+}
+
+impl Panel {
+    /// Creates a panel with the first pattern selected.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule the simulation starts with, so `n` moves on from it.
+    pub fn new(rule: &Rule) -> Panel {
+        let rule = RULES.iter().position(|&(_, rulestring)| rulestring == rule.to_string()).unwrap_or(RULES.len() - 1);
+        Panel { pattern: 0, rule }
+    }
+
+    /// Handles a key press meant for the panel.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key event.
+    ///
+    /// # Returns
+    ///
+    /// What the main loop should do, or `None` if the key is not one of the panel's.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Option<PanelAction> {
+        let patterns = library::names().count();
+        match key.code {
+            KeyCode::Up => self.pattern = (self.pattern + patterns - 1) % patterns,
+            KeyCode::Down => self.pattern = (self.pattern + 1) % patterns,
+            KeyCode::Enter => {
+                let name = library::names().nth(self.pattern).expect("the selection is in the library");
+                let pattern = library::get(name).expect("library names have patterns");
+                return Some(PanelAction::Place(pattern));
+            }
+            KeyCode::Char('n') => {
+                self.rule = (self.rule + 1) % RULES.len();
+                let rule = Rule::parse(RULES[self.rule].1).expect("panel rules are valid");
+                return Some(PanelAction::SetRule(rule));
+            }
+            _ => return None,
+        }
+        Some(PanelAction::Select)
+    }
+
+    /// Returns the name of the selected pattern.
+    pub fn selected_pattern(&self) -> &'static str {
+        library::names().nth(self.pattern).expect("the selection is in the library")
+    }
+
+    /// Draws the panel.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `left` - The terminal column the panel starts at.
+    /// * `rows` - The number of terminal rows the panel covers.
+    /// * `info` - The state of the simulation to show.
+    pub fn draw(&self, out: &mut impl Write, left: usize, rows: usize, info: &PanelInfo) -> Result<(), Box<dyn Error>> {
+        // The lines of the panel, with whether each is highlighted.
+        let mut lines: Vec<(String, bool)> = Vec::new();
+        let heading = |lines: &mut Vec<(String, bool)>, text: &str| {
+            if !lines.is_empty() {
+                lines.push((String::new(), false));
+            }
+            lines.push((text.to_string(), false));
+        };
+
+        heading(&mut lines, if info.paused { "Stats [paused]" } else { "Stats" });
+        for (name, value) in info.stats {
+            lines.push((format!(" {:<5} {}", name, value), false));
+        }
+
+        heading(&mut lines, "Rule");
+        let name = RULES.iter().find(|&&(_, rulestring)| rulestring == info.rule.to_string()).map(|&(name, _)| name);
+        lines.push((format!(" {}", info.rule), false));
+        if let Some(name) = name {
+            lines.push((format!(" {}", name), false));
+        }
+
+        heading(&mut lines, "Keys");
+        for (keys, action) in KEYS {
+            lines.push((format!(" {:<8}{}", keys, action), false));
+        }
+
+        // The library takes the rows left over, scrolled to keep the selection in view.
+        heading(&mut lines, "Patterns");
+        let shown = rows.saturating_sub(lines.len()).max(1);
+        let first = self.pattern.saturating_sub(shown - 1);
+        for (i, name) in library::names().enumerate().skip(first).take(shown) {
+            let selected = i == self.pattern;
+            lines.push((format!("{}{}", if selected { " > " } else { "   " }, name), selected));
+        }
+
+        let inner_width = PANEL_WIDTH - 1;
+        for row in 0..rows {
+            let (text, highlighted) = lines.get(row).cloned().unwrap_or_default();
+            let text: String = text.chars().chain(std::iter::repeat(' ')).take(inner_width).collect();
+            out.queue(cursor::MoveTo(left as u16, row as u16))?;
+            out.queue(Print("│"))?;
+            if highlighted {
+                out.queue(SetAttribute(Attribute::Reverse))?;
+                out.queue(Print(text))?;
+                out.queue(SetAttribute(Attribute::NoReverse))?;
+            } else {
+                out.queue(Print(text))?;
+            }
+        }
+        Ok(())
+    }
+}
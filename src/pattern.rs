@@ -0,0 +1,550 @@
+use std::{error::Error, fmt, fs, path::Path};
+
+use crate::library;
+
+/// A rectangular pattern of live and dead cells.
+///
+/// Patterns are loaded from files (RLE or plaintext) and are independent of any
+/// grid; they only become part of the board once they are placed.
+#[derive(Clone, Debug)]
+pub struct Pattern {
+    /// The width of the pattern's bounding box.
+    pub width: usize,
+    /// The height of the pattern's bounding box.
+    pub height: usize,
+    /// The cells of the pattern, stored row by row.
+    pub cells: Vec<Vec<bool>>,
+}
+
+impl Pattern {
+    /// Creates a pattern from rows of cells, padding short rows with dead cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows` - The rows of the pattern.
+    ///
+    /// # Returns
+    ///
+    /// The pattern.
+    pub fn from_rows(mut rows: Vec<Vec<bool>>) -> Pattern {
+        let width = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        for row in rows.iter_mut() {
+            row.resize(width, false);
+        }
+
+        Pattern {
+            width,
+            height: rows.len(),
+            cells: rows,
+        }
+    }
+
+    /// Loads a pattern from a file.
+    ///
+    /// Files ending in `.rle` are parsed as run-length encoded patterns; anything
+    /// else is sniffed for an RLE header and otherwise treated as plaintext.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the pattern file.
+    ///
+    /// # Returns
+    ///
+    /// The loaded pattern.
+    pub fn from_file(path: &Path) -> Result<Pattern, Box<dyn Error>> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read pattern file {}: {}", path.display(), e))?;
+
+        let is_rle = path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("rle"))
+            .unwrap_or(false)
+            || looks_like_rle(&text);
+
+        let pattern = if is_rle {
+            parse_rle(&text)
+        } else {
+            parse_plaintext(&text)
+        };
+
+        pattern.map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// Loads a pattern by file path or built-in library name.
+    ///
+    /// Existing files take precedence, so a file named like a library pattern can
+    /// still be loaded.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - A pattern file path or the name of a built-in pattern.
+    ///
+    /// # Returns
+    ///
+    /// The loaded pattern.
+    pub fn load(source: &str) -> Result<Pattern, Box<dyn Error>> {
+        let path = Path::new(source);
+        if path.exists() {
+            return Pattern::from_file(path);
+        }
+
+        library::get(source).ok_or_else(|| {
+            format!(
+                "No pattern file or built-in pattern named '{}' (built-in patterns: {})",
+                source,
+                library::names().collect::<Vec<_>>().join(", ")
+            )
+            .into()
+        })
+    }
+
+    /// Returns a transformed copy of the pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `transform` - The rotation or reflection to apply.
+    ///
+    /// # Returns
+    ///
+    /// The transformed pattern.
+    pub fn transformed(&self, transform: Transform) -> Pattern {
+        let (w, h) = (self.width, self.height);
+
+        // Map each cell of the new pattern back to the cell of the original it comes from.
+        let (new_w, new_h) = match transform {
+            Transform::Rot90 | Transform::Rot270 => (h, w),
+            Transform::Rot180 | Transform::FlipH | Transform::FlipV => (w, h),
+        };
+        let source = |x: usize, y: usize| -> (usize, usize) {
+            match transform {
+                Transform::Rot90 => (y, h - 1 - x),
+                Transform::Rot180 => (w - 1 - x, h - 1 - y),
+                Transform::Rot270 => (w - 1 - y, x),
+                Transform::FlipH => (w - 1 - x, y),
+                Transform::FlipV => (x, h - 1 - y),
+            }
+        };
+
+        let cells = (0..new_h)
+            .map(|y| {
+                (0..new_w)
+                    .map(|x| {
+                        let (sx, sy) = source(x, y);
+                        self.cells[sy][sx]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Pattern {
+            width: new_w,
+            height: new_h,
+            cells,
+        }
+    }
+
+    /// Encodes the pattern in run-length encoded (RLE) format.
+    ///
+    /// Trailing dead cells of each row and trailing empty rows are left out, as usual
+    /// for RLE, and the data is wrapped at 70 characters per line.
+    ///
+    /// # Returns
+    ///
+    /// The RLE text, including the `x = .., y = ..` header.
+    pub fn to_rle(&self) -> String {
+        let run = |count: usize, tag: char| if count == 1 { tag.to_string() } else { format!("{}{}", count, tag) };
+
+        let mut tokens = Vec::new();
+        let mut row_ends = 0;
+        for row in &self.cells {
+            let end = row.iter().rposition(|&cell| cell).map_or(0, |i| i + 1);
+            if end > 0 {
+                // Emit the row separators only once there is another row with live cells.
+                if row_ends > 0 {
+                    tokens.push(run(row_ends, '$'));
+                    row_ends = 0;
+                }
+
+                let mut x = 0;
+                while x < end {
+                    let alive = row[x];
+                    let count = row[x..end].iter().take_while(|&&cell| cell == alive).count();
+                    tokens.push(run(count, if alive { 'o' } else { 'b' }));
+                    x += count;
+                }
+            }
+            row_ends += 1;
+        }
+        tokens.push("!".to_string());
+
+        let mut rle = format!("x = {}, y = {}\n", self.width, self.height);
+        let mut line_len = 0;
+        for token in tokens {
+            if line_len + token.len() > 70 {
+                rle.push('\n');
+                line_len = 0;
+            }
+            line_len += token.len();
+            rle.push_str(&token);
+        }
+        rle.push('\n');
+        rle
+    }
+}
+
+/// A rotation or reflection applied to a pattern when it is placed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    /// Rotate 90 degrees clockwise.
+    Rot90,
+    /// Rotate 180 degrees.
+    Rot180,
+    /// Rotate 270 degrees clockwise (90 degrees counterclockwise).
+    Rot270,
+    /// Mirror left to right.
+    FlipH,
+    /// Mirror top to bottom.
+    FlipV,
+}
+
+impl Transform {
+    /// Parses a transform modifier (`rot90`, `rot180`, `rot270`, `fliph` or `flipv`).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The modifier name.
+    ///
+    /// # Returns
+    ///
+    /// The transform, or `None` if the name is not a known modifier.
+    pub fn parse(name: &str) -> Option<Transform> {
+        match name.to_ascii_lowercase().as_str() {
+            "rot90" => Some(Transform::Rot90),
+            "rot180" => Some(Transform::Rot180),
+            "rot270" => Some(Transform::Rot270),
+            "fliph" => Some(Transform::FlipH),
+            "flipv" => Some(Transform::FlipV),
+            _ => None,
+        }
+    }
+}
+
+/// An error found while parsing a pattern, with the line it was found on.
+#[derive(Debug)]
+pub struct ParseError {
+    /// The 1-based line number of the error.
+    pub line: usize,
+    /// A description of the error.
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// The most cells an RLE pattern may span across or down, so that a run count typed or
+/// fetched by mistake is reported rather than exhausting memory.
+pub const MAX_PATTERN_SIZE: usize = 8192;
+
+/// Reads the count of an RLE run, 1 if it has none, checking that the run keeps the
+/// pattern within a limit.
+///
+/// # Arguments
+///
+/// * `run` - The digits before the run's tag, possibly none.
+/// * `line` - The 1-based line number the run is on.
+/// * `filled` - The cells of the row, or the rows, the pattern already spans.
+/// * `limit` - The most cells across, or rows down, the pattern may span.
+/// * `direction` - `across` for cells of a row or `down` for rows, for the message.
+///
+/// # Returns
+///
+/// The number of cells or rows in the run.
+fn run_count(run: &str, line: usize, filled: usize, limit: usize, direction: &str) -> Result<usize, ParseError> {
+    let count = if run.is_empty() { Ok(1) } else { run.parse::<usize>() };
+    match count {
+        Ok(count) if count <= limit.saturating_sub(filled) => Ok(count),
+        _ => Err(ParseError {
+            line,
+            message: format!("run count {} makes the pattern more than {} cells {}", run, limit, direction),
+        }),
+    }
+}
+
+/// Checks whether a text contains an RLE header line (`x = ..., y = ...`).
+pub(crate) fn looks_like_rle(text: &str) -> bool {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.replace(' ', "").starts_with("x="))
+        .unwrap_or(false)
+}
+
+/// Parses a pattern in either format, as RLE if it has an RLE header and as
+/// plaintext otherwise.
+///
+/// # Arguments
+///
+/// * `text` - The pattern text.
+///
+/// # Returns
+///
+/// The parsed pattern.
+pub fn parse(text: &str) -> Result<Pattern, ParseError> {
+    if looks_like_rle(text) {
+        parse_rle(text)
+    } else {
+        parse_plaintext(text)
+    }
+}
+
+/// Parses a pattern in run-length encoded (RLE) format, at most [`MAX_PATTERN_SIZE`] cells
+/// across and down.
+///
+/// # Arguments
+///
+/// * `text` - The contents of the RLE file.
+///
+/// # Returns
+///
+/// The parsed pattern.
+pub fn parse_rle(text: &str) -> Result<Pattern, ParseError> {
+    parse_rle_within(text, MAX_PATTERN_SIZE, MAX_PATTERN_SIZE)
+}
+
+/// Parses a pattern in run-length encoded (RLE) format that must fit a board, such as the
+/// cells of a saved or recorded board, which may be larger than [`MAX_PATTERN_SIZE`].
+///
+/// # Arguments
+///
+/// * `text` - The RLE cells.
+/// * `width` - The most cells the pattern may span across.
+/// * `height` - The most rows the pattern may span down.
+///
+/// # Returns
+///
+/// The parsed pattern.
+pub fn parse_rle_within(text: &str, width: usize, height: usize) -> Result<Pattern, ParseError> {
+    let mut rows: Vec<Vec<bool>> = vec![Vec::new()];
+    let mut run = String::new();
+    let mut seen_header = false;
+
+    'lines: for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        // Skip blank lines and comments.
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // The header only describes the bounding box, which is recomputed from the cells.
+        if !seen_header && line.replace(' ', "").starts_with("x=") {
+            seen_header = true;
+            continue;
+        }
+
+        for ch in line.chars() {
+            match ch {
+                '0'..='9' => run.push(ch),
+                'b' | '.' | 'o' | 'A'..='Z' | '$' => {
+                    let count = if ch == '$' {
+                        run_count(&run, number + 1, rows.len(), height, "down")?
+                    } else {
+                        run_count(&run, number + 1, rows.last().map_or(0, Vec::len), width, "across")?
+                    };
+                    run.clear();
+
+                    match ch {
+                        '$' => {
+                            for _ in 0..count {
+                                rows.push(Vec::new());
+                            }
+                        }
+                        _ => {
+                            let alive = ch != 'b' && ch != '.';
+                            let row = rows.last_mut().expect("rows is never empty");
+                            row.extend(std::iter::repeat_n(alive, count));
+                        }
+                    }
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => {}
+                c => {
+                    return Err(ParseError {
+                        line: number + 1,
+                        message: format!("unexpected character '{}' in RLE data", c),
+                    });
+                }
+            }
+        }
+    }
+
+    // Drop trailing empty rows produced by a final `$`.
+    while rows.len() > 1 && rows.last().map(|row| row.is_empty()).unwrap_or(false) {
+        rows.pop();
+    }
+
+    Ok(Pattern::from_rows(rows))
+}
+
+/// Parses a pattern in plaintext format (`.` for dead cells, `O`, `*` or `#` for live cells).
+///
+/// # Arguments
+///
+/// * `text` - The contents of the plaintext file.
+///
+/// # Returns
+///
+/// The parsed pattern.
+pub fn parse_plaintext(text: &str) -> Result<Pattern, ParseError> {
+    let mut rows = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        // Lines starting with `!` are comments in the `.cells` format.
+        if line.starts_with('!') {
+            continue;
+        }
+
+        let mut row = Vec::new();
+        for ch in line.trim_end().chars() {
+            match ch {
+                '.' | ' ' => row.push(false),
+                'O' | 'o' | '*' | '#' => row.push(true),
+                c => {
+                    return Err(ParseError {
+                        line: number + 1,
+                        message: format!("unexpected character '{}' in plaintext pattern", c),
+                    });
+                }
+            }
+        }
+        rows.push(row);
+    }
+
+    Ok(Pattern::from_rows(rows))
+}
+
+/// Parses a multi-state pattern in Golly's extended RLE format.
+///
+/// State 0 is written `.` (or `b`), and states 1 to 24 are written `A` to `X`; `o` is
+/// accepted for state 1, as in two-state RLE.
+///
+/// # Arguments
+///
+/// * `text` - The contents of the RLE file.
+///
+/// # Returns
+///
+/// The state of each cell, row by row, with the rows padded to the same width.
+pub fn parse_multistate_rle(text: &str) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut rows: Vec<Vec<u8>> = vec![Vec::new()];
+    let mut run = String::new();
+    let mut seen_header = false;
+
+    'lines: for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+
+        // Skip blank lines, comments, and the header.
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !seen_header && line.replace(' ', "").starts_with("x=") {
+            seen_header = true;
+            continue;
+        }
+
+        for ch in line.chars() {
+            let state = match ch {
+                '0'..='9' => {
+                    run.push(ch);
+                    continue;
+                }
+                '!' => break 'lines,
+                c if c.is_whitespace() => continue,
+                '.' | 'b' => Some(0),
+                'o' => Some(1),
+                'A'..='X' => Some(ch as u8 - b'A' + 1),
+                '$' => None,
+                c => {
+                    return Err(ParseError {
+                        line: number + 1,
+                        message: format!("unexpected character '{}' in RLE data", c),
+                    });
+                }
+            };
+
+            let count = match state {
+                Some(_) => run_count(&run, number + 1, rows.last().map_or(0, Vec::len), MAX_PATTERN_SIZE, "across")?,
+                None => run_count(&run, number + 1, rows.len(), MAX_PATTERN_SIZE, "down")?,
+            };
+            run.clear();
+
+            match state {
+                Some(state) => rows.last_mut().expect("rows is never empty").extend(std::iter::repeat_n(state, count)),
+                None => rows.extend(std::iter::repeat_n(Vec::new(), count)),
+            }
+        }
+    }
+
+    Ok(pad_rows(rows))
+}
+
+/// Parses a multi-state pattern drawn as text, one character per cell.
+///
+/// `.` and spaces are state 0; every other state is drawn with its own character.
+///
+/// # Arguments
+///
+/// * `text` - The contents of the text file.
+/// * `glyphs` - The character of each state from 1, in order.
+///
+/// # Returns
+///
+/// The state of each cell, row by row, with the rows padded to the same width.
+pub fn parse_multistate_plaintext(text: &str, glyphs: &[char]) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut rows = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        // Lines starting with `!` are comments, as in the `.cells` format.
+        if line.starts_with('!') {
+            continue;
+        }
+
+        let mut row = Vec::new();
+        for ch in line.trim_end().chars() {
+            match ch {
+                '.' | ' ' => row.push(0),
+                c => match glyphs.iter().position(|&glyph| glyph == c) {
+                    Some(index) => row.push(index as u8 + 1),
+                    None => {
+                        return Err(ParseError {
+                            line: number + 1,
+                            message: format!(
+                                "unexpected character '{}' (expected '.', ' ', or one of '{}')",
+                                c,
+                                glyphs.iter().collect::<String>()
+                            ),
+                        });
+                    }
+                },
+            }
+        }
+        rows.push(row);
+    }
+
+    Ok(pad_rows(rows))
+}
+
+/// Drops trailing empty rows and pads the others to the width of the widest.
+fn pad_rows(mut rows: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    rows.iter_mut().for_each(|row| row.resize(width, 0));
+    rows
+}
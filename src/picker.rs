@@ -0,0 +1,281 @@
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    QueueableCommand,
+};
+use std::{error::Error, fs, io::Write, path::Path};
+
+use gameoflife::{
+    library,
+    pattern::{Pattern, Transform},
+    placement,
+};
+
+use crate::display::{self, DisplayStyle, Region, RenderMode};
+
+/// The width of the list of patterns, including its frame.
+const LIST_WIDTH: usize = 32;
+
+/// How far the preview moves with a capital `H`, `J`, `K`, or `L`, in cells.
+const BIG_MOVE: isize = 8;
+
+/// The color the preview of the pattern is drawn in.
+const PREVIEW_COLOR: Color = Color::Yellow;
+
+/// What the main loop should do after the picker handled a key.
+pub enum PickerAction {
+    /// Keep the picker open.
+    Continue,
+    /// Close the picker and stamp a pattern with its top-left corner at a cell.
+    Stamp {
+        /// The pattern, as rotated in the picker.
+        pattern: Pattern,
+        /// The column of the top-left corner.
+        x: isize,
+        /// The row of the top-left corner.
+        y: isize,
+    },
+    /// Close the picker without changing the board.
+    Cancel,
+}
+
+/// An overlay for picking a pattern and stamping it on the paused board.
+///
+/// The list holds the built-in library followed by the patterns of the user's folder. The
+/// picked pattern is previewed around a cursor on the board, which is moved with `h`, `j`,
+/// `k`, and `l` (eight cells at a time with capitals), and `r` turns it a quarter clockwise.
+pub struct Picker {
+    /// The names and patterns to pick from.
+    entries: Vec<(String, Pattern)>,
+    /// The entry picked.
+    selected: usize,
+    /// The number of quarter turns clockwise the pattern is rotated by.
+    turns: u8,
+    /// The cell the middle of the pattern goes on.
+    cursor: (isize, isize),
+    /// The number of columns and rows of the board.
+    board: (usize, usize),
+}
+
+impl Picker {
+    /// Creates a picker with the cursor in the middle of the board.
+    ///
+    /// Files in the folder that are not patterns are left out of the list.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The folder of the user's patterns, if there is one.
+    /// * `width` - The number of columns of the board.
+    /// * `height` - The number of rows of the board.
+    ///
+    /// # Returns
+    ///
+    /// The picker, and the number of files of the folder that could not be read.
+    pub fn new(dir: Option<&Path>, width: usize, height: usize) -> (Picker, usize) {
+        let mut entries: Vec<(String, Pattern)> = library::names()
+            .map(|name| (name.to_string(), library::get(name).expect("library names have patterns")))
+            .collect();
+
+        let mut paths: Vec<_> = dir
+            .and_then(|dir| fs::read_dir(dir).ok())
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+        let mut unreadable = 0;
+        for path in paths {
+            match Pattern::from_file(&path) {
+                Ok(pattern) => {
+                    let name = path.file_stem().map_or_else(|| path.display().to_string(), |stem| stem.to_string_lossy().into_owned());
+                    entries.push((name, pattern));
+                }
+                Err(_) => unreadable += 1,
+            }
+        }
+
+        let picker = Picker {
+            entries,
+            selected: 0,
+            turns: 0,
+            cursor: (width as isize / 2, height as isize / 2),
+            board: (width, height),
+        };
+        (picker, unreadable)
+    }
+
+    /// Returns the picked pattern, rotated as it will be stamped.
+    fn pattern(&self) -> Pattern {
+        let pattern = &self.entries[self.selected].1;
+        match self.turns {
+            1 => pattern.transformed(Transform::Rot90),
+            2 => pattern.transformed(Transform::Rot180),
+            3 => pattern.transformed(Transform::Rot270),
+            _ => pattern.clone(),
+        }
+    }
+
+    /// Returns the cell the top-left corner of a pattern goes on, so its middle is at the
+    /// cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern to stamp.
+    fn corner(&self, pattern: &Pattern) -> (isize, isize) {
+        (self.cursor.0 - pattern.width as isize / 2, self.cursor.1 - pattern.height as isize / 2)
+    }
+
+    /// Handles a key press while the picker is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key event.
+    ///
+    /// # Returns
+    ///
+    /// What the main loop should do next.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> PickerAction {
+        let entries = self.entries.len();
+        let (dx, dy) = match key.code {
+            KeyCode::Esc | KeyCode::Char('t') => return PickerAction::Cancel,
+            KeyCode::Enter => {
+                let pattern = self.pattern();
+                let (x, y) = self.corner(&pattern);
+                return PickerAction::Stamp { pattern, x, y };
+            }
+            KeyCode::Up => {
+                self.selected = (self.selected + entries - 1) % entries;
+                (0, 0)
+            }
+            KeyCode::Down => {
+                self.selected = (self.selected + 1) % entries;
+                (0, 0)
+            }
+            KeyCode::Char('r') => {
+                self.turns = (self.turns + 1) % 4;
+                (0, 0)
+            }
+            KeyCode::Char('h') => (-1, 0),
+            KeyCode::Char('l') => (1, 0),
+            KeyCode::Char('k') => (0, -1),
+            KeyCode::Char('j') => (0, 1),
+            KeyCode::Char('H') => (-BIG_MOVE, 0),
+            KeyCode::Char('L') => (BIG_MOVE, 0),
+            KeyCode::Char('K') => (0, -BIG_MOVE),
+            KeyCode::Char('J') => (0, BIG_MOVE),
+            _ => (0, 0),
+        };
+        let (width, height) = (self.board.0 as isize, self.board.1 as isize);
+        self.cursor = ((self.cursor.0 + dx).clamp(0, (width - 1).max(0)), (self.cursor.1 + dy).clamp(0, (height - 1).max(0)));
+        PickerAction::Continue
+    }
+
+    /// Returns the part of the terminal the list of patterns covers, if it fits.
+    ///
+    /// # Arguments
+    ///
+    /// * `cols` - The number of terminal columns of the board.
+    /// * `rows` - The number of terminal rows of the board.
+    pub fn list_area(&self, cols: usize, rows: usize) -> Option<Region> {
+        let (width, height) = (cols.min(LIST_WIDTH), rows.min(self.entries.len() + 3));
+        (width >= 6 && height >= 4).then_some(Region { left: 0, top: 0, cols: width, rows: height })
+    }
+
+    /// Draws the list of patterns and the preview of the picked one over the board.
+    ///
+    /// The preview is drawn on top of whatever the board shows beneath it, so the board is
+    /// redrawn whole after every key that moves it.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `style` - How the board is drawn, which decides where each cell is on the screen.
+    /// * `origin` - The cell at the top-left corner of the screen.
+    /// * `cols` - The number of terminal columns of the board.
+    /// * `rows` - The number of terminal rows of the board.
+    pub fn draw(&self, out: &mut impl Write, style: &DisplayStyle, origin: (usize, usize), cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+        self.draw_preview(out, style, origin, cols, rows)?;
+
+        let Some(Region { cols: width, rows: height, .. }) = self.list_area(cols, rows) else {
+            return Ok(());
+        };
+        let inner_width = width - 2;
+        let fit = |text: &str| -> String { text.chars().chain(std::iter::repeat(' ')).take(inner_width).collect() };
+
+        let title: String = " Patterns ".chars().take(inner_width).collect();
+        out.queue(cursor::MoveTo(0, 0))?;
+        out.queue(Print(format!("┌{}{}┐", title, "─".repeat(inner_width - title.chars().count()))))?;
+
+        // The list is scrolled to keep the picked entry in view.
+        let shown = height - 3;
+        let first = self.selected.saturating_sub(shown - 1);
+        for (i, (name, _)) in self.entries.iter().enumerate().skip(first).take(shown) {
+            out.queue(cursor::MoveTo(0, (1 + i - first) as u16))?;
+            out.queue(Print("│"))?;
+            if i == self.selected {
+                out.queue(SetAttribute(Attribute::Reverse))?;
+                out.queue(Print(fit(&format!(" {}", name))))?;
+                out.queue(SetAttribute(Attribute::NoReverse))?;
+            } else {
+                out.queue(Print(fit(&format!(" {}", name))))?;
+            }
+            out.queue(Print("│"))?;
+        }
+
+        out.queue(cursor::MoveTo(0, (height - 2) as u16))?;
+        out.queue(Print(format!("│{}│", fit(" hjkl move, r rotate, ⏎ stamp"))))?;
+        out.queue(cursor::MoveTo(0, (height - 1) as u16))?;
+        out.queue(Print(format!("└{}┘", "─".repeat(inner_width))))?;
+        Ok(())
+    }
+
+    /// Draws the live cells of the picked pattern where it would be stamped.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `style` - How the board is drawn.
+    /// * `origin` - The cell at the top-left corner of the screen.
+    /// * `cols` - The number of terminal columns of the board.
+    /// * `rows` - The number of terminal rows of the board.
+    fn draw_preview(&self, out: &mut impl Write, style: &DisplayStyle, origin: (usize, usize), cols: usize, rows: usize) -> Result<(), Box<dyn Error>> {
+        let (cell_cols, cell_rows) = style.mode.cells_per_char();
+        let pattern = self.pattern();
+        let (x, y) = self.corner(&pattern);
+        let (x, y) = (x - origin.0 as isize, y - origin.1 as isize);
+
+        // The pattern on an empty board the size of the view, so it is drawn in the same
+        // characters as the board.
+        let mut view = vec![vec![false; style.mode.board_columns(cols)]; rows * cell_rows];
+        placement::place_pattern(&mut view, &pattern, x, y);
+
+        let char_of = |cell: isize, per_char: usize| (cell.max(0) as usize) / per_char;
+        let (first_col, last_col) = (char_of(x, cell_cols), char_of(x + pattern.width as isize, cell_cols));
+        let (first_row, last_row) = (char_of(y, cell_rows), char_of(y + pattern.height as isize, cell_rows));
+
+        out.queue(SetForegroundColor(PREVIEW_COLOR))?;
+        for cy in first_row..=last_row.min(rows.saturating_sub(1)) {
+            for cx in first_col..=last_col {
+                let (glyph, alive) = if style.mode.draws_pixels() {
+                    // Pixel boards have no glyphs of their own, so any live cell fills its character.
+                    let cell = |dx: usize, dy: usize| view.get(cy * cell_rows + dy).and_then(|row| row.get(cx * cell_cols + dx)).copied();
+                    ('█', (0..cell_rows).any(|dy| (0..cell_cols).any(|dx| cell(dx, dy) == Some(true))))
+                } else {
+                    display::glyph_at(&view, cx, cy, style)
+                };
+                if !alive {
+                    continue;
+                }
+                let column = if style.mode == RenderMode::Hex { cx * 2 + cy % 2 } else { cx };
+                if column < cols {
+                    out.queue(cursor::MoveTo(column as u16, cy as u16))?;
+                    out.queue(Print(glyph))?;
+                }
+            }
+        }
+        out.queue(ResetColor)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+#[cfg(feature = "image")]
+use std::{fs::File, io::{BufReader, Read}};
+use std::{error::Error, path::Path};
+
+use gameoflife::seeding::Picture;
+
+/// The first bytes of every PNG file.
+#[cfg(feature = "image")]
+const PNG_SIGNATURE: &[u8] = b"\x89PNG";
+
+/// The first bytes of every GIF file.
+#[cfg(feature = "image")]
+const GIF_SIGNATURE: &[u8] = b"GIF8";
+
+/// Loads a PNG or GIF image to seed the board from with `--image`.
+///
+/// The format is told apart by the first bytes of the file, whatever its extension. Only
+/// the first frame of an animated GIF is used.
+///
+/// # Arguments
+///
+/// * `path` - The image file.
+/// * `threshold` - The luminance, from 0 to 1, below which cells start alive.
+#[cfg(feature = "image")]
+pub fn load_picture(path: &Path, threshold: f64) -> Result<Picture, Box<dyn Error>> {
+    let failed = |e: &dyn std::fmt::Display| format!("Failed to read {}: {}", path.display(), e);
+    let mut signature = [0; 4];
+    File::open(path).and_then(|mut file| file.read_exact(&mut signature)).map_err(|e| failed(&e))?;
+
+    let file = BufReader::new(File::open(path).map_err(|e| failed(&e))?);
+    let luminance = match &signature[..] {
+        PNG_SIGNATURE => decode_png(file).map_err(|e| failed(&*e))?,
+        GIF_SIGNATURE => decode_gif(file).map_err(|e| failed(&*e))?,
+        _ => return Err(format!("{} is not a PNG or GIF image", path.display()).into()),
+    };
+    Ok(Picture::new(luminance, threshold))
+}
+
+/// Fails, because the program was built without image support.
+#[cfg(not(feature = "image"))]
+pub fn load_picture(_path: &Path, _threshold: f64) -> Result<Picture, Box<dyn Error>> {
+    Err("--image needs image support; rebuild with `cargo build --features image`".into())
+}
+
+/// Returns the luminance of a pixel, from 0 for black to 1 for white.
+///
+/// Transparent pixels are taken to be over a white background, so they are left dead.
+///
+/// # Arguments
+///
+/// * `r` - The red channel.
+/// * `g` - The green channel.
+/// * `b` - The blue channel.
+/// * `a` - The alpha channel, 255 for an opaque pixel.
+#[cfg(feature = "image")]
+fn luminance(r: u8, g: u8, b: u8, a: u8) -> f64 {
+    let opaque = (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64) / 255.0;
+    let alpha = a as f64 / 255.0;
+    opaque * alpha + (1.0 - alpha)
+}
+
+/// Decodes the luminance of every pixel of a PNG image, row by row.
+///
+/// # Arguments
+///
+/// * `file` - The image file.
+#[cfg(feature = "image")]
+fn decode_png(file: BufReader<File>) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+    let mut decoder = png::Decoder::new(file);
+    // Palettes and low bit depths are expanded, and 16-bit channels cut to 8 bits.
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer)?;
+
+    let channels = frame.color_type.samples();
+    let rows = buffer[..frame.buffer_size()].chunks(frame.line_size);
+    Ok(rows
+        .map(|row| {
+            row[..frame.width as usize * channels]
+                .chunks(channels)
+                .map(|pixel| match *pixel {
+                    [gray] => luminance(gray, gray, gray, 255),
+                    [gray, a] => luminance(gray, gray, gray, a),
+                    [r, g, b] => luminance(r, g, b, 255),
+                    [r, g, b, a] => luminance(r, g, b, a),
+                    _ => 1.0,
+                })
+                .collect()
+        })
+        .collect())
+}
+
+/// Decodes the luminance of every pixel of the first frame of a GIF image, row by row.
+///
+/// # Arguments
+///
+/// * `file` - The image file.
+#[cfg(feature = "image")]
+fn decode_gif(file: BufReader<File>) -> Result<Vec<Vec<f64>>, Box<dyn Error>> {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(file)?;
+    let frame = decoder.read_next_frame()?.ok_or("the image has no frames")?;
+
+    let width = frame.width as usize;
+    Ok(frame
+        .buffer
+        .chunks(width.max(1) * 4)
+        .map(|row| row.chunks(4).map(|pixel| luminance(pixel[0], pixel[1], pixel[2], pixel[3])).collect())
+        .collect())
+}
@@ -0,0 +1,325 @@
+use std::error::Error;
+
+use crate::pattern::{Pattern, Transform};
+
+/// A request to place a pattern at a position on the board.
+///
+/// Placements are written on the command line as `pattern.rle@30,10`, where the
+/// coordinates are the column and row of the pattern's top-left corner. The
+/// pattern may also be the name of a built-in pattern, and may be followed by
+/// transform modifiers, as in `gun@40,10:rot90:fliph`.
+#[derive(Clone, Debug)]
+pub struct Placement {
+    /// The pattern file path or built-in pattern name.
+    pub source: String,
+    /// The column of the pattern's top-left corner.
+    pub x: isize,
+    /// The row of the pattern's top-left corner.
+    pub y: isize,
+    /// The transforms applied to the pattern before placing it, in order.
+    pub transforms: Vec<Transform>,
+    /// The pattern itself, when it was read up front instead of loaded from `source`.
+    pub pattern: Option<Pattern>,
+}
+
+impl Placement {
+    /// Parses a placement from `source@x,y:modifier...` syntax.
+    ///
+    /// The coordinates are optional; `source` alone places the pattern at the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `spec` - The placement specification.
+    ///
+    /// # Returns
+    ///
+    /// The parsed placement.
+    pub fn parse(spec: &str) -> Result<Placement, Box<dyn Error>> {
+        // Split off the transform modifiers, which follow the coordinates.
+        let mut parts = spec.split(':');
+        let target = parts.next().unwrap_or_default();
+        let transforms = parts
+            .map(|name| {
+                Transform::parse(name).ok_or_else(|| {
+                    format!(
+                        "Invalid placement '{}': unknown modifier '{}' (expected rot90, rot180, rot270, fliph or flipv)",
+                        spec, name
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (source, coords) = match target.rsplit_once('@') {
+            Some((source, coords)) => (source, Some(coords)),
+            None => (target, None),
+        };
+
+        if source.is_empty() {
+            return Err(format!("Invalid placement '{}': missing pattern", spec).into());
+        }
+
+        let (x, y) = match coords {
+            Some(coords) => parse_coords(coords)
+                .ok_or_else(|| format!("Invalid placement '{}': expected coordinates as x,y", spec))?,
+            None => (0, 0),
+        };
+
+        Ok(Placement {
+            source: source.to_string(),
+            x,
+            y,
+            transforms,
+            pattern: None,
+        })
+    }
+
+    /// Creates a placement of a pattern that was already read, such as one piped in on
+    /// standard input, with its top-left corner at the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Where the pattern came from, for messages.
+    /// * `pattern` - The pattern to place.
+    ///
+    /// # Returns
+    ///
+    /// The placement.
+    pub fn from_pattern(source: &str, pattern: Pattern) -> Placement {
+        Placement {
+            source: source.to_string(),
+            x: 0,
+            y: 0,
+            transforms: Vec::new(),
+            pattern: Some(pattern),
+        }
+    }
+
+    /// Loads the pattern and applies the placement's transforms to it.
+    ///
+    /// # Returns
+    ///
+    /// The transformed pattern, ready to be placed.
+    pub fn load_pattern(&self) -> Result<Pattern, Box<dyn Error>> {
+        let mut pattern = match &self.pattern {
+            Some(pattern) => pattern.clone(),
+            None => Pattern::load(&self.source)?,
+        };
+        for &transform in &self.transforms {
+            pattern = pattern.transformed(transform);
+        }
+        Ok(pattern)
+    }
+
+    /// Moves the placement so the pattern sits in the middle of a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board.
+    /// * `height` - The height of the board.
+    pub fn center(&mut self, width: usize, height: usize) -> Result<(), Box<dyn Error>> {
+        let pattern = self.load_pattern()?;
+        self.x = (width as isize - pattern.width as isize) / 2;
+        self.y = (height as isize - pattern.height as isize) / 2;
+        Ok(())
+    }
+}
+
+/// Parses a pair of `x,y` coordinates.
+pub fn parse_coords(coords: &str) -> Option<(isize, isize)> {
+    let (x, y) = coords.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Describes what happened when a pattern was placed on the grid.
+#[derive(Debug, Default)]
+pub struct PlacementReport {
+    /// The number of live cells written to the grid.
+    pub placed: usize,
+    /// The number of live cells that fell outside the grid and were dropped.
+    pub clipped: usize,
+    /// The number of live cells that landed on cells that were already alive.
+    pub overlapped: usize,
+}
+
+/// Stamps a pattern onto the grid with its top-left corner at (`x`, `y`).
+///
+/// Live cells falling outside the grid are clipped, and live cells landing on
+/// already-live cells are counted as overlaps. Dead pattern cells never clear
+/// existing cells, so several patterns can share the board.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to place the pattern on.
+/// * `pattern` - The pattern to place.
+/// * `x` - The column of the pattern's top-left corner.
+/// * `y` - The row of the pattern's top-left corner.
+///
+/// # Returns
+///
+/// A report of the placed, clipped, and overlapping cells.
+pub fn place_pattern(grid: &mut [Vec<bool>], pattern: &Pattern, x: isize, y: isize) -> PlacementReport {
+    let mut report = PlacementReport::default();
+
+    for (dy, row) in pattern.cells.iter().enumerate() {
+        for (dx, &cell) in row.iter().enumerate() {
+            if !cell {
+                continue;
+            }
+
+            let gx = x + dx as isize;
+            let gy = y + dy as isize;
+
+            // Look up the target cell, treating negative coordinates as out of bounds.
+            let target = usize::try_from(gy)
+                .ok()
+                .zip(usize::try_from(gx).ok())
+                .and_then(|(gy, gx)| grid.get_mut(gy).and_then(|row| row.get_mut(gx)));
+
+            match target {
+                Some(target) => {
+                    if *target {
+                        report.overlapped += 1;
+                    }
+                    *target = true;
+                    report.placed += 1;
+                }
+                None => report.clipped += 1,
+            }
+        }
+    }
+
+    report
+}
+
+/// Loads and places every requested pattern onto the grid.
+///
+/// A placement whose pattern lands entirely outside the grid is an error.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to place the patterns on.
+/// * `placements` - The placements to apply, in order.
+///
+/// # Returns
+///
+/// A line describing each placement, followed by warnings about clipped or
+/// overlapping cells.
+pub fn apply_placements(grid: &mut [Vec<bool>], placements: &[Placement]) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut messages = Vec::new();
+
+    for placement in placements {
+        let pattern = placement.load_pattern()?;
+        let report = place_pattern(grid, &pattern, placement.x, placement.y);
+
+        if report.placed == 0 && report.clipped > 0 {
+            return Err(format!(
+                "Pattern {} placed at {},{} lies entirely outside the {}x{} grid",
+                placement.source,
+                placement.x,
+                placement.y,
+                grid.first().map(|row| row.len()).unwrap_or(0),
+                grid.len()
+            )
+            .into());
+        }
+
+        messages.push(format!(
+            "Placed {} ({}x{}) at {},{} ({} cells)",
+            placement.source,
+            pattern.width,
+            pattern.height,
+            placement.x,
+            placement.y,
+            report.placed
+        ));
+        if report.clipped > 0 {
+            messages.push(format!("  Warning: {} cells fell outside the grid and were clipped", report.clipped));
+        }
+        if report.overlapped > 0 {
+            messages.push(format!("  Warning: {} cells overlap previously placed patterns", report.overlapped));
+        }
+    }
+
+    Ok(messages)
+}
+
+/// What to do when the placed patterns do not fit on the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FitPolicy {
+    /// Place the patterns anyway, clipping the cells that fall outside the board.
+    #[default]
+    Crop,
+    /// Grow the board until every pattern fits.
+    Expand,
+    /// Draw more cells per character, so the board filling the terminal holds every pattern.
+    Zoom,
+    /// Refuse to start.
+    Error,
+}
+
+impl FitPolicy {
+    /// Parses a fit policy name (`expand`, `zoom`, `crop` or `error`).
+    pub fn parse(name: &str) -> Option<FitPolicy> {
+        match name {
+            "expand" => Some(FitPolicy::Expand),
+            "zoom" => Some(FitPolicy::Zoom),
+            "crop" => Some(FitPolicy::Crop),
+            "error" => Some(FitPolicy::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the size of the smallest board that holds every placed pattern.
+///
+/// Patterns placed at negative coordinates are clipped on any board, so only their
+/// right and bottom edges count.
+///
+/// # Arguments
+///
+/// * `placements` - The placements to measure.
+///
+/// # Returns
+///
+/// The width and height needed, as `(width, height)`.
+pub fn required_size(placements: &[Placement]) -> Result<(usize, usize), Box<dyn Error>> {
+    let mut size = (0, 0);
+    for placement in placements {
+        let pattern = placement.load_pattern()?;
+        let right = (placement.x + pattern.width as isize).max(0) as usize;
+        let bottom = (placement.y + pattern.height as isize).max(0) as usize;
+        size = (size.0.max(right), size.1.max(bottom));
+    }
+    Ok(size)
+}
+
+/// Finds the first placed pattern that does not lie entirely inside a board of the given size.
+///
+/// # Arguments
+///
+/// * `placements` - The placements to check.
+/// * `width` - The width of the board.
+/// * `height` - The height of the board.
+///
+/// # Returns
+///
+/// A message naming the first pattern that does not fit, or `None` if they all fit. A
+/// pattern that cannot be loaded is an error instead, so that it is not taken for one
+/// that is too big.
+pub fn first_unfit(placements: &[Placement], width: usize, height: usize) -> Result<Option<String>, Box<dyn Error>> {
+    for placement in placements {
+        let pattern = placement.load_pattern()?;
+        let fits = placement.x >= 0
+            && placement.y >= 0
+            && placement.x as usize + pattern.width <= width
+            && placement.y as usize + pattern.height <= height;
+
+        if !fits {
+            return Ok(Some(format!(
+                "Pattern {} ({}x{}) placed at {},{} does not fit the {}x{} grid",
+                placement.source, pattern.width, pattern.height, placement.x, placement.y, width, height
+            )));
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,143 @@
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    QueueableCommand,
+};
+use std::{error::Error, io::Write};
+
+/// What the main loop should do after the prompt handled a key.
+pub enum PromptAction {
+    /// Keep the prompt open.
+    Continue,
+    /// The text was entered with Enter.
+    Submit(String),
+    /// Close the prompt without doing anything.
+    Cancel,
+}
+
+/// A line of text typed into the status bar, as for a new rule.
+///
+/// The prompt stays open when what was entered is rejected, showing why beside the text
+/// so it can be corrected.
+pub struct Prompt {
+    /// What is asked for, shown before the text.
+    label: &'static str,
+    /// The text typed so far.
+    text: Vec<char>,
+    /// The character position of the cursor within the text.
+    cursor: usize,
+    /// Why the text entered last was rejected, if it was.
+    error: Option<String>,
+}
+
+impl Prompt {
+    /// Creates a prompt, with the cursor at the end of its starting text.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - What is asked for.
+    /// * `text` - The text to start from.
+    pub fn new(label: &'static str, text: &str) -> Prompt {
+        let text: Vec<char> = text.chars().collect();
+        Prompt { label, cursor: text.len(), text, error: None }
+    }
+
+    /// Shows why the text entered was rejected, keeping the prompt open.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The reason.
+    pub fn reject(&mut self, error: String) {
+        self.error = Some(error);
+    }
+
+    /// Types pasted text at the cursor, leaving out line breaks.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The pasted text.
+    pub fn paste(&mut self, text: &str) {
+        for c in text.chars().filter(|c| !c.is_control()) {
+            self.text.insert(self.cursor, c);
+            self.cursor += 1;
+        }
+        self.error = None;
+    }
+
+    /// Handles a key press while the prompt is open.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key event.
+    ///
+    /// # Returns
+    ///
+    /// What the main loop should do next.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> PromptAction {
+        match key.code {
+            KeyCode::Esc => return PromptAction::Cancel,
+            KeyCode::Enter => return PromptAction::Submit(self.text.iter().collect()),
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.cursor);
+            }
+            KeyCode::Delete if self.cursor < self.text.len() => {
+                self.text.remove(self.cursor);
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.len()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.len(),
+            _ => return PromptAction::Continue,
+        }
+        self.error = None;
+        PromptAction::Continue
+    }
+
+    /// Draws the prompt over a row of the terminal, with the cursor shown in reverse video.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `row` - The terminal row to draw on.
+    /// * `cols` - The width of the terminal.
+    pub fn draw(&self, out: &mut impl Write, row: usize, cols: usize) -> Result<(), Box<dyn Error>> {
+        let before: String = self.text[..self.cursor].iter().collect();
+        let under = self.text.get(self.cursor).copied().unwrap_or(' ');
+        let after: String = self.text.iter().skip(self.cursor + 1).collect();
+
+        // Whatever runs past the edge of the terminal is cut off, from the error at the end.
+        let mut left = cols;
+        let mut take = |text: &str| -> String {
+            let text: String = text.chars().take(left).collect();
+            left -= text.chars().count();
+            text
+        };
+        let label = take(&format!("{}: ", self.label));
+        let before = take(&before);
+        let under = take(&under.to_string());
+        let after = take(&after);
+        let error = take(&self.error.as_ref().map_or(String::new(), |error| format!("  {}", error)));
+        let padding = " ".repeat(left);
+
+        out.queue(cursor::MoveTo(0, row as u16))?;
+        out.queue(SetAttribute(Attribute::Bold))?;
+        out.queue(Print(label))?;
+        out.queue(SetAttribute(Attribute::NormalIntensity))?;
+        out.queue(Print(before))?;
+        out.queue(SetAttribute(Attribute::Reverse))?;
+        out.queue(Print(under))?;
+        out.queue(SetAttribute(Attribute::NoReverse))?;
+        out.queue(Print(after))?;
+        out.queue(SetForegroundColor(Color::Red))?;
+        out.queue(Print(error))?;
+        out.queue(ResetColor)?;
+        out.queue(Print(padding))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use std::{
+    error::Error,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::font;
+
+/// An RGB color.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parses a hex color like `#33ff66` (the `#` is optional).
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The color to parse.
+    ///
+    /// # Returns
+    ///
+    /// The color, or `None` if the value is not a six-digit hex color.
+    pub fn parse(value: &str) -> Option<Rgb> {
+        let hex = value.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Rgb(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Returns the nearest color of the 256-color terminal palette, for terminals without
+    /// 24-bit color.
+    ///
+    /// # Returns
+    ///
+    /// The number of the color: one of the 6×6×6 color cube from 16, or one of the grays
+    /// from 232.
+    pub fn ansi256(&self) -> u8 {
+        // The channel values of the color cube, and the step of each channel nearest to a value.
+        const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+        let step = |value: u8| match value {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (value as usize - 35) / 40,
+        };
+        let distance = |Rgb(r, g, b): Rgb| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, self.0) + d(g, self.1) + d(b, self.2)
+        };
+
+        let (r, g, b) = (step(self.0), step(self.1), step(self.2));
+        let cube = Rgb(CUBE[r], CUBE[g], CUBE[b]);
+        // The grays run from 8 to 238 in steps of 10.
+        let average = (self.0 as usize + self.1 as usize + self.2 as usize) / 3;
+        let gray_step = (average.saturating_sub(3) / 10).min(23);
+        let gray = (8 + gray_step * 10) as u8;
+
+        if distance(Rgb(gray, gray, gray)) < distance(cube) {
+            232 + gray_step as u8
+        } else {
+            (16 + 36 * r + 6 * g + b) as u8
+        }
+    }
+}
+
+/// An RGB image held in memory.
+pub struct Image {
+    /// The width of the image in pixels.
+    pub width: usize,
+    /// The height of the image in pixels.
+    pub height: usize,
+    /// The pixel data, three bytes per pixel, row by row.
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Creates an image filled with a single color.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the image in pixels.
+    /// * `height` - The height of the image in pixels.
+    /// * `color` - The fill color.
+    pub fn new(width: usize, height: usize, color: Rgb) -> Image {
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&[color.0, color.1, color.2]);
+        }
+
+        Image { width, height, pixels }
+    }
+
+    /// Fills a rectangle with a color, clipping it to the image bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The left edge of the rectangle.
+    /// * `y` - The top edge of the rectangle.
+    /// * `width` - The width of the rectangle.
+    /// * `height` - The height of the rectangle.
+    /// * `color` - The fill color.
+    pub fn fill_rect(&mut self, x: usize, y: usize, width: usize, height: usize, color: Rgb) {
+        for py in y..(y + height).min(self.height) {
+            for px in x..(x + width).min(self.width) {
+                let offset = (py * self.width + px) * 3;
+                self.pixels[offset..offset + 3].copy_from_slice(&[color.0, color.1, color.2]);
+            }
+        }
+    }
+
+    /// Draws text with the embedded bitmap font.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The left edge of the text.
+    /// * `y` - The top edge of the text.
+    /// * `text` - The text to draw.
+    /// * `scale` - The size of each font pixel in image pixels.
+    /// * `color` - The text color.
+    pub fn draw_text(&mut self, x: usize, y: usize, text: &str, scale: usize, color: Rgb) {
+        for (i, ch) in text.chars().enumerate() {
+            let glyph = font::glyph(ch);
+            let gx = x + i * (font::GLYPH_WIDTH + 1) * scale;
+
+            for row in 0..font::GLYPH_HEIGHT {
+                for col in 0..font::GLYPH_WIDTH {
+                    if font::is_lit(&glyph, col, row) {
+                        self.fill_rect(gx + col * scale, y + row * scale, scale, scale, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Saves the image as a PNG file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the PNG file.
+    pub fn save_png(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        self.write_png(BufWriter::new(file))
+    }
+
+    /// Encodes the image as PNG.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where the PNG data is written.
+    pub fn write_png(&self, out: impl Write) -> Result<(), Box<dyn Error>> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        Ok(())
+    }
+}
+
+/// Returns the width in pixels of a line of text drawn with the bitmap font.
+///
+/// # Arguments
+///
+/// * `text` - The text.
+/// * `scale` - The size of each font pixel in image pixels.
+pub fn text_width(text: &str, scale: usize) -> usize {
+    let chars = text.chars().count();
+    (chars * (font::GLYPH_WIDTH + 1)).saturating_sub(1) * scale
+}
+
+/// Controls how a grid is rasterized into an image.
+#[derive(Clone, Debug)]
+pub struct RasterStyle {
+    /// The size of each cell in pixels.
+    pub cell_size: usize,
+    /// The color of live cells.
+    pub alive: Rgb,
+    /// The color of dead cells.
+    pub dead: Rgb,
+    /// The width of the border around the board in pixels (0 for no border).
+    pub border: usize,
+    /// The color of the border and the caption strip.
+    pub border_color: Rgb,
+    /// The text drawn in a strip below the board, if any.
+    pub caption: Option<String>,
+    /// The color of the caption text.
+    pub caption_color: Rgb,
+}
+
+impl Default for RasterStyle {
+    fn default() -> Self {
+        RasterStyle {
+            cell_size: 4,
+            alive: Rgb(0x7c, 0xff, 0x9a),
+            dead: Rgb(0x10, 0x14, 0x18),
+            border: 8,
+            border_color: Rgb(0x2a, 0x31, 0x3a),
+            caption: None,
+            caption_color: Rgb(0xd8, 0xde, 0xe6),
+        }
+    }
+}
+
+/// The scale of the caption font.
+const CAPTION_SCALE: usize = 2;
+
+/// Rasterizes a grid into an image.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to render.
+/// * `style` - The colors, sizes, and caption to use.
+///
+/// # Returns
+///
+/// The rendered image.
+pub fn render_grid(grid: &[Vec<bool>], style: &RasterStyle) -> Image {
+    let rows = grid.len();
+    let cols = grid.first().map(|row| row.len()).unwrap_or(0);
+
+    let board_width = cols * style.cell_size;
+    let board_height = rows * style.cell_size;
+
+    // The caption sits in its own strip below the board, inside the border.
+    let (caption_width, caption_height) = match &style.caption {
+        Some(caption) => (
+            text_width(caption, CAPTION_SCALE),
+            font::GLYPH_HEIGHT * CAPTION_SCALE + 2 * CAPTION_SCALE * 2,
+        ),
+        None => (0, 0),
+    };
+
+    // Widen the image if the caption is longer than the board.
+    let width = board_width.max(caption_width) + 2 * style.border;
+    let height = board_height + 2 * style.border + caption_height;
+    let mut image = Image::new(width, height, style.border_color);
+
+    // Paint the board background, then the live cells on top of it.
+    image.fill_rect(style.border, style.border, board_width, board_height, style.dead);
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            if cell {
+                image.fill_rect(
+                    style.border + x * style.cell_size,
+                    style.border + y * style.cell_size,
+                    style.cell_size,
+                    style.cell_size,
+                    style.alive,
+                );
+            }
+        }
+    }
+
+    if let Some(caption) = &style.caption {
+        image.draw_text(
+            style.border,
+            style.border + board_height + CAPTION_SCALE * 2,
+            caption,
+            CAPTION_SCALE,
+            style.caption_color,
+        );
+    }
+
+    image
+}
@@ -0,0 +1,343 @@
+use std::{
+    error::Error,
+    fs::{self, File},
+    io::{BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    raster::{self, RasterStyle},
+    runlog::RunLogRecorder,
+};
+
+/// Receives every simulated generation, e.g. to write it to an animation file.
+pub trait FrameRecorder {
+    /// Records a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The grid state.
+    /// * `generation` - The generation number of the state.
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>>;
+
+    /// Finishes the recording, flushing any buffered output.
+    ///
+    /// # Returns
+    ///
+    /// The path of the written file.
+    fn finish(self: Box<Self>) -> Result<PathBuf, Box<dyn Error>>;
+}
+
+/// Settings shared by the recorders.
+#[derive(Clone, Debug)]
+pub struct RecordSettings {
+    /// Only every `every`-th generation is recorded.
+    pub every: u64,
+    /// The colors and cell size of the recorded frames.
+    pub style: RasterStyle,
+    /// The number of generations simulated per second, which sets the playback speed.
+    pub speed: f64,
+}
+
+impl Default for RecordSettings {
+    fn default() -> Self {
+        RecordSettings {
+            every: 1,
+            style: RasterStyle {
+                border: 0,
+                ..RasterStyle::default()
+            },
+            speed: 10.0,
+        }
+    }
+}
+
+/// Opens a recorder for the output file, choosing the format from its extension.
+///
+/// # Arguments
+///
+/// * `path` - The output file.
+/// * `settings` - The recording settings.
+///
+/// # Returns
+///
+/// The recorder.
+pub fn open(path: &Path, settings: &RecordSettings) -> Result<Box<dyn FrameRecorder>, Box<dyn Error>> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase);
+
+    match extension.as_deref() {
+        Some("gif") => Ok(Box::new(GifRecorder::new(path, settings)?)),
+        Some("apng") => Ok(Box::new(ApngRecorder::new(path, settings)?)),
+        Some("golrec") => Ok(Box::new(RunLogRecorder::new(path, settings)?)),
+        _ => Err(format!("Unsupported recording format for {} (expected .gif, .apng or .golrec)", path.display()).into()),
+    }
+}
+
+/// Records generations into an animated GIF.
+///
+/// The encoder is only created once the first frame arrives, because the frame
+/// size depends on the grid.
+pub struct GifRecorder {
+    /// The output file.
+    path: PathBuf,
+    /// The recording settings.
+    settings: RecordSettings,
+    /// The GIF encoder, created with the first frame.
+    encoder: Option<gif::Encoder<BufWriter<File>>>,
+}
+
+impl GifRecorder {
+    /// Creates a GIF recorder, making sure the output file can be written.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The output file.
+    /// * `settings` - The recording settings.
+    pub fn new(path: &Path, settings: &RecordSettings) -> Result<GifRecorder, Box<dyn Error>> {
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+        Ok(GifRecorder {
+            path: path.to_path_buf(),
+            settings: settings.clone(),
+            encoder: None,
+        })
+    }
+}
+
+impl FrameRecorder for GifRecorder {
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        if !generation.is_multiple_of(self.settings.every) {
+            return Ok(());
+        }
+
+        let style = &self.settings.style;
+        let image = raster::render_grid(grid, style);
+        let (width, height) = (image.width as u16, image.height as u16);
+
+        // Frames only use the dead, alive, and border colors, so a three-color palette is exact.
+        let palette = [style.dead, style.alive, style.border_color];
+        let indices = palette_indices(&image, &palette);
+
+        if self.encoder.is_none() {
+            let file = File::create(&self.path).map_err(|e| format!("Failed to create {}: {}", self.path.display(), e))?;
+            let flat_palette: Vec<u8> = palette.iter().flat_map(|c| [c.0, c.1, c.2]).collect();
+            let mut encoder = gif::Encoder::new(BufWriter::new(file), width, height, &flat_palette)?;
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+            self.encoder = Some(encoder);
+        }
+
+        let mut frame = gif::Frame::from_indexed_pixels(width, height, indices, None);
+        // GIF delays are in hundredths of a second.
+        frame.delay = (100.0 * self.settings.every as f64 / self.settings.speed).round().max(2.0) as u16;
+
+        self.encoder
+            .as_mut()
+            .expect("encoder is created above")
+            .write_frame(&frame)?;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<PathBuf, Box<dyn Error>> {
+        if let Some(encoder) = self.encoder {
+            encoder.into_inner()?;
+        }
+        Ok(self.path)
+    }
+}
+
+/// Maps the pixels of a rendered frame to indices into a palette.
+///
+/// # Arguments
+///
+/// * `image` - The rendered frame.
+/// * `palette` - The colors the frame is drawn with.
+///
+/// # Returns
+///
+/// One palette index per pixel.
+fn palette_indices(image: &raster::Image, palette: &[raster::Rgb]) -> Vec<u8> {
+    image
+        .pixels
+        .chunks_exact(3)
+        .map(|px| {
+            let color = raster::Rgb(px[0], px[1], px[2]);
+            palette.iter().position(|&c| c == color).unwrap_or(0) as u8
+        })
+        .collect()
+}
+
+/// Records generations into a lossless animated PNG.
+///
+/// Frames are streamed into the file as they arrive. The APNG header has to state the
+/// number of frames up front, so a placeholder is written first and replaced with the
+/// real count when the recording is finished.
+pub struct ApngRecorder {
+    /// The output file.
+    path: PathBuf,
+    /// The recording settings.
+    settings: RecordSettings,
+    /// The PNG writer, created with the first frame.
+    writer: Option<png::Writer<BufWriter<File>>>,
+    /// The number of frames written so far.
+    frames: u32,
+}
+
+impl ApngRecorder {
+    /// Creates an APNG recorder, making sure the output file can be written.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The output file.
+    /// * `settings` - The recording settings.
+    pub fn new(path: &Path, settings: &RecordSettings) -> Result<ApngRecorder, Box<dyn Error>> {
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+        Ok(ApngRecorder {
+            path: path.to_path_buf(),
+            settings: settings.clone(),
+            writer: None,
+            frames: 0,
+        })
+    }
+}
+
+impl FrameRecorder for ApngRecorder {
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        if !generation.is_multiple_of(self.settings.every) {
+            return Ok(());
+        }
+
+        let style = &self.settings.style;
+        let image = raster::render_grid(grid, style);
+        let palette = [style.dead, style.alive, style.border_color];
+
+        if self.writer.is_none() {
+            let file = File::create(&self.path).map_err(|e| format!("Failed to create {}: {}", self.path.display(), e))?;
+            let mut encoder = png::Encoder::new(BufWriter::new(file), image.width as u32, image.height as u32);
+            encoder.set_color(png::ColorType::Indexed);
+            encoder.set_depth(png::BitDepth::Eight);
+            encoder.set_palette(palette.iter().flat_map(|c| [c.0, c.1, c.2]).collect::<Vec<u8>>());
+            // The real frame count is patched in by `finish`; zero plays loops forever.
+            encoder.set_animated(u32::MAX, 0)?;
+            self.writer = Some(encoder.write_header()?);
+        }
+
+        let writer = self.writer.as_mut().expect("writer is created above");
+        // Frame delays are stored as a fraction of a second; use milliseconds.
+        let delay = (1000.0 * self.settings.every as f64 / self.settings.speed).round().clamp(1.0, u16::MAX as f64);
+        writer.set_frame_delay(delay as u16, 1000)?;
+        writer.write_image_data(&palette_indices(&image, &palette))?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn finish(self: Box<Self>) -> Result<PathBuf, Box<dyn Error>> {
+        if let Some(writer) = self.writer {
+            writer.finish()?;
+            patch_frame_count(&self.path, self.frames)?;
+        }
+        Ok(self.path)
+    }
+}
+
+/// Computes the CRC-32 checksum used by PNG chunks.
+///
+/// # Arguments
+///
+/// * `data` - The chunk type and data.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Replaces the frame count in the `acTL` chunk of an APNG file.
+///
+/// # Arguments
+///
+/// * `path` - The APNG file.
+/// * `frames` - The number of frames actually written.
+fn patch_frame_count(path: &Path, frames: u32) -> Result<(), Box<dyn Error>> {
+    let mut file = File::options().read(true).write(true).open(path)?;
+
+    // Walk the chunks after the 8-byte signature until the animation control chunk.
+    let mut offset = 8;
+    loop {
+        let mut header = [0u8; 8];
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut header)?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+
+        match &header[4..8] {
+            b"acTL" => break,
+            b"IDAT" | b"IEND" => return Err(format!("{} has no animation control chunk", path.display()).into()),
+            _ => offset += 12 + length,
+        }
+    }
+
+    // The chunk holds the frame count and play count; the CRC covers the type and data.
+    let mut chunk = [0u8; 12];
+    file.seek(SeekFrom::Start(offset + 4))?;
+    file.read_exact(&mut chunk)?;
+    chunk[4..8].copy_from_slice(&frames.to_be_bytes());
+
+    file.seek(SeekFrom::Start(offset + 4))?;
+    file.write_all(&chunk)?;
+    file.write_all(&crc32(&chunk).to_be_bytes())?;
+    Ok(())
+}
+
+/// Writes every recorded generation to its own numbered PNG file.
+///
+/// Frames are numbered consecutively from 1 (`frame_000001.png`, ...), so tools like
+/// ffmpeg can assemble them into a video even when only every n-th generation is recorded.
+pub struct PngSequenceRecorder {
+    /// The directory the frames are written to.
+    dir: PathBuf,
+    /// The recording settings.
+    settings: RecordSettings,
+    /// The number of frames written so far.
+    frames: u64,
+}
+
+impl PngSequenceRecorder {
+    /// Creates a frame sequence recorder, creating the output directory if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir` - The output directory.
+    /// * `settings` - The recording settings.
+    pub fn new(dir: &Path, settings: &RecordSettings) -> Result<PngSequenceRecorder, Box<dyn Error>> {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create directory {}: {}", dir.display(), e))?;
+
+        Ok(PngSequenceRecorder {
+            dir: dir.to_path_buf(),
+            settings: settings.clone(),
+            frames: 0,
+        })
+    }
+}
+
+impl FrameRecorder for PngSequenceRecorder {
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        if !generation.is_multiple_of(self.settings.every) {
+            return Ok(());
+        }
+
+        self.frames += 1;
+        let path = self.dir.join(format!("frame_{:06}.png", self.frames));
+        raster::render_grid(grid, &self.settings.style).save_png(&path)
+    }
+
+    fn finish(self: Box<Self>) -> Result<PathBuf, Box<dyn Error>> {
+        Ok(self.dir)
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{rule::Rule, snapshot::Snapshot};
+
+/// The value of the `format` field that identifies a replay.
+const REPLAY_FORMAT: &str = "golr";
+
+/// The version of the replay format written by this build.
+const REPLAY_VERSION: u32 = 1;
+
+/// The first line of a replay, describing how the run started.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    /// Always `golr`.
+    format: String,
+    /// The version of the format.
+    version: u32,
+    /// The command-line arguments of the run, without the ones that recorded it.
+    args: Vec<String>,
+    /// The board, rule, and random number generator the run started with.
+    start: Snapshot,
+}
+
+/// Something done to a run from outside, by a key press or over the control socket.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Intervention {
+    /// The simulation was paused.
+    Pause,
+    /// The simulation was resumed.
+    Resume,
+    /// One generation was computed while paused.
+    Step,
+    /// Cells were placed on the board, with the top-left corner of the pattern at `x`, `y`.
+    Place {
+        /// The column of the left edge of the pattern.
+        x: isize,
+        /// The row of the top edge of the pattern.
+        y: isize,
+        /// The pattern, as RLE.
+        cells: String,
+    },
+    /// The rule was switched.
+    SetRule {
+        /// The new rule, stored as its rulestring.
+        rule: Rule,
+    },
+    /// The board was replaced, by stepping back or loading a snapshot.
+    Restore {
+        /// The state the run continued from.
+        state: Snapshot,
+    },
+}
+
+/// One line of a replay after the header: an intervention and when it happened.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayEvent {
+    /// The generation the board was at.
+    pub generation: u64,
+    /// What was done.
+    #[serde(rename = "event")]
+    pub intervention: Intervention,
+}
+
+/// A recorded run: how it started and everything done to it along the way.
+///
+/// Replays (`.golr`) are JSON Lines files: a header line with the command-line arguments
+/// and the starting state, then one line per intervention, in the order they happened.
+/// Everything else about a run follows from its start, so a replay reproduces it
+/// generation for generation. They are written with `--record-replay run.golr` and played
+/// with `--replay run.golr`.
+#[derive(Debug)]
+pub struct Replay {
+    /// The command-line arguments of the run, without the ones that recorded it.
+    pub args: Vec<String>,
+    /// The board, rule, and random number generator the run started with.
+    pub start: Snapshot,
+    /// The interventions not replayed yet, in order.
+    events: VecDeque<ReplayEvent>,
+}
+
+impl Replay {
+    /// Reads a replay.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The replay file.
+    pub fn load(path: &Path) -> Result<Replay, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let invalid = |line: usize, e: &dyn fmt::Display| format!("Invalid replay {} at line {}: {}", path.display(), line, e);
+
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let header: Header = match lines.next() {
+            Some((i, line)) => serde_json::from_str(line).map_err(|e| invalid(i + 1, &e))?,
+            None => return Err(format!("Invalid replay {}: the file is empty", path.display()).into()),
+        };
+        if header.format != REPLAY_FORMAT || header.version != REPLAY_VERSION {
+            return Err(format!(
+                "{} is not a version {} replay (found format '{}', version {})",
+                path.display(),
+                REPLAY_VERSION,
+                header.format,
+                header.version
+            )
+            .into());
+        }
+        header.start.grid().map_err(|e| invalid(1, &e))?;
+
+        let events = lines
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| invalid(i + 1, &e)))
+            .collect::<Result<VecDeque<ReplayEvent>, _>>()?;
+
+        Ok(Replay { args: header.args, start: header.start, events })
+    }
+
+    /// Returns whether an intervention is due at a generation, so the run must not be
+    /// advanced past it before the intervention is taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation the board is at.
+    pub fn is_due(&self, generation: u64) -> bool {
+        self.events.front().is_some_and(|event| event.generation == generation)
+    }
+
+    /// Takes the next intervention, if it is due at a generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation the board is at.
+    pub fn next_due(&mut self, generation: u64) -> Option<Intervention> {
+        if self.is_due(generation) {
+            self.events.pop_front().map(|event| event.intervention)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of interventions not replayed yet.
+    pub fn remaining(&self) -> usize {
+        self.events.len()
+    }
+}
+
+/// Records a run into a replay.
+pub struct ReplayRecorder {
+    /// The output file.
+    path: PathBuf,
+    /// The writer of the replay.
+    file: BufWriter<File>,
+}
+
+impl ReplayRecorder {
+    /// Creates a replay and writes its header.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The output file.
+    /// * `args` - The command-line arguments of the run, without the ones that record it.
+    /// * `start` - The state the run starts from.
+    pub fn new(path: &Path, args: Vec<String>, start: Snapshot) -> Result<ReplayRecorder, Box<dyn Error>> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        let mut recorder = ReplayRecorder { path: path.to_path_buf(), file: BufWriter::new(file) };
+
+        let header = Header { format: REPLAY_FORMAT.to_string(), version: REPLAY_VERSION, args, start };
+        writeln!(recorder.file, "{}", serde_json::to_string(&header)?)?;
+        recorder.file.flush()?;
+        Ok(recorder)
+    }
+
+    /// Records an intervention.
+    ///
+    /// Each one is written out at once, so a run that ends in a crash can still be replayed
+    /// up to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation the board is at.
+    /// * `intervention` - What was done.
+    pub fn record(&mut self, generation: u64, intervention: Intervention) -> Result<(), Box<dyn Error>> {
+        let event = ReplayEvent { generation, intervention };
+        writeln!(self.file, "{}", serde_json::to_string(&event)?)?;
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// Finishes the replay.
+    ///
+    /// # Returns
+    ///
+    /// The path of the replay.
+    pub fn finish(mut self) -> Result<PathBuf, Box<dyn Error>> {
+        self.file.flush()?;
+        Ok(self.path)
+    }
+}
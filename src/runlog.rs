@@ -0,0 +1,363 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    census,
+    hash::BoardHash,
+    pattern::{self, Pattern},
+    placement,
+    recorder::{FrameRecorder, RecordSettings},
+    Grid,
+};
+
+/// The value of the `format` field that identifies a run log.
+const RUN_LOG_FORMAT: &str = "golrec";
+
+/// The version of the run log format written by this build.
+const RUN_LOG_VERSION: u32 = 2;
+
+/// The first line of a run log, describing the board.
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    /// Always `golrec`.
+    format: String,
+    /// The version of the format.
+    version: u32,
+    /// The width of the board.
+    width: usize,
+    /// The height of the board.
+    height: usize,
+}
+
+/// One recorded generation of a run log.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RunFrame {
+    /// The generation number.
+    pub generation: u64,
+    /// The number of live cells.
+    pub population: usize,
+    /// The hash of the board, as printed by [`BoardHash`].
+    pub hash: String,
+    /// The live cells, as RLE.
+    pub cells: String,
+}
+
+/// A recorded run: the board of every recorded generation, with its hash and population.
+///
+/// Run logs (`.golrec`) are JSON Lines files: a header line with the board size, then one
+/// line per recorded generation. They are written with `--record run.golrec` and compared
+/// with `gameoflife diff`.
+#[derive(Debug)]
+pub struct RunLog {
+    /// The file the run was loaded from.
+    pub path: PathBuf,
+    /// The width of the board.
+    pub width: usize,
+    /// The height of the board.
+    pub height: usize,
+    /// The recorded generations, in order.
+    pub frames: Vec<RunFrame>,
+}
+
+impl RunLog {
+    /// Reads a run log.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The run log file.
+    pub fn load(path: &Path) -> Result<RunLog, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let invalid = |line: usize, e: &dyn fmt::Display| format!("Invalid run log {} at line {}: {}", path.display(), line, e);
+
+        let mut lines = text.lines().enumerate().filter(|(_, line)| !line.trim().is_empty());
+        let header: Header = match lines.next() {
+            Some((i, line)) => serde_json::from_str(line).map_err(|e| invalid(i + 1, &e))?,
+            None => return Err(format!("Invalid run log {}: the file is empty", path.display()).into()),
+        };
+        if header.format != RUN_LOG_FORMAT || header.version != RUN_LOG_VERSION {
+            return Err(format!(
+                "{} is not a version {} run log (found format '{}', version {})",
+                path.display(),
+                RUN_LOG_VERSION,
+                header.format,
+                header.version
+            )
+            .into());
+        }
+
+        let frames = lines
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| invalid(i + 1, &e)))
+            .collect::<Result<Vec<RunFrame>, _>>()?;
+
+        Ok(RunLog {
+            path: path.to_path_buf(),
+            width: header.width,
+            height: header.height,
+            frames,
+        })
+    }
+
+    /// Restores the board of a recorded generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - A generation of this run.
+    pub fn grid(&self, frame: &RunFrame) -> Result<Grid, Box<dyn Error>> {
+        let pattern = pattern::parse_rle_within(&frame.cells, self.width, self.height)?;
+        let mut grid = vec![vec![false; self.width]; self.height];
+        placement::place_pattern(&mut grid, &pattern, 0, 0);
+        Ok(grid)
+    }
+}
+
+/// Records generations into a run log.
+///
+/// The header is written with the first frame, because the board size depends on the grid.
+pub struct RunLogRecorder {
+    /// The output file.
+    path: PathBuf,
+    /// The recording settings.
+    settings: RecordSettings,
+    /// The writer of the run log.
+    file: BufWriter<File>,
+    /// Whether the header has been written.
+    started: bool,
+}
+
+impl RunLogRecorder {
+    /// Creates a run log recorder.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The output file.
+    /// * `settings` - The recording settings.
+    pub fn new(path: &Path, settings: &RecordSettings) -> Result<RunLogRecorder, Box<dyn Error>> {
+        let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+
+        Ok(RunLogRecorder {
+            path: path.to_path_buf(),
+            settings: settings.clone(),
+            file: BufWriter::new(file),
+            started: false,
+        })
+    }
+}
+
+impl FrameRecorder for RunLogRecorder {
+    fn record(&mut self, grid: &[Vec<bool>], generation: u64) -> Result<(), Box<dyn Error>> {
+        if !generation.is_multiple_of(self.settings.every) {
+            return Ok(());
+        }
+
+        if !self.started {
+            let header = Header {
+                format: RUN_LOG_FORMAT.to_string(),
+                version: RUN_LOG_VERSION,
+                width: grid.first().map_or(0, |row| row.len()),
+                height: grid.len(),
+            };
+            writeln!(self.file, "{}", serde_json::to_string(&header)?)?;
+            self.started = true;
+        }
+
+        let frame = RunFrame {
+            generation,
+            population: grid.iter().flatten().filter(|&&cell| cell).count(),
+            hash: BoardHash::of(grid).to_string(),
+            cells: Pattern::from_rows(grid.to_vec()).to_rle(),
+        };
+        writeln!(self.file, "{}", serde_json::to_string(&frame)?)?;
+        Ok(())
+    }
+
+    fn finish(mut self: Box<Self>) -> Result<PathBuf, Box<dyn Error>> {
+        self.file.flush()?;
+        Ok(self.path)
+    }
+}
+
+/// The number of objects of one kind in two runs, for object kinds whose counts differ.
+pub type CensusDelta = (&'static str, usize, usize);
+
+/// The first generation at which two runs differ.
+#[derive(Debug)]
+pub struct Divergence {
+    /// The generation number.
+    pub generation: u64,
+    /// The board hashes of the two runs.
+    pub hashes: (String, String),
+    /// The populations of the two runs.
+    pub populations: (usize, usize),
+    /// The object kinds whose counts differ.
+    pub census: Vec<CensusDelta>,
+}
+
+/// The result of comparing two recorded runs generation by generation.
+#[derive(Debug)]
+pub struct RunDiff {
+    /// The files of the two runs.
+    pub paths: (PathBuf, PathBuf),
+    /// The board sizes of the two runs.
+    pub sizes: ((usize, usize), (usize, usize)),
+    /// The number of generations recorded in each run.
+    pub lengths: (usize, usize),
+    /// The number of generations recorded in both runs.
+    pub compared: usize,
+    /// The number of generations recorded in both runs whose boards differ.
+    pub differing: usize,
+    /// The first generation whose boards differ.
+    pub first_divergence: Option<Divergence>,
+    /// The largest difference in population, as (generation, population A, population B).
+    pub largest_population_gap: Option<(u64, usize, usize)>,
+    /// The last generation recorded in both runs, and the object kinds whose counts differ there.
+    pub final_census: Option<(u64, Vec<CensusDelta>)>,
+}
+
+impl RunDiff {
+    /// Returns whether the runs are identical in every generation they share.
+    pub fn identical(&self) -> bool {
+        self.first_divergence.is_none() && self.sizes.0 == self.sizes.1
+    }
+}
+
+/// Returns the object kinds whose counts differ between two boards.
+fn census_deltas(a: &[Vec<bool>], b: &[Vec<bool>]) -> Vec<CensusDelta> {
+    let (a, b) = (census::census(a), census::census(b));
+    let kinds: BTreeSet<&'static str> = a.keys().chain(b.keys()).copied().collect();
+    kinds
+        .into_iter()
+        .map(|kind| (kind, a.get(kind).copied().unwrap_or(0), b.get(kind).copied().unwrap_or(0)))
+        .filter(|&(_, a, b)| a != b)
+        .collect()
+}
+
+/// Compares two recorded runs generation by generation.
+///
+/// Generations are matched by number, so runs recorded with different `--record-every`
+/// settings are compared on the generations they share.
+///
+/// # Arguments
+///
+/// * `a` - The first run.
+/// * `b` - The second run.
+///
+/// # Returns
+///
+/// The comparison, including the first divergence and the object census deltas.
+pub fn compare(a: &RunLog, b: &RunLog) -> Result<RunDiff, Box<dyn Error>> {
+    let b_frames: HashMap<u64, &RunFrame> = b.frames.iter().map(|frame| (frame.generation, frame)).collect();
+    let common: Vec<(&RunFrame, &RunFrame)> = a
+        .frames
+        .iter()
+        .filter_map(|frame| b_frames.get(&frame.generation).map(|other| (frame, *other)))
+        .collect();
+
+    let mut diff = RunDiff {
+        paths: (a.path.clone(), b.path.clone()),
+        sizes: ((a.width, a.height), (b.width, b.height)),
+        lengths: (a.frames.len(), b.frames.len()),
+        compared: common.len(),
+        differing: 0,
+        first_divergence: None,
+        largest_population_gap: None,
+        final_census: None,
+    };
+
+    for &(frame_a, frame_b) in &common {
+        let gap = frame_a.population.abs_diff(frame_b.population);
+        let largest = diff.largest_population_gap.map_or(0, |(_, pop_a, pop_b)| pop_a.abs_diff(pop_b));
+        if gap > largest {
+            diff.largest_population_gap = Some((frame_a.generation, frame_a.population, frame_b.population));
+        }
+
+        if frame_a.hash == frame_b.hash {
+            continue;
+        }
+        diff.differing += 1;
+        if diff.first_divergence.is_none() {
+            diff.first_divergence = Some(Divergence {
+                generation: frame_a.generation,
+                hashes: (frame_a.hash.clone(), frame_b.hash.clone()),
+                populations: (frame_a.population, frame_b.population),
+                census: census_deltas(&a.grid(frame_a)?, &b.grid(frame_b)?),
+            });
+        }
+    }
+
+    if let Some(&(frame_a, frame_b)) = common.last() {
+        diff.final_census = Some((frame_a.generation, census_deltas(&a.grid(frame_a)?, &b.grid(frame_b)?)));
+    }
+    Ok(diff)
+}
+
+/// Formats census deltas as "block 4 vs 3, glider 0 vs 1", or "same" when there are none.
+fn format_census(deltas: &[CensusDelta]) -> String {
+    if deltas.is_empty() {
+        return "same".to_string();
+    }
+    deltas
+        .iter()
+        .map(|(kind, a, b)| format!("{} {} vs {}", kind, a, b))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl fmt::Display for RunDiff {
+    /// Shows the comparison as a report of several lines.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ((width_a, height_a), (width_b, height_b)) = self.sizes;
+        writeln!(
+            f,
+            "A: {} ({}x{}, {} generations)",
+            self.paths.0.display(),
+            width_a,
+            height_a,
+            self.lengths.0
+        )?;
+        writeln!(
+            f,
+            "B: {} ({}x{}, {} generations)",
+            self.paths.1.display(),
+            width_b,
+            height_b,
+            self.lengths.1
+        )?;
+        if self.sizes.0 != self.sizes.1 {
+            writeln!(f, "The boards have different sizes")?;
+        }
+        writeln!(f, "Generations compared: {}", self.compared)?;
+
+        match &self.first_divergence {
+            None => writeln!(f, "No divergence: the runs are identical in every compared generation")?,
+            Some(divergence) => {
+                writeln!(f, "First divergence: generation {}", divergence.generation)?;
+                writeln!(f, "  hash: {} vs {}", divergence.hashes.0, divergence.hashes.1)?;
+                writeln!(f, "  population: {} vs {}", divergence.populations.0, divergence.populations.1)?;
+                writeln!(f, "  census: {}", format_census(&divergence.census))?;
+                writeln!(f, "Differing generations: {} of {}", self.differing, self.compared)?;
+            }
+        }
+
+        if let Some((generation, pop_a, pop_b)) = self.largest_population_gap {
+            writeln!(
+                f,
+                "Largest population gap: {} at generation {} ({} vs {})",
+                pop_a.abs_diff(pop_b),
+                generation,
+                pop_a,
+                pop_b
+            )?;
+        }
+        if let Some((generation, deltas)) = &self.final_census {
+            write!(f, "Census at generation {}: {}", generation, format_census(deltas))?;
+        }
+        Ok(())
+    }
+}
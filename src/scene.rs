@@ -0,0 +1,302 @@
+use rand::Rng;
+use serde::Deserialize;
+use std::{error::Error, fs, path::Path};
+
+use crate::{brians_brain_step, raster::Rgb, rule::Rule, update_grid};
+
+/// The colors given to layers that do not set their own, one palette per layer.
+const DEFAULT_PALETTES: [&[Rgb]; 4] = [
+    &[Rgb(0x33, 0xff, 0x66)],
+    &[Rgb(0x66, 0x88, 0xff), Rgb(0x22, 0x33, 0x66)],
+    &[Rgb(0xff, 0x66, 0x33), Rgb(0x66, 0x22, 0x11)],
+    &[Rgb(0xff, 0xdd, 0x33), Rgb(0x66, 0x55, 0x11)],
+];
+
+/// The default probability that a cell of a layer starts alive.
+const DEFAULT_DENSITY: f64 = 0.2;
+
+/// A scene file, as written in TOML.
+///
+/// ```toml
+/// [[layers]]
+/// automaton = "life"
+/// rule = "B3/S23"
+/// density = 0.25
+/// colors = ["#33ff66"]
+///
+/// [[layers]]
+/// automaton = "brians-brain"
+/// density = 0.1
+/// colors = ["#6688ff", "#223366"]
+///
+/// [[couplings]]
+/// from = 0
+/// to = 1
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    /// The layers, bottom first.
+    layers: Vec<LayerFile>,
+    /// The couplings between layers.
+    #[serde(default)]
+    couplings: Vec<Coupling>,
+}
+
+/// A `[[layers]]` table of a scene file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LayerFile {
+    /// The automaton name: `life` or `brians-brain`.
+    automaton: String,
+    /// The rulestring of a `life` layer.
+    rule: Option<String>,
+    /// The probability that a cell starts alive (firing, for Brian's Brain).
+    density: Option<f64>,
+    /// The colors of the live states, as hex colors.
+    colors: Option<Vec<String>>,
+}
+
+/// A one-way coupling between two layers: cells born in one layer are born in the other too.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Coupling {
+    /// The index of the layer whose births seed the other.
+    pub from: usize,
+    /// The index of the layer that receives the births.
+    pub to: usize,
+}
+
+/// The cellular automaton that runs on a layer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayerAutomaton {
+    /// A Life-like automaton with two states: 0 dead and 1 alive.
+    Life(Rule),
+    /// Brian's Brain, with three states: 0 ready, 1 firing, and 2 refractory.
+    BriansBrain,
+}
+
+impl LayerAutomaton {
+    /// Returns the name of the automaton, as written in scene files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LayerAutomaton::Life(_) => "life",
+            LayerAutomaton::BriansBrain => "brians-brain",
+        }
+    }
+
+    /// Returns the number of states other than 0 (dead or ready).
+    fn live_states(&self) -> usize {
+        match self {
+            LayerAutomaton::Life(_) => 1,
+            LayerAutomaton::BriansBrain => 2,
+        }
+    }
+}
+
+/// One automaton of a scene, with its cells and colors.
+#[derive(Clone, Debug)]
+pub struct Layer {
+    /// The automaton that runs on the layer.
+    pub automaton: LayerAutomaton,
+    /// The probability that a cell starts alive.
+    pub density: f64,
+    /// The color of each state other than 0, in order.
+    pub palette: Vec<Rgb>,
+    /// The state of each cell, row by row.
+    pub cells: Vec<Vec<u8>>,
+}
+
+impl Layer {
+    /// Returns the number of cells in a state other than 0.
+    pub fn population(&self) -> usize {
+        self.cells.iter().flatten().filter(|&&state| state != 0).count()
+    }
+
+    /// Computes the next generation of the layer.
+    fn step(&self) -> Vec<Vec<u8>> {
+        match self.automaton {
+            LayerAutomaton::Life(rule) => {
+                let grid: Vec<Vec<bool>> = self.cells.iter().map(|row| row.iter().map(|&state| state != 0).collect()).collect();
+                update_grid(&grid, &rule)
+                    .into_iter()
+                    .map(|row| row.into_iter().map(u8::from).collect())
+                    .collect()
+            }
+            LayerAutomaton::BriansBrain => brians_brain_step(&self.cells),
+        }
+    }
+}
+
+/// Several automata running on separate layers of the same board.
+///
+/// Each layer evolves on its own, except that couplings carry the births of one layer
+/// into another after every generation. The layers are drawn on top of each other, with
+/// the colors of overlapping cells blended.
+#[derive(Clone, Debug)]
+pub struct Scene {
+    /// The layers, bottom first.
+    pub layers: Vec<Layer>,
+    /// The couplings between layers.
+    pub couplings: Vec<Coupling>,
+}
+
+impl Scene {
+    /// Reads a scene file.
+    ///
+    /// The layers are empty until [`Scene::seed`] is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The TOML scene file.
+    pub fn load(path: &Path) -> Result<Scene, Box<dyn Error>> {
+        let text = fs::read_to_string(path).map_err(|e| format!("Failed to read scene {}: {}", path.display(), e))?;
+        let file: SceneFile = toml::from_str(&text).map_err(|e| format!("Invalid scene {}: {}", path.display(), e))?;
+        let invalid = |message: String| -> Box<dyn Error> { format!("Invalid scene {}: {}", path.display(), message).into() };
+
+        if file.layers.is_empty() {
+            return Err(invalid("a scene needs at least one [[layers]] table".to_string()));
+        }
+
+        let mut layers = Vec::new();
+        for (i, layer) in file.layers.iter().enumerate() {
+            let automaton = match layer.automaton.as_str() {
+                "life" => LayerAutomaton::Life(match &layer.rule {
+                    Some(rule) => Rule::parse(rule).map_err(|e| invalid(format!("layer {}: {}", i, e)))?,
+                    None => Rule::default(),
+                }),
+                "brians-brain" => LayerAutomaton::BriansBrain,
+                other => {
+                    return Err(invalid(format!(
+                        "layer {}: unknown automaton '{}' (expected life or brians-brain)",
+                        i, other
+                    )))
+                }
+            };
+
+            let density = layer.density.unwrap_or(DEFAULT_DENSITY);
+            if !(0.0..=1.0).contains(&density) {
+                return Err(invalid(format!("layer {}: density must be between 0 and 1", i)));
+            }
+
+            // Missing colors come from the layer's default palette.
+            let defaults = DEFAULT_PALETTES[i % DEFAULT_PALETTES.len()];
+            let mut palette = Vec::new();
+            for state in 0..automaton.live_states() {
+                let color = match layer.colors.as_ref().and_then(|colors| colors.get(state)) {
+                    Some(color) => Rgb::parse(color).ok_or_else(|| invalid(format!("layer {}: invalid color '{}'", i, color)))?,
+                    None => defaults.get(state).or(defaults.last()).copied().unwrap_or(Rgb(0xff, 0xff, 0xff)),
+                };
+                palette.push(color);
+            }
+
+            layers.push(Layer {
+                automaton,
+                density,
+                palette,
+                cells: Vec::new(),
+            });
+        }
+
+        for coupling in &file.couplings {
+            if coupling.from >= layers.len() || coupling.to >= layers.len() || coupling.from == coupling.to {
+                return Err(invalid(format!(
+                    "coupling from {} to {} must join two different layers of the {}",
+                    coupling.from,
+                    coupling.to,
+                    layers.len()
+                )));
+            }
+        }
+
+        Ok(Scene {
+            layers,
+            couplings: file.couplings,
+        })
+    }
+
+    /// Fills every layer with random cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the board.
+    /// * `height` - The height of the board.
+    /// * `rng` - The random number generator.
+    pub fn seed(&mut self, width: usize, height: usize, rng: &mut impl Rng) {
+        for layer in &mut self.layers {
+            layer.cells = (0..height)
+                .map(|_| (0..width).map(|_| u8::from(rng.gen_bool(layer.density))).collect())
+                .collect();
+        }
+    }
+
+    /// Advances every layer by one generation, then applies the couplings.
+    pub fn step(&mut self) {
+        let next: Vec<Vec<Vec<u8>>> = self.layers.iter().map(Layer::step).collect();
+
+        // Births are cells that went from state 0 to state 1 in this generation.
+        let births: Vec<Vec<Vec<bool>>> = self
+            .layers
+            .iter()
+            .zip(&next)
+            .map(|(layer, next)| {
+                layer
+                    .cells
+                    .iter()
+                    .zip(next)
+                    .map(|(old, new)| old.iter().zip(new).map(|(&old, &new)| old == 0 && new == 1).collect())
+                    .collect()
+            })
+            .collect();
+
+        for (layer, cells) in self.layers.iter_mut().zip(next) {
+            layer.cells = cells;
+        }
+        for coupling in &self.couplings {
+            let target = &mut self.layers[coupling.to].cells;
+            for (row, born_row) in target.iter_mut().zip(&births[coupling.from]) {
+                for (state, &born) in row.iter_mut().zip(born_row) {
+                    if born && *state == 0 {
+                        *state = 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Blends the layers into one color per cell.
+    ///
+    /// Where several layers have a cell in a state other than 0, their colors are averaged.
+    ///
+    /// # Returns
+    ///
+    /// The color of each cell, or `None` where every layer is empty, row by row.
+    pub fn colors(&self) -> Vec<Vec<Option<Rgb>>> {
+        let height = self.layers.first().map_or(0, |layer| layer.cells.len());
+        let width = self.layers.first().and_then(|layer| layer.cells.first()).map_or(0, |row| row.len());
+
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let colors: Vec<Rgb> = self
+                            .layers
+                            .iter()
+                            .filter_map(|layer| match layer.cells[y][x] {
+                                0 => None,
+                                state => layer.palette.get(state as usize - 1).copied(),
+                            })
+                            .collect();
+                        if colors.is_empty() {
+                            return None;
+                        }
+                        let average = |channel: fn(&Rgb) -> u8| {
+                            (colors.iter().map(|color| channel(color) as usize).sum::<usize>() / colors.len()) as u8
+                        };
+                        Some(Rgb(average(|c| c.0), average(|c| c.1), average(|c| c.2)))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
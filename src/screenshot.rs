@@ -0,0 +1,64 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use gameoflife::raster::{self, RasterStyle};
+
+/// Describes the simulation state captioned on a screenshot.
+pub struct Caption<'a> {
+    /// The rulestring of the simulation.
+    pub rule: &'a str,
+    /// The generation shown on the screenshot.
+    pub generation: u64,
+    /// The seed of the random number generator.
+    pub seed: u64,
+}
+
+impl Caption<'_> {
+    /// Formats the caption text.
+    fn text(&self) -> String {
+        format!("{}  GEN {}  SEED {}", self.rule, self.generation, self.seed)
+    }
+}
+
+/// Renders the board to a styled PNG in the screenshots directory.
+///
+/// The file is named after the seed and generation, with a numeric suffix added
+/// if a screenshot of the same generation already exists.
+///
+/// # Arguments
+///
+/// * `grid` - The grid to capture.
+/// * `dir` - The screenshots directory, created if it does not exist.
+/// * `style` - The raster style; its caption is replaced by `caption`.
+/// * `caption` - The simulation state to caption the screenshot with.
+///
+/// # Returns
+///
+/// The path of the saved screenshot.
+pub fn save_screenshot(
+    grid: &[Vec<bool>],
+    dir: &Path,
+    style: &RasterStyle,
+    caption: &Caption,
+) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    let stem = format!("gol-{}-gen{:06}", caption.seed, caption.generation);
+    let mut path = dir.join(format!("{}.png", stem));
+    let mut suffix = 1;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.png", stem, suffix));
+        suffix += 1;
+    }
+
+    let style = RasterStyle {
+        caption: Some(caption.text()),
+        ..style.clone()
+    };
+    raster::render_grid(grid, &style).save_png(&path)?;
+
+    Ok(path)
+}
@@ -0,0 +1,275 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    io::{BufRead, BufReader, Write},
+    process::{Child, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use gameoflife::rule::Rule;
+use serde::Serialize;
+
+use crate::control::ControlRequest;
+
+/// How long a script may take to exit once the run is over.
+const EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How long a script may take over its turn for one event, commands included, before it is
+/// stopped so the simulation can carry on.
+pub const TURN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Something that happened in the simulation, sent to the script as one JSON line whose
+/// `event` field names it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum ScriptEvent {
+    /// The simulation is about to start.
+    Init {
+        /// The width of the board in cells.
+        width: usize,
+        /// The height of the board in cells.
+        height: usize,
+        /// The generation the board starts at.
+        generation: u64,
+        /// The rule of the run, as a rulestring.
+        rule: Rule,
+    },
+    /// A generation was computed.
+    ///
+    /// The cells are not sent, which would make every line as large as the board; a script
+    /// that needs them asks for them with `status` and `"cells": true`.
+    Generation {
+        /// The generation the board is at.
+        generation: u64,
+        /// The number of live cells.
+        population: usize,
+    },
+    /// A key was pressed. The key also does what it is bound to, if anything.
+    Key {
+        /// The name of the key, such as `"k"`, `"left"`, `"f5"`, or `"ctrl+k"`.
+        key: String,
+    },
+}
+
+impl ScriptEvent {
+    /// Returns the event of a key press.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key event.
+    ///
+    /// # Returns
+    ///
+    /// The event, or `None` for keys without a name, such as a lone modifier.
+    pub fn key(key: &KeyEvent) -> Option<ScriptEvent> {
+        let name = match key.code {
+            KeyCode::Char(' ') => "space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::F(n) => format!("f{}", n),
+            KeyCode::Left => "left".to_string(),
+            KeyCode::Right => "right".to_string(),
+            KeyCode::Up => "up".to_string(),
+            KeyCode::Down => "down".to_string(),
+            KeyCode::Enter => "enter".to_string(),
+            KeyCode::Esc => "esc".to_string(),
+            KeyCode::Tab => "tab".to_string(),
+            KeyCode::Backspace => "backspace".to_string(),
+            _ => return None,
+        };
+        let key = if key.modifiers.contains(KeyModifiers::CONTROL) { format!("ctrl+{}", name) } else { name };
+        Some(ScriptEvent::Key { key })
+    }
+}
+
+/// A script run alongside the simulation with `--script`, which is told about the start of
+/// the run, every generation, and every key press, and can answer each with commands.
+///
+/// The script is any program, started with the shell. The events are written to its
+/// standard input, one JSON line each, and after each one the simulation waits for the
+/// script's turn: the script writes commands to its standard output, in the JSON of the
+/// [control socket](crate::control::ControlSocket), reads the reply to each from its
+/// standard input, and ends the turn with an empty line. A script that takes longer than
+/// [`TURN_TIMEOUT`] over a turn is stopped. Its standard error is discarded, since the
+/// terminal is drawn on.
+pub struct Script {
+    /// The running script, with the input the events and replies are written to.
+    child: Child,
+    /// The lines of its output, read on a thread of their own so a turn can time out.
+    output: Receiver<String>,
+    /// The events the script has not had its turn for yet.
+    events: VecDeque<ScriptEvent>,
+    /// Whether the script has exited or stopped reading.
+    ended: bool,
+    /// Whether the script was stopped for taking too long over a turn.
+    timed_out: bool,
+}
+
+impl Script {
+    /// Starts a script.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command line of the script, run with `sh -c`, such as
+    ///   `python3 seed.py` or `lua hooks.lua`.
+    pub fn spawn(command: &str) -> Result<Script, Box<dyn Error>> {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Cannot start the script '{}': {}", command, e))?;
+        let stdout = child.stdout.take().expect("the script's output is piped");
+        let (lines, output) = mpsc::channel();
+        thread::spawn(move || {
+            // The thread ends when the script closes its output, or once the script is
+            // dropped and no one takes the lines.
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if lines.send(line).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Script { child, output, events: VecDeque::new(), ended: false, timed_out: false })
+    }
+
+    /// Queues an event, which the script gets its turn for at the next [`Script::turn`].
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event.
+    pub fn push(&mut self, event: ScriptEvent) {
+        if !self.ended {
+            self.events.push_back(event);
+        }
+    }
+
+    /// Returns whether there are events the script has not had its turn for yet, so the
+    /// simulation waits for it before computing the next generation.
+    pub fn is_due(&self) -> bool {
+        !self.events.is_empty()
+    }
+
+    /// Returns whether the script has exited or stopped reading its events.
+    pub fn ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Returns whether the script was stopped for taking longer than [`TURN_TIMEOUT`] over
+    /// a turn.
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Sends the queued events and takes the script's commands in reply.
+    ///
+    /// # Returns
+    ///
+    /// The commands, which are read one at a time: each is only read once the reply to
+    /// the one before it was given, so they can be carried out by the same code as those
+    /// of the control socket.
+    pub fn turn(&mut self) -> ScriptTurn<'_> {
+        ScriptTurn { script: self, answer: None, deadline: None }
+    }
+
+    /// Writes one line to the script, or marks it ended if it stopped reading.
+    fn send(&mut self, line: &str) -> Option<()> {
+        let input = self.child.stdin.as_mut().expect("the script's input is piped");
+        let sent = writeln!(input, "{}", line).and_then(|()| input.flush());
+        self.end_if(sent.is_err());
+        sent.ok()
+    }
+
+    /// Reads one line from the script, or marks it ended if it exited.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - When the script's turn is over; a script that has not written the
+    ///   line by then is stopped.
+    fn receive(&mut self, deadline: Instant) -> Option<String> {
+        match self.output.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(line) => Some(line),
+            Err(RecvTimeoutError::Timeout) => {
+                let _ = self.child.kill();
+                self.timed_out = true;
+                self.end_if(true);
+                None
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                self.end_if(true);
+                None
+            }
+        }
+    }
+
+    /// Marks the script ended, with no more turns for the events it was sent.
+    fn end_if(&mut self, ended: bool) {
+        if ended {
+            self.ended = true;
+            self.events.clear();
+        }
+    }
+}
+
+impl Drop for Script {
+    fn drop(&mut self) {
+        // Closing its input tells the script the run is over, so it can write out what it
+        // measured. A script that does not exit soon after is stopped.
+        drop(self.child.stdin.take());
+        let deadline = Instant::now() + EXIT_TIMEOUT;
+        while matches!(self.child.try_wait(), Ok(None)) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// The commands a script sends for its queued events, from [`Script::turn`].
+pub struct ScriptTurn<'a> {
+    /// The script.
+    script: &'a mut Script,
+    /// Where the reply to the last command arrives, until it is passed on.
+    answer: Option<Receiver<String>>,
+    /// When the turn for the event last sent is over, until the script ends it.
+    deadline: Option<Instant>,
+}
+
+impl Iterator for ScriptTurn<'_> {
+    type Item = ControlRequest;
+
+    fn next(&mut self) -> Option<ControlRequest> {
+        loop {
+            // The script gets the reply to its last command before it sends the next.
+            if let Some(answer) = self.answer.take() {
+                let reply = answer.recv().ok()?;
+                self.script.send(&reply)?;
+            }
+            let deadline = match self.deadline {
+                Some(deadline) => deadline,
+                None => {
+                    let event = self.script.events.pop_front()?;
+                    self.script.send(&serde_json::to_string(&event).expect("events serialize to JSON"))?;
+                    *self.deadline.insert(Instant::now() + TURN_TIMEOUT)
+                }
+            };
+
+            let line = self.script.receive(deadline)?;
+            if line.trim().is_empty() {
+                self.deadline = None;
+                continue;
+            }
+            match ControlRequest::from_line(&line) {
+                Ok((request, answer)) => {
+                    self.answer = Some(answer);
+                    return Some(request);
+                }
+                Err(reply) => self.script.send(&reply)?,
+            }
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use rand::{seq::SliceRandom, Rng, RngCore};
+use std::fmt;
+
+use crate::{random_grid, Grid};
+
+/// Makes the random board a run starts from.
+///
+/// Every seeder is given the probability the board was asked for, and spreads its live
+/// cells over the board in its own way.
+pub trait Seeder {
+    /// Generates a board.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of columns.
+    /// * `height` - The number of rows.
+    /// * `probability` - The probability that a cell starts alive, on average over the board.
+    /// * `rng` - The random number generator.
+    fn seed(&self, width: usize, height: usize, probability: f64, rng: &mut dyn RngCore) -> Grid;
+}
+
+/// Every cell starts alive with the same probability.
+pub struct Uniform;
+
+impl Seeder for Uniform {
+    fn seed(&self, width: usize, height: usize, probability: f64, mut rng: &mut dyn RngCore) -> Grid {
+        random_grid(width, height, probability, &mut rng)
+    }
+}
+
+/// Fills a board whose cells each have a probability of their own.
+///
+/// # Arguments
+///
+/// * `width` - The number of columns.
+/// * `height` - The number of rows.
+/// * `rng` - The random number generator.
+/// * `probability_at` - The probability that the cell at a column and row starts alive.
+fn seed_by_cell(width: usize, height: usize, rng: &mut dyn RngCore, probability_at: impl Fn(usize, usize) -> f64) -> Grid {
+    (0..height)
+        .map(|y| (0..width).map(|x| rng.gen_bool(probability_at(x, y).clamp(0.0, 1.0))).collect())
+        .collect()
+}
+
+/// Returns how far a cell is along a board, from 0 at the first one to 1 at the last.
+fn fraction(index: usize, len: usize) -> f64 {
+    if len > 1 {
+        index as f64 / (len - 1) as f64
+    } else {
+        0.5
+    }
+}
+
+/// Dense in the middle of the board and thinning out towards the edges.
+///
+/// The probability is twice the one asked for in the middle and falls off with the
+/// distance from it, reaching nothing at the middle of each edge.
+pub struct Radial;
+
+impl Seeder for Radial {
+    fn seed(&self, width: usize, height: usize, probability: f64, rng: &mut dyn RngCore) -> Grid {
+        seed_by_cell(width, height, rng, |x, y| {
+            let (dx, dy) = (2.0 * fraction(x, width) - 1.0, 2.0 * fraction(y, height) - 1.0);
+            2.0 * probability * (1.0 - (dx * dx + dy * dy).sqrt()).max(0.0)
+        })
+    }
+}
+
+/// Dense on the left of the board and thinning out towards the right.
+///
+/// The probability is twice the one asked for in the first column and none in the last.
+pub struct Horizontal;
+
+impl Seeder for Horizontal {
+    fn seed(&self, width: usize, height: usize, probability: f64, rng: &mut dyn RngCore) -> Grid {
+        seed_by_cell(width, height, rng, |x, _| 2.0 * probability * (1.0 - fraction(x, width)))
+    }
+}
+
+/// The number of cells of the board for every blob of [`Clusters`].
+const CELLS_PER_CLUSTER: usize = 400;
+
+/// Round blobs of live cells scattered over an otherwise empty board.
+///
+/// There is a blob for every few hundred cells of the board, each with a random size, in
+/// which cells start alive with twice the probability asked for.
+pub struct Clusters;
+
+impl Seeder for Clusters {
+    fn seed(&self, width: usize, height: usize, probability: f64, rng: &mut dyn RngCore) -> Grid {
+        let mut grid = vec![vec![false; width]; height];
+        if width == 0 || height == 0 {
+            return grid;
+        }
+
+        let max_radius = (width.min(height) / 6).max(3);
+        for _ in 0..(width * height / CELLS_PER_CLUSTER).max(1) {
+            let (cx, cy) = (rng.gen_range(0..width) as isize, rng.gen_range(0..height) as isize);
+            let radius = rng.gen_range(2..=max_radius) as isize;
+            for y in (cy - radius).max(0)..(cy + radius + 1).min(height as isize) {
+                for x in (cx - radius).max(0)..(cx + radius + 1).min(width as isize) {
+                    let inside = (x - cx).pow(2) + (y - cy).pow(2) <= radius * radius;
+                    if inside && rng.gen_bool((2.0 * probability).min(1.0)) {
+                        grid[y as usize][x as usize] = true;
+                    }
+                }
+            }
+        }
+        grid
+    }
+}
+
+/// The noise level above which cells of a [`Perlin`] board start alive by default.
+pub const DEFAULT_THRESHOLD: f64 = 0.5;
+
+/// The width and height, in cells, of the features of the coarsest octave of noise.
+const NOISE_SCALE: f64 = 12.0;
+
+/// The number of octaves of noise, each with features half the size of the one before.
+const OCTAVES: u32 = 3;
+
+/// Connected regions of live cells, cut out of Perlin noise.
+///
+/// The noise varies smoothly over the board, so the cells where it is above the threshold
+/// form organic blobs and channels instead of scattered single cells. A higher threshold
+/// leaves fewer and smaller regions. The probability of the board is only used to leave it
+/// empty when it is 0, as it is under placed patterns.
+pub struct Perlin {
+    /// The noise level, from 0 to 1, above which cells start alive.
+    pub threshold: f64,
+}
+
+impl Perlin {
+    /// Returns the noise at a point, from -1 to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `permutation` - The shuffled numbers from 0 to 255 that pick the gradients.
+    /// * `x` - The column of the point, in lattice cells.
+    /// * `y` - The row of the point, in lattice cells.
+    fn noise(permutation: &[usize], x: f64, y: f64) -> f64 {
+        let (xi, yi) = (x.floor() as i64 as usize, y.floor() as i64 as usize);
+        let (xf, yf) = (x - x.floor(), y - y.floor());
+        let hash = |i: usize, j: usize| permutation[(permutation[i & 255] + j) & 255];
+        let gradient = |hash: usize, x: f64, y: f64| match hash & 7 {
+            0 => x + y,
+            1 => y - x,
+            2 => x - y,
+            3 => -x - y,
+            4 => x,
+            5 => -x,
+            6 => y,
+            _ => -y,
+        };
+        let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |a: f64, b: f64, t: f64| a + t * (b - a);
+
+        let (u, v) = (fade(xf), fade(yf));
+        let top = lerp(gradient(hash(xi, yi), xf, yf), gradient(hash(xi + 1, yi), xf - 1.0, yf), u);
+        let bottom = lerp(gradient(hash(xi, yi + 1), xf, yf - 1.0), gradient(hash(xi + 1, yi + 1), xf - 1.0, yf - 1.0), u);
+        lerp(top, bottom, v).clamp(-1.0, 1.0)
+    }
+}
+
+impl Seeder for Perlin {
+    fn seed(&self, width: usize, height: usize, probability: f64, rng: &mut dyn RngCore) -> Grid {
+        if probability <= 0.0 {
+            return vec![vec![false; width]; height];
+        }
+
+        let mut permutation: Vec<usize> = (0..256).collect();
+        permutation.shuffle(rng);
+        // Start somewhere in the noise other than the lattice corner, where it is always 0.
+        let (offset_x, offset_y) = (rng.gen_range(0.0..256.0), rng.gen_range(0.0..256.0));
+
+        let amplitudes: f64 = (0..OCTAVES).map(|octave| 0.5f64.powi(octave as i32)).sum();
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let total: f64 = (0..OCTAVES)
+                            .map(|octave| {
+                                let frequency = 2f64.powi(octave as i32) / NOISE_SCALE;
+                                let point = (offset_x + x as f64 * frequency, offset_y + y as f64 * frequency);
+                                0.5f64.powi(octave as i32) * Perlin::noise(&permutation, point.0, point.1)
+                            })
+                            .sum();
+                        (total / amplitudes + 1.0) / 2.0 >= self.threshold
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// The luminance below which cells of a [`Picture`] board start alive by default.
+pub const DEFAULT_LUMINANCE_THRESHOLD: f64 = 0.5;
+
+/// A picture stretched over the board, with cells alive where it is dark.
+///
+/// Every cell takes the average luminance of the pixels that fall on it, or of the nearest
+/// pixel when the board has more cells than the picture has pixels, and starts alive when
+/// that is below the threshold. The probability of the board is not used, so patterns
+/// placed on it go over the picture.
+#[derive(Clone, Debug)]
+pub struct Picture {
+    /// The luminance of every pixel, row by row, from 0 for black to 1 for white.
+    luminance: Vec<Vec<f64>>,
+    /// The luminance, from 0 to 1, below which cells start alive.
+    pub threshold: f64,
+}
+
+impl Picture {
+    /// Creates a picture from the luminance of its pixels.
+    ///
+    /// # Arguments
+    ///
+    /// * `luminance` - The luminance of every pixel, row by row, from 0 for black to 1 for white.
+    /// * `threshold` - The luminance below which cells start alive.
+    pub fn new(luminance: Vec<Vec<f64>>, threshold: f64) -> Picture {
+        Picture { luminance, threshold }
+    }
+
+    /// Returns the width and height of the picture in pixels.
+    pub fn size(&self) -> (usize, usize) {
+        (self.luminance.first().map_or(0, |row| row.len()), self.luminance.len())
+    }
+}
+
+impl Seeder for Picture {
+    fn seed(&self, width: usize, height: usize, _probability: f64, _rng: &mut dyn RngCore) -> Grid {
+        let (columns, rows) = self.size();
+        if columns == 0 || rows == 0 {
+            return vec![vec![false; width]; height];
+        }
+
+        // The pixels from the first to the last one that fall on a cell, at least one.
+        let span = |cell: usize, cells: usize, pixels: usize| {
+            let start = (cell * pixels / cells).min(pixels - 1);
+            start..((cell + 1) * pixels / cells).clamp(start + 1, pixels)
+        };
+        (0..height)
+            .map(|y| {
+                let ys = span(y, height, rows);
+                (0..width)
+                    .map(|x| {
+                        let xs = span(x, width, columns);
+                        let total: f64 = self.luminance[ys.clone()].iter().map(|row| row[xs.clone()].iter().sum::<f64>()).sum();
+                        total / ((xs.len() * ys.len()) as f64) < self.threshold
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// How the live cells of a random board are spread, chosen with `--seed-mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SeedMode {
+    /// The same probability everywhere.
+    #[default]
+    Uniform,
+    /// Dense in the middle, sparse at the edges.
+    Radial,
+    /// Dense on the left, sparse on the right.
+    Horizontal,
+    /// Blobs of cells on an empty board.
+    Clusters,
+    /// Regions of Perlin noise above a threshold, from 0 to 1.
+    Noise {
+        /// The noise level above which cells start alive.
+        threshold: f64,
+    },
+}
+
+impl SeedMode {
+    /// Parses a seed mode name: `uniform`, `radial`, `horizontal`, `clusters`, or `noise`,
+    /// which starts with the [default threshold](DEFAULT_THRESHOLD).
+    pub fn parse(name: &str) -> Option<SeedMode> {
+        match name {
+            "uniform" => Some(SeedMode::Uniform),
+            "radial" => Some(SeedMode::Radial),
+            "horizontal" => Some(SeedMode::Horizontal),
+            "clusters" => Some(SeedMode::Clusters),
+            "noise" => Some(SeedMode::Noise { threshold: DEFAULT_THRESHOLD }),
+            _ => None,
+        }
+    }
+
+    /// Returns the seeder that makes boards in this mode.
+    pub fn seeder(&self) -> Box<dyn Seeder> {
+        match self {
+            SeedMode::Uniform => Box::new(Uniform),
+            SeedMode::Radial => Box::new(Radial),
+            SeedMode::Horizontal => Box::new(Horizontal),
+            SeedMode::Clusters => Box::new(Clusters),
+            SeedMode::Noise { threshold } => Box::new(Perlin { threshold: *threshold }),
+        }
+    }
+}
+
+impl fmt::Display for SeedMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SeedMode::Uniform => "uniform",
+            SeedMode::Radial => "radial",
+            SeedMode::Horizontal => "horizontal",
+            SeedMode::Clusters => "clusters",
+            SeedMode::Noise { .. } => "noise",
+        })
+    }
+}
@@ -0,0 +1,282 @@
+use std::{
+    collections::HashSet,
+    error::Error,
+    io::{BufReader, BufWriter, ErrorKind, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, Receiver, SyncSender, TryRecvError, TrySendError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use gameoflife::{
+    noise::Noise,
+    rule::Rule,
+    stream::{self, Message, MAGIC},
+    world::World,
+    Grid,
+};
+
+use crate::{
+    display::{clear_screen, display_grid, display_status, DisplayStyle, Region, Screen, Terminal},
+    input::{self, Command, Input},
+};
+
+/// How long a client may take to accept a generation before it is dropped.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The most generations waiting to be sent to a client before it is dropped as too slow.
+const CLIENT_QUEUE: usize = 64;
+
+/// How often the client checks for new generations and key presses.
+const CLIENT_TICK: Duration = Duration::from_millis(20);
+
+/// A client of the server, written to by a thread of its own.
+struct Client {
+    /// The address of the client, for the log.
+    peer: String,
+    /// Where the encoded generations for the writer are queued.
+    queue: SyncSender<Arc<[u8]>>,
+    /// The writer thread, which ends once the queue is dropped and emptied.
+    writer: JoinHandle<()>,
+}
+
+impl Client {
+    /// Starts writing the stream to a client that just connected.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The connection to the client.
+    /// * `peer` - The address of the client.
+    /// * `board` - The whole board, sent first.
+    fn start(stream: TcpStream, peer: String, board: Message) -> Client {
+        let (queue, generations) = mpsc::sync_channel(CLIENT_QUEUE);
+        let name = peer.clone();
+        let writer = thread::spawn(move || {
+            if let Err(e) = write_stream(stream, &board, generations) {
+                println!("{} disconnected ({})", name, e);
+            }
+        });
+        Client { peer, queue, writer }
+    }
+}
+
+/// Sends the start of a stream and the whole board to a client, then each generation
+/// queued for it, until the queue is dropped.
+fn write_stream(mut client: TcpStream, board: &Message, generations: Receiver<Arc<[u8]>>) -> Result<(), Box<dyn Error>> {
+    client.set_nonblocking(false)?;
+    client.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    client.set_nodelay(true)?;
+    let mut out = BufWriter::new(&mut client);
+    out.write_all(MAGIC)?;
+    board.write(&mut out)?;
+    out.flush()?;
+    drop(out);
+    for encoded in generations {
+        client.write_all(&encoded)?;
+    }
+    Ok(())
+}
+
+/// Runs the simulation without a terminal and streams every generation to TCP clients.
+///
+/// Clients can connect at any time: each one is sent the whole board first, then the
+/// changes of every generation. Each client is written to by a thread of its own, so a
+/// slow one never holds up the simulation: a client that falls more than
+/// [`CLIENT_QUEUE`] generations behind, takes longer than [`WRITE_TIMEOUT`] over a write,
+/// or disconnects is dropped, and the simulation keeps going.
+///
+/// # Arguments
+///
+/// * `address` - The address to listen on, such as `0.0.0.0:7777`.
+/// * `world` - The initial board, in the engine and with the boundary it is stepped with.
+/// * `rule` - The rule to apply.
+/// * `speed` - The number of generations per second.
+/// * `generations` - The number of generations to stream, or `None` to run until stopped.
+/// * `noise` - Random flips made before each generation, if any.
+pub fn serve(
+    address: &str,
+    mut world: World,
+    rule: &Rule,
+    speed: f64,
+    generations: Option<u64>,
+    mut noise: Option<&mut Noise>,
+) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(address).map_err(|e| format!("Cannot listen on {}: {}", address, e))?;
+    listener.set_nonblocking(true)?;
+    let rulestring = rule.to_string();
+    let (width, height) = world.bounds();
+    println!("Streaming a {}x{} board ({}) on {}", width, height, rulestring, listener.local_addr()?);
+
+    let mut clients: Vec<Client> = Vec::new();
+    let mut generation = 0;
+    let tick = Duration::from_secs_f64(1.0 / speed);
+    let mut next_tick = Instant::now();
+
+    while generations.is_none_or(|limit| generation < limit) {
+        // Welcome everyone who connected since the last generation.
+        loop {
+            match listener.accept() {
+                Ok((client, peer)) => {
+                    println!("{} connected", peer);
+                    let board = Message::Board { generation, rule: rulestring.clone(), grid: world.to_vec() };
+                    clients.push(Client::start(client, peer.to_string(), board));
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                // A failed connection, or running out of file descriptors, only costs the
+                // client; the others are still served and accepting is tried again.
+                Err(e) => {
+                    println!("Could not accept a client: {}", e);
+                    break;
+                }
+            }
+        }
+
+        next_tick += tick;
+        thread::sleep(next_tick.saturating_duration_since(Instant::now()));
+
+        // A cell flipped by the noise and back by the step did not change.
+        let mut flipped = HashSet::new();
+        if let Some(noise) = noise.as_deref_mut() {
+            for (x, y) in noise.flips(width, height) {
+                world.toggle(x, y);
+                flipped.insert((x, y));
+            }
+        }
+        world.step(rule);
+        generation += 1;
+        for &cell in world.changes() {
+            if !flipped.remove(&cell) {
+                flipped.insert(cell);
+            }
+        }
+        let mut flipped: Vec<(usize, usize)> = flipped.into_iter().collect();
+        flipped.sort_unstable_by_key(|&(x, y)| (y, x));
+        let mut encoded = Vec::new();
+        Message::flipped(&flipped, &world, generation, &rulestring).write(&mut encoded)?;
+        let encoded: Arc<[u8]> = encoded.into();
+
+        clients.retain(|client| match client.queue.try_send(Arc::clone(&encoded)) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                println!("{} fell {} generations behind and was dropped", client.peer, CLIENT_QUEUE);
+                false
+            }
+            // The writer stopped on an error, which it reported.
+            Err(TrySendError::Disconnected(_)) => false,
+        });
+    }
+
+    // The clients still connected are sent what is queued for them before the server stops.
+    for Client { queue, writer, .. } in clients {
+        drop(queue);
+        let _ = writer.join();
+    }
+    println!("Streamed {} generations", generation);
+    Ok(())
+}
+
+/// Connects to a server started with `--serve` and draws the board it streams.
+///
+/// Generations are received on a thread of their own and drawn as they arrive, so the
+/// server sets the pace. `q` quits; when the server goes away, the last board stays on
+/// screen until then.
+///
+/// # Arguments
+///
+/// * `address` - The address of the server, such as `example.com:7777`.
+/// * `style` - The render mode and glyphs to draw with.
+/// * `terminal` - The size of the terminal in characters.
+pub fn connect(address: &str, style: &DisplayStyle, terminal: (usize, usize)) -> Result<(), Box<dyn Error>> {
+    let connection = TcpStream::connect(address).map_err(|e| format!("Cannot connect to {}: {}", address, e))?;
+    let mut reader = BufReader::new(connection);
+    stream::read_header(&mut reader)?;
+
+    // Errors cross threads as text.
+    let (sender, receiver) = mpsc::channel::<Result<Message, String>>();
+    thread::spawn(move || loop {
+        let message = match Message::read(&mut reader) {
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => break,
+            Err(e) => Err(e.to_string()),
+        };
+        let failed = message.is_err();
+        if sender.send(message).is_err() || failed {
+            break;
+        }
+    });
+
+    let mut term = Terminal::new(None);
+    let screen = Screen::enter()?;
+    let raw_mode = input::RawMode::enable().ok();
+    clear_screen(&mut term, style)?;
+
+    let (cols, rows) = (terminal.0, terminal.1.saturating_sub(1));
+    let mut grid: Grid = Vec::new();
+    let mut prev_grid: Grid = Vec::new();
+    let mut generation = 0;
+    let mut rule = String::new();
+    // Why the stream stopped, once it has.
+    let mut ended: Option<String> = None;
+    let mut running = true;
+
+    while running {
+        let mut changed = false;
+        loop {
+            match receiver.try_recv() {
+                Ok(Ok(message)) => {
+                    generation = message.generation();
+                    if let Message::Board { rule: board_rule, grid: board, .. } = &message {
+                        // A board of another size is drawn from scratch.
+                        if board.len() != grid.len() || board.first().map(Vec::len) != grid.first().map(Vec::len) {
+                            clear_screen(&mut term, style)?;
+                            prev_grid = vec![vec![false; board.first().map_or(0, Vec::len)]; board.len()];
+                        }
+                        rule = board_rule.clone();
+                    }
+                    message.apply(&mut grid);
+                    changed = true;
+                }
+                Ok(Err(e)) => ended = Some(e),
+                Err(TryRecvError::Disconnected) => {
+                    ended.get_or_insert_with(|| "the server closed the stream".to_string());
+                    break;
+                }
+                Err(TryRecvError::Empty) => break,
+            }
+        }
+
+        if changed {
+            display_grid(&mut term, &grid, &prev_grid, style, None, Region::screen(cols, rows))?;
+            prev_grid = grid.clone();
+        }
+        let population = grid.iter().flatten().filter(|&&cell| cell).count();
+        let status = match &ended {
+            Some(reason) => format!("gen {} | pop {} | {} | {}; q quits", generation, population, rule, reason),
+            None => format!("gen {} | pop {} | {} | {}", generation, population, rule, address),
+        };
+        display_status(&mut term, &status, rows, cols, style)?;
+        term.flush()?;
+
+        let inputs = if raw_mode.is_some() {
+            input::wait_for_input(CLIENT_TICK)?
+        } else {
+            thread::sleep(CLIENT_TICK);
+            Vec::new()
+        };
+        for input in inputs {
+            if let Input::Key(key) = input {
+                if input::map_key(&key) == Some(Command::Quit) {
+                    running = false;
+                }
+            }
+        }
+    }
+
+    drop(raw_mode);
+    drop(screen);
+    println!("Exiting...");
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! The settings of a run that can be changed while it runs.
+//!
+//! They serialize with serde, with the rule as its rulestring as in snapshots and replays,
+//! so the control socket reports and takes them as JSON in the same schema.
+
+use serde::{Deserialize, Serialize};
+
+use crate::rule::Rule;
+
+/// The slowest speed a run takes, in generations per second. Slower speeds would wait
+/// longer between generations than the clocks of the program can count.
+pub const MIN_SPEED: f64 = 0.001;
+
+/// The settings of a running simulation.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    /// The rule the board follows.
+    pub rule: Rule,
+    /// The number of generations computed per second.
+    pub speed: f64,
+    /// The number of generations computed before each frame is drawn.
+    pub steps_per_frame: u32,
+    /// Whether the simulation is paused.
+    pub paused: bool,
+}
+
+impl Settings {
+    /// Checks that the settings can be run with.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_steps_per_frame` - The most generations that may be computed per frame.
+    ///
+    /// # Returns
+    ///
+    /// An explanation of the first setting that is out of range, if any.
+    pub fn check(&self, max_steps_per_frame: u32) -> Result<(), String> {
+        if !(self.speed.is_finite() && self.speed >= MIN_SPEED) {
+            return Err(format!("the speed must be at least {} generations per second, not {}", MIN_SPEED, self.speed));
+        }
+        if !(1..=max_steps_per_frame).contains(&self.steps_per_frame) {
+            return Err(format!("the steps per frame must be between 1 and {}", max_steps_per_frame));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! The sixel encoder that `--render sixel` draws the board with, as real pixels on the
+//! terminals that show sixel graphics.
+
+use std::fmt::Write;
+
+use crate::raster::{Image, Rgb};
+
+/// The most colors a sixel image registers; the rest are drawn in the nearest of them.
+pub const MAX_COLORS: usize = 256;
+
+/// Encodes an image as a sixel escape sequence, ready to be written to a terminal.
+///
+/// The image is sent in bands six pixels high, each with one pass per color that is in
+/// it, and runs of the same column pattern are run-length encoded. The image is drawn at
+/// the cursor, so it is moved to the right place first.
+///
+/// # Arguments
+///
+/// * `image` - The image to encode.
+///
+/// # Returns
+///
+/// The escape sequence, from the `ESC P` that starts it to the `ESC \` that ends it.
+pub fn encode(image: &Image) -> String {
+    let mut palette: Vec<Rgb> = Vec::new();
+    let indices: Vec<usize> = image
+        .pixels
+        .chunks(3)
+        .map(|pixel| {
+            let color = Rgb(pixel[0], pixel[1], pixel[2]);
+            match palette.iter().position(|&known| known == color) {
+                Some(index) => index,
+                None if palette.len() < MAX_COLORS => {
+                    palette.push(color);
+                    palette.len() - 1
+                }
+                None => nearest(&palette, color),
+            }
+        })
+        .collect();
+
+    // The raster attributes give the aspect ratio of the pixels, 1:1, and the size.
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+    for (index, &Rgb(r, g, b)) in palette.iter().enumerate() {
+        // Sixel colors are percentages of each channel.
+        let percent = |channel: u8| (channel as usize * 100 + 127) / 255;
+        let _ = write!(out, "#{};2;{};{};{}", index, percent(r), percent(g), percent(b));
+    }
+
+    for band in (0..image.height).step_by(6) {
+        let rows = band..(band + 6).min(image.height);
+        let mut first = true;
+        for color in 0..palette.len() {
+            // The pixels of this color in every column of the band, one bit per row.
+            let columns: Vec<u8> = (0..image.width)
+                .map(|x| rows.clone().enumerate().fold(0, |bits, (bit, y)| bits | ((indices[y * image.width + x] == color) as u8) << bit))
+                .collect();
+            if columns.iter().all(|&bits| bits == 0) {
+                continue;
+            }
+            // Each color after the first goes back to the start of the band.
+            if !first {
+                out.push('$');
+            }
+            first = false;
+            let _ = write!(out, "#{}", color);
+            push_runs(&mut out, &columns);
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Appends the sixels of a band for one color, run-length encoded.
+///
+/// # Arguments
+///
+/// * `out` - The escape sequence being written.
+/// * `columns` - The pixels of the color in each column, one bit per row from the top.
+fn push_runs(out: &mut String, columns: &[u8]) {
+    let mut i = 0;
+    while i < columns.len() {
+        let run = columns[i..].iter().take_while(|&&bits| bits == columns[i]).count();
+        let sixel = (63 + columns[i]) as char;
+        if run > 3 {
+            let _ = write!(out, "!{}{}", run, sixel);
+        } else {
+            (0..run).for_each(|_| out.push(sixel));
+        }
+        i += run;
+    }
+}
+
+/// Returns the index of the color of a palette nearest to a color.
+///
+/// # Arguments
+///
+/// * `palette` - The colors registered so far.
+/// * `color` - The color to draw.
+fn nearest(palette: &[Rgb], Rgb(r, g, b): Rgb) -> usize {
+    let distance = |&Rgb(pr, pg, pb): &Rgb| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(pr, r) + d(pg, g) + d(pb, b)
+    };
+    (0..palette.len()).min_by_key(|&i| distance(&palette[i])).unwrap_or(0)
+}
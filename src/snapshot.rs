@@ -0,0 +1,118 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::Path};
+
+use crate::{
+    pattern::{self, Pattern},
+    placement,
+    rule::Rule,
+    Grid,
+};
+
+/// The version of the snapshot format written by this build.
+const SNAPSHOT_VERSION: u32 = 1;
+
+/// The complete state of a simulation, saved so a run can be resumed later.
+///
+/// Snapshots are stored as JSON. The cells are kept as RLE text so that the file stays
+/// small and the board can be copied into other Life tools, and the random number
+/// generator is stored as its seed and position in the stream, which is enough to
+/// continue the exact same sequence of random numbers.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The version of the snapshot format.
+    pub version: u32,
+    /// The width of the board.
+    pub width: usize,
+    /// The height of the board.
+    pub height: usize,
+    /// The generation the board is at.
+    pub generation: u64,
+    /// The rule, stored as its rulestring.
+    pub rule: Rule,
+    /// The seed of the random number generator.
+    pub seed: u64,
+    /// The number of 32-bit words the random number generator has produced.
+    pub rng_word_pos: u128,
+    /// The live cells, as RLE.
+    pub cells: String,
+}
+
+impl Snapshot {
+    /// Captures the state of a simulation.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    /// * `generation` - The generation the board is at.
+    /// * `rule` - The rule the simulation runs under.
+    /// * `seed` - The seed the random number generator was created from.
+    /// * `rng` - The random number generator.
+    pub fn capture(grid: &[Vec<bool>], generation: u64, rule: &Rule, seed: u64, rng: &ChaCha12Rng) -> Snapshot {
+        let pattern = Pattern::from_rows(grid.to_vec());
+        Snapshot {
+            version: SNAPSHOT_VERSION,
+            width: pattern.width,
+            height: pattern.height,
+            generation,
+            rule: *rule,
+            seed,
+            rng_word_pos: rng.get_word_pos(),
+            cells: pattern.to_rle(),
+        }
+    }
+
+    /// Writes the snapshot to a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The snapshot file.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).map_err(|e| format!("Failed to write snapshot {}: {}", path.display(), e))?;
+        Ok(())
+    }
+
+    /// Reads a snapshot from a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The snapshot file.
+    ///
+    /// # Returns
+    ///
+    /// The snapshot, after checking that its cells can be restored.
+    pub fn load(path: &Path) -> Result<Snapshot, Box<dyn Error>> {
+        let json = fs::read_to_string(path).map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+        let snapshot: Snapshot =
+            serde_json::from_str(&json).map_err(|e| format!("Invalid snapshot {}: {}", path.display(), e))?;
+
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(format!(
+                "Snapshot {} has version {}, but only version {} is supported",
+                path.display(),
+                snapshot.version,
+                SNAPSHOT_VERSION
+            )
+            .into());
+        }
+        snapshot.grid().map_err(|e| format!("Invalid snapshot {}: {}", path.display(), e))?;
+        Ok(snapshot)
+    }
+
+    /// Returns the board of the snapshot.
+    pub fn grid(&self) -> Result<Grid, Box<dyn Error>> {
+        let pattern = pattern::parse_rle_within(&self.cells, self.width, self.height)?;
+        let mut grid = vec![vec![false; self.width]; self.height];
+        placement::place_pattern(&mut grid, &pattern, 0, 0);
+        Ok(grid)
+    }
+
+    /// Returns the random number generator, at the position it was saved at.
+    pub fn rng(&self) -> ChaCha12Rng {
+        let mut rng = ChaCha12Rng::seed_from_u64(self.seed);
+        rng.set_word_pos(self.rng_word_pos);
+        rng
+    }
+}
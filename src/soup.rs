@@ -0,0 +1,202 @@
+//! Soup searches, which run many random soups and count the objects they settle into.
+//!
+//! Each soup is a small square of random cells in the middle of an empty board. It is
+//! run until it repeats itself, and what is left is split into objects and named with the
+//! known-object table of [`census`]. Spaceships that fly off are counted as they reach
+//! the edge of the board and removed, since they would otherwise never let it settle.
+
+use std::{collections::BTreeMap, fmt};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+use crate::{
+    bitgrid::BitGrid,
+    census::{self, ObjectKind},
+    cycle::{CycleDetector, DEFAULT_MAX_PERIOD},
+    hash::BoardHash,
+    random_grid,
+    rule::Rule,
+    symmetry::Symmetry,
+};
+
+/// The default number of soups searched.
+pub const DEFAULT_SOUPS: u64 = 100;
+
+/// The default number of generations a soup may take to settle.
+pub const DEFAULT_MAX_GENERATIONS: u64 = 10_000;
+
+/// The width and height of every soup, as in apgsearch.
+pub const SOUP_SIZE: usize = 16;
+
+/// The probability that a cell of a soup starts alive.
+const SOUP_DENSITY: f64 = 0.5;
+
+/// The empty space around a soup, which leaves room for its debris to spread.
+const MARGIN: usize = 48;
+
+/// How close to the edge of the board a spaceship gets before it counts as escaped.
+const EDGE: usize = 6;
+
+/// The settings of a soup search.
+#[derive(Clone, Debug)]
+pub struct SoupSearch {
+    /// The number of soups to run.
+    pub soups: u64,
+    /// The seed of the first soup; each soup after it has the next seed.
+    pub first_seed: u64,
+    /// The number of generations after which a soup that has not settled is given up on.
+    pub max_generations: u64,
+    /// The symmetry every soup is made with, if any.
+    pub symmetry: Option<Symmetry>,
+}
+
+/// The objects found by a soup search.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SoupCensus {
+    /// The number of soups run.
+    pub soups: u64,
+    /// The seed of the first soup.
+    pub first_seed: u64,
+    /// The number of soups that had not settled when they were given up on.
+    pub unsettled: u64,
+    /// The number of objects of each kind the settled soups were left with, by name.
+    pub objects: BTreeMap<&'static str, u64>,
+    /// The number of spaceships of each kind that escaped, by name.
+    pub escaped: BTreeMap<&'static str, u64>,
+}
+
+impl SoupSearch {
+    /// Runs the search.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule the soups evolve under. The known objects are those of
+    ///   Conway's Life, so under other rules most objects are counted as unknown.
+    ///
+    /// # Returns
+    ///
+    /// The census of every object found.
+    pub fn run(&self, rule: &Rule) -> SoupCensus {
+        let mut census = SoupCensus { first_seed: self.first_seed, ..SoupCensus::default() };
+        for soup in 0..self.soups {
+            self.run_soup(self.first_seed.wrapping_add(soup), rule, &mut census);
+            census.soups += 1;
+        }
+        census
+    }
+
+    /// Runs one soup until it settles and adds what it left to the census.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the soup.
+    /// * `rule` - The rule the soup evolves under.
+    /// * `census` - The census to add the objects to.
+    fn run_soup(&self, seed: u64, rule: &Rule, census: &mut SoupCensus) {
+        let mut soup = random_grid(SOUP_SIZE, SOUP_SIZE, SOUP_DENSITY, &mut ChaCha12Rng::seed_from_u64(seed));
+        if let Some(symmetry) = self.symmetry {
+            symmetry.apply(&mut soup);
+        }
+        let size = SOUP_SIZE + 2 * MARGIN;
+        let mut grid = vec![vec![false; size]; size];
+        for (row, soup_row) in grid[MARGIN..].iter_mut().zip(&soup) {
+            row[MARGIN..MARGIN + SOUP_SIZE].copy_from_slice(soup_row);
+        }
+
+        let mut bits = BitGrid::from_grid(&grid);
+        let mut hash = BoardHash::of(&grid);
+        let mut detector = CycleDetector::new(DEFAULT_MAX_PERIOD);
+        detector.observe(hash, 0);
+        for generation in 1..=self.max_generations {
+            let next = bits.step(rule);
+            hash.apply_changes(bits.changes(&next));
+            bits = next;
+            remove_escaped(&mut bits, &mut hash, census);
+
+            if detector.observe(hash, generation).is_some() {
+                for (name, count) in census::census(&bits.to_grid()) {
+                    *census.objects.entry(name).or_insert(0) += count as u64;
+                }
+                return;
+            }
+        }
+        census.unsettled += 1;
+    }
+}
+
+/// Removes the spaceships that reached the edge of the board and counts them as escaped.
+///
+/// # Arguments
+///
+/// * `bits` - The board.
+/// * `hash` - The hash of the board, which is kept up to date.
+/// * `census` - The census to count the escaped spaceships in.
+fn remove_escaped(bits: &mut BitGrid, hash: &mut BoardHash, census: &mut SoupCensus) {
+    let (width, height) = (bits.width(), bits.height());
+    let near_edge = |x: usize, y: usize| x < EDGE || y < EDGE || x >= width - EDGE || y >= height - EDGE;
+
+    // Most generations have nothing near the edge, which is much cheaper to check than
+    // splitting the whole board into objects.
+    let alive_near_edge = (0..height).any(|y| {
+        if y < EDGE || y >= height - EDGE {
+            (0..width).any(|x| bits.get(x, y))
+        } else {
+            (0..EDGE).chain(width - EDGE..width).any(|x| bits.get(x, y))
+        }
+    });
+    if !alive_near_edge {
+        return;
+    }
+
+    for cells in census::objects(&bits.to_grid()) {
+        if !cells.iter().any(|&(x, y)| near_edge(x, y)) {
+            continue;
+        }
+        let name = census::classify(&cells);
+        if census::kind(name) == Some(ObjectKind::Spaceship) {
+            for (x, y) in cells {
+                bits.set(x, y, false);
+                hash.toggle(x, y);
+            }
+            *census.escaped.entry(name).or_insert(0) += 1;
+        }
+    }
+}
+
+impl fmt::Display for SoupCensus {
+    /// Formats the census as a table of the objects found, grouped by kind and listed
+    /// from the most to the least common.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Searched {} soups of {}x{} cells (seeds {} to {})",
+            self.soups,
+            SOUP_SIZE,
+            SOUP_SIZE,
+            self.first_seed,
+            self.first_seed.wrapping_add(self.soups.saturating_sub(1))
+        )?;
+
+        let groups = [
+            ("Still lifes", &self.objects, Some(ObjectKind::StillLife)),
+            ("Oscillators", &self.objects, Some(ObjectKind::Oscillator)),
+            ("Escaped spaceships", &self.escaped, Some(ObjectKind::Spaceship)),
+            ("Unrecognized objects", &self.objects, None),
+        ];
+        for (title, counts, kind) in groups {
+            let mut counts: Vec<(&str, u64)> =
+                counts.iter().filter(|&(&name, _)| census::kind(name) == kind).map(|(&name, &count)| (name, count)).collect();
+            if counts.is_empty() {
+                continue;
+            }
+            counts.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            writeln!(f, "{}:", title)?;
+            for (name, count) in counts {
+                writeln!(f, "  {:<10} {}", name, count)?;
+            }
+        }
+
+        write!(f, "Soups that did not settle: {}", self.unsettled)
+    }
+}
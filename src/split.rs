@@ -0,0 +1,195 @@
+use crossterm::{
+    cursor,
+    style::{Attribute, Print, SetAttribute},
+    QueueableCommand,
+};
+use std::{
+    error::Error,
+    io::Write,
+    thread,
+    time::Duration,
+};
+
+use gameoflife::{boundary::Boundary, rule::Rule, update_grid_with_boundary, Grid};
+
+use crate::{
+    display::{clear_screen, display_grid, display_status, DisplayStyle, Region, Screen, Terminal},
+    input::{self, Command, Input},
+};
+
+/// The most universes a split screen shows.
+pub const MAX_UNIVERSES: usize = 16;
+
+/// The fewest terminal columns and rows of board each universe needs.
+const MIN_REGION: (usize, usize) = (8, 3);
+
+/// How the terminal is split between universes with `--split`, as in `2x2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// The number of universes side by side.
+    pub columns: usize,
+    /// The number of universes above each other.
+    pub rows: usize,
+}
+
+impl Layout {
+    /// Parses a layout like `2x2` or `3x1`, columns first.
+    pub fn parse(text: &str) -> Option<Layout> {
+        let (columns, rows) = text.split_once(['x', 'X'])?;
+        let layout = Layout { columns: columns.trim().parse().ok()?, rows: rows.trim().parse().ok()? };
+        (layout.columns > 0 && layout.rows > 0).then_some(layout)
+    }
+
+    /// Returns the number of universes of the layout.
+    pub fn universes(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// Splits the terminal into the regions the universes are drawn in, row by row.
+    ///
+    /// Each universe has a caption row above its board and one blank column between it
+    /// and its neighbour on the right, and the bottom row is left for the status bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `terminal` - The size of the terminal in characters.
+    ///
+    /// # Returns
+    ///
+    /// The region of each universe's board, without its caption.
+    pub fn regions(&self, terminal: (usize, usize)) -> Result<Vec<Region>, Box<dyn Error>> {
+        let (cols, rows) = (terminal.0, terminal.1.saturating_sub(1));
+        let width = cols.saturating_sub(self.columns - 1) / self.columns;
+        let height = rows / self.rows;
+        if width < MIN_REGION.0 || height < MIN_REGION.1 + 1 {
+            return Err(format!("The terminal is too small to split {}x{}", self.columns, self.rows).into());
+        }
+
+        let regions = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .map(|(column, row)| Region { left: column * (width + 1), top: row * height + 1, cols: width, rows: height - 1 })
+            .collect();
+        Ok(regions)
+    }
+}
+
+/// One of the independent boards of a split screen.
+pub struct Universe {
+    /// The rule the board follows.
+    pub rule: Rule,
+    /// The seed the board was made from.
+    pub seed: u64,
+    /// The board.
+    pub grid: Grid,
+    /// The part of the terminal the board is drawn in.
+    pub region: Region,
+    /// The board drawn last frame.
+    prev_grid: Grid,
+}
+
+impl Universe {
+    /// Creates a universe.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The rule the board follows.
+    /// * `seed` - The seed the board was made from, shown in its caption.
+    /// * `grid` - The starting board.
+    /// * `region` - The part of the terminal the board is drawn in.
+    pub fn new(rule: Rule, seed: u64, grid: Grid, region: Region) -> Universe {
+        Universe { rule, seed, grid, region, prev_grid: Vec::new() }
+    }
+
+    /// Returns the number of live cells.
+    fn population(&self) -> usize {
+        self.grid.iter().flatten().filter(|&&cell| cell).count()
+    }
+
+    /// Draws the caption above the board: its rule, seed, and population.
+    fn draw_caption(&self, out: &mut impl Write) -> Result<(), Box<dyn Error>> {
+        let caption = format!("{} seed {}: {} cells", self.rule, self.seed, self.population());
+        let caption: String = caption.chars().chain(std::iter::repeat(' ')).take(self.region.cols).collect();
+        out.queue(cursor::MoveTo(self.region.left as u16, (self.region.top - 1) as u16))?;
+        out.queue(SetAttribute(Attribute::Reverse))?;
+        out.queue(Print(caption))?;
+        out.queue(SetAttribute(Attribute::NoReverse))?;
+        Ok(())
+    }
+}
+
+/// Runs several universes side by side until the user quits, each in its own part of the
+/// terminal.
+///
+/// The universes all advance together, one generation per tick, so boards with different
+/// seeds or rules can be compared as they evolve. Quitting, pausing, and stepping forward
+/// while paused are supported, as for the other simulations.
+///
+/// # Arguments
+///
+/// * `universes` - The universes, each with the region it is drawn in.
+/// * `boundary` - What lies past the edges of every board.
+/// * `speed` - The number of generations per second.
+/// * `style` - The render mode and glyphs to draw with.
+/// * `terminal` - The size of the terminal in characters; the status bar goes on the bottom row.
+pub fn run(universes: &mut [Universe], boundary: Boundary, speed: f64, style: &DisplayStyle, terminal: (usize, usize)) -> Result<(), Box<dyn Error>> {
+    let (cols, rows) = terminal;
+    let mut term = Terminal::new(None);
+    let screen = Screen::enter()?;
+    let raw_mode = input::RawMode::enable().ok();
+    clear_screen(&mut term, style)?;
+
+    let mut generation: u64 = 0;
+    let mut paused = false;
+    let mut running = true;
+    let step = |universes: &mut [Universe]| {
+        for universe in universes.iter_mut() {
+            universe.grid = update_grid_with_boundary(&universe.grid, &universe.rule, boundary);
+        }
+    };
+
+    while running {
+        for universe in universes.iter_mut() {
+            display_grid(&mut term, &universe.grid, &universe.prev_grid, style, None, universe.region)?;
+            universe.draw_caption(&mut term)?;
+            universe.prev_grid = universe.grid.clone();
+        }
+        let status = format!("gen {} | {} universes{}", generation, universes.len(), if paused { " [paused]" } else { "" });
+        display_status(&mut term, &status, rows.saturating_sub(1), cols, style)?;
+        term.flush()?;
+
+        if !paused {
+            step(universes);
+            generation += 1;
+        }
+
+        let wait = Duration::from_secs_f64(1.0 / speed);
+        let inputs = if raw_mode.is_some() {
+            input::wait_for_input(wait)?
+        } else {
+            thread::sleep(wait);
+            Vec::new()
+        };
+        for input in inputs {
+            if let Input::Key(key) = input {
+                match input::map_key(&key) {
+                    Some(Command::Quit) => running = false,
+                    Some(Command::TogglePause) => paused = !paused,
+                    Some(Command::StepForward) if paused => {
+                        step(universes);
+                        generation += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    drop(raw_mode);
+    drop(screen);
+    println!("Exiting...");
+    println!("gen: {}", generation);
+    for universe in universes.iter() {
+        println!("{} seed {}: {} cells", universe.rule, universe.seed, universe.population());
+    }
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use std::{collections::VecDeque, error::Error, fs, path::Path};
+
+/// The changes between two consecutive generations.
+///
+/// A diff is handed to every [`StatCollector`] after each generation is computed.
+#[derive(Debug, Default)]
+pub struct GenerationDiff {
+    /// The generation number of the new state (the initial grid is generation 0).
+    pub generation: u64,
+    /// The coordinates (`x`, `y`) of cells that were born.
+    pub births: Vec<(usize, usize)>,
+    /// The coordinates (`x`, `y`) of cells that died.
+    pub deaths: Vec<(usize, usize)>,
+    /// The number of live cells in the new state.
+    pub population: usize,
+}
+
+impl GenerationDiff {
+    /// Computes the diff between two grids.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The previous grid state.
+    /// * `next` - The new grid state.
+    /// * `generation` - The generation number of `next`.
+    ///
+    /// # Returns
+    ///
+    /// The diff.
+    pub fn between(prev: &[Vec<bool>], next: &[Vec<bool>], generation: u64) -> GenerationDiff {
+        let mut diff = GenerationDiff {
+            generation,
+            ..GenerationDiff::default()
+        };
+
+        for (y, (prev_row, next_row)) in prev.iter().zip(next).enumerate() {
+            for (x, (&was, &is)) in prev_row.iter().zip(next_row).enumerate() {
+                match (was, is) {
+                    (false, true) => diff.births.push((x, y)),
+                    (true, false) => diff.deaths.push((x, y)),
+                    _ => {}
+                }
+                diff.population += is as usize;
+            }
+        }
+
+        diff
+    }
+}
+
+/// A custom statistic computed from the stream of generation diffs.
+///
+/// Implementors are registered with [`Stats`] and are called once per generation.
+/// Their current value is shown in the status bar, written to the stats file, and
+/// printed in the exit summary.
+pub trait StatCollector {
+    /// The short label shown next to the value.
+    fn name(&self) -> &str;
+
+    /// Updates the statistic with the changes of a new generation.
+    fn observe(&mut self, diff: &GenerationDiff);
+
+    /// Formats the current value of the statistic.
+    fn value(&self) -> String;
+}
+
+/// Counts the generations computed so far.
+#[derive(Default)]
+pub struct GenerationCount(u64);
+
+impl StatCollector for GenerationCount {
+    fn name(&self) -> &str {
+        "gen"
+    }
+
+    fn observe(&mut self, diff: &GenerationDiff) {
+        self.0 = diff.generation;
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Tracks the current population.
+#[derive(Default)]
+pub struct Population(usize);
+
+impl StatCollector for Population {
+    fn name(&self) -> &str {
+        "pop"
+    }
+
+    fn observe(&mut self, diff: &GenerationDiff) {
+        self.0 = diff.population;
+    }
+
+    fn value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+/// Tracks the births and deaths of the latest generation.
+#[derive(Default)]
+pub struct Churn {
+    births: usize,
+    deaths: usize,
+}
+
+impl StatCollector for Churn {
+    fn name(&self) -> &str {
+        "+/-"
+    }
+
+    fn observe(&mut self, diff: &GenerationDiff) {
+        self.births = diff.births.len();
+        self.deaths = diff.deaths.len();
+    }
+
+    fn value(&self) -> String {
+        format!("+{}/-{}", self.births, self.deaths)
+    }
+}
+
+/// Tracks the highest population seen and the generation it was first reached at.
+#[derive(Default)]
+pub struct PeakPopulation {
+    population: usize,
+    generation: u64,
+}
+
+impl StatCollector for PeakPopulation {
+    fn name(&self) -> &str {
+        "peak"
+    }
+
+    fn observe(&mut self, diff: &GenerationDiff) {
+        if diff.population > self.population {
+            self.population = diff.population;
+            self.generation = diff.generation;
+        }
+    }
+
+    fn value(&self) -> String {
+        format!("{} (gen {})", self.population, self.generation)
+    }
+}
+
+/// The bars of a sparkline, from the lowest value to the highest.
+const SPARK_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Draws the population of the last few hundred generations as a sparkline.
+///
+/// Each character of the sparkline is the average of a run of generations, scaled between
+/// the lowest and highest average shown, so growth, decay, and long oscillations stand out
+/// whatever the size of the board.
+pub struct PopulationSparkline {
+    /// The populations of the latest generations, oldest first.
+    history: VecDeque<usize>,
+    /// The number of generations kept.
+    generations: usize,
+    /// The number of characters drawn.
+    width: usize,
+}
+
+impl PopulationSparkline {
+    /// The number of generations kept by default.
+    pub const DEFAULT_GENERATIONS: usize = 300;
+
+    /// The number of characters drawn by default.
+    pub const DEFAULT_WIDTH: usize = 30;
+
+    /// Creates a sparkline.
+    ///
+    /// # Arguments
+    ///
+    /// * `generations` - The number of generations kept.
+    /// * `width` - The number of characters drawn.
+    pub fn new(generations: usize, width: usize) -> PopulationSparkline {
+        PopulationSparkline {
+            history: VecDeque::with_capacity(generations),
+            generations: generations.max(1),
+            width: width.max(1),
+        }
+    }
+}
+
+impl Default for PopulationSparkline {
+    fn default() -> Self {
+        PopulationSparkline::new(PopulationSparkline::DEFAULT_GENERATIONS, PopulationSparkline::DEFAULT_WIDTH)
+    }
+}
+
+impl StatCollector for PopulationSparkline {
+    fn name(&self) -> &str {
+        "trend"
+    }
+
+    fn observe(&mut self, diff: &GenerationDiff) {
+        if self.history.len() == self.generations {
+            self.history.pop_front();
+        }
+        self.history.push_back(diff.population);
+    }
+
+    fn value(&self) -> String {
+        // Average runs of generations so the history fits the width, keeping the
+        // newest generation in the last character.
+        let run = self.history.len().div_ceil(self.width).max(1);
+        let newest_first: Vec<usize> = self.history.iter().rev().copied().collect();
+        let mut values: Vec<f64> = newest_first
+            .chunks(run)
+            .map(|chunk| chunk.iter().sum::<usize>() as f64 / chunk.len() as f64)
+            .collect();
+        values.reverse();
+
+        let low = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let high = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        values
+            .iter()
+            .map(|&value| {
+                let level = if high > low { (value - low) / (high - low) } else { 0.0 };
+                SPARK_BARS[((level * (SPARK_BARS.len() - 1) as f64).round() as usize).min(SPARK_BARS.len() - 1)]
+            })
+            .collect()
+    }
+}
+
+/// The set of registered statistic collectors.
+pub struct Stats {
+    collectors: Vec<Box<dyn StatCollector>>,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats::new()
+    }
+}
+
+impl Stats {
+    /// Creates a registry containing the built-in collectors.
+    pub fn new() -> Stats {
+        let mut stats = Stats { collectors: Vec::new() };
+        stats.register(Box::new(GenerationCount::default()));
+        stats.register(Box::new(Population::default()));
+        stats.register(Box::new(Churn::default()));
+        stats
+    }
+
+    /// Registers an additional collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `collector` - The collector to register.
+    pub fn register(&mut self, collector: Box<dyn StatCollector>) {
+        self.collectors.push(collector);
+    }
+
+    /// Removes the collectors with a given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the statistic to stop collecting.
+    pub fn unregister(&mut self, name: &str) {
+        self.collectors.retain(|collector| collector.name() != name);
+    }
+
+    /// Passes a generation diff to every collector.
+    ///
+    /// # Arguments
+    ///
+    /// * `diff` - The changes of the new generation.
+    pub fn observe(&mut self, diff: &GenerationDiff) {
+        for collector in self.collectors.iter_mut() {
+            collector.observe(diff);
+        }
+    }
+
+    /// Returns the name and current value of every collector.
+    pub fn values(&self) -> Vec<(String, String)> {
+        self.collectors
+            .iter()
+            .map(|collector| (collector.name().to_string(), collector.value()))
+            .collect()
+    }
+
+    /// Formats the collector values as a single status bar line.
+    pub fn status_line(&self) -> String {
+        self.values()
+            .iter()
+            .map(|(name, value)| format!("{} {}", name, value))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// Formats the collector values as a multi-line summary.
+    pub fn summary(&self) -> String {
+        self.values()
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Writes the collector values to a stats file, one `name=value` pair per line.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the stats file.
+    pub fn write_file(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let mut contents = String::new();
+        for (name, value) in self.values() {
+            contents.push_str(&format!("{}={}\n", name, value));
+        }
+
+        fs::write(path, contents)
+            .map_err(|e| format!("Failed to write stats file {}: {}", path.display(), e).into())
+    }
+}
@@ -0,0 +1,236 @@
+//! The wire format of `--serve` and `--connect`, which stream a running board over TCP.
+//!
+//! A stream starts with [`MAGIC`], then carries one message per generation. The first
+//! message is the whole board; after that, each generation is sent as the cells that
+//! flipped, unless the whole board is smaller. All numbers are little-endian.
+//!
+//! * A board is the byte `B`, the generation (u64), the rulestring (u16 length and UTF-8
+//!   bytes), the width and height (u32 each), and the rows, each packed into bytes with
+//!   the leftmost cell in the lowest bit.
+//! * Changes are the byte `C`, the generation (u64), the number of cells (u32), and the
+//!   column and row of each cell (u32 each).
+
+use std::{
+    error::Error,
+    io::{self, Read, Write},
+};
+
+use crate::{bitgrid::BitGrid, Grid};
+
+/// The bytes every stream starts with: a name and the version of the format.
+pub const MAGIC: &[u8; 5] = b"LIFE\x01";
+
+/// The largest board a client accepts, in cells, so a bad stream cannot exhaust memory.
+pub const MAX_CELLS: usize = 1 << 28;
+
+/// The widest and tallest board a client accepts, checked on its own so that a board with
+/// no columns cannot ask for a huge number of rows.
+pub const MAX_SIDE: usize = 1 << 16;
+
+/// One generation of a streamed board.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    /// The whole board.
+    Board {
+        /// The generation of the board.
+        generation: u64,
+        /// The rule the board follows, as a rulestring.
+        rule: String,
+        /// The cells of the board.
+        grid: Grid,
+    },
+    /// The cells that flipped since the previous message.
+    Changes {
+        /// The generation the changes lead to.
+        generation: u64,
+        /// The column and row of each cell that flipped.
+        cells: Vec<(u32, u32)>,
+    },
+}
+
+impl Message {
+    /// Encodes the step from one generation to the next as the smaller of the two messages.
+    ///
+    /// # Arguments
+    ///
+    /// * `prev` - The previous generation, which the receiver already has.
+    /// * `next` - The new generation.
+    /// * `generation` - The number of the new generation.
+    /// * `rule` - The rulestring, sent along with a whole board.
+    ///
+    /// # Returns
+    ///
+    /// The changes between the generations, or the whole new board if that is smaller.
+    pub fn between(prev: &BitGrid, next: &BitGrid, generation: u64, rule: &str) -> Message {
+        let board_bytes = next.width().div_ceil(8) * next.height();
+        let mut cells = Vec::new();
+        for (x, y) in prev.changes(next) {
+            if cells.len() * 8 > board_bytes {
+                return Message::Board { generation, rule: rule.to_string(), grid: next.to_grid() };
+            }
+            cells.push((x as u32, y as u32));
+        }
+        Message::Changes { generation, cells }
+    }
+
+    /// Encodes cells that flipped since the previous generation as the smaller of the two
+    /// messages, for boards that already know which cells changed, such as a world.
+    ///
+    /// # Arguments
+    ///
+    /// * `flipped` - The column and row of each cell that flipped, each once.
+    /// * `next` - The new generation.
+    /// * `generation` - The number of the new generation.
+    /// * `rule` - The rulestring, sent along with a whole board.
+    ///
+    /// # Returns
+    ///
+    /// The changes, or the whole new board if that is smaller.
+    pub fn flipped(flipped: &[(usize, usize)], next: &Grid, generation: u64, rule: &str) -> Message {
+        let board_bytes = next.first().map_or(0, Vec::len).div_ceil(8) * next.len();
+        if flipped.len() * 8 > board_bytes {
+            return Message::Board { generation, rule: rule.to_string(), grid: next.clone() };
+        }
+        Message::Changes { generation, cells: flipped.iter().map(|&(x, y)| (x as u32, y as u32)).collect() }
+    }
+
+    /// Returns the generation the message brings the board to.
+    pub fn generation(&self) -> u64 {
+        match self {
+            Message::Board { generation, .. } | Message::Changes { generation, .. } => *generation,
+        }
+    }
+
+    /// Writes the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - Where to write the message.
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        match self {
+            Message::Board { generation, rule, grid } => {
+                let height = grid.len();
+                let width = grid.first().map_or(0, |row| row.len());
+                out.write_all(b"B")?;
+                out.write_all(&generation.to_le_bytes())?;
+                out.write_all(&(rule.len() as u16).to_le_bytes())?;
+                out.write_all(rule.as_bytes())?;
+                out.write_all(&(width as u32).to_le_bytes())?;
+                out.write_all(&(height as u32).to_le_bytes())?;
+                for row in grid {
+                    let mut packed = vec![0u8; width.div_ceil(8)];
+                    for (x, _) in row.iter().enumerate().filter(|&(_, &alive)| alive) {
+                        packed[x / 8] |= 1 << (x % 8);
+                    }
+                    out.write_all(&packed)?;
+                }
+            }
+            Message::Changes { generation, cells } => {
+                out.write_all(b"C")?;
+                out.write_all(&generation.to_le_bytes())?;
+                out.write_all(&(cells.len() as u32).to_le_bytes())?;
+                for &(x, y) in cells {
+                    out.write_all(&x.to_le_bytes())?;
+                    out.write_all(&y.to_le_bytes())?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the next message.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - Where to read the message from.
+    ///
+    /// # Returns
+    ///
+    /// The message, or `None` if the stream ended cleanly between messages.
+    pub fn read(input: &mut impl Read) -> Result<Option<Message>, Box<dyn Error>> {
+        let mut tag = [0u8; 1];
+        match input.read_exact(&mut tag) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let generation = u64::from_le_bytes(read_array(input)?);
+
+        match &tag {
+            b"B" => {
+                let mut rule = vec![0u8; u16::from_le_bytes(read_array(input)?) as usize];
+                input.read_exact(&mut rule)?;
+                let rule = String::from_utf8(rule).map_err(|_| "The stream sent a rule that is not UTF-8")?;
+                let width = u32::from_le_bytes(read_array(input)?) as usize;
+                let height = u32::from_le_bytes(read_array(input)?) as usize;
+                if width > MAX_SIDE || height > MAX_SIDE {
+                    return Err(format!("The stream sent a {}x{} board, more than {} cells across or down", width, height, MAX_SIDE).into());
+                }
+                if width * height > MAX_CELLS {
+                    return Err(format!("The stream sent a {}x{} board, larger than {} cells", width, height, MAX_CELLS).into());
+                }
+
+                let mut packed = vec![0u8; width.div_ceil(8)];
+                let mut grid = Vec::with_capacity(height);
+                for _ in 0..height {
+                    input.read_exact(&mut packed)?;
+                    grid.push((0..width).map(|x| packed[x / 8] >> (x % 8) & 1 == 1).collect());
+                }
+                Ok(Some(Message::Board { generation, rule, grid }))
+            }
+            b"C" => {
+                let count = u32::from_le_bytes(read_array(input)?) as usize;
+                if count > MAX_CELLS {
+                    return Err(format!("The stream sent {} changes, more than {} cells", count, MAX_CELLS).into());
+                }
+                let cells = (0..count)
+                    .map(|_| Ok((u32::from_le_bytes(read_array(input)?), u32::from_le_bytes(read_array(input)?))))
+                    .collect::<io::Result<_>>()?;
+                Ok(Some(Message::Changes { generation, cells }))
+            }
+            _ => Err(format!("The stream sent an unknown message '{}'", tag[0].escape_ascii()).into()),
+        }
+    }
+
+    /// Brings a board up to date with the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board, replaced by a whole board or changed cell by cell; changes
+    ///   outside it are ignored.
+    pub fn apply(self, grid: &mut Grid) {
+        match self {
+            Message::Board { grid: board, .. } => *grid = board,
+            Message::Changes { cells, .. } => {
+                for (x, y) in cells {
+                    if let Some(cell) = grid.get_mut(y as usize).and_then(|row| row.get_mut(x as usize)) {
+                        *cell = !*cell;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Reads a fixed number of bytes.
+fn read_array<const N: usize>(input: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    input.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads the start of a stream and checks that it is one.
+///
+/// # Arguments
+///
+/// * `input` - The stream.
+pub fn read_header(input: &mut impl Read) -> Result<(), Box<dyn Error>> {
+    let header: [u8; 5] = read_array(input)?;
+    if header[..4] != MAGIC[..4] {
+        return Err("The server did not send a Game of Life stream".into());
+    }
+    if header[4] != MAGIC[4] {
+        return Err(format!("The server streams version {} of the format, but version {} is supported", header[4], MAGIC[4]).into());
+    }
+    Ok(())
+}
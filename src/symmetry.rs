@@ -0,0 +1,100 @@
+use std::fmt;
+
+/// A symmetry that random soups are made with, by copying one part of the soup onto the
+/// rest with mirrors and rotations.
+///
+/// The names are those of apgsearch: `C` for rotations and `D` for rotations combined with
+/// mirrors, with the number of copies of each cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    /// The same after half a turn.
+    C2,
+    /// The same after a quarter turn.
+    C4,
+    /// Mirrored left to right.
+    D2,
+    /// Mirrored left to right and top to bottom.
+    D4,
+    /// The same in every rotation and reflection of a square.
+    D8,
+}
+
+impl Symmetry {
+    /// Parses a symmetry name: `C2`, `C4`, `D2`, `D4`, or `D8`, in either case.
+    pub fn parse(name: &str) -> Option<Symmetry> {
+        match name.to_ascii_uppercase().as_str() {
+            "C2" => Some(Symmetry::C2),
+            "C4" => Some(Symmetry::C4),
+            "D2" => Some(Symmetry::D2),
+            "D4" => Some(Symmetry::D4),
+            "D8" => Some(Symmetry::D8),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the symmetry turns the board a quarter, which only maps a square
+    /// onto itself.
+    pub fn needs_square(&self) -> bool {
+        matches!(self, Symmetry::C4 | Symmetry::D8)
+    }
+
+    /// Returns the cells a cell is copied to, itself included.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell.
+    /// * `y` - The row of the cell.
+    /// * `width` - The width of the area made symmetric.
+    /// * `height` - The height of the area, the same as the width for a quarter turn.
+    fn images(&self, x: usize, y: usize, width: usize, height: usize) -> Vec<(usize, usize)> {
+        let (mx, my) = (width - 1 - x, height - 1 - y);
+        let turns = |x: usize, y: usize| [(x, y), (width - 1 - y, x), (width - 1 - x, width - 1 - y), (y, width - 1 - x)];
+        match self {
+            Symmetry::C2 => vec![(x, y), (mx, my)],
+            Symmetry::C4 => turns(x, y).to_vec(),
+            Symmetry::D2 => vec![(x, y), (mx, y)],
+            Symmetry::D4 => vec![(x, y), (mx, y), (x, my), (mx, my)],
+            Symmetry::D8 => turns(x, y).into_iter().chain(turns(y, x)).collect(),
+        }
+    }
+
+    /// Makes a board symmetric.
+    ///
+    /// Every set of cells the symmetry maps onto each other takes the state of the first of
+    /// them, row by row, so the board keeps the density it was made with. A quarter turn only
+    /// maps a square onto itself, so for `C4` and `D8` the largest square in the middle of
+    /// the board is made symmetric and the cells beside it are cleared.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    pub fn apply(&self, grid: &mut [Vec<bool>]) {
+        let (width, height) = (grid.first().map_or(0, |row| row.len()), grid.len());
+        let (columns, rows) = if self.needs_square() { (width.min(height), width.min(height)) } else { (width, height) };
+        let (left, top) = ((width - columns) / 2, (height - rows) / 2);
+
+        let source: Vec<Vec<bool>> = grid.to_vec();
+        for (y, row) in grid.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                let inside = (left..left + columns).contains(&x) && (top..top + rows).contains(&y);
+                *cell = inside && {
+                    let images = self.images(x - left, y - top, columns, rows);
+                    let (sx, sy) = images.into_iter().min_by_key(|&(x, y)| (y, x)).unwrap_or((x - left, y - top));
+                    source[top + sy][left + sx]
+                };
+            }
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Symmetry::C2 => "C2",
+            Symmetry::C4 => "C4",
+            Symmetry::D2 => "D2",
+            Symmetry::D4 => "D4",
+            Symmetry::D8 => "D8",
+        })
+    }
+}
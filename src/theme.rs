@@ -0,0 +1,99 @@
+use crossterm::style::Color;
+
+use crate::display::DisplayStyle;
+
+/// The colors a board is drawn in, chosen by name with `--theme`.
+///
+/// Colors left as `None` are the terminal's own.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Theme {
+    /// The color of live cells.
+    pub alive: Option<Color>,
+    /// The color the ghost trails of dying cells fade from.
+    pub dying: Option<Color>,
+    /// The color behind the board.
+    pub background: Option<Color>,
+    /// The color of the status bar text.
+    pub status: Option<Color>,
+    /// The color behind the status bar text.
+    pub status_background: Option<Color>,
+}
+
+impl Theme {
+    /// Switches a display style to the theme's colors.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The style to update.
+    pub fn apply(&self, style: &mut DisplayStyle) {
+        style.alive_color = self.alive;
+        style.dying_color = self.dying;
+        style.background = self.background;
+        style.status_color = self.status;
+        style.status_background = self.status_background;
+    }
+}
+
+/// Returns a color from its red, green, and blue channels.
+const fn rgb(r: u8, g: u8, b: u8) -> Option<Color> {
+    Some(Color::Rgb { r, g, b })
+}
+
+/// The themes that are always available, by name.
+const BUILT_IN: [(&str, Theme); 4] = [
+    (
+        "matrix",
+        Theme { alive: rgb(0, 255, 65), dying: rgb(0, 143, 17), background: rgb(13, 2, 8), status: rgb(13, 2, 8), status_background: rgb(0, 200, 50) },
+    ),
+    (
+        "amber",
+        Theme { alive: rgb(255, 176, 0), dying: rgb(160, 90, 0), background: rgb(26, 15, 0), status: rgb(26, 15, 0), status_background: rgb(255, 176, 0) },
+    ),
+    (
+        "ocean",
+        Theme { alive: rgb(127, 219, 255), dying: rgb(0, 116, 217), background: rgb(0, 31, 63), status: rgb(0, 31, 63), status_background: rgb(127, 219, 255) },
+    ),
+    (
+        "mono",
+        Theme { alive: rgb(255, 255, 255), dying: rgb(128, 128, 128), background: rgb(0, 0, 0), status: rgb(0, 0, 0), status_background: rgb(255, 255, 255) },
+    ),
+];
+
+/// The themes that can be chosen by name: the built-in ones and any defined in the
+/// configuration file.
+#[derive(Clone, Debug)]
+pub struct Themes {
+    /// The themes by name, in the order they were added.
+    themes: Vec<(String, Theme)>,
+}
+
+impl Default for Themes {
+    fn default() -> Self {
+        Themes { themes: BUILT_IN.iter().map(|&(name, theme)| (name.to_string(), theme)).collect() }
+    }
+}
+
+impl Themes {
+    /// Adds a theme, replacing any theme of the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the theme is chosen by.
+    /// * `theme` - The theme.
+    pub fn insert(&mut self, name: &str, theme: Theme) {
+        match self.themes.iter_mut().find(|(existing, _)| existing == name) {
+            Some((_, existing)) => *existing = theme,
+            None => self.themes.push((name.to_string(), theme)),
+        }
+    }
+
+    /// Looks a theme up by name.
+    pub fn get(&self, name: &str) -> Option<&Theme> {
+        self.themes.iter().find(|(existing, _)| existing == name).map(|(_, theme)| theme)
+    }
+
+    /// Returns the names of the themes, for error messages.
+    pub fn names(&self) -> String {
+        self.themes.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
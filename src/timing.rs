@@ -0,0 +1,109 @@
+use crossterm::{
+    cursor,
+    style::{Attribute, Print, SetAttribute},
+    QueueableCommand,
+};
+use std::{
+    collections::VecDeque,
+    error::Error,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use crate::display::Region;
+
+/// How far back the generation rate is measured.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// How much of the latest frame goes into the smoothed phase times.
+const SMOOTHING: f64 = 0.1;
+
+/// The width of the overlay in characters.
+pub const OVERLAY_WIDTH: usize = 22;
+
+/// The height of the overlay in lines.
+pub const OVERLAY_HEIGHT: usize = 5;
+
+/// Measures how fast the main loop runs, for the timing overlay.
+///
+/// The update and render phases of every frame are timed separately, so a slow engine
+/// can be told apart from a slow terminal.
+#[derive(Default)]
+pub struct FrameTimer {
+    /// The generation reached at recent frames, oldest first.
+    samples: VecDeque<(Instant, u64)>,
+    /// The smoothed time spent computing generations per frame, in seconds.
+    update: f64,
+    /// The smoothed time spent drawing per frame, in seconds.
+    render: f64,
+    /// The number of frames whose work took longer than the frame was meant to last.
+    dropped: u64,
+    /// The number of frames not drawn because the terminal was still writing earlier ones.
+    skipped: u64,
+}
+
+impl FrameTimer {
+    /// Records a frame of the main loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation reached by the end of the frame.
+    /// * `update` - The time spent computing generations.
+    /// * `render` - The time spent drawing.
+    /// * `budget` - How long the frame was meant to last, or `None` if it has no deadline,
+    ///   as in turbo mode.
+    pub fn record(&mut self, generation: u64, update: Duration, render: Duration, budget: Option<Duration>) {
+        let now = Instant::now();
+        self.samples.push_back((now, generation));
+        while self.samples.front().is_some_and(|&(at, _)| now.duration_since(at) > RATE_WINDOW) {
+            self.samples.pop_front();
+        }
+
+        let smooth = |average: f64, latest: Duration| average + (latest.as_secs_f64() - average) * SMOOTHING;
+        self.update = smooth(self.update, update);
+        self.render = smooth(self.render, render);
+        if budget.is_some_and(|budget| update + render > budget) {
+            self.dropped += 1;
+        }
+    }
+
+    /// Records a frame that was not drawn because the terminal was still busy.
+    pub fn skip(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// Returns the number of generations computed per second over the last second.
+    pub fn generations_per_second(&self) -> f64 {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(&(first, from)), Some(&(last, to))) if last > first => {
+                to.saturating_sub(from) as f64 / last.duration_since(first).as_secs_f64()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Draws the overlay over the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw on.
+    /// * `region` - Where the overlay goes, [`OVERLAY_WIDTH`] by [`OVERLAY_HEIGHT`] at most.
+    pub fn draw(&self, out: &mut impl Write, region: Region) -> Result<(), Box<dyn Error>> {
+        let lines: [String; OVERLAY_HEIGHT] = [
+            format!("gen/s   {:.1}", self.generations_per_second()),
+            format!("update  {:.2} ms", self.update * 1000.0),
+            format!("render  {:.2} ms", self.render * 1000.0),
+            format!("dropped {}", self.dropped),
+            format!("skipped {}", self.skipped),
+        ];
+
+        out.queue(SetAttribute(Attribute::Reverse))?;
+        for (row, line) in lines.iter().enumerate().take(region.rows) {
+            let line: String = format!(" {}", line).chars().chain(std::iter::repeat(' ')).take(region.cols).collect();
+            out.queue(cursor::MoveTo(region.left as u16, (region.top + row) as u16))?;
+            out.queue(Print(line))?;
+        }
+        out.queue(SetAttribute(Attribute::NoReverse))?;
+        Ok(())
+    }
+}
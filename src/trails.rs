@@ -0,0 +1,72 @@
+//! The fading trails drawn behind moving patterns by `--trails`.
+
+/// The age of a cell that is alive.
+const ALIVE: u8 = u8::MAX;
+
+/// The longest trail, in generations.
+pub const MAX_LENGTH: u8 = ALIVE - 1;
+
+/// How long ago every cell of a board died, for the cells that died recently.
+#[derive(Clone)]
+pub struct Trails {
+    /// For each cell: [`ALIVE`], the number of generations since it died, or 0 once the
+    /// trail has faded.
+    ages: Vec<Vec<u8>>,
+    /// The number of generations a trail lasts.
+    length: u8,
+}
+
+impl Trails {
+    /// Creates trails for a board on which no cell has died yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The number of columns of the board.
+    /// * `height` - The number of rows of the board.
+    /// * `length` - The number of generations a trail lasts, at most [`MAX_LENGTH`].
+    pub fn new(width: usize, height: usize, length: u8) -> Trails {
+        Trails { ages: vec![vec![0; width]; height], length: length.min(MAX_LENGTH) }
+    }
+
+    /// Returns the number of generations a trail lasts.
+    pub fn length(&self) -> u8 {
+        self.length
+    }
+
+    /// Moves the trails on to a new generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board, which must be the size of the trails.
+    pub fn observe(&mut self, grid: &[Vec<bool>]) {
+        for (ages, row) in self.ages.iter_mut().zip(grid) {
+            for (age, &alive) in ages.iter_mut().zip(row) {
+                *age = match *age {
+                    _ if alive => ALIVE,
+                    ALIVE => 1,
+                    0 => 0,
+                    age if age < self.length => age + 1,
+                    _ => 0,
+                };
+            }
+        }
+    }
+
+    /// Returns how many generations ago a cell died.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell.
+    /// * `y` - The row of the cell.
+    ///
+    /// # Returns
+    ///
+    /// The age of the cell's trail, from 1 for a cell that just died, or `None` if the
+    /// cell is alive, has no trail, or is outside the board.
+    pub fn age(&self, x: usize, y: usize) -> Option<u8> {
+        match self.ages.get(y).and_then(|row| row.get(x)).copied() {
+            Some(ALIVE) | Some(0) | None => None,
+            age => age,
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use rand::Rng;
+use std::fmt;
+
+use crate::{boundary::Boundary, raster::Rgb, rule::Rule};
+
+/// The state of every cell of a multi-state board, stored row by row.
+///
+/// State 0 is a dead cell; a live cell has a state from 1 up to the number of colors of
+/// its variant.
+pub type StateGrid = Vec<Vec<u8>>;
+
+/// The colors of the two Immigration states.
+const IMMIGRATION_PALETTE: [Rgb; 2] = [Rgb(0xff, 0x55, 0x44), Rgb(0x44, 0x99, 0xff)];
+
+/// The colors of the four QuadLife states.
+const QUADLIFE_PALETTE: [Rgb; 4] = [Rgb(0xff, 0x55, 0x44), Rgb(0x44, 0x99, 0xff), Rgb(0x55, 0xdd, 0x55), Rgb(0xff, 0xdd, 0x33)];
+
+/// A variant of the Game of Life whose live cells carry more than one state.
+///
+/// Which cells live and die is decided by the rule as usual; the variant only decides
+/// the state of each newborn cell. Surviving cells keep their state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Variant {
+    /// Plain two-state Life.
+    #[default]
+    Life,
+    /// The Immigration Game: live cells come in two colors, and a newborn takes the
+    /// majority color of its parents.
+    Immigration,
+    /// QuadLife: like Immigration with four colors, except that a newborn whose three
+    /// parents all differ takes the fourth color.
+    QuadLife,
+}
+
+impl Variant {
+    /// Parses a variant name: `life`, `immigration`, or `quadlife`.
+    pub fn parse(name: &str) -> Option<Variant> {
+        match name {
+            "life" => Some(Variant::Life),
+            "immigration" => Some(Variant::Immigration),
+            "quadlife" => Some(Variant::QuadLife),
+            _ => None,
+        }
+    }
+
+    /// Returns the colors of the live states, in order; empty for plain Life.
+    pub fn palette(&self) -> &'static [Rgb] {
+        match self {
+            Variant::Life => &[],
+            Variant::Immigration => &IMMIGRATION_PALETTE,
+            Variant::QuadLife => &QUADLIFE_PALETTE,
+        }
+    }
+
+    /// Returns whether live cells carry a state beyond being alive.
+    pub fn is_multi_state(&self) -> bool {
+        !self.palette().is_empty()
+    }
+
+    /// Gives every live cell of a board a random state.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The board.
+    /// * `rng` - The random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The states of the board.
+    pub fn color(&self, grid: &[Vec<bool>], rng: &mut impl Rng) -> StateGrid {
+        let mut states = vec![vec![0; grid.first().map_or(0, |row| row.len())]; grid.len()];
+        self.reconcile(&mut states, grid, rng);
+        states
+    }
+
+    /// Brings the states in line with a board that was changed from outside the variant,
+    /// such as by the editor or by stepping back.
+    ///
+    /// Live cells keep their state, cells that came alive get a random state, and dead
+    /// cells are cleared. The states are resized to the board if needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The states, updated in place.
+    /// * `grid` - The board.
+    /// * `rng` - The random number generator.
+    pub fn reconcile(&self, states: &mut StateGrid, grid: &[Vec<bool>], rng: &mut impl Rng) {
+        let colors = self.palette().len().max(1) as u8;
+        states.resize(grid.len(), Vec::new());
+        for (row, cells) in states.iter_mut().zip(grid) {
+            row.resize(cells.len(), 0);
+            for (state, &alive) in row.iter_mut().zip(cells) {
+                if !alive {
+                    *state = 0;
+                } else if *state == 0 {
+                    *state = rng.gen_range(1..=colors);
+                }
+            }
+        }
+    }
+
+    /// Computes the states of the next generation.
+    ///
+    /// The parents of a newborn are the live cells of its neighbourhood under the rule,
+    /// including those seen across the edges of the board. The cells of a live boundary
+    /// have no state and are left out.
+    ///
+    /// # Arguments
+    ///
+    /// * `states` - The states of the current generation.
+    /// * `next` - The next generation, as computed by the rule.
+    /// * `rule` - The rule that computed it, for the shape and reach of the neighbourhood.
+    /// * `boundary` - The boundary it was computed with.
+    ///
+    /// # Returns
+    ///
+    /// The states of the next generation: survivors keep their state, and each newborn
+    /// takes the most common state among its live neighbours, the lowest on a tie. In
+    /// QuadLife, a newborn whose parents all have different states and leave exactly one
+    /// state out takes that one.
+    pub fn step(&self, states: &[Vec<u8>], next: &[Vec<bool>], rule: &Rule, boundary: Boundary) -> StateGrid {
+        let colors = self.palette().len().max(1);
+        let (width, height) = (states.first().map_or(0, Vec::len), states.len());
+        let state = |x: isize, y: isize| -> u8 { boundary.map_cell(x, y, width, height).map_or(0, |(x, y)| states[y][x]) };
+        let (radius, neighborhood) = rule.neighborhood();
+        let r = radius as isize;
+
+        next.iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, &alive)| {
+                        let current = state(x as isize, y as isize);
+                        if !alive || current != 0 {
+                            return if alive { current } else { 0 };
+                        }
+
+                        // Count the states of the parents, then take the most common one.
+                        let mut counts = vec![0usize; colors + 1];
+                        for dy in -r..=r {
+                            for dx in -r..=r {
+                                if (dx != 0 || dy != 0) && neighborhood.contains(dx, dy, radius, y) {
+                                    counts[state(x as isize + dx, y as isize + dy) as usize] += 1;
+                                }
+                            }
+                        }
+                        if *self == Variant::QuadLife && counts[1..].iter().all(|&count| count <= 1) {
+                            let absent: Vec<usize> = (1..=colors).filter(|&s| counts[s] == 0).collect();
+                            if let [absent] = absent[..] {
+                                return absent as u8;
+                            }
+                        }
+                        (1..=colors).rev().max_by_key(|&s| counts[s]).unwrap_or(1) as u8
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Variant::Life => "life",
+            Variant::Immigration => "immigration",
+            Variant::QuadLife => "quadlife",
+        })
+    }
+}
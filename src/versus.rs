@@ -0,0 +1,242 @@
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    QueueableCommand,
+};
+use std::{
+    error::Error,
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use gameoflife::{boundary::Boundary, raster::Rgb, rule::Rule, update_grid, variant::{StateGrid, Variant}, Grid};
+
+use crate::{
+    display::{clear_screen, display_grid, display_status, CellStates, DisplayStyle, Region, RenderMode, Screen, Terminal},
+    input::{self, Input},
+};
+
+/// The number of cells each player places per round by default.
+pub const DEFAULT_BUDGET: usize = 12;
+
+/// The number of generations between placement rounds by default.
+pub const DEFAULT_ROUND: u64 = 50;
+
+/// The names of the players, in the order of their states.
+const PLAYERS: [&str; 2] = ["Red", "Blue"];
+
+/// The settings of a two-player match.
+#[derive(Clone, Copy, Debug)]
+pub struct Match {
+    /// The number of generations the match lasts.
+    pub generations: u64,
+    /// The number of cells each player places per round.
+    pub budget: usize,
+    /// The number of generations between placement rounds.
+    pub round: u64,
+}
+
+/// One of the two players.
+struct Player {
+    /// The state of the player's cells: 1 for red, 2 for blue.
+    state: u8,
+    /// The cell the player's cursor is on.
+    cursor: (usize, usize),
+    /// The columns of the player's half of the board.
+    columns: std::ops::Range<usize>,
+    /// The cells the player can still place this round.
+    budget: usize,
+    /// Whether the player is done placing for this round.
+    ready: bool,
+}
+
+impl Player {
+    /// Moves the cursor, keeping it on the player's half of the board.
+    fn step_cursor(&mut self, dx: isize, dy: isize, height: usize) {
+        let (x, y) = self.cursor;
+        let x = (x as isize + dx).clamp(self.columns.start as isize, self.columns.end as isize - 1);
+        let y = (y as isize + dy).clamp(0, height as isize - 1);
+        self.cursor = (x as usize, y as usize);
+    }
+
+    /// Places a cell of the player's color under the cursor, if it is empty and the
+    /// budget allows.
+    fn place(&mut self, states: &mut StateGrid) {
+        let (x, y) = self.cursor;
+        if self.budget > 0 && states[y][x] == 0 {
+            states[y][x] = self.state;
+            self.budget -= 1;
+            self.ready = self.budget == 0;
+        }
+    }
+}
+
+/// Returns the live cells of each player.
+fn territory(states: &[Vec<u8>]) -> [usize; 2] {
+    let count = |state: u8| states.iter().flatten().filter(|&&cell| cell == state).count();
+    [count(1), count(2)]
+}
+
+/// Runs a two-player match on one keyboard.
+///
+/// Red owns the left half of the board and blue the right. Each round starts with both
+/// players placing up to their budget of cells on their own half, red with `WASD` and
+/// `F` and blue with the arrow keys and `Enter`; `G` and `Backspace` end a player's turn
+/// early. The board then runs for a round of generations, newborn cells taking the color
+/// of most of their parents as in Immigration. When the match is over, the player with
+/// more live cells wins.
+///
+/// # Arguments
+///
+/// * `settings` - The length of the match, the budget, and the length of a round.
+/// * `rule` - The rule the board follows.
+/// * `speed` - The number of generations per second.
+/// * `style` - The glyphs to draw with; cells are always drawn one per character.
+/// * `size` - The width and height of the board.
+/// * `console` - The number of terminal columns and rows available for the board.
+pub fn run(settings: Match, rule: &Rule, speed: f64, style: &DisplayStyle, size: (usize, usize), console: (usize, usize)) -> Result<(), Box<dyn Error>> {
+    let (width, height) = size;
+    if width < 2 || height < 1 {
+        return Err("The board is too small for two players".into());
+    }
+    let (cols, rows) = console;
+    let style = DisplayStyle { mode: RenderMode::Ascii, ..style.clone() };
+    let palette = Variant::Immigration.palette();
+
+    let mut states: StateGrid = vec![vec![0; width]; height];
+    let mut players = [
+        Player { state: 1, cursor: (width / 4, height / 2), columns: 0..width / 2, budget: 0, ready: true },
+        Player { state: 2, cursor: (width / 2 + width / 4, height / 2), columns: width / 2..width, budget: 0, ready: true },
+    ];
+    let mut generation: u64 = 0;
+    let tick = Duration::from_secs_f64(1.0 / speed);
+
+    let mut term = Terminal::new(None);
+    let screen = Screen::enter()?;
+    let raw_mode = input::RawMode::enable().map_err(|e| format!("Two-player mode needs keyboard input: {}", e))?;
+    clear_screen(&mut term, &style)?;
+    let mut prev_grid: Grid = vec![vec![false; width]; height];
+    let mut prev_states: StateGrid = Vec::new();
+    // The cursor cells drawn last frame, redrawn as plain cells once the cursors move on.
+    let mut drawn_cursors: Vec<(usize, usize)> = Vec::new();
+    // The generation the last placement round started at.
+    let mut last_round = None;
+    let mut running = true;
+
+    while running {
+        // A new round of placements starts every `round` generations.
+        let placing = players.iter().any(|player| !player.ready);
+        if !placing && generation < settings.generations && generation.is_multiple_of(settings.round) && last_round != Some(generation) {
+            for player in players.iter_mut() {
+                player.budget = settings.budget;
+                player.ready = false;
+            }
+            last_round = Some(generation);
+        }
+        let placing = players.iter().any(|player| !player.ready);
+
+        // Draw the board, then the cursors of the players still placing on top of it.
+        let grid: Grid = states.iter().map(|row| row.iter().map(|&state| state != 0).collect()).collect();
+        for &(x, y) in &drawn_cursors {
+            prev_grid[y][x] = !grid[y][x];
+        }
+        let cells = CellStates { states: &states, prev_states: &prev_states, palette };
+        display_grid(&mut term, &grid, &prev_grid, &style, Some(&cells), Region::screen(cols, rows))?;
+        prev_grid = grid.clone();
+        prev_states = states.clone();
+        drawn_cursors.clear();
+        for player in players.iter().filter(|player| !player.ready) {
+            let (x, y) = player.cursor;
+            if x < cols && y < rows {
+                let Rgb(r, g, b) = palette[player.state as usize - 1];
+                term.queue(cursor::MoveTo(x as u16, y as u16))?;
+                term.queue(SetForegroundColor(Color::Rgb { r, g, b }))?;
+                term.queue(SetAttribute(Attribute::Reverse))?;
+                term.queue(Print(if states[y][x] != 0 { style.alive_glyph } else { '+' }))?;
+                term.queue(SetAttribute(Attribute::NoReverse))?;
+                term.queue(ResetColor)?;
+                drawn_cursors.push((x, y));
+            }
+        }
+
+        let score = territory(&states);
+        let phase = if placing {
+            let budgets: Vec<String> = players.iter().map(|player| format!("{} places {}", PLAYERS[player.state as usize - 1], player.budget)).collect();
+            budgets.join(", ")
+        } else if generation >= settings.generations {
+            match score[0].cmp(&score[1]) {
+                std::cmp::Ordering::Greater => "Red wins! Esc quits".to_string(),
+                std::cmp::Ordering::Less => "Blue wins! Esc quits".to_string(),
+                std::cmp::Ordering::Equal => "A draw! Esc quits".to_string(),
+            }
+        } else {
+            format!("next round at gen {}", (generation / settings.round + 1) * settings.round)
+        };
+        let status = format!("Red {} | Blue {} | gen {}/{} | {}", score[0], score[1], generation, settings.generations, phase);
+        display_status(&mut term, &status, rows, cols, &style)?;
+        term.flush()?;
+
+        // The board only runs between placement rounds, until the match is over.
+        let started = Instant::now();
+        if !placing && generation < settings.generations {
+            let next = update_grid(&grid, rule);
+            states = Variant::Immigration.step(&states, &next, rule, Boundary::Dead);
+            generation += 1;
+        }
+
+        let wait = if placing { Duration::from_millis(20) } else { tick.saturating_sub(started.elapsed()) };
+        for input in input::wait_for_input(wait)? {
+            let Input::Key(key) = input else { continue };
+            if input::is_ctrl_c(&key) || key.code == KeyCode::Esc {
+                running = false;
+            } else if placing {
+                handle_placement_key(&key, &mut players, &mut states, height);
+            }
+        }
+    }
+
+    drop(raw_mode);
+    drop(screen);
+    let score = territory(&states);
+    println!("Exiting...");
+    println!("Red {} | Blue {} after {} generations", score[0], score[1], generation);
+    Ok(())
+}
+
+/// Moves a cursor or places a cell for the player a key belongs to.
+fn handle_placement_key(key: &KeyEvent, players: &mut [Player; 2], states: &mut StateGrid, height: usize) {
+    let (player, action) = match key.code {
+        KeyCode::Char(c) => match c.to_ascii_lowercase() {
+            'w' => (0, (0, -1)),
+            'a' => (0, (-1, 0)),
+            's' => (0, (0, 1)),
+            'd' => (0, (1, 0)),
+            'f' => (0, (0, 0)),
+            'g' => {
+                players[0].ready = true;
+                return;
+            }
+            _ => return,
+        },
+        KeyCode::Up => (1, (0, -1)),
+        KeyCode::Left => (1, (-1, 0)),
+        KeyCode::Down => (1, (0, 1)),
+        KeyCode::Right => (1, (1, 0)),
+        KeyCode::Enter => (1, (0, 0)),
+        KeyCode::Backspace => {
+            players[1].ready = true;
+            return;
+        }
+        _ => return,
+    };
+
+    let player = &mut players[player];
+    if player.ready {
+        return;
+    }
+    match action {
+        (0, 0) => player.place(states),
+        (dx, dy) => player.step_cursor(dx, dy, height),
+    }
+}
@@ -0,0 +1,50 @@
+use std::{fs, path::Path, time::SystemTime};
+
+use gameoflife::placement::Placement;
+
+/// Watches a pattern file for changes so the board can be reset to it.
+///
+/// The file's modification time is polled once per generation, which is cheap
+/// enough for a single file and needs no platform-specific notification API.
+pub struct PatternWatcher {
+    /// The placement of the watched pattern.
+    pub placement: Placement,
+    /// The modification time seen at the last check.
+    last_modified: Option<SystemTime>,
+}
+
+impl PatternWatcher {
+    /// Starts watching the pattern file of a placement.
+    ///
+    /// # Arguments
+    ///
+    /// * `placement` - The placement whose source is the watched file.
+    pub fn new(placement: Placement) -> PatternWatcher {
+        let last_modified = modified(Path::new(&placement.source));
+        PatternWatcher {
+            placement,
+            last_modified,
+        }
+    }
+
+    /// Checks whether the file changed since the last check.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the file's modification time changed, including when the file
+    /// appeared or disappeared.
+    pub fn changed(&mut self) -> bool {
+        let modified = modified(Path::new(&self.placement.source));
+        if modified != self.last_modified {
+            self.last_modified = modified;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Returns the modification time of a file, or `None` if it cannot be read.
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,531 @@
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::HashSet,
+    ops::Deref,
+    sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError},
+    thread,
+};
+
+use crate::{
+    boundary::Boundary,
+    engine::{Engine, Packed},
+    hash::BoardHash,
+    rule::Rule,
+    stats::GenerationDiff,
+    Grid,
+};
+
+/// A board that is advanced in place, with every buffer kept from one generation to the
+/// next.
+///
+/// [`update_grid`](crate::update_grid) returns a new board each generation, and packs and
+/// unpacks it on the way. A world instead leaves the board in an [`Engine`], the
+/// bit-parallel one unless [another is given](World::with_engine), which keeps its own
+/// buffers between steps. The cells are only unpacked when the board is read, so a run
+/// that only looks at the [population](World::population) or the
+/// [cells that changed](World::changes) costs what the engine costs, which for the sparse
+/// engine is the live cells rather than the whole board. Once the buffers have grown to
+/// the size of the board, stepping a life-like rule with the bit-parallel engine allocates
+/// nothing, even when every generation is read.
+///
+/// A world may also give its cells a [maximum age](World::with_max_age), past which they
+/// die whatever the rule says.
+///
+/// The world keeps the [hash](World::hash) of its board up to date from the cells that
+/// change, so looking for cycles does not rehash the board every generation.
+///
+/// The world reads as its current board. It is edited through the engine, a cell at a
+/// time with [`World::set`], or with [`World::edit`] for the functions that change a grid.
+///
+/// A world is `Send`, so it can be stepped on another thread than the one that draws it.
+/// It can also [compute its generations ahead](World::with_lookahead) on a thread of its
+/// own, while the thread that owns it draws the ones before.
+#[derive(Debug)]
+pub struct World {
+    /// The engine that holds the board and computes each generation.
+    engine: Box<dyn Engine>,
+    /// The board unpacked from the engine, once it has been read since the last change.
+    view: OnceCell<Grid>,
+    /// The buffers of the last board unpacked, reused for the next one.
+    spare: RefCell<Grid>,
+    /// The cells that were born or died in the last step.
+    changes: Vec<(usize, usize)>,
+    /// The hash of the board, updated with every cell that changes.
+    hash: BoardHash,
+    /// The most generations in a row a cell may be alive, if there is a limit.
+    max_age: Option<u32>,
+    /// The number of generations in a row each live cell has been alive, kept while there
+    /// is a maximum age. Cells set by editing the board count from 0.
+    ages: Vec<Vec<u32>>,
+    /// The thread that computes the generations ahead, if there is one.
+    lookahead: Option<Lookahead>,
+}
+
+/// The most generations a look-ahead thread computes before they are taken.
+pub const GENERATIONS_AHEAD: usize = 4;
+
+impl World {
+    /// Creates a world.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The starting board.
+    /// * `boundary` - What lies past the edges of the board.
+    pub fn new(grid: Grid, boundary: Boundary) -> World {
+        let mut engine: Box<dyn Engine> = Box::new(Packed::new(boundary));
+        engine.load(&grid);
+        World {
+            engine,
+            hash: BoardHash::of(&grid),
+            view: OnceCell::from(grid),
+            spare: RefCell::default(),
+            changes: Vec::new(),
+            max_age: None,
+            ages: Vec::new(),
+            lookahead: None,
+        }
+    }
+
+    /// Limits how many generations in a row a cell may be alive.
+    ///
+    /// A cell that has been alive for this many generations dies in the next one, even if
+    /// the rule would have it survive, so no pattern stays still for long. Ageing visits
+    /// every cell of the board each step.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age` - The most generations in a row a cell may be alive, from 1.
+    pub fn with_max_age(mut self, max_age: u32) -> World {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Computes the generations with another engine, such as one of those
+    /// [`EngineKind::create`](crate::engine::EngineKind::create) makes. The board is
+    /// handed over to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine, created with the boundary the board should have.
+    pub fn with_engine(mut self, mut engine: Box<dyn Engine>) -> World {
+        engine.load(&self);
+        self.engine = engine;
+        self
+    }
+
+    /// Computes the generations on a thread of their own, up to [`GENERATIONS_AHEAD`] of
+    /// them before they are taken, and sends each back through a bounded queue as the cells
+    /// that changed. A step then only applies those cells, so a thread that spends its time
+    /// drawing leaves the neighbour counting to the other.
+    ///
+    /// The thread keeps a board of its own. Whenever this one was edited or the rule
+    /// changed since the thread was last given it, which the [hash](World::hash) tells, the
+    /// generations computed ahead are thrown away and the thread starts over from the
+    /// edited board, so a step gives the same board as without the thread, at the cost of a
+    /// wait. Boards edited before every step, as with noise, are better stepped without it.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - Another engine of the kind the world uses, created with the same
+    ///   boundary, for the thread's board. The thread is started at the first step, with the
+    ///   [maximum age](World::with_max_age) of the world.
+    pub fn with_lookahead(mut self, engine: Box<dyn Engine>) -> World {
+        self.lookahead = Some(Lookahead::Idle(engine));
+        self
+    }
+
+    /// Returns the width and height of the board in cells.
+    pub fn bounds(&self) -> (usize, usize) {
+        self.engine.bounds()
+    }
+
+    /// Returns whether a cell is alive, without unpacking the board.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell, within the bounds.
+    /// * `y` - The row of the cell, within the bounds.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        match self.view.get() {
+            Some(view) => view[y][x],
+            None => self.engine.get(x, y),
+        }
+    }
+
+    /// Brings a cell to life or kills it.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell, within the bounds.
+    /// * `y` - The row of the cell, within the bounds.
+    /// * `alive` - Whether the cell is alive.
+    pub fn set(&mut self, x: usize, y: usize, alive: bool) {
+        if self.engine.get(x, y) != alive {
+            self.hash.toggle(x, y);
+        }
+        self.engine.set(x, y, alive);
+        if let Some(view) = self.view.get_mut() {
+            view[y][x] = alive;
+        }
+    }
+
+    /// Brings a dead cell to life or kills a live one.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The column of the cell, within the bounds.
+    /// * `y` - The row of the cell, within the bounds.
+    pub fn toggle(&mut self, x: usize, y: usize) {
+        let alive = self.get(x, y);
+        self.set(x, y, !alive);
+    }
+
+    /// Returns the number of live cells, as the engine counts them.
+    pub fn population(&self) -> usize {
+        self.engine.population()
+    }
+
+    /// Replaces the board, which may have another size.
+    ///
+    /// # Arguments
+    ///
+    /// * `grid` - The new board.
+    pub fn replace(&mut self, grid: Grid) {
+        self.engine.load(&grid);
+        self.hash = BoardHash::of(&grid);
+        self.view = OnceCell::from(grid);
+    }
+
+    /// Changes the board with a function that edits a grid, such as placing a pattern.
+    ///
+    /// The engine is given only the cells the function changed, or the whole board if it
+    /// changed the size.
+    ///
+    /// # Arguments
+    ///
+    /// * `edit` - The function, given the current board.
+    ///
+    /// # Returns
+    ///
+    /// What the function returned.
+    pub fn edit<T>(&mut self, edit: impl FnOnce(&mut Grid) -> T) -> T {
+        let mut grid = self.unpacked();
+        let result = edit(&mut grid);
+        let (width, height) = self.engine.bounds();
+        if grid.len() != height || grid.iter().any(|row| row.len() != width) {
+            self.engine.load(&grid);
+            self.hash = BoardHash::of(&grid);
+        } else {
+            for (y, row) in grid.iter().enumerate() {
+                for (x, &alive) in row.iter().enumerate() {
+                    if self.engine.get(x, y) != alive {
+                        self.engine.set(x, y, alive);
+                        self.hash.toggle(x, y);
+                    }
+                }
+            }
+        }
+        self.view = OnceCell::from(grid);
+        result
+    }
+
+    /// Returns the hash of the board, as [`BoardHash::of`] would compute it.
+    pub fn hash(&self) -> BoardHash {
+        self.hash
+    }
+
+    /// Returns the cells that were born or died in the last step, including those that
+    /// died of old age, as `(x, y)`.
+    pub fn changes(&self) -> &[(usize, usize)] {
+        &self.changes
+    }
+
+    /// Returns the births, deaths, and population of the last step, from the cells that
+    /// changed rather than by comparing whole boards.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - The generation the step reached.
+    pub fn diff(&self, generation: u64) -> GenerationDiff {
+        let (births, deaths) = self.changes.iter().partition(|&&(x, y)| self.get(x, y));
+        GenerationDiff { generation, births, deaths, population: self.population() }
+    }
+
+    /// Advances the board by one generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The birth and survival rule to apply.
+    pub fn step(&mut self, rule: &Rule) {
+        if let Some(mut lookahead) = self.lookahead.take() {
+            let changes = lookahead.next(self, rule);
+            self.lookahead = Some(lookahead);
+            if let Some(changes) = changes {
+                self.apply(changes);
+                return;
+            }
+        }
+
+        self.engine.step(rule, &mut self.changes);
+        if let Some(view) = self.view.take() {
+            self.spare.replace(view);
+        }
+        if let Some(max_age) = self.max_age {
+            self.age_cells(max_age);
+        }
+        self.hash.apply_changes(self.changes.iter().copied());
+    }
+
+    /// Advances the board by a step computed from the same board elsewhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `changes` - The cells that were born or died in the step.
+    fn apply(&mut self, changes: Vec<(usize, usize)>) {
+        for &(x, y) in &changes {
+            let alive = self.engine.get(x, y);
+            self.engine.set(x, y, !alive);
+        }
+        if let Some(view) = self.view.take() {
+            self.spare.replace(view);
+        }
+        self.hash.apply_changes(changes.iter().copied());
+        self.changes = changes;
+    }
+
+    /// Returns an iterator that advances the board one generation each time it is polled,
+    /// yielding a copy of each new generation.
+    ///
+    /// Nothing is computed until an item is asked for, and the iterator never ends, so it
+    /// is bounded with adapters such as `take`, as in
+    /// `world.iter_generations(&rule).take(1000).map(|g| g.population())`.
+    ///
+    /// The world stays borrowed while the iterator lives, and is left at the last
+    /// generation yielded. Each item owns its cells, so it may be kept past the next step;
+    /// when only a figure such as the population is wanted, calling [`World::step`] in a
+    /// loop avoids the copies.
+    ///
+    /// # Arguments
+    ///
+    /// * `rule` - The birth and survival rule to apply.
+    pub fn iter_generations<'a>(&'a mut self, rule: &'a Rule) -> Generations<'a> {
+        Generations { world: self, rule, steps: 0 }
+    }
+
+    /// Takes the board out of the view, or unpacks it from the engine if it has changed
+    /// since it was last read.
+    fn unpacked(&mut self) -> Grid {
+        self.view.take().unwrap_or_else(|| {
+            let mut grid = self.spare.take();
+            self.engine.store(&mut grid);
+            grid
+        })
+    }
+
+    /// Ages the cells that survived the last step, and kills those past the maximum age.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_age` - The most generations in a row a cell may be alive.
+    fn age_cells(&mut self, max_age: u32) {
+        let (width, height) = self.engine.bounds();
+        if self.ages.len() != height || self.ages.first().map_or(0, |row| row.len()) != width {
+            self.ages = vec![vec![0; width]; height];
+        }
+
+        // A live cell that did not change was alive before the step as well.
+        let changed: HashSet<(usize, usize)> = self.changes.iter().copied().collect();
+        for y in 0..height {
+            for x in 0..width {
+                let age = &mut self.ages[y][x];
+                *age = match (self.engine.get(x, y), changed.contains(&(x, y))) {
+                    (false, _) => 0,
+                    (true, false) => (*age).max(1) + 1,
+                    (true, true) => 1,
+                };
+                // Only cells that survived get past an age of 1, so these were alive before.
+                if *age > max_age {
+                    *age = 0;
+                    self.engine.set(x, y, false);
+                    self.changes.push((x, y));
+                }
+            }
+        }
+    }
+}
+
+/// The thread of a world that computes its generations ahead.
+#[derive(Debug)]
+enum Lookahead {
+    /// The engine of the thread's board, until the first step starts the thread.
+    Idle(Box<dyn Engine>),
+    /// The running thread.
+    Running {
+        /// Where the boards the thread starts over from are sent.
+        jobs: Sender<Job>,
+        /// The steps the thread computed, in order.
+        steps: Receiver<Ahead>,
+        /// The last job the thread was given.
+        job: u64,
+        /// The rule of the last job.
+        rule: Rule,
+    },
+    /// The thread is gone, and the world steps itself.
+    Stopped,
+}
+
+/// A board for the look-ahead thread to start over from.
+struct Job {
+    /// The number of the job, which the steps computed from it carry.
+    id: u64,
+    /// The board.
+    grid: Grid,
+    /// The rule to step it by.
+    rule: Rule,
+}
+
+/// A step computed by the look-ahead thread.
+struct Ahead {
+    /// The job the step was computed for.
+    job: u64,
+    /// The size and hash of the board the step was computed from.
+    from: ((usize, usize), BoardHash),
+    /// The cells that were born or died in the step.
+    changes: Vec<(usize, usize)>,
+}
+
+impl Lookahead {
+    /// Takes the next step of a world from the thread, starting the thread over from the
+    /// world's board first if the thread was stepping another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `world` - The world, at the board the step starts from.
+    /// * `rule` - The rule to step it by.
+    ///
+    /// # Returns
+    ///
+    /// The cells that change in the step, or `None` if the thread is gone.
+    fn next(&mut self, world: &World, rule: &Rule) -> Option<Vec<(usize, usize)>> {
+        *self = match std::mem::replace(self, Lookahead::Stopped) {
+            Lookahead::Idle(engine) => Lookahead::spawn(engine, world, rule),
+            running => running,
+        };
+        let Lookahead::Running { jobs, steps, job, rule: job_rule } = self else { return None };
+
+        let mut stale = job_rule != rule;
+        loop {
+            if stale {
+                *job += 1;
+                *job_rule = *rule;
+                if jobs.send(Job { id: *job, grid: world.to_vec(), rule: *rule }).is_err() {
+                    *self = Lookahead::Stopped;
+                    return None;
+                }
+            }
+            match steps.recv() {
+                // Steps of earlier jobs were computed from boards since replaced.
+                Ok(step) if step.job != *job => stale = false,
+                Ok(step) if step.from == (world.bounds(), world.hash()) => return Some(step.changes),
+                // The board was edited since the thread was given it.
+                Ok(_) => stale = true,
+                Err(_) => {
+                    *self = Lookahead::Stopped;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// Starts the thread on a world's board.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine of the thread's board.
+    /// * `world` - The world, for its board and maximum age.
+    /// * `rule` - The rule to step the board by.
+    fn spawn(engine: Box<dyn Engine>, world: &World, rule: &Rule) -> Lookahead {
+        let (jobs, queue): (Sender<Job>, Receiver<Job>) = mpsc::channel();
+        let (results, steps): (SyncSender<Ahead>, Receiver<Ahead>) = mpsc::sync_channel(GENERATIONS_AHEAD);
+        let mut board = World::new(world.to_vec(), Boundary::default()).with_engine(engine);
+        board.max_age = world.max_age;
+        let mut current = (0, *rule);
+
+        thread::spawn(move || loop {
+            // A new job replaces the board; otherwise the thread carries on with the last.
+            match queue.try_recv() {
+                Ok(job) => {
+                    board.replace(job.grid);
+                    current = (job.id, job.rule);
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => return,
+            }
+            let from = (board.bounds(), board.hash());
+            board.step(&current.1);
+            // The queue is full until the world takes a step, and gone with the world.
+            if results.send(Ahead { job: current.0, from, changes: board.changes.clone() }).is_err() {
+                return;
+            }
+        });
+        Lookahead::Running { jobs, steps, job: 0, rule: *rule }
+    }
+}
+
+/// The generations of a world, computed one at a time as they are asked for.
+///
+/// Made by [`World::iter_generations`].
+pub struct Generations<'a> {
+    /// The world being advanced.
+    world: &'a mut World,
+    /// The rule the world is advanced by.
+    rule: &'a Rule,
+    /// The number of generations yielded so far.
+    steps: u64,
+}
+
+impl Iterator for Generations<'_> {
+    type Item = Generation;
+
+    fn next(&mut self) -> Option<Generation> {
+        self.world.step(self.rule);
+        self.steps += 1;
+        Some(Generation { steps: self.steps, cells: Grid::clone(self.world) })
+    }
+}
+
+/// A generation yielded by [`Generations`], with a copy of its cells.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Generation {
+    /// How many steps past the board the iterator started from this generation is, from 1.
+    pub steps: u64,
+    /// The cells of the generation.
+    pub cells: Grid,
+}
+
+impl Generation {
+    /// Returns the number of live cells.
+    pub fn population(&self) -> usize {
+        self.cells.iter().flatten().filter(|&&alive| alive).count()
+    }
+}
+
+impl Deref for Generation {
+    type Target = Grid;
+
+    fn deref(&self) -> &Grid {
+        &self.cells
+    }
+}
+
+impl Deref for World {
+    type Target = Grid;
+
+    fn deref(&self) -> &Grid {
+        self.view.get_or_init(|| {
+            let mut grid = self.spare.take();
+            self.engine.store(&mut grid);
+            grid
+        })
+    }
+}
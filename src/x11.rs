@@ -0,0 +1,626 @@
+//! A window on an X11 display, for the `--gui` frontend of the `gui` feature.
+//!
+//! The X11 protocol is spoken directly over the display's socket, so no windowing library
+//! is needed: the window is created, the board is sent to it as images, and the keys,
+//! mouse buttons, and size changes come back as [`Event`]s. Only 24-bit TrueColor
+//! displays are supported, which covers X servers and XWayland as they are set up today.
+
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, Read, Write},
+    net::TcpStream,
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use crate::raster::{Image, Rgb};
+
+/// The atom of the `WM_NAME` property, which holds the window title.
+const WM_NAME: u32 = 39;
+
+/// The atom of the `STRING` type.
+const STRING: u32 = 31;
+
+/// The atom of the `ATOM` type.
+const ATOM: u32 = 4;
+
+/// The events the window asks for: key and button presses and releases, drags, exposure,
+/// and changes to its size.
+const EVENT_MASK: u32 = 0x1 | 0x4 | 0x8 | 0x2000 | 0x8000 | 0x20000;
+
+/// The modifier bits of the state of a key or button event.
+const SHIFT_MASK: u16 = 0x1;
+const CONTROL_MASK: u16 = 0x4;
+
+/// The bits of the state of a motion event telling which buttons are held.
+const BUTTON1_MASK: u16 = 0x100;
+const BUTTON3_MASK: u16 = 0x400;
+
+/// Something that happened to the window.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A key was pressed.
+    Key {
+        /// The keysym of the key, such as `0x71` for `q` or `0xff1b` for Escape.
+        keysym: u32,
+        /// Whether Control was held.
+        control: bool,
+    },
+    /// A mouse button was pressed, or the mouse moved with a button held.
+    Button {
+        /// The button: 1 for the left one, 2 for the middle one, 3 for the right one.
+        button: u8,
+        /// The column of the pixel under the mouse.
+        x: i16,
+        /// The row of the pixel under the mouse.
+        y: i16,
+    },
+    /// Part of the window has to be drawn again.
+    Expose,
+    /// The window changed size.
+    Resize {
+        /// The new width in pixels.
+        width: u16,
+        /// The new height in pixels.
+        height: u16,
+    },
+    /// The window was closed, or the display went away.
+    Close,
+}
+
+/// The socket of the display.
+enum Socket {
+    /// A socket on this machine, such as `/tmp/.X11-unix/X0`.
+    Unix(UnixStream),
+    /// A display over TCP.
+    Tcp(TcpStream),
+}
+
+impl Socket {
+    /// Returns a second handle on the socket, for the thread reading the events.
+    fn try_clone(&self) -> io::Result<Socket> {
+        Ok(match self {
+            Socket::Unix(socket) => Socket::Unix(socket.try_clone()?),
+            Socket::Tcp(socket) => Socket::Tcp(socket.try_clone()?),
+        })
+    }
+}
+
+impl Read for Socket {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Socket::Unix(socket) => socket.read(buf),
+            Socket::Tcp(socket) => socket.read(buf),
+        }
+    }
+}
+
+impl Write for Socket {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Socket::Unix(socket) => socket.write(buf),
+            Socket::Tcp(socket) => socket.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Socket::Unix(socket) => socket.flush(),
+            Socket::Tcp(socket) => socket.flush(),
+        }
+    }
+}
+
+/// How the display lays out the pixels of an image.
+#[derive(Clone, Copy, Debug)]
+struct PixelFormat {
+    /// The depth of the window, in bits of color.
+    depth: u8,
+    /// The bits each pixel takes in an image.
+    bits_per_pixel: u8,
+    /// The bits each row of an image is padded to.
+    scanline_pad: u8,
+    /// Whether the bytes of a pixel go most significant first.
+    big_endian: bool,
+    /// The masks of the red, green, and blue bits of a pixel.
+    masks: [u32; 3],
+}
+
+impl PixelFormat {
+    /// Returns the value of a pixel of a color.
+    fn pixel(&self, Rgb(r, g, b): Rgb) -> u32 {
+        let channel = |value: u8, mask: u32| {
+            let bits = mask.count_ones().min(8);
+            ((value as u32) >> (8 - bits)) << mask.trailing_zeros()
+        };
+        channel(r, self.masks[0]) | channel(g, self.masks[1]) | channel(b, self.masks[2])
+    }
+
+    /// Returns the number of bytes of each row of an image of a width.
+    fn row_bytes(&self, width: usize) -> usize {
+        let pad = self.scanline_pad as usize / 8;
+        (width * self.bits_per_pixel as usize / 8).div_ceil(pad) * pad
+    }
+}
+
+/// What the display said about itself when the connection was set up.
+struct Setup {
+    /// The first id this client may give to the things it creates.
+    id_base: u32,
+    /// The bits of the ids this client may set.
+    id_mask: u32,
+    /// The longest request in 4-byte units.
+    max_request: usize,
+    /// The smallest keycode.
+    min_keycode: u8,
+    /// The largest keycode.
+    max_keycode: u8,
+    /// The root window of the screen.
+    root: u32,
+    /// The width and height of the screen in pixels.
+    screen_size: (u16, u16),
+    /// How the screen lays out pixels.
+    format: PixelFormat,
+}
+
+/// A window showing images on an X11 display.
+pub struct Window {
+    /// The socket requests are written to.
+    socket: Socket,
+    /// The events read by the reader thread, or the error that stopped it.
+    events: Receiver<Result<Event, String>>,
+    /// The id of the window.
+    window: u32,
+    /// The id of the graphics context images are drawn with.
+    gc: u32,
+    /// How the display lays out pixels.
+    format: PixelFormat,
+    /// The longest request in 4-byte units.
+    max_request: usize,
+    /// The width and height of the screen in pixels.
+    screen_size: (u16, u16),
+    /// The pixel data of the last image, reused for the next one.
+    buffer: Vec<u8>,
+}
+
+impl Window {
+    /// Opens a window on a display.
+    ///
+    /// # Arguments
+    ///
+    /// * `display` - The display, as in `DISPLAY`: `:0`, `host:0`, or the path of a
+    ///   socket followed by the display number, such as `/tmp/x.sock:0`.
+    /// * `width` - The width of the window in pixels.
+    /// * `height` - The height of the window in pixels.
+    /// * `title` - The title of the window.
+    /// * `background` - The color the window shows before the first image.
+    pub fn open(display: &str, width: u16, height: u16, title: &str, background: Rgb) -> Result<Window, Box<dyn Error>> {
+        let (mut socket, number) = connect(display)?;
+        let setup = handshake(&mut socket, &number)?;
+        let keymap = KeyMap::read(&mut socket, &setup)?;
+        let protocols = intern_atom(&mut socket, "WM_PROTOCOLS")?;
+        let delete = intern_atom(&mut socket, "WM_DELETE_WINDOW")?;
+
+        let id = |n: u32| setup.id_base | ((n << setup.id_mask.trailing_zeros()) & setup.id_mask);
+        let (window, gc) = (id(1), id(2));
+        let mut body = Vec::new();
+        for value in [window, setup.root] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [0u16, 0, width.max(1), height.max(1), 0, 1] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        // The visual is the parent's; the values are the background pixel and the events.
+        for value in [0, 0x2 | 0x800, setup.format.pixel(background), EVENT_MASK] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        socket.write_all(&request(1, setup.format.depth, &body))?;
+        change_property(&mut socket, window, WM_NAME, STRING, 8, title.as_bytes())?;
+        change_property(&mut socket, window, protocols, ATOM, 32, &delete.to_le_bytes())?;
+        let gc_body: Vec<u8> = [gc, window, 0].iter().flat_map(|value| value.to_le_bytes()).collect();
+        socket.write_all(&request(55, 0, &gc_body))?;
+        socket.write_all(&request(8, 0, &window.to_le_bytes()))?;
+        socket.flush()?;
+
+        let (sender, events) = mpsc::channel();
+        let mut reader = socket.try_clone()?;
+        // Nothing comes after the window is closed, so the thread stops there.
+        thread::spawn(move || loop {
+            let event = match read_event(&mut reader, &keymap, delete) {
+                Ok(None) => continue,
+                Ok(Some(event)) => Ok(event),
+                Err(message) => Err(message),
+            };
+            let last = !matches!(event, Ok(Event::Key { .. } | Event::Button { .. } | Event::Expose | Event::Resize { .. }));
+            if sender.send(event).is_err() || last {
+                return;
+            }
+        });
+
+        Ok(Window {
+            socket,
+            events,
+            window,
+            gc,
+            format: setup.format,
+            max_request: setup.max_request,
+            screen_size: setup.screen_size,
+            buffer: Vec::new(),
+        })
+    }
+
+    /// Returns the width and height of the screen the window is on, in pixels.
+    pub fn screen_size(&self) -> (u16, u16) {
+        self.screen_size
+    }
+
+    /// Shows an image in the window, with its top-left corner at the top-left corner of
+    /// the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image.
+    pub fn draw(&mut self, image: &Image) -> Result<(), Box<dyn Error>> {
+        let row_bytes = self.format.row_bytes(image.width);
+        let bytes_per_pixel = self.format.bits_per_pixel as usize / 8;
+        // An image too big for one request is sent in bands of rows.
+        let rows_per_request = ((self.max_request - 6) * 4 / row_bytes.max(1)).max(1);
+        for top in (0..image.height).step_by(rows_per_request) {
+            let rows = rows_per_request.min(image.height - top);
+            self.buffer.clear();
+            for y in top..top + rows {
+                let start = self.buffer.len();
+                for pixel in image.pixels[y * image.width * 3..(y + 1) * image.width * 3].chunks_exact(3) {
+                    let value = self.format.pixel(Rgb(pixel[0], pixel[1], pixel[2]));
+                    let bytes = if self.format.big_endian { value.to_be_bytes() } else { value.to_le_bytes() };
+                    match (self.format.big_endian, bytes_per_pixel) {
+                        (true, n) => self.buffer.extend_from_slice(&bytes[4 - n..]),
+                        (false, n) => self.buffer.extend_from_slice(&bytes[..n]),
+                    }
+                }
+                self.buffer.resize(start + row_bytes, 0);
+            }
+
+            let mut body = Vec::with_capacity(20 + self.buffer.len());
+            body.extend_from_slice(&self.window.to_le_bytes());
+            body.extend_from_slice(&self.gc.to_le_bytes());
+            for value in [image.width as u16, rows as u16, 0, top as u16] {
+                body.extend_from_slice(&value.to_le_bytes());
+            }
+            body.extend_from_slice(&[0, self.format.depth, 0, 0]);
+            body.extend_from_slice(&self.buffer);
+            self.socket.write_all(&request(72, 2, &body))?;
+        }
+        self.socket.flush()?;
+        Ok(())
+    }
+
+    /// Changes the size of the window. The window manager may give it another size, which
+    /// comes back as an [`Event::Resize`].
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width in pixels.
+    /// * `height` - The height in pixels.
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<(), Box<dyn Error>> {
+        let mut body = self.window.to_le_bytes().to_vec();
+        // The values are the width and height.
+        body.extend_from_slice(&[0x4 | 0x8, 0, 0, 0]);
+        for value in [width.max(1) as u32, height.max(1) as u32] {
+            body.extend_from_slice(&value.to_le_bytes());
+        }
+        self.socket.write_all(&request(12, 0, &body))?;
+        self.socket.flush()?;
+        Ok(())
+    }
+
+    /// Fills the whole window with its background color.
+    pub fn clear(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut body = self.window.to_le_bytes().to_vec();
+        body.extend_from_slice(&[0; 8]);
+        self.socket.write_all(&request(61, 0, &body))?;
+        self.socket.flush()?;
+        Ok(())
+    }
+
+    /// Changes the title of the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The new title.
+    pub fn set_title(&mut self, title: &str) -> Result<(), Box<dyn Error>> {
+        change_property(&mut self.socket, self.window, WM_NAME, STRING, 8, title.as_bytes())?;
+        self.socket.flush()?;
+        Ok(())
+    }
+
+    /// Waits for the next event.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait.
+    ///
+    /// # Returns
+    ///
+    /// The event, or `None` if none came in time.
+    pub fn next_event(&self, timeout: Duration) -> Result<Option<Event>, Box<dyn Error>> {
+        match self.events.recv_timeout(timeout) {
+            Ok(Ok(event)) => Ok(Some(event)),
+            Ok(Err(message)) => Err(message.into()),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Ok(Some(Event::Close)),
+        }
+    }
+}
+
+/// Connects to the socket of a display.
+///
+/// # Returns
+///
+/// The socket and the display number, as text, which picks the authorization cookie.
+fn connect(display: &str) -> Result<(Socket, String), Box<dyn Error>> {
+    let invalid = || format!("Invalid X display '{}' (expected one like :0)", display);
+    let (host, rest) = display.rsplit_once(':').ok_or_else(invalid)?;
+    let number = rest.split('.').next().unwrap_or_default();
+    let port: u16 = number.parse().map_err(|_| invalid())?;
+    let socket = if host.starts_with('/') {
+        UnixStream::connect(host).map(Socket::Unix)
+    } else if host.is_empty() || host == "unix" {
+        UnixStream::connect(format!("/tmp/.X11-unix/X{}", number)).map(Socket::Unix)
+    } else {
+        TcpStream::connect((host, 6000 + port)).map(Socket::Tcp)
+    };
+    let socket = socket.map_err(|e| format!("Cannot connect to the X display '{}': {}", display, e))?;
+    Ok((socket, number.to_string()))
+}
+
+/// Returns the name and data of the authorization cookie for a display number, from the
+/// file in `XAUTHORITY` or `~/.Xauthority`, or empty ones if there is none.
+fn cookie(number: &str) -> (Vec<u8>, Vec<u8>) {
+    let path = env::var_os("XAUTHORITY").map(PathBuf::from).or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".Xauthority")));
+    let Some(data) = path.and_then(|path| fs::read(path).ok()) else {
+        return (Vec::new(), Vec::new());
+    };
+
+    // Each entry is a family and four fields, each a big-endian length and its bytes.
+    let mut at = 0;
+    let mut field = || -> Option<&[u8]> {
+        let len = u16::from_be_bytes([*data.get(at)?, *data.get(at + 1)?]) as usize;
+        let bytes = data.get(at + 2..at + 2 + len)?;
+        at += 2 + len;
+        Some(bytes)
+    };
+    loop {
+        let (Some(_family), Some(_address), Some(entry_number), Some(name), Some(cookie)) = (field(), field(), field(), field(), field()) else {
+            return (Vec::new(), Vec::new());
+        };
+        if (entry_number.is_empty() || entry_number == number.as_bytes()) && name == b"MIT-MAGIC-COOKIE-1" {
+            return (name.to_vec(), cookie.to_vec());
+        }
+    }
+}
+
+/// Sets up the connection and reads what the display says about itself.
+fn handshake(socket: &mut Socket, number: &str) -> Result<Setup, Box<dyn Error>> {
+    let (name, data) = cookie(number);
+    let mut hello = vec![b'l', 0];
+    for value in [11u16, 0, name.len() as u16, data.len() as u16, 0] {
+        hello.extend_from_slice(&value.to_le_bytes());
+    }
+    hello.extend_from_slice(&padded(&name));
+    hello.extend_from_slice(&padded(&data));
+    socket.write_all(&hello)?;
+
+    let mut header = [0; 8];
+    socket.read_exact(&mut header)?;
+    let mut info = vec![0; u16::from_le_bytes([header[6], header[7]]) as usize * 4];
+    socket.read_exact(&mut info)?;
+    if header[0] != 1 {
+        let reason = String::from_utf8_lossy(&info[..(header[1] as usize).min(info.len())]).trim().to_string();
+        return Err(format!("The X display refused the connection: {}", reason).into());
+    }
+    parse_setup(&info).ok_or_else(|| "The X display sent a setup it cannot be used with".into())
+}
+
+/// Parses the connection setup, without its first 8 bytes.
+///
+/// # Returns
+///
+/// The setup, or `None` if the setup is cut short or the screen is not TrueColor.
+fn parse_setup(info: &[u8]) -> Option<Setup> {
+    let u16_at = |at: usize| Some(u16::from_le_bytes([*info.get(at)?, *info.get(at + 1)?]));
+    let u32_at = |at: usize| Some(u32::from_le_bytes(info.get(at..at + 4)?.try_into().ok()?));
+
+    let vendor_len = u16_at(16)? as usize;
+    let (screens, formats) = (*info.get(20)?, *info.get(21)? as usize);
+    if screens == 0 {
+        return None;
+    }
+    let formats_at = 32 + vendor_len.div_ceil(4) * 4;
+    let screen = formats_at + 8 * formats;
+    let (root, depth) = (u32_at(screen)?, *info.get(screen + 38)?);
+    let (root_visual, depths) = (u32_at(screen + 32)?, *info.get(screen + 39)?);
+
+    // The bits of a pixel and the padding of a row go with the depth.
+    let (bits_per_pixel, scanline_pad) = (0..formats)
+        .map(|i| formats_at + 8 * i)
+        .find(|&at| info.get(at) == Some(&depth))
+        .map(|at| Some((*info.get(at + 1)?, *info.get(at + 2)?)))??;
+
+    // The masks of the colors come from the visual of the root window, which must be a
+    // TrueColor one.
+    let mut at = screen + 40;
+    let mut masks = None;
+    for _ in 0..depths {
+        let visuals = u16_at(at + 2)? as usize;
+        for visual in (0..visuals).map(|i| at + 8 + 24 * i) {
+            if u32_at(visual)? == root_visual && *info.get(visual + 4)? == 4 {
+                masks = Some([u32_at(visual + 8)?, u32_at(visual + 12)?, u32_at(visual + 16)?]);
+            }
+        }
+        at += 8 + 24 * visuals;
+    }
+    if !matches!(bits_per_pixel, 24 | 32) || scanline_pad < 8 {
+        return None;
+    }
+
+    Some(Setup {
+        id_base: u32_at(4)?,
+        id_mask: u32_at(8)?,
+        max_request: u16_at(18)? as usize,
+        min_keycode: *info.get(26)?,
+        max_keycode: *info.get(27)?,
+        root,
+        screen_size: (u16_at(screen + 20)?, u16_at(screen + 22)?),
+        format: PixelFormat { depth, bits_per_pixel, scanline_pad, big_endian: *info.get(22)? == 1, masks: masks? },
+    })
+}
+
+/// The keysyms of every keycode, without and with Shift.
+struct KeyMap {
+    /// The smallest keycode.
+    min_keycode: u8,
+    /// The number of keysyms of each keycode.
+    per_keycode: usize,
+    /// The keysyms, `per_keycode` for each keycode from the smallest.
+    keysyms: Vec<u32>,
+}
+
+impl KeyMap {
+    /// Asks the display for the keysyms of its keycodes.
+    fn read(socket: &mut Socket, setup: &Setup) -> Result<KeyMap, Box<dyn Error>> {
+        let count = setup.max_keycode.saturating_sub(setup.min_keycode).saturating_add(1);
+        socket.write_all(&request(101, 0, &[setup.min_keycode, count, 0, 0]))?;
+        socket.flush()?;
+        let (header, data) = read_reply(socket)?;
+        let keysyms = data.chunks_exact(4).map(|bytes| u32::from_le_bytes(bytes.try_into().expect("chunks of 4"))).collect();
+        Ok(KeyMap { min_keycode: setup.min_keycode, per_keycode: header[1].max(1) as usize, keysyms })
+    }
+
+    /// Returns the keysym of a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `keycode` - The keycode of the key.
+    /// * `shift` - Whether Shift was held.
+    fn keysym(&self, keycode: u8, shift: bool) -> u32 {
+        let at = keycode.saturating_sub(self.min_keycode) as usize * self.per_keycode;
+        let lookup = |column: usize| self.keysyms.get(at + column).copied().filter(|&keysym| keysym != 0);
+        let plain = lookup(0).unwrap_or(0);
+        match (shift, lookup(1)) {
+            (true, Some(shifted)) => shifted,
+            // A letter without a shifted keysym is shifted to its capital.
+            (true, None) if (0x61..=0x7a).contains(&plain) => plain - 0x20,
+            _ => plain,
+        }
+    }
+}
+
+/// Returns the atom of a name, creating it if the display does not have it yet.
+fn intern_atom(socket: &mut Socket, name: &str) -> Result<u32, Box<dyn Error>> {
+    let mut body = (name.len() as u16).to_le_bytes().to_vec();
+    body.extend_from_slice(&[0, 0]);
+    body.extend_from_slice(name.as_bytes());
+    socket.write_all(&request(16, 0, &body))?;
+    socket.flush()?;
+    let (header, _) = read_reply(socket)?;
+    Ok(u32::from_le_bytes(header[8..12].try_into().expect("a 32-byte reply")))
+}
+
+/// Replaces a property of a window.
+fn change_property(socket: &mut Socket, window: u32, property: u32, kind: u32, format: u8, data: &[u8]) -> io::Result<()> {
+    let mut body = Vec::with_capacity(20 + data.len());
+    for value in [window, property, kind] {
+        body.extend_from_slice(&value.to_le_bytes());
+    }
+    body.extend_from_slice(&[format, 0, 0, 0]);
+    body.extend_from_slice(&((data.len() / (format as usize / 8)) as u32).to_le_bytes());
+    body.extend_from_slice(data);
+    socket.write_all(&request(18, 0, &body))
+}
+
+/// Reads the reply to the last request, skipping any events that come before it.
+///
+/// # Returns
+///
+/// The first 32 bytes of the reply and the rest of it.
+fn read_reply(socket: &mut Socket) -> Result<([u8; 32], Vec<u8>), Box<dyn Error>> {
+    loop {
+        let mut header = [0; 32];
+        socket.read_exact(&mut header)?;
+        match header[0] {
+            0 => return Err(format!("The X display reported error {} to request {}", header[1], header[10]).into()),
+            1 => {
+                let mut data = vec![0; u32::from_le_bytes(header[4..8].try_into().expect("a 32-byte reply")) as usize * 4];
+                socket.read_exact(&mut data)?;
+                return Ok((header, data));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Reads the next packet from the display and turns it into an event.
+///
+/// # Returns
+///
+/// The event, `None` for a packet the window does not care about, or an error from the
+/// display.
+fn read_event(socket: &mut Socket, keymap: &KeyMap, delete: u32) -> Result<Option<Event>, String> {
+    let mut packet = [0; 32];
+    if socket.read_exact(&mut packet).is_err() {
+        return Ok(Some(Event::Close));
+    }
+    let u16_at = |at: usize| u16::from_le_bytes([packet[at], packet[at + 1]]);
+    let i16_at = |at: usize| i16::from_le_bytes([packet[at], packet[at + 1]]);
+    let event = match packet[0] & 0x7f {
+        0 => return Err(format!("The X display reported error {} to request {}", packet[1], packet[10])),
+        // Replies and generic events carry more data after the packet, which is skipped.
+        1 | 35 => {
+            let extra = u32::from_le_bytes(packet[4..8].try_into().expect("a 32-byte packet")) as u64 * 4;
+            if io::copy(&mut Read::by_ref(socket).take(extra), &mut io::sink()).is_err() {
+                return Ok(Some(Event::Close));
+            }
+            None
+        }
+        2 => Some(Event::Key {
+            keysym: keymap.keysym(packet[1], u16_at(28) & SHIFT_MASK != 0),
+            control: u16_at(28) & CONTROL_MASK != 0,
+        }),
+        4 => Some(Event::Button { button: packet[1], x: i16_at(24), y: i16_at(26) }),
+        6 => {
+            let state = u16_at(28);
+            let button = if state & BUTTON1_MASK != 0 { 1 } else if state & BUTTON3_MASK != 0 { 3 } else { 0 };
+            (button != 0).then(|| Event::Button { button, x: i16_at(24), y: i16_at(26) })
+        }
+        // Only the last of a run of exposures is passed on.
+        12 => (u16_at(16) == 0).then_some(Event::Expose),
+        22 => Some(Event::Resize { width: u16_at(20), height: u16_at(22) }),
+        33 if u32::from_le_bytes(packet[12..16].try_into().expect("a 32-byte packet")) == delete => Some(Event::Close),
+        _ => None,
+    };
+    Ok(event)
+}
+
+/// Builds a request: its opcode, a byte of data, its length, and its body padded to
+/// 4 bytes.
+fn request(opcode: u8, data: u8, body: &[u8]) -> Vec<u8> {
+    let body = padded(body);
+    let mut request = Vec::with_capacity(4 + body.len());
+    request.extend_from_slice(&[opcode, data]);
+    request.extend_from_slice(&(((4 + body.len()) / 4) as u16).to_le_bytes());
+    request.extend_from_slice(&body);
+    request
+}
+
+/// Pads bytes with zeros to a multiple of 4.
+fn padded(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.resize(bytes.len().div_ceil(4) * 4, 0);
+    padded
+}
@@ -0,0 +1,44 @@
+//! Tests of the boundaries and the surfaces they glue the board into.
+
+use gameoflife::{bitgrid::BitGrid, boundary::Boundary, rule::Rule};
+
+#[test]
+fn twisted_surfaces_glue_their_edges_with_a_flip() {
+    let (width, height) = (5, 4);
+    // A Klein bottle wraps the columns as a torus does, and mirrors what crosses the top or bottom.
+    assert_eq!(Boundary::Klein.map_cell(-1, 1, width, height), Some((4, 1)));
+    assert_eq!(Boundary::Klein.map_cell(1, -1, width, height), Some((3, 3)));
+    assert_eq!(Boundary::Klein.map_cell(0, 4, width, height), Some((4, 0)));
+    // A cross-surface mirrors what crosses any edge.
+    assert_eq!(Boundary::Cross.map_cell(-1, 1, width, height), Some((4, 2)));
+    assert_eq!(Boundary::Cross.map_cell(5, 0, width, height), Some((0, 3)));
+    assert_eq!(Boundary::Cross.map_cell(1, 4, width, height), Some((3, 0)));
+    assert_eq!(Boundary::Wrap.map_cell(1, -1, width, height), Some((1, 3)));
+
+    // The names of the surfaces pick the boundaries that glue the board into them.
+    assert_eq!(Boundary::parse_topology("torus"), Some(Boundary::Wrap));
+    assert_eq!(Boundary::parse_topology("klein"), Boundary::parse("klein"));
+    assert_eq!(Boundary::parse_topology("cross").map(|boundary| boundary.to_string()).as_deref(), Some("cross"));
+}
+
+/// A glider on a wrapped board flies off one corner and comes back from the other, while on
+/// a dead board it crashes into the corner.
+#[test]
+fn gliders_fly_around_a_wrapped_board() {
+    let mut glider = vec![vec![false; 10]; 10];
+    for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        glider[y][x] = true;
+    }
+
+    // A glider moves one cell diagonally in four generations, so forty take it around.
+    let rule = Rule::default();
+    let mut wrapped = BitGrid::from_grid(&glider).with_boundary(Boundary::Wrap);
+    let mut dead = BitGrid::from_grid(&glider);
+    for _ in 0..40 {
+        wrapped = wrapped.step(&rule);
+        dead = dead.step(&rule);
+    }
+    assert_eq!(wrapped.to_grid(), glider);
+    assert_eq!(wrapped.boundary(), Boundary::Wrap);
+    assert_ne!(dead.population(), 5);
+}
@@ -0,0 +1,10 @@
+//! Helpers shared by the integration tests.
+
+use gameoflife::Grid;
+
+/// Builds a board whose cells are the bits of `bits`, row by row.
+pub fn board(width: usize, height: usize, bits: u64) -> Grid {
+    (0..height)
+        .map(|y| (0..width).map(|x| bits >> (y * width + x) & 1 == 1).collect())
+        .collect()
+}
@@ -0,0 +1,55 @@
+//! Tests of the edit history and the brushes that paint the board.
+
+use gameoflife::{brush::Brush, edits::{Edit, EditStack}};
+
+#[test]
+fn edits_are_undone_and_redone_in_order_until_the_board_moves_on() {
+    let empty = vec![vec![false; 4]; 3];
+    let mut grid = empty.clone();
+    let mut edits = EditStack::default();
+
+    grid[0][0] = true;
+    edits.record(Edit::between(&empty, &grid), 5);
+    let first = grid.clone();
+    grid[2][3] = true;
+    grid[0][0] = false;
+    edits.record(Edit::between(&first, &grid), 5);
+    let second = grid.clone();
+    assert_eq!(edits.counts(), (2, 0));
+
+    assert!(edits.undo(&mut grid, 5));
+    assert_eq!(grid, first);
+    assert!(edits.undo(&mut grid, 5));
+    assert_eq!(grid, empty);
+    assert!(!edits.undo(&mut grid, 5));
+    assert!(edits.redo(&mut grid, 5));
+    assert!(edits.redo(&mut grid, 5));
+    assert_eq!(grid, second);
+
+    // A new edit forgets what was undone, and a later generation forgets everything.
+    edits.undo(&mut grid, 5);
+    edits.record(Edit::between(&grid, &empty), 5);
+    assert_eq!(edits.counts(), (2, 0));
+    assert!(!edits.undo(&mut grid, 6));
+    assert_eq!(edits.counts(), (0, 0));
+}
+
+#[test]
+fn brushes_paint_their_shape_and_strokes_leave_no_gaps() {
+    let count = |grid: &[Vec<bool>]| grid.iter().flatten().filter(|&&cell| cell).count();
+    let sizes: Vec<usize> = [Brush::Dot, Brush::Square3, Brush::Square5, Brush::Circle].iter().map(|brush| brush.offsets().len()).collect();
+    assert_eq!(sizes, [1, 9, 25, 29]);
+    assert_eq!((Brush::Dot.smaller(), Brush::Circle.larger(), Brush::Square3.larger()), (Brush::Dot, Brush::Circle, Brush::Square5));
+
+    // Cells past the edges are left out, and erasing only counts the cells that were alive.
+    let mut grid = vec![vec![false; 10]; 10];
+    assert_eq!(Brush::Square3.paint(&mut grid, 0, 0, true), 4);
+    assert_eq!(Brush::Square5.paint(&mut grid, 0, 0, false), 4);
+    assert_eq!(count(&grid), 0);
+
+    // A stroke from one corner to the other paints the whole diagonal.
+    assert_eq!(Brush::Dot.stroke(&mut grid, (0, 0), (9, 9), true), 10);
+    assert!((0..10).all(|i| grid[i][i]));
+    assert_eq!(Brush::Dot.stroke(&mut grid, (1, 0), (9, 3), true), 9);
+    assert_eq!(count(&grid), 19);
+}
@@ -0,0 +1,269 @@
+//! Differential tests of the bit-parallel engine against the naive reference engine.
+
+mod common;
+
+use common::board;
+use gameoflife::{
+    bitgrid::BitGrid,
+    boundary::Boundary,
+    live_neighbors,
+    live_neighbors_within,
+    random_grid,
+    rule::{Neighborhood, Rule},
+    update_grid,
+    update_grid_naive,
+    update_grid_with_boundary,
+    Grid,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// Rules covering ordinary Life, births from zero neighbours, and every count at once.
+const RULES: [&str; 6] = ["B3/S23", "B36/S23", "B2/S", "B0/S8", "B1357/S02468", "B012345678/S012345678"];
+
+/// Checks that both engines agree on one step of the given board.
+fn assert_same_step(grid: &Grid, rule: &Rule) {
+    assert_eq!(
+        update_grid(grid, rule),
+        update_grid_naive(grid, rule),
+        "engines disagree under {} on {:?}",
+        rule,
+        grid
+    );
+}
+
+/// Every single-count rule (`Bn` alone or `Sn` alone) on every 3×3 board. The optimized engine
+/// combines counts by OR-ing the matches of each count, so this covers each count's matching.
+#[test]
+fn every_3x3_board_under_every_single_count_rule() {
+    for n in 0..=8 {
+        for rulestring in [format!("B{}/S", n), format!("B/S{}", n)] {
+            let rule = Rule::parse(&rulestring).unwrap();
+            for bits in 0..1 << 9 {
+                assert_same_step(&board(3, 3, bits), &rule);
+            }
+        }
+    }
+}
+
+/// Every 4×4 board, where every cell touches an edge or a corner.
+#[test]
+fn every_4x4_board() {
+    for rulestring in RULES {
+        let rule = Rule::parse(rulestring).unwrap();
+        for bits in 0..1 << 16 {
+            assert_same_step(&board(4, 4, bits), &rule);
+        }
+    }
+}
+
+/// Every single-row board up to 12 cells wide.
+#[test]
+fn every_single_row_board() {
+    for rulestring in RULES {
+        let rule = Rule::parse(rulestring).unwrap();
+        for width in 1..=12 {
+            for bits in 0..1 << width {
+                assert_same_step(&board(width, 1, bits), &rule);
+            }
+        }
+    }
+}
+
+/// The sliced count of the eight neighbours agrees with counting them one by one, also
+/// on the edges and for positions just outside the board.
+#[test]
+fn every_4x4_board_has_the_same_neighbour_counts() {
+    for bits in 0..1 << 16 {
+        let grid = board(4, 4, bits);
+        for y in 0..=5 {
+            for x in 0..=5 {
+                assert_eq!(live_neighbors(&grid, x, y), live_neighbors_within(&grid, x, y, 1, Neighborhood::Moore));
+            }
+        }
+    }
+}
+
+/// Every pattern of a 3×4 window straddling a word boundary or the right edge, where the
+/// shifted carries between words and the masking of unused bits happen.
+#[test]
+fn every_window_at_word_boundaries_and_right_edges() {
+    let rule = Rule::default();
+    // (board width, first column of the window)
+    let windows = [(130, 62), (130, 126), (64, 60), (65, 61), (128, 124), (129, 125)];
+
+    for (width, left) in windows {
+        for bits in 0..1u64 << 12 {
+            let mut grid = vec![vec![false; width]; 3];
+            for (y, row) in grid.iter_mut().enumerate() {
+                for (x, cell) in row[left..left + 4].iter_mut().enumerate() {
+                    *cell = bits >> (y * 4 + x) & 1 == 1;
+                }
+            }
+            assert_same_step(&grid, &rule);
+        }
+    }
+}
+
+/// Every 4×4 board and random soups under von Neumann and hexagonal rules, where the adders
+/// leave some of the neighbours out.
+#[test]
+fn every_4x4_board_under_other_neighborhoods() {
+    let mut rng = StdRng::seed_from_u64(0x4e16);
+    for rulestring in ["B2/S013V", "B1/S1V", "B0/S4V", "B2/S34H", "B24/S35H", "B0123456/S6H"] {
+        let rule = Rule::parse(rulestring).unwrap();
+        for bits in 0..1 << 16 {
+            assert_same_step(&board(4, 4, bits), &rule);
+        }
+        for width in [1, 63, 64, 65, 130] {
+            assert_same_step(&random_grid(width, 9, 0.4, &mut rng), &rule);
+        }
+    }
+}
+
+/// A hex cell on an even row touches the cells to the north-west and south-west, and one
+/// on an odd row, shifted right, those to the north-east and south-east.
+#[test]
+fn hex_neighbours_follow_the_row_offset() {
+    // B1/S: a lone cell lights up exactly its six neighbours.
+    let rule = Rule::parse("B1/SH").unwrap();
+    let lit = |x: usize, y: usize| {
+        let mut grid = vec![vec![false; 5]; 5];
+        grid[y][x] = true;
+        let next = update_grid(&grid, &rule);
+        let mut cells: Vec<(usize, usize)> = (0..5).flat_map(|y| (0..5).map(move |x| (x, y))).filter(|&(x, y)| next[y][x]).collect();
+        cells.sort_unstable_by_key(|&(x, y)| (y, x));
+        cells
+    };
+    assert_eq!(lit(2, 2), [(1, 1), (2, 1), (1, 2), (3, 2), (1, 3), (2, 3)]);
+    assert_eq!(lit(2, 1), [(2, 0), (3, 0), (1, 1), (3, 1), (2, 2), (3, 2)]);
+}
+
+/// Random soups of awkward widths, run for many generations.
+#[test]
+fn random_soups_of_awkward_sizes() {
+    let mut rng = StdRng::seed_from_u64(0x5eed);
+    for rulestring in RULES {
+        let rule = Rule::parse(rulestring).unwrap();
+        for width in [1, 2, 63, 64, 65, 127, 128, 129, 200] {
+            for height in [1, 2, 3, 17] {
+                let mut naive = random_grid(width, height, 0.4, &mut rng);
+                let mut bits = BitGrid::from_grid(&naive);
+                for generation in 1..=30 {
+                    naive = update_grid_naive(&naive, &rule);
+                    bits = bits.step(&rule);
+                    assert_eq!(bits.to_grid(), naive, "{} {}x{} diverged at generation {}", rule, width, height, generation);
+                }
+            }
+        }
+    }
+}
+
+/// Steps a board one cell at a time, counting the neighbours past the edges with the
+/// boundary.
+fn step_with_boundary_naive(grid: &Grid, rule: &Rule, boundary: Boundary) -> Grid {
+    let (radius, neighborhood) = rule.neighborhood();
+    let r = radius as isize;
+    let mut next = grid.clone();
+    for (y, row) in next.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let mut count = 0;
+            for dy in -r..=r {
+                for dx in -r..=r {
+                    if (dx, dy) != (0, 0) && neighborhood.contains(dx, dy, radius, y) {
+                        count += boundary.cell(grid, x as isize + dx, y as isize + dy) as usize;
+                    }
+                }
+            }
+            *cell = rule.next_state(grid[y][x], count);
+        }
+    }
+    next
+}
+
+/// Every boundary, under Moore, von Neumann, hexagonal, and Larger than Life neighbourhoods,
+/// on boards of awkward sizes where the cells past the edges fall in and across words.
+#[test]
+fn every_boundary_matches_the_naive_count() {
+    let mut rng = StdRng::seed_from_u64(0xb0d);
+    let rules = ["B3/S23", "B0/S8", "B1357/S02468", "B2/S013V", "B2/S34H", "R2,C0,M0,S3..7,B4..6,NM"];
+    for boundary in [Boundary::Dead, Boundary::Wrap, Boundary::Reflect, Boundary::Alive, Boundary::Klein, Boundary::Cross] {
+        for rulestring in rules {
+            let rule = Rule::parse(rulestring).unwrap();
+            for width in [1, 2, 63, 64, 65, 130] {
+                for height in [1, 2, 3, 9] {
+                    let mut grid = random_grid(width, height, 0.4, &mut rng);
+                    for generation in 1..=5 {
+                        let next = step_with_boundary_naive(&grid, &rule, boundary);
+                        let stepped = update_grid_with_boundary(&grid, &rule, boundary);
+                        assert_eq!(stepped, next, "{} {} {}x{} diverged at generation {}", boundary, rule, width, height, generation);
+                        grid = next;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The packed board reports the same changes and population as the unpacked one.
+#[test]
+fn changes_and_population_match_the_unpacked_board() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let grid = random_grid(130, 20, 0.3, &mut rng);
+    let next = update_grid_naive(&grid, &Rule::default());
+    let (before, after) = (BitGrid::from_grid(&grid), BitGrid::from_grid(&next));
+
+    let mut expected = Vec::new();
+    for (y, (old_row, new_row)) in grid.iter().zip(&next).enumerate() {
+        for (x, (old, new)) in old_row.iter().zip(new_row).enumerate() {
+            if old != new {
+                expected.push((x, y));
+            }
+        }
+    }
+    assert_eq!(before.changes(&after).collect::<Vec<_>>(), expected);
+    assert_eq!(after.population(), next.iter().flatten().filter(|&&cell| cell).count());
+}
+
+/// The summed-area Larger than Life engine agrees with counting every neighbour, for square
+/// and diamond neighbourhoods, with and without the middle cell, and on boards narrower than
+/// the neighbourhood.
+#[test]
+fn larger_than_life_matches_the_naive_count() {
+    let mut rng = StdRng::seed_from_u64(0x7171);
+    let rules = ["R5,C0,M1,S34..58,B34..45,NM", "R2,C0,M0,S3..7,B4..6,NN", "R3,S2..9,B3..4", "R1,C0,M0,S2..3,B3..3,NM", "R2,C0,M1,S4..9,B3..5,NH"];
+    for rulestring in rules {
+        let rule = Rule::parse(rulestring).unwrap();
+        for (width, height) in [(1, 1), (3, 40), (40, 3), (37, 23)] {
+            let mut grid = random_grid(width, height, 0.45, &mut rng);
+            for generation in 1..=10 {
+                let next = update_grid_naive(&grid, &rule);
+                assert_eq!(update_grid(&grid, &rule), next, "{} {}x{} diverged at generation {}", rule, width, height, generation);
+                grid = next;
+            }
+        }
+    }
+
+    // At radius 1, a Larger than Life rule is the life-like rule with the same counts.
+    let grid = random_grid(50, 50, 0.4, &mut rng);
+    assert_eq!(update_grid(&grid, &Rule::parse("R1,C0,M0,S2..3,B3..3,NM").unwrap()), update_grid(&grid, &Rule::default()));
+}
+
+/// An isotropic rule that lists every class of its counts is the life-like rule, and
+/// rulestrings in every notation are written back the way they were read.
+#[test]
+fn isotropic_rules_with_every_class_match_the_life_like_rule() {
+    let rule = Rule::parse("B3cekainyqjr/S2cekain3cekainyqjr").unwrap();
+    let mut rng = StdRng::seed_from_u64(0x4e15e1);
+    let mut grid = random_grid(60, 40, 0.35, &mut rng);
+    for _ in 0..20 {
+        let next = update_grid_naive(&grid, &Rule::default());
+        assert_eq!(update_grid(&grid, &rule), next);
+        grid = next;
+    }
+
+    for rulestring in ["B2-a/S12", "B3/S2-i34q", "B2ce3-ae/S1e2ak", "B2/S013V", "B2/S34H", "R2,C0,M1,S4..9,B3..5,NH"] {
+        assert_eq!(Rule::parse(rulestring).unwrap().to_string(), rulestring);
+    }
+    assert!(Rule::parse("B1k/S23").is_err());
+    assert!(Rule::parse("B5/S23V").is_err());
+}
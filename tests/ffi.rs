@@ -0,0 +1,32 @@
+//! Tests of the C ABI of the `ffi` feature.
+
+#[cfg(feature = "ffi")]
+#[test]
+fn the_c_abi_steps_a_blinker_and_reads_its_cells_back() {
+    use gameoflife::ffi::{gol_free, gol_get_cell, gol_get_cells, gol_get_rule, gol_new, gol_set_cell, gol_set_rule, gol_step};
+
+    // SAFETY: the board comes from `gol_new`, the buffer has room for every cell, and the
+    // board is freed once at the end.
+    unsafe {
+        assert!(gol_new(5, 5, c"B3/S23x".as_ptr()).is_null());
+        let world = gol_new(5, 5, std::ptr::null());
+        for x in 1..4 {
+            assert!(gol_set_cell(world, x, 2, true));
+        }
+        assert!(!gol_set_cell(world, 5, 0, true));
+
+        let mut cells = vec![0u8; gol_get_cells(world, std::ptr::null_mut(), 0)];
+        gol_step(world, 1);
+        assert_eq!(gol_get_cells(world, cells.as_mut_ptr(), cells.len()), 25);
+        let alive: Vec<usize> = (0..25).filter(|&i| cells[i] == 1).collect();
+        assert_eq!(alive, [7, 12, 17]);
+        assert!(gol_get_cell(world, 2, 1) && !gol_get_cell(world, 1, 2) && !gol_get_cell(world, 5, 2));
+
+        assert!(!gol_set_rule(world, c"B3/S23x".as_ptr()));
+        assert!(gol_set_rule(world, c"B36/S23".as_ptr()));
+        let mut rule = vec![0 as std::ffi::c_char; gol_get_rule(world, std::ptr::null_mut(), 0)];
+        assert_eq!(gol_get_rule(world, rule.as_mut_ptr(), rule.len()), 8);
+        assert_eq!(std::ffi::CStr::from_ptr(rule.as_ptr()).to_str(), Ok("B36/S23"));
+        gol_free(world);
+    }
+}
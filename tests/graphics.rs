@@ -0,0 +1,142 @@
+//! Tests of the image protocols and the colors cells are drawn in.
+
+use gameoflife::{
+    gradient::{self, Gradient, GradientKey, Shading},
+    heatmap::Heatmap,
+    iterm,
+    kitty,
+    raster::{Image, Rgb},
+    sixel,
+    trails::Trails,
+};
+
+#[test]
+fn sixel_images_are_sent_in_bands_of_six_rows_per_color() {
+    // A white top row and one white pixel in the seventh row, on black.
+    let mut image = Image::new(5, 7, Rgb(0, 0, 0));
+    image.fill_rect(0, 0, 5, 1, Rgb(255, 255, 255));
+    image.fill_rect(2, 6, 1, 1, Rgb(255, 255, 255));
+
+    let expected = concat!(
+        // The colors are numbered in the order they are first seen, and given in percent.
+        "\x1bP0;1;0q\"1;1;5;7#0;2;100;100;100#1;2;0;0;0",
+        // The first band has the white row at the top of every column, and runs are counted.
+        "#0!5@$#1!5}-",
+        // The second band is one row high.
+        "#0??@??$#1@@?@@-",
+        "\x1b\\",
+    );
+    assert_eq!(sixel::encode(&image), expected);
+}
+
+#[test]
+fn iterm_images_are_sent_as_png_files_of_their_own_size() {
+    let image = Image::new(2, 1, Rgb(255, 0, 0));
+    let mut png = Vec::new();
+    image.write_png(&mut png).unwrap();
+    assert!(png.starts_with(b"\x89PNG"));
+
+    let expected = format!("\x1b]1337;File=inline=1;size={};width=2px;height=1px;preserveAspectRatio=0:{}\x07", png.len(), kitty::base64(&png));
+    assert_eq!(iterm::inline_image(&image).unwrap(), expected);
+}
+
+#[test]
+fn kitty_images_are_sent_as_base64_in_chunks() {
+    assert_eq!(kitty::base64(b""), "");
+    assert_eq!(kitty::base64(b"f"), "Zg==");
+    assert_eq!(kitty::base64(b"fo"), "Zm8=");
+    assert_eq!(kitty::base64(b"foo"), "Zm9v");
+    assert_eq!(kitty::base64(&[255, 0, 0]), "/wAA");
+
+    let pixel = Image::new(1, 1, Rgb(255, 0, 0));
+    assert_eq!(kitty::transmit(&pixel, 1), "\x1b_Ga=T,f=24,s=1,v=1,i=1,q=2,C=1,m=0;/wAA\x1b\\");
+    assert_eq!(kitty::update(&pixel, 1, 4, 8), "\x1b_Ga=f,r=1,f=24,s=1,v=1,x=4,y=8,i=1,q=2,m=0;/wAA\x1b\\");
+
+    // The 12288 bytes of a 64×64 image are 16384 in base64, sent as four sequences of 4096
+    // with `m=1` on all but the last.
+    let image = Image::new(64, 64, Rgb(0, 0, 0));
+    let sent = kitty::transmit(&image, 2);
+    let chunks: Vec<&str> = sent.split("\x1b\\").filter(|chunk| !chunk.is_empty()).collect();
+    assert_eq!(chunks.len(), 4);
+    assert!(chunks[0].starts_with("\x1b_Ga=T,f=24,s=64,v=64,i=2,q=2,C=1,m=1;"));
+    assert!(chunks[1].starts_with("\x1b_Gm=1,q=2;"));
+    assert!(chunks[chunks.len() - 1].starts_with("\x1b_Gm=0,q=2;"));
+    let data: usize = chunks.iter().map(|chunk| chunk.split(';').nth(1).unwrap().len()).sum();
+    assert_eq!(data, 64 * 64 * 4);
+}
+
+#[test]
+fn heatmap_fades_after_cells_die() {
+    let mut heatmap = Heatmap::new(2, 1, 0.9);
+    heatmap.observe(&[vec![true, true]]);
+    let mut faded_after = None;
+    for generation in 1..100 {
+        heatmap.observe(&[vec![true, false]]);
+        let colors = heatmap.colors();
+        assert!(colors[0][0].is_some());
+        if faded_after.is_none() && colors[0][1].is_none() {
+            faded_after = Some(generation);
+        }
+    }
+    // The dead cell stays lit for a while, and the live one ends up at the hot end.
+    assert!(faded_after.is_some_and(|generation| generation > 5), "faded after {:?}", faded_after);
+    assert_eq!(heatmap.colors()[0][0], Some(gameoflife::raster::Rgb(255, 255, 240)));
+}
+
+#[test]
+fn trails_age_until_they_fade() {
+    let mut trails = Trails::new(3, 1, 2);
+    trails.observe(&[vec![true, true, false]]);
+    assert_eq!((trails.age(0, 0), trails.age(1, 0), trails.age(2, 0)), (None, None, None));
+
+    trails.observe(&[vec![false, true, false]]);
+    assert_eq!(trails.age(0, 0), Some(1));
+    trails.observe(&[vec![false, false, false]]);
+    assert_eq!((trails.age(0, 0), trails.age(1, 0)), (Some(2), Some(1)));
+    trails.observe(&[vec![false, false, false]]);
+    assert_eq!((trails.age(0, 0), trails.age(1, 0)), (None, Some(2)));
+
+    // A cell born again on its trail is alive, and starts a new trail when it dies.
+    trails.observe(&[vec![false, true, false]]);
+    assert_eq!(trails.age(1, 0), None);
+    trails.observe(&[vec![false, false, false]]);
+    assert_eq!(trails.age(1, 0), Some(1));
+    assert_eq!(trails.age(5, 5), None);
+}
+
+#[test]
+fn shading_follows_cell_age_and_density() {
+    let gradient = Gradient::new(GradientKey::Age);
+    let palette = gradient.palette();
+    assert_eq!((palette.len(), palette[0], palette[gradient::LEVELS - 1]), (gradient::LEVELS, gradient.from, gradient.to));
+
+    // Cells start at the first color when born and reach the last once they are old.
+    let mut shading = Shading::new(&gradient);
+    let block = vec![vec![true, true, false]; 2];
+    shading.observe(&block);
+    assert_eq!(shading.levels()[0], vec![1, 1, 0]);
+    for _ in 1..gradient::MAX_AGE + 5 {
+        shading.observe(&block);
+    }
+    assert_eq!(shading.levels()[0][0] as usize, gradient::LEVELS);
+    shading.observe(&[vec![false, true, true], vec![true, true, false]]);
+    assert_eq!(shading.levels()[0], vec![0, gradient::LEVELS as u8, 1]);
+
+    // A lonely cell is at the start of a density gradient, and one in a full block at its end.
+    let mut shading = Shading::new(&Gradient::new(GradientKey::Density));
+    let mut grid = vec![vec![true; 3]; 3];
+    grid.push(vec![false, false, false]);
+    grid.push(vec![false, false, true]);
+    shading.observe(&grid);
+    assert_eq!((shading.levels()[1][1] as usize, shading.levels()[4][2], shading.levels()[3][0]), (gradient::LEVELS, 1, 0));
+}
+
+#[test]
+fn colors_fall_back_to_the_nearest_of_the_256_color_palette() {
+    assert_eq!(Rgb(0, 0, 0).ansi256(), 16);
+    assert_eq!(Rgb(255, 255, 255).ansi256(), 231);
+    assert_eq!(Rgb(255, 0, 0).ansi256(), 196);
+    assert_eq!(Rgb(0, 135, 255).ansi256(), 33);
+    assert_eq!(Rgb(128, 128, 128).ansi256(), 244);
+    assert_eq!(Rgb(100, 102, 98).ansi256(), 241);
+}
@@ -0,0 +1,204 @@
+//! Tests of the X11 window of the `gui` feature, against a fake display on a Unix socket.
+
+#![cfg(all(unix, feature = "gui"))]
+
+use std::{
+    io::{Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+use gameoflife::{
+    raster::{Image, Rgb},
+    x11::{Event, Window},
+};
+
+/// The root window of the fake display.
+const ROOT: u32 = 0x100;
+
+/// The atoms the fake display hands out, in the order they are asked for.
+const FIRST_ATOM: u32 = 300;
+
+/// Returns the connection setup of a 640x480 screen with a 24-bit TrueColor visual and the
+/// keycodes 8 and 9, without the first 8 bytes.
+fn setup() -> Vec<u8> {
+    let mut info = Vec::new();
+    info.extend_from_slice(&0u32.to_le_bytes()); // release
+    info.extend_from_slice(&0x0200_0000u32.to_le_bytes()); // id base
+    info.extend_from_slice(&0x001f_ffffu32.to_le_bytes()); // id mask
+    info.extend_from_slice(&0u32.to_le_bytes()); // motion buffer
+    info.extend_from_slice(&4u16.to_le_bytes()); // vendor length
+    info.extend_from_slice(&0xffffu16.to_le_bytes()); // maximum request length
+    info.extend_from_slice(&[1, 1, 0, 0, 32, 32, 8, 9, 0, 0, 0, 0]);
+    info.extend_from_slice(b"fake");
+    info.extend_from_slice(&[24, 32, 32, 0, 0, 0, 0, 0]); // the format of depth 24
+
+    info.extend_from_slice(&ROOT.to_le_bytes());
+    info.extend_from_slice(&[0; 16]); // colormap, white, black, and input masks
+    info.extend_from_slice(&640u16.to_le_bytes());
+    info.extend_from_slice(&480u16.to_le_bytes());
+    info.extend_from_slice(&[0; 8]); // millimeters and installed maps
+    info.extend_from_slice(&0x21u32.to_le_bytes()); // root visual
+    info.extend_from_slice(&[0, 0, 24, 1]); // backing stores, save unders, depth, depths
+
+    info.extend_from_slice(&[24, 0]);
+    info.extend_from_slice(&1u16.to_le_bytes());
+    info.extend_from_slice(&[0; 4]);
+    info.extend_from_slice(&0x21u32.to_le_bytes());
+    info.extend_from_slice(&[4, 8, 0, 1]); // TrueColor, 8 bits per channel
+    for mask in [0xff0000u32, 0xff00, 0xff, 0] {
+        info.extend_from_slice(&mask.to_le_bytes());
+    }
+    info
+}
+
+/// Reads one request from the client, returning its opcode, data byte, and body.
+fn read_request(stream: &mut UnixStream) -> (u8, u8, Vec<u8>) {
+    let mut header = [0; 4];
+    stream.read_exact(&mut header).unwrap();
+    let mut body = vec![0; u16::from_le_bytes([header[2], header[3]]) as usize * 4 - 4];
+    stream.read_exact(&mut body).unwrap();
+    (header[0], header[1], body)
+}
+
+/// Writes a reply with a data byte, a word, and extra data after its 32 bytes.
+fn write_reply(stream: &mut UnixStream, data: u8, word: u32, extra: &[u8]) {
+    let mut reply = vec![1, data, 0, 0];
+    reply.extend_from_slice(&(extra.len() as u32 / 4).to_le_bytes());
+    reply.extend_from_slice(&word.to_le_bytes());
+    reply.resize(32, 0);
+    reply.extend_from_slice(extra);
+    stream.write_all(&reply).unwrap();
+}
+
+/// Opens a window on a fake display.
+///
+/// # Returns
+///
+/// The window and the display's end of the connection, at the first request after the
+/// two atoms were interned.
+fn open(name: &str) -> (Window, UnixStream) {
+    let path = std::env::temp_dir().join(format!("gameoflife-x11-{}-{}.sock", std::process::id(), name));
+    let _ = std::fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).unwrap();
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut hello = [0; 12];
+        stream.read_exact(&mut hello).unwrap();
+        assert_eq!(&hello[..4], &[b'l', 0, 11, 0]);
+        let auth = |at: usize| (u16::from_le_bytes([hello[at], hello[at + 1]]) as usize).div_ceil(4) * 4;
+        let mut cookie = vec![0; auth(6) + auth(8)];
+        stream.read_exact(&mut cookie).unwrap();
+
+        let info = setup();
+        let mut header = vec![1, 0, 11, 0, 0, 0];
+        header.extend_from_slice(&(info.len() as u16 / 4).to_le_bytes());
+        stream.write_all(&header).unwrap();
+        stream.write_all(&info).unwrap();
+
+        // Keycode 8 is `q`, with no shifted keysym, and keycode 9 is `1` and `!`.
+        let (opcode, _, body) = read_request(&mut stream);
+        assert_eq!((opcode, body[0], body[1]), (101, 8, 2));
+        let keysyms: Vec<u8> = [0x71u32, 0, 0x31, 0x21].iter().flat_map(|keysym| keysym.to_le_bytes()).collect();
+        write_reply(&mut stream, 2, 0, &keysyms);
+        for atom in [FIRST_ATOM, FIRST_ATOM + 1] {
+            assert_eq!(read_request(&mut stream).0, 16);
+            write_reply(&mut stream, 0, atom, &[]);
+        }
+        sender.send(stream).unwrap();
+    });
+
+    let window = Window::open(&format!("{}:0", path.display()), 2, 1, "Life", Rgb(1, 2, 3)).unwrap();
+    let stream = receiver.recv().unwrap();
+    let _ = std::fs::remove_file(&path);
+    (window, stream)
+}
+
+/// Returns a key press event of a keycode with a modifier state.
+fn key_press(keycode: u8, state: u16) -> [u8; 32] {
+    let mut event = [0; 32];
+    event[0] = 2;
+    event[1] = keycode;
+    event[28..30].copy_from_slice(&state.to_le_bytes());
+    event
+}
+
+#[test]
+fn a_window_is_created_titled_and_mapped() {
+    let (_window, mut display) = open("create");
+
+    let (opcode, depth, body) = read_request(&mut display);
+    assert_eq!((opcode, depth), (1, 24));
+    assert_eq!(u32::from_le_bytes(body[4..8].try_into().unwrap()), ROOT);
+    assert_eq!(u16::from_le_bytes([body[12], body[13]]), 2);
+    // The background pixel comes first among the values, then the events.
+    assert_eq!(u32::from_le_bytes(body[28..32].try_into().unwrap()), 0x010203);
+
+    let (opcode, _, body) = read_request(&mut display);
+    assert_eq!((opcode, &body[4..8]), (18, &39u32.to_le_bytes()[..]));
+    assert_eq!(&body[20..24], b"Life");
+    let (opcode, _, body) = read_request(&mut display);
+    assert_eq!((opcode, &body[4..8], &body[20..24]), (18, &FIRST_ATOM.to_le_bytes()[..], &(FIRST_ATOM + 1).to_le_bytes()[..]));
+    assert_eq!(read_request(&mut display).0, 55);
+    assert_eq!(read_request(&mut display).0, 8);
+}
+
+#[test]
+fn images_are_sent_as_pixels_of_the_screen_format() {
+    let (mut window, mut display) = open("draw");
+    for _ in 0..5 {
+        read_request(&mut display);
+    }
+
+    let mut image = Image::new(2, 1, Rgb(0, 0, 0));
+    image.fill_rect(1, 0, 1, 1, Rgb(0x12, 0x34, 0x56));
+    window.draw(&image).unwrap();
+    let (opcode, format, body) = read_request(&mut display);
+    assert_eq!((opcode, format), (72, 2));
+    assert_eq!(&body[8..12], &[2, 0, 1, 0]);
+    assert_eq!(body[17], 24);
+    assert_eq!(&body[20..28], &[0, 0, 0, 0, 0x56, 0x34, 0x12, 0]);
+
+    window.set_title("generation 1").unwrap();
+    let (opcode, _, body) = read_request(&mut display);
+    assert_eq!((opcode, &body[20..32]), (18, &b"generation 1"[..]));
+}
+
+#[test]
+fn keys_buttons_and_the_close_button_come_back_as_events() {
+    let (window, mut display) = open("events");
+    let wait = Duration::from_secs(5);
+
+    display.write_all(&key_press(8, 0)).unwrap();
+    assert_eq!(window.next_event(wait).unwrap(), Some(Event::Key { keysym: 'q' as u32, control: false }));
+    // Letters without a shifted keysym are shifted to capitals.
+    display.write_all(&key_press(8, 0x1 | 0x4)).unwrap();
+    assert_eq!(window.next_event(wait).unwrap(), Some(Event::Key { keysym: 'Q' as u32, control: true }));
+    display.write_all(&key_press(9, 0x1)).unwrap();
+    assert_eq!(window.next_event(wait).unwrap(), Some(Event::Key { keysym: '!' as u32, control: false }));
+
+    let mut button = [0; 32];
+    button[0] = 4;
+    button[1] = 3;
+    button[24..26].copy_from_slice(&7i16.to_le_bytes());
+    button[26..28].copy_from_slice(&9i16.to_le_bytes());
+    display.write_all(&button).unwrap();
+    assert_eq!(window.next_event(wait).unwrap(), Some(Event::Button { button: 3, x: 7, y: 9 }));
+
+    let mut close = [0; 32];
+    close[0] = 33;
+    close[12..16].copy_from_slice(&(FIRST_ATOM + 1).to_le_bytes());
+    display.write_all(&close).unwrap();
+    assert_eq!(window.next_event(wait).unwrap(), Some(Event::Close));
+}
+
+#[test]
+fn a_display_that_goes_away_closes_the_window() {
+    let (window, display) = open("gone");
+    drop(display);
+    assert_eq!(window.next_event(Duration::from_secs(5)).unwrap(), Some(Event::Close));
+}
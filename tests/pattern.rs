@@ -0,0 +1,39 @@
+//! Tests of the pattern parsers and of placing patterns.
+
+use gameoflife::{
+    pattern::{self, MAX_PATTERN_SIZE},
+    placement::{self, Placement},
+};
+
+/// Runs that would make a pattern wider or taller than the limit are errors rather than
+/// allocations, in both RLE parsers, and patterns up to the limit still parse.
+#[test]
+fn rle_runs_past_the_size_limit_are_parse_errors() {
+    for text in ["99999999999o!", "x = 1, y = 1\n99999999999999999999999o!", "8192b$8192$o!", "bo$4096o4097o!"] {
+        assert!(pattern::parse_rle(text).is_err(), "{:?} parsed", text);
+        assert!(pattern::parse_multistate_rle(text).is_err(), "{:?} parsed", text);
+    }
+    let error = pattern::parse_rle("o$\n99999999999o!").unwrap_err();
+    assert_eq!(error.line, 2);
+
+    let widest = pattern::parse_rle(&format!("{}o${}$o!", MAX_PATTERN_SIZE, MAX_PATTERN_SIZE - 2)).unwrap();
+    assert_eq!((widest.width, widest.height), (MAX_PATTERN_SIZE, MAX_PATTERN_SIZE));
+    assert_eq!(pattern::parse_rle("3o$bo!").unwrap().cells, [[true, true, true], [false, true, false]]);
+
+    // Boards are held to their own size instead.
+    let wide = pattern::parse_rle_within("9000o!", 9000, 1).unwrap();
+    assert_eq!((wide.width, wide.height), (9000, 1));
+    assert!(pattern::parse_rle_within("9001o!", 9000, 1).is_err());
+    assert!(pattern::parse_rle_within("o$o!", 9000, 1).is_err());
+}
+
+/// Patterns too big for the board are reported by name, while patterns that cannot be
+/// loaded are errors of their own.
+#[test]
+fn unfit_patterns_are_told_from_patterns_that_do_not_load() {
+    let glider = [Placement::parse("glider@2,2").unwrap()];
+    assert_eq!(placement::first_unfit(&glider, 5, 5).unwrap(), None);
+    let unfit = placement::first_unfit(&glider, 4, 5).unwrap().unwrap();
+    assert!(unfit.contains("glider") && unfit.contains("4x5"), "{}", unfit);
+    assert!(placement::first_unfit(&[Placement::parse("no-such-pattern.rle").unwrap()], 5, 5).is_err());
+}
@@ -0,0 +1,122 @@
+//! Tests of the rule families and of rules and boundaries as text.
+
+mod common;
+
+use common::board;
+use gameoflife::{
+    automaton::Simulation,
+    boundary::Boundary,
+    hensel,
+    margolus::{Margolus, MargolusRule},
+    random_grid,
+    rule::{Neighborhood, Rule},
+    settings::{Settings, MIN_SPEED},
+    snapshot::Snapshot,
+    variant::Variant,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+/// The Hensel classes of each neighbour count split its arrangements without overlap: 1, 2, 6,
+/// 10, 13, 10, 6, 2, and 1 classes, 51 in all.
+#[test]
+fn hensel_classes_partition_every_arrangement() {
+    let letters = ["", "ce", "cekain", "cekainyqjr", "cekainyqjrtwz", "cekainyqjr", "cekain", "ce", ""];
+    let mut seen = [false; 256];
+    for (count, letters) in letters.iter().enumerate() {
+        let classes: Vec<Vec<u8>> = if letters.is_empty() {
+            vec![(0..=255u8).filter(|bits| bits.count_ones() as usize == count).collect()]
+        } else {
+            letters.chars().map(|letter| hensel::class(count, letter).unwrap()).collect()
+        };
+        for bits in classes.concat() {
+            assert_eq!(bits.count_ones() as usize, count, "{:08b} is in a class of {}", bits, count);
+            assert!(!seen[bits as usize], "{:08b} is in two classes", bits);
+            seen[bits as usize] = true;
+        }
+    }
+    assert!(seen.iter().all(|&seen| seen));
+}
+
+/// Both Margolus rules are reversible, mapping the 16 blocks onto each other, and conserve
+/// the live cells they draw.
+#[test]
+fn margolus_rules_are_reversible_and_conserve_cells() {
+    let mut rng = StdRng::seed_from_u64(0xb10c);
+    for rule in [MargolusRule::Critters, MargolusRule::BilliardBalls] {
+        let mut seen = [false; 16];
+        for block in 0..16 {
+            seen[rule.apply(block) as usize] = true;
+        }
+        assert!(seen.iter().all(|&seen| seen), "{} maps two blocks onto one", rule);
+
+        let mut board = Margolus::new(rule, &random_grid(41, 30, 0.2, &mut rng));
+        let population = |board: &Margolus| board.view().iter().flatten().filter(|&&state| state != 0).count();
+        let start = population(&board);
+        for generation in 1..=25 {
+            board.step();
+            assert_eq!(population(&board), start, "{} changed the population at generation {}", rule, generation);
+        }
+    }
+}
+
+#[test]
+fn rules_and_boundaries_serialize_as_the_text_the_command_line_takes() {
+    for rulestring in ["B3/S23", "B2/S34H", "R2,C0,M0,S3..7,B4..6,NM", "B3/S23-k"] {
+        let rule = Rule::parse(rulestring).unwrap();
+        let json = serde_json::to_string(&rule).unwrap();
+        assert_eq!(json, format!("\"{}\"", rule));
+        assert_eq!(serde_json::from_str::<Rule>(&json).unwrap(), rule);
+    }
+    assert!(serde_json::from_str::<Rule>("\"B9/S23\"").is_err());
+
+    assert_eq!(serde_json::to_string(&Boundary::Klein).unwrap(), "\"klein\"");
+    assert_eq!(serde_json::from_str::<Boundary>("\"torus\"").unwrap(), Boundary::Wrap);
+    assert!(serde_json::from_str::<Boundary>("\"sphere\"").is_err());
+    assert_eq!(serde_json::from_str::<Neighborhood>("\"hex\"").unwrap(), Neighborhood::Hex);
+
+    // Snapshots keep the rule as a rulestring, so files written before stay readable.
+    let snapshot = Snapshot::capture(&board(4, 3, 0b0111_0000), 0, &Rule::parse("B36/S23").unwrap(), 7, &ChaCha12Rng::seed_from_u64(7));
+    let json: serde_json::Value = serde_json::to_value(&snapshot).unwrap();
+    assert_eq!(json["rule"], "B36/S23");
+    assert_eq!(serde_json::from_value::<Snapshot>(json).unwrap(), snapshot);
+
+    // So do the runtime settings the control socket reports and takes.
+    let settings = Settings { rule: Rule::parse("B2/S34H").unwrap(), speed: 12.5, steps_per_frame: 4, paused: true };
+    let json = serde_json::to_value(settings).unwrap();
+    assert_eq!(json, serde_json::json!({ "rule": "B2/S34H", "speed": 12.5, "steps_per_frame": 4, "paused": true }));
+    assert_eq!(serde_json::from_value::<Settings>(json).unwrap(), settings);
+    assert!(settings.check(8).is_ok());
+    assert!(Settings { speed: 0.0, ..settings }.check(8).is_err());
+    // A speed too slow for the wait between generations to be counted is refused too.
+    assert!(Settings { speed: 1e-300, ..settings }.check(8).is_err());
+    assert!(Settings { speed: MIN_SPEED, ..settings }.check(8).is_ok());
+    assert!(Settings { steps_per_frame: 9, ..settings }.check(8).is_err());
+}
+
+/// Newborns of a multi-state variant take their color from the parents the rule counted:
+/// those across a wrapping edge, and only those of its neighbourhood.
+#[test]
+fn newborn_colors_come_from_the_neighbourhood_of_the_rule() {
+    let life = Rule::parse("B3/S23").unwrap();
+    let mut states = vec![vec![0; 5]; 5];
+    for row in &mut states[1..=3] {
+        row[4] = 2;
+    }
+    let mut next = vec![vec![false; 5]; 5];
+    next[2][0] = true;
+    // Across the edge of a dead board there are no parents, and the lowest color wins.
+    assert_eq!(Variant::Immigration.step(&states, &next, &life, Boundary::Dead)[2][0], 1);
+    assert_eq!(Variant::Immigration.step(&states, &next, &life, Boundary::Wrap)[2][0], 2);
+
+    // Two blue parents on the diagonals outvote a red one above, unless the rule only
+    // counts the cells beside the newborn.
+    let mut states = vec![vec![0; 5]; 5];
+    states[1][1] = 2;
+    states[1][3] = 2;
+    states[1][2] = 1;
+    let mut next = vec![vec![false; 5]; 5];
+    next[2][2] = true;
+    assert_eq!(Variant::Immigration.step(&states, &next, &life, Boundary::Dead)[2][2], 2);
+    assert_eq!(Variant::Immigration.step(&states, &next, &Rule::parse("B1/S1V").unwrap(), Boundary::Dead)[2][2], 1);
+}
@@ -0,0 +1,124 @@
+//! Tests of the ways the starting board is seeded.
+
+use gameoflife::{
+    font,
+    placement::Placement,
+    random_grid,
+    seeding::{Perlin, Picture, SeedMode, Seeder, Uniform},
+    symmetry::Symmetry,
+    Grid,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+#[test]
+fn seed_modes_spread_the_live_cells_as_named() {
+    let population = |grid: &Grid, columns: std::ops::Range<usize>, rows: std::ops::Range<usize>| {
+        grid[rows].iter().map(|row| row[columns.clone()].iter().filter(|&&cell| cell).count()).sum::<usize>()
+    };
+
+    // Uniform boards are the same as before there were seed modes, so seeds still reproduce.
+    assert_eq!(Uniform.seed(30, 20, 0.3, &mut StdRng::seed_from_u64(5)), random_grid(30, 20, 0.3, &mut StdRng::seed_from_u64(5)));
+
+    let radial = SeedMode::Radial.seeder().seed(60, 60, 0.3, &mut StdRng::seed_from_u64(5));
+    assert!(population(&radial, 20..40, 20..40) > 2 * population(&radial, 0..20, 20..40));
+
+    let horizontal = SeedMode::Horizontal.seeder().seed(60, 40, 0.3, &mut StdRng::seed_from_u64(5));
+    assert!(population(&horizontal, 0..20, 0..40) > 2 * population(&horizontal, 40..60, 0..40));
+    assert_eq!(population(&horizontal, 59..60, 0..40), 0);
+
+    // Clusters leave most of the board empty.
+    let clusters = SeedMode::Clusters.seeder().seed(80, 50, 0.3, &mut StdRng::seed_from_u64(5));
+    let alive = population(&clusters, 0..80, 0..50);
+    assert!(alive > 0 && alive < 80 * 50 * 3 / 10, "{} cells alive", alive);
+    assert_eq!(clusters, SeedMode::Clusters.seeder().seed(80, 50, 0.3, &mut StdRng::seed_from_u64(5)));
+}
+
+#[test]
+fn noise_boards_are_connected_regions_above_the_threshold() {
+    // Neighbouring cells of a noise board mostly agree, unlike those of a uniform soup.
+    let agreement = |grid: &Grid| {
+        let pairs: Vec<bool> = grid.iter().flat_map(|row| row.windows(2).map(|pair| pair[0] == pair[1])).collect();
+        pairs.iter().filter(|&&same| same).count() as f64 / pairs.len() as f64
+    };
+    let noise = Perlin { threshold: 0.5 }.seed(80, 60, 0.3, &mut StdRng::seed_from_u64(9));
+    let alive = noise.iter().flatten().filter(|&&cell| cell).count() as f64 / (80.0 * 60.0);
+    assert!((0.2..0.8).contains(&alive), "{} of the cells alive", alive);
+    assert!(agreement(&noise) > 0.85, "{} of neighbours agree", agreement(&noise));
+    assert!(agreement(&random_grid(80, 60, 0.5, &mut StdRng::seed_from_u64(9))) < 0.6);
+
+    // Raising the threshold only takes cells away.
+    let higher = Perlin { threshold: 0.6 }.seed(80, 60, 0.3, &mut StdRng::seed_from_u64(9));
+    assert!(noise.iter().flatten().zip(higher.iter().flatten()).all(|(&low, &high)| low || !high));
+    assert!(Perlin { threshold: 0.0 }.seed(10, 10, 0.3, &mut StdRng::seed_from_u64(9)).iter().flatten().all(|&cell| cell));
+    assert_eq!(SeedMode::parse("noise"), Some(SeedMode::Noise { threshold: 0.5 }));
+}
+
+#[test]
+fn pictures_are_stretched_over_the_board_and_cut_at_the_threshold() {
+    // A 4x2 picture: black on the left, mid gray, and white on the right.
+    let picture = Picture::new(vec![vec![0.0, 0.0, 0.4, 1.0]; 2], 0.5);
+    assert_eq!(picture.size(), (4, 2));
+
+    // Every cell of a larger board takes its nearest pixel.
+    let board = picture.seed(8, 3, 0.2, &mut StdRng::seed_from_u64(1));
+    assert_eq!(board, vec![vec![true, true, true, true, true, true, false, false]; 3]);
+
+    // Every cell of a smaller board takes the average of its pixels.
+    assert_eq!(picture.seed(2, 1, 0.2, &mut StdRng::seed_from_u64(1)), vec![vec![true, false]]);
+    assert_eq!(Picture::new(vec![vec![0.0, 0.0, 0.4, 1.0]; 2], 0.3).seed(4, 1, 0.0, &mut StdRng::seed_from_u64(1)), vec![vec![true, true, false, false]]);
+}
+
+#[test]
+fn text_is_drawn_in_the_bitmap_font_and_centered_on_the_board() {
+    let text = font::text_pattern("Hi");
+    assert_eq!((text.width, text.height), (2 * font::GLYPH_WIDTH + 1, font::GLYPH_HEIGHT));
+    assert!(text.cells.iter().all(|row| !row[font::GLYPH_WIDTH]), "the letters are a column apart");
+    assert_eq!(text.cells, font::text_pattern("HI").cells);
+    let lit = |pattern: &gameoflife::pattern::Pattern, x: usize, y: usize| pattern.cells[y][x];
+    assert!((0..font::GLYPH_HEIGHT).all(|y| lit(&text, 0, y) && lit(&text, 4, y)), "the sides of the H");
+
+    // Lines are a row apart, and the shorter one is centered on the longer.
+    let lines = font::text_pattern("I\\nHI");
+    assert_eq!((lines.width, lines.height), (text.width, 2 * font::GLYPH_HEIGHT + 1));
+    assert_eq!(lines.cells[0], [vec![false; 4], vec![true; 3], vec![false; 4]].concat());
+    assert_eq!(lines.cells, font::text_pattern("I\nHI").cells);
+
+    let mut placement = Placement::from_pattern("text", text);
+    placement.center(40, 20).unwrap();
+    assert_eq!((placement.x, placement.y), (14, 6));
+}
+
+#[test]
+fn symmetric_soups_are_unchanged_by_their_mirrors_and_turns() {
+    let mut rng = StdRng::seed_from_u64(3);
+    for symmetry in [Symmetry::C2, Symmetry::C4, Symmetry::D2, Symmetry::D4, Symmetry::D8] {
+        for (width, height) in [(9, 7), (8, 8), (6, 11)] {
+            let mut grid = random_grid(width, height, 0.5, &mut rng);
+            symmetry.apply(&mut grid);
+            let before = grid.clone();
+            symmetry.apply(&mut grid);
+            assert_eq!(grid, before, "{} is settled after one pass", symmetry);
+
+            let flipped = |grid: &Grid| grid.iter().map(|row| row.iter().rev().copied().collect()).collect::<Grid>();
+            let turned = |grid: &Grid| grid.iter().rev().cloned().collect::<Grid>();
+            match symmetry {
+                Symmetry::C2 => assert_eq!(flipped(&turned(&grid)), grid),
+                Symmetry::D2 => assert_eq!(flipped(&grid), grid),
+                Symmetry::D4 => assert_eq!((flipped(&grid), turned(&grid)), (grid.clone(), grid.clone())),
+                Symmetry::C4 | Symmetry::D8 => {
+                    // The square in the middle turns onto itself, and nothing is left beside it.
+                    let side = width.min(height);
+                    let (left, top) = ((width - side) / 2, (height - side) / 2);
+                    let square: Grid = grid[top..top + side].iter().map(|row| row[left..left + side].to_vec()).collect();
+                    let quarter: Grid = (0..side).map(|y| (0..side).map(|x| square[side - 1 - x][y]).collect()).collect();
+                    assert_eq!(quarter, square, "{} on {}x{}", symmetry, width, height);
+                    if symmetry == Symmetry::D8 {
+                        assert_eq!(flipped(&square), square);
+                    }
+                    let population = grid.iter().flatten().filter(|&&cell| cell).count();
+                    assert_eq!(population, square.iter().flatten().filter(|&&cell| cell).count());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Tests of the world, its engines, and the headless run as they advance a board.
+
+mod common;
+
+use common::board;
+use gameoflife::{
+    boundary::Boundary,
+    engine::EngineKind,
+    hash::BoardHash,
+    headless,
+    noise::Noise,
+    random_grid,
+    rule::Rule,
+    update_grid,
+    update_grid_with_boundary,
+    world::World,
+};
+use rand::{rngs::StdRng, SeedableRng};
+
+/// A world stepped in place follows the same generations as stepping a new board each
+/// time, knows the cells each step changed and the hash they lead to, and picks up edits
+/// and a board of another size.
+#[test]
+fn world_steps_in_place_like_update_grid() {
+    let mut rng = StdRng::seed_from_u64(0x3071d);
+    for rulestring in ["B3/S23", "B2/S34H", "R2,C0,M0,S3..7,B4..6,NM", "B3/S23-k"] {
+        let rule = Rule::parse(rulestring).unwrap();
+        for boundary in [Boundary::Dead, Boundary::Wrap] {
+            let mut expected = random_grid(70, 9, 0.4, &mut rng);
+            let mut world = World::new(expected.clone(), boundary);
+            for generation in 1..=12 {
+                if generation == 6 {
+                    expected = random_grid(33, 20, 0.4, &mut rng);
+                    world.replace(expected.clone());
+                }
+                let previous = expected.clone();
+                expected = update_grid_with_boundary(&expected, &rule, boundary);
+                world.step(&rule);
+                assert_eq!(*world, expected, "{} {} diverged at generation {}", boundary, rule, generation);
+                let mut changes = world.changes().to_vec();
+                changes.sort_by_key(|&(x, y)| (y, x));
+                let changed: Vec<_> = (0..expected.len())
+                    .flat_map(|y| (0..expected[y].len()).map(move |x| (x, y)))
+                    .filter(|&(x, y)| previous[y][x] != expected[y][x])
+                    .collect();
+                assert_eq!(changes, changed);
+                assert_eq!(world.hash(), BoardHash::of(&expected));
+                let diff = world.diff(generation);
+                assert_eq!(diff.population, expected.iter().flatten().filter(|&&cell| cell).count());
+                assert!(diff.births.iter().all(|&(x, y)| expected[y][x] && !previous[y][x]));
+            }
+            // Edits go through the engine, a cell at a time or as a whole grid.
+            world.set(0, 0, true);
+            world.toggle(1, 0);
+            let toggled = !world.get(2, 0);
+            world.edit(|grid| grid[0][2] = toggled);
+            expected[0][0] = true;
+            expected[0][1] = !expected[0][1];
+            expected[0][2] = toggled;
+            assert_eq!(*world, expected);
+            assert_eq!(world.hash(), BoardHash::of(&expected));
+            world.step(&rule);
+            assert_eq!(*world, update_grid_with_boundary(&expected, &rule, boundary));
+        }
+    }
+}
+
+#[test]
+fn every_engine_steps_the_board_like_update_grid() {
+    let mut rng = StdRng::seed_from_u64(0xe291e);
+    let rules = ["B3/S23", "B36/S23", "B2/S34H", "B2/S013V", "B0/S8", "R2,C0,M0,S3..7,B4..6,NM", "B3/S23-k"];
+    let boundaries = [Boundary::Dead, Boundary::Wrap, Boundary::Reflect, Boundary::Alive, Boundary::Klein, Boundary::Cross];
+    for kind in [EngineKind::Packed, EngineKind::Dense, EngineKind::Sparse] {
+        for rulestring in rules {
+            let rule = Rule::parse(rulestring).unwrap();
+            for boundary in boundaries {
+                let mut expected = random_grid(13, 9, 0.3, &mut rng);
+                let mut world = World::new(expected.clone(), boundary).with_engine(kind.create(boundary));
+                for generation in 1..=6 {
+                    let next = update_grid_with_boundary(&expected, &rule, boundary);
+                    world.step(&rule);
+                    assert_eq!(*world, next, "{} {} {} diverged at generation {}", kind, boundary, rule, generation);
+                    let changed = expected.iter().flatten().zip(next.iter().flatten()).filter(|(old, new)| old != new).count();
+                    assert_eq!(world.changes().len(), changed, "{} {} {} changes at generation {}", kind, boundary, rule, generation);
+                    assert_eq!(world.population(), next.iter().flatten().filter(|&&cell| cell).count());
+                    expected = next;
+                }
+            }
+        }
+    }
+
+    let mut engine = EngineKind::Sparse.create(Boundary::Dead);
+    engine.load(&board(4, 3, 0b0111_0000));
+    assert_eq!((engine.bounds(), engine.population()), ((4, 3), 3));
+    engine.set(0, 0, true);
+    assert!(engine.get(0, 0) && !engine.get(3, 0));
+    assert_eq!(EngineKind::parse("sparse"), Some(EngineKind::Sparse));
+    assert_eq!(EngineKind::parse("hashlife"), None);
+}
+
+/// A world can be moved to another thread with its engine and stepped there.
+#[test]
+fn worlds_step_on_other_threads() {
+    let rule = Rule::parse("B3/S23").unwrap();
+    let start = random_grid(40, 30, 0.35, &mut StdRng::seed_from_u64(0x5e4d));
+    for kind in [EngineKind::Packed, EngineKind::Dense, EngineKind::Sparse] {
+        let mut world = World::new(start.clone(), Boundary::Wrap).with_engine(kind.create(Boundary::Wrap));
+        world = std::thread::spawn(move || {
+            (0..10).for_each(|_| world.step(&rule));
+            world
+        })
+        .join()
+        .unwrap();
+        let expected = (0..10).fold(start.clone(), |grid, _| update_grid_with_boundary(&grid, &rule, Boundary::Wrap));
+        assert_eq!(*world, expected, "{}", kind);
+    }
+}
+
+/// A world that computes its generations ahead on another thread gives the same boards as
+/// one that steps itself, through edits, a board of another size, and a change of rule.
+#[test]
+fn worlds_computed_ahead_follow_edits_and_rule_changes() {
+    let life = Rule::parse("B3/S23").unwrap();
+    let highlife = Rule::parse("B36/S23").unwrap();
+    let start = random_grid(40, 30, 0.35, &mut StdRng::seed_from_u64(0x10a4));
+    for kind in [EngineKind::Packed, EngineKind::Sparse] {
+        let mut ahead = World::new(start.clone(), Boundary::Wrap).with_engine(kind.create(Boundary::Wrap)).with_lookahead(kind.create(Boundary::Wrap));
+        let mut world = World::new(start.clone(), Boundary::Wrap).with_engine(kind.create(Boundary::Wrap));
+        for generation in 1..=30 {
+            match generation {
+                5 => [&mut ahead, &mut world].into_iter().for_each(|world| world.toggle(3, 4)),
+                12 => [&mut ahead, &mut world].into_iter().for_each(|world| world.edit(|grid| grid[0].fill(true))),
+                20 => [&mut ahead, &mut world].into_iter().for_each(|world| world.replace(vec![vec![true; 12]; 7])),
+                _ => {}
+            }
+            let rule = if generation < 16 { life } else { highlife };
+            ahead.step(&rule);
+            world.step(&rule);
+            assert_eq!(*ahead, *world, "{} diverged at generation {}", kind, generation);
+            let sorted = |world: &World| {
+                let mut changes = world.changes().to_vec();
+                changes.sort_unstable();
+                changes
+            };
+            assert_eq!((sorted(&ahead), ahead.hash()), (sorted(&world), world.hash()));
+        }
+    }
+}
+
+#[test]
+fn cells_past_the_maximum_age_die_whatever_the_rule() {
+    let rule = Rule::default();
+    let block = vec![vec![false; 4], vec![false, true, true, false], vec![false, true, true, false], vec![false; 4]];
+    let mut world = World::new(block.clone(), Boundary::Dead).with_max_age(3);
+    for _ in 0..2 {
+        world.step(&rule);
+        assert_eq!(*world, block, "the block lives for three generations");
+    }
+    world.step(&rule);
+    assert!(world.iter().flatten().all(|&cell| !cell), "and then dies all at once");
+    assert_eq!((world.population(), world.hash()), (0, BoardHash::default()));
+
+    // The middle of a blinker is alive in every phase, and is the only cell that ages out.
+    let blinker = vec![vec![false; 3], vec![true; 3], vec![false; 3]];
+    let mut world = World::new(blinker, Boundary::Dead).with_max_age(2);
+    world.step(&rule);
+    assert!(world[1][1] && world[0][1] && world[2][1]);
+    world.step(&rule);
+    assert_eq!(*world, [vec![false; 3], vec![true, false, true], vec![false; 3]]);
+
+    // Cells placed by editing the board count from the generation they appear in.
+    let mut world = World::new(vec![vec![false; 4]; 4], Boundary::Dead).with_max_age(2);
+    world.step(&rule);
+    world.replace(block.clone());
+    world.step(&rule);
+    assert_eq!(*world, block);
+    world.step(&rule);
+    assert!(world.iter().flatten().all(|&cell| !cell));
+}
+
+#[test]
+fn generations_are_computed_lazily_and_leave_the_world_at_the_last_one() {
+    let rule = Rule::default();
+    let glider = vec![
+        vec![false, true, false, false, false, false],
+        vec![false, false, true, false, false, false],
+        vec![true, true, true, false, false, false],
+        vec![false; 6],
+        vec![false; 6],
+        vec![false; 6],
+    ];
+    let mut world = World::new(glider.clone(), Boundary::Wrap);
+    let generations: Vec<_> = world.iter_generations(&rule).take(4).collect();
+    assert_eq!(generations.iter().map(|g| g.steps).collect::<Vec<_>>(), [1, 2, 3, 4]);
+    assert!(generations.iter().all(|g| g.population() == 5));
+
+    // Each generation is a copy, kept as it was while the world moves on.
+    let mut expected = glider;
+    for generation in &generations {
+        expected = update_grid_with_boundary(&expected, &rule, Boundary::Wrap);
+        assert_eq!(**generation, expected);
+    }
+    assert_eq!(*world, expected);
+
+    // Only the generations asked for are computed.
+    world.iter_generations(&rule).take(0).for_each(drop);
+    assert_eq!(*world, expected);
+}
+
+/// Noise flips the same cells for the same seed, at about the requested rate, and the
+/// headless run keeps its state hash in step with the flips.
+#[test]
+fn noise_is_reproducible() {
+    let flips: Vec<_> = (0..100).flat_map(|_| Noise::new(0.01, 7).flips(100, 100)).collect();
+    assert_eq!(flips, (0..100).flat_map(|_| Noise::new(0.01, 7).flips(100, 100)).collect::<Vec<_>>());
+    let mut noise = Noise::new(0.01, 7);
+    let total: usize = (0..100).map(|_| noise.flips(100, 100).len()).sum();
+    assert!((9_000..11_000).contains(&total), "{} flips", total);
+    assert_eq!(Noise::new(1.0, 7).flips(3, 2).len(), 6);
+    assert!(Noise::new(0.0, 7).flips(3, 2).is_empty());
+
+    let rule = Rule::parse("B3/S23").unwrap();
+    let mut rng = StdRng::seed_from_u64(0x9015e);
+    let grid = random_grid(50, 40, 0.3, &mut rng);
+    let mut expected = grid.clone();
+    let mut noise = Noise::new(0.002, 11);
+    for _ in 0..50 {
+        noise.apply(&mut expected);
+        expected = update_grid(&expected, &rule);
+    }
+    let mut noise = Noise::new(0.002, 11);
+    let report = headless::run(World::new(grid, Boundary::Dead), &rule, 50, &mut [], None, &mut || unreachable!(), Some(&mut noise)).unwrap();
+    assert_eq!(report.final_hash, BoardHash::of(&expected));
+}
@@ -0,0 +1,44 @@
+//! Tests of the statistics kept over a run and of the soup search.
+
+use gameoflife::{
+    soup::SoupSearch,
+    rule::Rule,
+    stats::{GenerationDiff, PeakPopulation, PopulationSparkline, StatCollector},
+};
+
+#[test]
+fn sparkline_keeps_the_latest_generations() {
+    let mut sparkline = PopulationSparkline::new(8, 4);
+    for population in [0, 10, 20, 30] {
+        sparkline.observe(&GenerationDiff { population, ..GenerationDiff::default() });
+    }
+    assert_eq!(sparkline.value(), "▁▃▆█");
+
+    // Older generations fall out of the history, and each character averages two.
+    for population in [30, 20, 10, 0, 0, 0] {
+        sparkline.observe(&GenerationDiff { population, ..GenerationDiff::default() });
+    }
+    assert_eq!(sparkline.value(), "██▂▁");
+}
+
+#[test]
+fn peak_population_keeps_the_first_generation_it_was_reached() {
+    let mut peak = PeakPopulation::default();
+    for (generation, population) in [(0, 5), (1, 9), (2, 4), (3, 9)] {
+        peak.observe(&GenerationDiff { generation, population, ..GenerationDiff::default() });
+    }
+    assert_eq!(peak.value(), "9 (gen 1)");
+}
+
+#[test]
+fn soup_search_settles_soups_into_known_objects() {
+    let search = SoupSearch { soups: 3, first_seed: 11, max_generations: 10_000, symmetry: None };
+    let census = search.run(&Rule::default());
+    assert_eq!(census.soups, 3);
+    assert_eq!(census.unsettled, 0);
+    assert!(census.objects.get("block").is_some_and(|&count| count > 0));
+    assert!(census.escaped.keys().all(|&name| ["glider", "lwss", "mwss", "hwss"].contains(&name)));
+
+    // The soups are seeded in turn, so the same search finds the same objects.
+    assert_eq!(search.run(&Rule::default()), census);
+}
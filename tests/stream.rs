@@ -0,0 +1,196 @@
+//! Tests of the streams, emitters, and replays that carry a run to other programs.
+
+mod common;
+
+use common::board;
+use gameoflife::{
+    bitgrid::BitGrid,
+    emit::{CsvRecorder, NdjsonRecorder},
+    recorder::FrameRecorder,
+    replay::{Intervention, Replay, ReplayRecorder},
+    random_grid,
+    stream::{self, Message},
+    rule::Rule,
+    snapshot::Snapshot,
+    update_grid,
+    Grid,
+};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+
+/// Streamed generations rebuild the board on the other end, as changes or whole boards.
+#[test]
+fn streamed_generations_rebuild_the_board() {
+    let rule = Rule::parse("B3/S23").unwrap();
+    let mut rng = StdRng::seed_from_u64(0x57ea);
+    let mut bits = BitGrid::from_grid(&random_grid(37, 23, 0.3, &mut rng));
+
+    let mut wire = stream::MAGIC.to_vec();
+    Message::Board { generation: 0, rule: rule.to_string(), grid: bits.to_grid() }.write(&mut wire).unwrap();
+    let mut expected = vec![bits.to_grid()];
+    for generation in 1..=40 {
+        let next = bits.step(&rule);
+        Message::between(&bits, &next, generation, &rule.to_string()).write(&mut wire).unwrap();
+        bits = next;
+        expected.push(bits.to_grid());
+    }
+    // A board that changes completely is cheaper to send whole.
+    let full = BitGrid::from_grid(&vec![vec![true; 37]; 23]);
+    assert!(matches!(Message::between(&bits, &full, 41, "B3/S23"), Message::Board { .. }));
+
+    let mut input = wire.as_slice();
+    stream::read_header(&mut input).unwrap();
+    let mut grid = Vec::new();
+    for (generation, expected) in expected.iter().enumerate() {
+        let message = Message::read(&mut input).unwrap().unwrap();
+        assert_eq!(message.generation(), generation as u64);
+        message.apply(&mut grid);
+        assert_eq!(&grid, expected, "generation {}", generation);
+    }
+    assert!(Message::read(&mut input).unwrap().is_none());
+    assert!(Message::read(&mut &b"X"[..]).is_err());
+    // Boards too wide or too tall are refused before anything is allocated for them.
+    for (width, height) in [(0u32, u32::MAX), (u32::MAX, 0), (1, 1 << 17)] {
+        let mut message = b"B".to_vec();
+        message.extend(0u64.to_le_bytes());
+        message.extend(0u16.to_le_bytes());
+        message.extend(width.to_le_bytes());
+        message.extend(height.to_le_bytes());
+        assert!(Message::read(&mut message.as_slice()).is_err(), "{}x{}", width, height);
+    }
+    assert!(stream::read_header(&mut &b"HTTP/"[..]).is_err());
+}
+
+/// The cells a world reports as changed, with the flips of noise, rebuild the board.
+#[test]
+fn flipped_cells_rebuild_the_board() {
+    let rule = Rule::parse("B3/S23").unwrap();
+    let mut grid = vec![vec![false; 64]; 32];
+    for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+        grid[y][x] = true;
+    }
+
+    for generation in 1..=20 {
+        let mut next = update_grid(&grid, &rule);
+        // A noise flip, which the step did not make.
+        next[20][generation as usize + 30] = !next[20][generation as usize + 30];
+        let flipped: Vec<(usize, usize)> = (0..32).flat_map(|y| (0..64).map(move |x| (x, y))).filter(|&(x, y)| grid[y][x] != next[y][x]).collect();
+        let message = Message::flipped(&flipped, &next, generation, "B3/S23");
+        assert!(matches!(&message, Message::Changes { cells, .. } if cells.len() == flipped.len()));
+        message.apply(&mut grid);
+        assert_eq!(grid, next);
+    }
+    // A board that changes more than its packed size is sent whole.
+    let full = vec![vec![true; 64]; 32];
+    let flipped: Vec<(usize, usize)> = (0..32).flat_map(|y| (0..64).map(move |x| (x, y))).filter(|&(x, y)| !grid[y][x]).collect();
+    let message = Message::flipped(&flipped, &full, 21, "B3/S23");
+    assert!(matches!(message, Message::Board { .. }));
+    message.apply(&mut grid);
+    assert_eq!(grid, full);
+}
+
+#[test]
+fn emitted_lines_count_births_and_deaths() {
+    let rule = Rule::parse("B3/S23").unwrap();
+    let mut rng = StdRng::seed_from_u64(0xe317);
+    let mut grid = random_grid(19, 13, 0.35, &mut rng);
+
+    let mut out = Vec::new();
+    let mut recorder = NdjsonRecorder::new(&mut out, true);
+    let mut expected = Vec::new();
+    for generation in 0..20 {
+        let next = update_grid(&grid, &rule);
+        recorder.record(&grid, generation).unwrap();
+        expected.push(grid);
+        grid = next;
+    }
+    drop(recorder);
+
+    let lines: Vec<serde_json::Value> = out.split(|&b| b == b'\n').filter(|line| !line.is_empty()).map(|line| serde_json::from_slice(line).unwrap()).collect();
+    assert_eq!(lines.len(), expected.len());
+    for (generation, line) in lines.iter().enumerate() {
+        let grid = &expected[generation];
+        let alive = |grid: &Grid, x: usize, y: usize| grid[y][x];
+        let (mut births, mut deaths) = (0, 0);
+        if generation > 0 {
+            let prev = &expected[generation - 1];
+            for y in 0..13 {
+                for x in 0..19 {
+                    births += (alive(grid, x, y) && !alive(prev, x, y)) as u64;
+                    deaths += (!alive(grid, x, y) && alive(prev, x, y)) as u64;
+                }
+            }
+        }
+        let population = grid.iter().flatten().filter(|&&cell| cell).count();
+        assert_eq!(line["generation"], generation as u64);
+        assert_eq!(line["population"], population as u64);
+        assert_eq!(line["births"], births);
+        assert_eq!(line["deaths"], deaths);
+        let cells = line["cells"].as_array().unwrap();
+        assert_eq!(cells.len(), population);
+        assert!(cells.iter().all(|cell| alive(grid, cell[0].as_u64().unwrap() as usize, cell[1].as_u64().unwrap() as usize)));
+    }
+}
+
+#[test]
+fn csv_rows_follow_the_generations() {
+    let path = std::env::temp_dir().join(format!("gameoflife-stats-{}.csv", std::process::id()));
+    let mut recorder = Box::new(CsvRecorder::create(&path).unwrap());
+    recorder.record(&[vec![true, true, false]], 0).unwrap();
+    recorder.record(&[vec![false, true, true]], 1).unwrap();
+    assert_eq!(recorder.finish().unwrap(), path);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(contents, "generation,population,births,deaths\n0,2,0,0\n1,2,1,1\n");
+}
+
+#[test]
+fn replays_give_back_the_start_and_each_intervention_at_its_generation() {
+    let path = std::env::temp_dir().join(format!("gameoflife-replay-{}.golr", std::process::id()));
+    let mut rng = ChaCha12Rng::seed_from_u64(7);
+    rng.next_u64();
+    let start = Snapshot::capture(&board(4, 3, 0b0111_0000), 0, &Rule::parse("B3/S23").unwrap(), 7, &rng);
+    let restored = Snapshot::capture(&board(4, 3, 0b0010_0010_0010), 4, &Rule::parse("B3/S23").unwrap(), 7, &rng);
+
+    let mut recorder = ReplayRecorder::new(&path, vec!["--seed".to_string(), "7".to_string()], start.clone()).unwrap();
+    let events = [
+        (3, Intervention::Pause),
+        (3, Intervention::Step),
+        (4, Intervention::Place { x: -1, y: 2, cells: "x = 2, y = 1\n2o!\n".to_string() }),
+        (4, Intervention::SetRule { rule: Rule::parse("B36/S23").unwrap() }),
+        (5, Intervention::Restore { state: restored }),
+    ];
+    for (generation, intervention) in events.iter().cloned() {
+        recorder.record(generation, intervention).unwrap();
+    }
+    assert_eq!(recorder.finish().unwrap(), path);
+
+    let mut replay = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(replay.args, ["--seed", "7"]);
+    assert_eq!(replay.start, start);
+    assert_eq!(replay.start.rng().next_u64(), rng.next_u64());
+
+    // Nothing is due before its generation, and interventions made at the same generation
+    // come out in order.
+    assert!(!replay.is_due(2));
+    assert_eq!(replay.next_due(4), None);
+    for (generation, intervention) in events {
+        assert_eq!(replay.next_due(generation), Some(intervention));
+    }
+    assert_eq!(replay.remaining(), 0);
+}
+
+/// Saved boards wider than the limit of pattern files still load, but cells past the edge
+/// of the board they were saved from are errors.
+#[test]
+fn snapshots_of_boards_past_the_pattern_limit_load() {
+    let mut grid = vec![vec![false; 9000]; 2];
+    grid[1][8999] = true;
+    let snapshot = Snapshot::capture(&grid, 3, &Rule::parse("B3/S23").unwrap(), 7, &ChaCha12Rng::seed_from_u64(7));
+    assert_eq!(snapshot.grid().unwrap(), grid);
+
+    let outside = Snapshot { cells: "$9001o!".to_string(), ..snapshot };
+    assert!(outside.grid().is_err());
+}
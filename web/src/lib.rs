@@ -0,0 +1,164 @@
+//! The browser frontend of the Game of Life, drawing the board on an HTML canvas.
+//!
+//! This crate compiles to a WebAssembly module without a bindings generator: it exports
+//! plain functions over numbers, and `index.html` reads the pixels of the board straight
+//! out of the module's memory. A page has a single board, kept between calls.
+
+use std::cell::RefCell;
+
+use gameoflife::{bitgrid::BitGrid, random_grid, raster::RasterStyle, rule::Rule};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+/// The longest rulestring the page can pass to [`life_set_rule`], in bytes.
+const RULE_CAPACITY: usize = 256;
+
+/// The board shown on the page.
+struct Universe {
+    /// The cells of the board.
+    cells: BitGrid,
+    /// The rule the board follows.
+    rule: Rule,
+    /// The number of generations since the board was filled.
+    generation: u64,
+    /// The board drawn as RGBA pixels, one pixel per cell, row by row.
+    pixels: Vec<u8>,
+    /// Where the page writes a rulestring before calling [`life_set_rule`].
+    rule_text: Vec<u8>,
+}
+
+thread_local! {
+    static UNIVERSE: RefCell<Universe> = RefCell::new(Universe {
+        cells: BitGrid::new(0, 0),
+        rule: Rule::default(),
+        generation: 0,
+        pixels: Vec::new(),
+        rule_text: vec![0; RULE_CAPACITY],
+    });
+}
+
+/// Runs a function on the board of the page.
+fn with_universe<T>(f: impl FnOnce(&mut Universe) -> T) -> T {
+    UNIVERSE.with(|universe| f(&mut universe.borrow_mut()))
+}
+
+/// Fills the board with random cells, keeping the rule.
+///
+/// # Arguments
+///
+/// * `width` - The width of the board in cells.
+/// * `height` - The height of the board in cells.
+/// * `probability` - The probability that a cell starts alive.
+/// * `seed` - The seed of the random board; the same seed gives the same board.
+#[no_mangle]
+pub extern "C" fn life_reset(width: u32, height: u32, probability: f64, seed: u32) {
+    let mut rng = ChaCha12Rng::seed_from_u64(u64::from(seed));
+    let grid = random_grid(width as usize, height as usize, probability.clamp(0.0, 1.0), &mut rng);
+    with_universe(|universe| {
+        universe.cells = BitGrid::from_grid(&grid);
+        universe.generation = 0;
+    });
+}
+
+/// Advances the board.
+///
+/// # Arguments
+///
+/// * `generations` - The number of generations to compute.
+#[no_mangle]
+pub extern "C" fn life_step(generations: u32) {
+    with_universe(|universe| {
+        for _ in 0..generations {
+            universe.cells = universe.cells.step(&universe.rule);
+        }
+        universe.generation += u64::from(generations);
+    });
+}
+
+/// Flips a cell between alive and dead, as when it is clicked; cells off the board are ignored.
+#[no_mangle]
+pub extern "C" fn life_toggle(x: u32, y: u32) {
+    with_universe(|universe| {
+        let (x, y) = (x as usize, y as usize);
+        if x < universe.cells.width() && y < universe.cells.height() {
+            let alive = universe.cells.get(x, y);
+            universe.cells.set(x, y, !alive);
+        }
+    });
+}
+
+/// Returns where the page writes a rulestring of up to [`RULE_CAPACITY`] bytes for
+/// [`life_set_rule`].
+#[no_mangle]
+pub extern "C" fn life_rule_buffer() -> *mut u8 {
+    with_universe(|universe| universe.rule_text.as_mut_ptr())
+}
+
+/// Switches to the rule written to [`life_rule_buffer`].
+///
+/// # Arguments
+///
+/// * `len` - The length of the rulestring in bytes.
+///
+/// # Returns
+///
+/// Whether the rule was valid; an invalid rule leaves the current one in place.
+#[no_mangle]
+pub extern "C" fn life_set_rule(len: u32) -> bool {
+    with_universe(|universe| {
+        let text = universe.rule_text.get(..len as usize).and_then(|text| std::str::from_utf8(text).ok());
+        match text.map(Rule::parse) {
+            Some(Ok(rule)) => {
+                universe.rule = rule;
+                true
+            }
+            _ => false,
+        }
+    })
+}
+
+/// Returns the width of the board in cells.
+#[no_mangle]
+pub extern "C" fn life_width() -> u32 {
+    with_universe(|universe| universe.cells.width() as u32)
+}
+
+/// Returns the height of the board in cells.
+#[no_mangle]
+pub extern "C" fn life_height() -> u32 {
+    with_universe(|universe| universe.cells.height() as u32)
+}
+
+/// Returns the number of generations since the board was filled.
+#[no_mangle]
+pub extern "C" fn life_generation() -> f64 {
+    with_universe(|universe| universe.generation as f64)
+}
+
+/// Returns the number of live cells.
+#[no_mangle]
+pub extern "C" fn life_population() -> u32 {
+    with_universe(|universe| universe.cells.population() as u32)
+}
+
+/// Draws the board in the colors of the image exporters.
+///
+/// # Returns
+///
+/// Where the RGBA pixels start, one pixel per cell, row by row; they stay valid until the
+/// next call.
+#[no_mangle]
+pub extern "C" fn life_render() -> *const u8 {
+    let style = RasterStyle::default();
+    with_universe(|universe| {
+        let (width, height) = (universe.cells.width(), universe.cells.height());
+        universe.pixels.clear();
+        for y in 0..height {
+            for x in 0..width {
+                let color = if universe.cells.get(x, y) { style.alive } else { style.dead };
+                universe.pixels.extend_from_slice(&[color.0, color.1, color.2, 0xff]);
+            }
+        }
+        universe.pixels.as_ptr()
+    })
+}